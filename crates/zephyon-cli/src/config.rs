@@ -0,0 +1,65 @@
+//! CLI configuration file.
+//!
+//! ```toml
+//! rpc_url = "https://api.devnet.solana.com"
+//! keypair = "~/.config/solana/id.json"
+//! commitment = "confirmed"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use solana_keypair::{read_keypair_file, Keypair};
+
+use crate::Result;
+
+/// Used when `--config` / `ZEPHYON_CONFIG` is not given.
+pub const DEFAULT_PATH: &str = "~/.config/zephyon/cli.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// JSON-RPC endpoint.
+    pub rpc_url: String,
+
+    /// Keypair file signing and paying for transactions (the treasury
+    /// authority for privileged commands, the user for deposits).
+    pub keypair: PathBuf,
+
+    /// Commitment for reads and confirmations.
+    #[serde(default = "default_commitment")]
+    pub commitment: String,
+}
+
+fn default_commitment() -> String {
+    "confirmed".to_string()
+}
+
+impl Config {
+    pub fn load(path: &str) -> Result<Self> {
+        let path = expand_home(path);
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("reading config {}: {e}", path.display()))?;
+        let mut config: Config =
+            toml::from_str(&text).map_err(|e| format!("parsing config {}: {e}", path.display()))?;
+        config.keypair = expand_home(&config.keypair.to_string_lossy());
+        Ok(config)
+    }
+
+    pub fn signer(&self) -> Result<Keypair> {
+        read_keypair(&self.keypair)
+    }
+}
+
+/// Read a keypair file (`~/` is expanded).
+pub fn read_keypair(path: &Path) -> Result<Keypair> {
+    let path = expand_home(&path.to_string_lossy());
+    read_keypair_file(&path).map_err(|e| format!("reading keypair {}: {e}", path.display()).into())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,909 @@
+//! `zephyon` — operator CLI for the Zephyon protocol program.
+//!
+//! Reads the RPC URL and signing keypair from a TOML config file (see
+//! `config`), prints JSON on stdout, and with `--dry-run` simulates
+//! transactions instead of submitting them.
+//!
+//! Privileged commands can also `--export` an unsigned transaction for an
+//! authority key kept offline; `sign` signs it on the offline machine and
+//! `submit` adds the signatures and sends it.
+
+mod config;
+mod output;
+mod rpc;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, TokenAccount};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_keypair::{write_keypair_file, Keypair};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use zephyon_client::accounts::{
+    decode, decode_receipt, decode_treasury, decode_user_profile, MintLedger, Receipt, UserProfile,
+};
+use zephyon_client::events::logs::parse_logs;
+use zephyon_client::instructions::{self as builders, FlowOptions};
+use zephyon_client::nonce::{self, NonceAccount};
+use zephyon_client::offline::{self, Lifetime};
+use zephyon_client::reconcile::{reconcile, Books};
+use zephyon_client::{args, pda, MemoCpiMode, PROGRAM_ID};
+
+use crate::config::{read_keypair, Config};
+use crate::output::{
+    mint_report_json, nonce_json, print, receipt_json, transaction_json, treasury_json,
+};
+use crate::rpc::Rpc;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "zephyon",
+    version,
+    about = "Operator CLI for the Zephyon protocol program"
+)]
+struct Cli {
+    /// Config file (TOML with rpc_url, keypair, commitment).
+    #[arg(long, global = true, env = "ZEPHYON_CONFIG", default_value = config::DEFAULT_PATH)]
+    config: String,
+
+    /// Simulate transactions instead of submitting them.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the treasury PDA with the config keypair as authority.
+    InitTreasury,
+
+    /// Pause all value-moving flows.
+    Pause {
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+
+    /// Resume value-moving flows.
+    Unpause {
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+
+    /// Treasury payout to a recipient (config keypair = treasury authority).
+    ///
+    /// With --reference the payout goes through `spl_pay_with_reference`, so
+    /// the same reference can never be paid twice.
+    Pay {
+        #[arg(long)]
+        recipient: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Raw token units.
+        #[arg(long)]
+        amount: u64,
+        /// 32-byte reference as 64 hex characters.
+        #[arg(long)]
+        reference: Option<String>,
+        /// Memo stored on the receipt (UTF-8, at most 64 bytes).
+        #[arg(long)]
+        memo: Option<String>,
+        /// Also write a wallet-visible memo through the SPL Memo program.
+        #[arg(long, value_enum)]
+        memo_cpi: Option<MemoMode>,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+
+    /// Deposit from the config keypair into the treasury.
+    Deposit {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Raw token units.
+        #[arg(long)]
+        amount: u64,
+        /// Write a receipt keyed by this nonce (`spl_deposit_with_receipt`).
+        #[arg(long)]
+        nonce: Option<u64>,
+        /// Memo stored on the receipt (requires --nonce).
+        #[arg(long, requires = "nonce")]
+        memo: Option<String>,
+    },
+
+    /// Treasury withdrawal to a user (config keypair = treasury authority).
+    Withdraw {
+        #[arg(long)]
+        user: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Raw token units.
+        #[arg(long)]
+        amount: u64,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+
+    /// Print the treasury account.
+    ShowTreasury,
+
+    /// List receipts, newest first.
+    ListReceipts {
+        /// Only receipts whose `user` is this wallet.
+        #[arg(long)]
+        user: Option<Pubkey>,
+        /// Only this direction (1 deposit, 2 withdraw, 3 pay, 4 dispute,
+        /// 5 merchant payment).
+        #[arg(long)]
+        direction: Option<u8>,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// Decode a receipt from the chain or from raw account data.
+    #[command(group(ArgGroup::new("source").required(true).args(["address", "data"])))]
+    DecodeReceipt {
+        /// Receipt account address.
+        address: Option<Pubkey>,
+        /// Base64 account data (decoded offline).
+        #[arg(long)]
+        data: Option<String>,
+    },
+
+    /// Upgrade every receipt still stored in an older layout
+    /// (`migrate_receipt`, rent top-up paid by the config keypair), and the
+    /// config keypair's own user profile (`update_user_profile`).
+    ///
+    /// Flows reject legacy receipts and profiles until they are migrated;
+    /// current ones are skipped, so it is safe to re-run.
+    Migrate,
+
+    /// Open the per-mint ledger the value flows keep (config keypair =
+    /// treasury authority). The current treasury ATA balance becomes its
+    /// opening balance.
+    OpenLedger {
+        #[arg(long)]
+        mint: Pubkey,
+        #[command(flatten)]
+        tx: TxArgs,
+    },
+
+    /// Check a mint's treasury ATA against its ledger on-chain
+    /// (`assert_solvency`).
+    AssertSolvency {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+
+    /// Reconcile the treasury ATA balances with receipts, events and the
+    /// per-mint ledgers.
+    ///
+    /// Receipt-less flows are read from the transaction history of each
+    /// treasury ATA. Prints one report per mint and exits non-zero if any
+    /// mint is out of balance.
+    Reconcile {
+        /// Only this mint (repeatable; default: every mint with a treasury
+        /// ATA, receipt or ledger).
+        #[arg(long = "mint")]
+        mints: Vec<Pubkey>,
+    },
+
+    /// Sign an exported transaction (no RPC access needed).
+    ///
+    /// Prints the transaction for review along with the signature to pass to
+    /// `submit --signature`.
+    Sign {
+        /// Base64 transaction, or a file holding one.
+        transaction: String,
+        /// Keypair to sign with instead of the config keypair.
+        #[arg(long)]
+        keypair: Option<PathBuf>,
+    },
+
+    /// Manage durable nonce accounts.
+    Nonce {
+        #[command(subcommand)]
+        command: NonceCommand,
+    },
+
+    /// Add offline signatures to an exported transaction and submit it.
+    ///
+    /// The config keypair also signs if it is a required signer (e.g. the fee
+    /// payer).
+    Submit {
+        /// Base64 transaction, or a file holding one.
+        transaction: String,
+        /// `<PUBKEY>=<SIGNATURE>` as printed by `sign`; repeatable.
+        #[arg(long = "signature", value_parser = parse_signer_signature)]
+        signatures: Vec<(Pubkey, Signature)>,
+    },
+}
+
+#[derive(Subcommand)]
+enum NonceCommand {
+    /// Create a nonce account, paid for by the config keypair.
+    Create {
+        /// Keypair file of the new account; generated if it does not exist.
+        #[arg(long)]
+        keypair: PathBuf,
+        /// Nonce authority (default: the config keypair).
+        #[arg(long)]
+        authority: Option<Pubkey>,
+    },
+
+    /// Print a nonce account's authority and current value.
+    Show { account: Pubkey },
+
+    /// Advance a nonce (config keypair = nonce authority), invalidating
+    /// every transaction signed against its current value.
+    Advance { account: Pubkey },
+}
+
+/// How privileged transactions are built, and whether they are exported
+/// unsigned instead of submitted.
+#[derive(Args, Default)]
+struct TxArgs {
+    /// Treasury authority, when its key is not the config keypair (e.g. kept
+    /// offline).
+    #[arg(long)]
+    authority: Option<Pubkey>,
+
+    /// Fee payer (default: the authority).
+    #[arg(long)]
+    fee_payer: Option<Pubkey>,
+
+    /// Blockhash to use instead of fetching one; with --nonce, the value
+    /// stored in the nonce account (so it is not fetched either).
+    #[arg(long)]
+    blockhash: Option<Hash>,
+
+    /// Durable nonce account keeping the transaction valid until it is
+    /// submitted (see `zephyon nonce`).
+    #[arg(long)]
+    nonce: Option<Pubkey>,
+
+    /// Nonce account authority (default: read from the nonce account, or the
+    /// authority when --blockhash is given).
+    #[arg(long, requires = "nonce")]
+    nonce_authority: Option<Pubkey>,
+
+    /// Export the unsigned transaction instead of submitting it: `-` prints
+    /// the base64, anything else is a file to write it to.
+    #[arg(long, value_name = "PATH")]
+    export: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MemoMode {
+    /// Forward the memo bytes as-is.
+    Raw,
+    /// Write `zephyon:<receipt address>`.
+    ReceiptLink,
+}
+
+impl From<MemoMode> for MemoCpiMode {
+    fn from(mode: MemoMode) -> Self {
+        match mode {
+            MemoMode::Raw => MemoCpiMode::Raw,
+            MemoMode::ReceiptLink => MemoCpiMode::ReceiptLink,
+        }
+    }
+}
+
+/// Receipts upgraded per `migrate` transaction.
+const MIGRATE_BATCH: usize = 8;
+
+struct Session {
+    config: Config,
+    rpc: Rpc,
+    dry_run: bool,
+}
+
+impl Session {
+    fn open(cli: &Cli) -> Result<Self> {
+        let config = Config::load(&cli.config)?;
+        Ok(Self {
+            rpc: Rpc::new(&config.rpc_url, &config.commitment),
+            config,
+            dry_run: cli.dry_run,
+        })
+    }
+
+    fn signer(&self) -> Result<Keypair> {
+        self.config.signer()
+    }
+
+    /// `--authority`, or the config keypair.
+    fn authority(&self, tx: &TxArgs) -> Result<Pubkey> {
+        match tx.authority {
+            Some(authority) => Ok(authority),
+            None => Ok(self.signer()?.pubkey()),
+        }
+    }
+
+    /// Build a transaction for `ixs` as described by `tx`, then either export
+    /// it unsigned or sign it with the config keypair and send it; the result
+    /// is added to `out`.
+    fn execute(
+        &self,
+        ixs: &[Instruction],
+        tx: &TxArgs,
+        authority: &Pubkey,
+        mut out: Value,
+    ) -> Result<Value> {
+        let lifetime = match (tx.nonce, tx.blockhash) {
+            (Some(account), Some(value)) => Lifetime::Nonce {
+                account,
+                authority: tx.nonce_authority.unwrap_or(*authority),
+                value,
+            },
+            (Some(account), None) => {
+                let state = self.nonce(&account)?;
+                if let Some(expected) = tx.nonce_authority.filter(|a| *a != state.authority) {
+                    return Err(format!(
+                        "nonce {account} is controlled by {}, not {expected}",
+                        state.authority
+                    )
+                    .into());
+                }
+                nonce::lifetime(&account, &state)
+            }
+            (None, Some(blockhash)) => Lifetime::Blockhash(blockhash),
+            (None, None) => Lifetime::Blockhash(self.rpc.latest_blockhash()?),
+        };
+        let mut transaction =
+            offline::unsigned(ixs, &tx.fee_payer.unwrap_or(*authority), &lifetime);
+
+        if let Some(path) = &tx.export {
+            let encoded = offline::encode(&transaction)?;
+            out["unsigned"] = transaction_json(&transaction);
+            if path == "-" {
+                out["transaction"] = json!(encoded);
+            } else {
+                std::fs::write(path, encoded).map_err(|e| format!("writing {path}: {e}"))?;
+                out["exported"] = json!(path);
+            }
+            return Ok(out);
+        }
+
+        sign_with(&mut transaction, &self.signer()?)?;
+        self.send(&transaction, out)
+    }
+
+    /// Sign with the config keypair and send, using a fresh blockhash.
+    fn submit(&self, ix: Instruction, out: Value) -> Result<Value> {
+        let authority = self.signer()?.pubkey();
+        self.execute(&[ix], &TxArgs::default(), &authority, out)
+    }
+
+    /// Send (or simulate) a fully signed transaction. Transactions built
+    /// against a durable nonce are first checked against the nonce's current
+    /// value.
+    fn send(&self, tx: &Transaction, mut out: Value) -> Result<Value> {
+        let missing = offline::missing_signers(tx);
+        if !missing.is_empty() {
+            let missing: Vec<String> = missing.iter().map(Pubkey::to_string).collect();
+            return Err(format!("missing signatures from {}", missing.join(", ")).into());
+        }
+
+        if let Some(account) = nonce::nonce_account(tx) {
+            let state = self.nonce(&account)?;
+            nonce::check(tx, &account, &state)?;
+            out["nonce"] = nonce_json(&account, &state);
+        }
+
+        if self.dry_run {
+            out["dry_run"] = json!(true);
+            out["simulation"] = self.rpc.simulate(tx)?;
+        } else {
+            out["signature"] = json!(self.rpc.send_and_confirm(tx)?);
+        }
+        Ok(out)
+    }
+
+    fn nonce(&self, account: &Pubkey) -> Result<NonceAccount> {
+        let data = self
+            .rpc
+            .account_data(account)?
+            .ok_or_else(|| format!("nonce account {account} not found"))?;
+        Ok(nonce::decode(&data)?)
+    }
+
+    /// Rebuild the books of every mint (or only `only`) and check them
+    /// against the treasury ATAs and ledgers.
+    fn reconcile(&self, only: &[Pubkey]) -> Result<Value> {
+        let treasury = pda::treasury().0;
+
+        let mut books = Books::new();
+        for (address, data) in self
+            .rpc
+            .program_accounts(&PROGRAM_ID, &[(0, Receipt::DISCRIMINATOR)])?
+        {
+            let receipt = decode_receipt(&data).map_err(|e| format!("receipt {address}: {e}"))?;
+            books.add_receipt(address, &receipt);
+        }
+
+        let mut balances = BTreeMap::new();
+        for (address, data) in self.rpc.token_accounts_by_owner(&treasury, &token::ID)? {
+            let account = TokenAccount::try_deserialize(&mut &data[..])?;
+            // Only the canonical ATA counts; other token accounts the
+            // treasury owns are outside the program's books.
+            if address == get_associated_token_address(&treasury, &account.mint) {
+                balances.insert(account.mint, account.amount);
+            }
+        }
+
+        let mut ledgers = BTreeMap::new();
+        for (address, data) in self.rpc.program_accounts(
+            &PROGRAM_ID,
+            &[(0, MintLedger::DISCRIMINATOR), (8, treasury.as_ref())],
+        )? {
+            let ledger: MintLedger =
+                decode(&data).map_err(|e| format!("mint ledger {address}: {e}"))?;
+            ledgers.insert(ledger.mint, ledger);
+        }
+
+        let mut mints: BTreeSet<Pubkey> = books
+            .mints()
+            .chain(balances.keys())
+            .chain(ledgers.keys())
+            .copied()
+            .collect();
+        if !only.is_empty() {
+            mints.retain(|mint| only.contains(mint));
+        }
+
+        // Receipt-less flows only show up in events: replay the history of
+        // every treasury ATA (a transaction may touch several).
+        let mut signatures = BTreeSet::new();
+        for mint in &mints {
+            let ata = get_associated_token_address(&treasury, mint);
+            signatures.extend(self.rpc.successful_signatures(&ata)?);
+        }
+        for signature in &signatures {
+            let logs = self.rpc.transaction_logs(signature)?;
+            for parsed in parse_logs(&PROGRAM_ID, &logs)? {
+                books.add_event(&parsed.event);
+            }
+        }
+
+        let reports: Vec<_> = reconcile(&books, &balances, &ledgers)
+            .into_iter()
+            .filter(|report| mints.contains(&report.mint))
+            .collect();
+
+        Ok(json!({
+            "treasury": treasury.to_string(),
+            "transactions": signatures.len(),
+            "balanced": reports.iter().all(|report| report.is_balanced()),
+            "mints": reports.iter().map(mint_report_json).collect::<Vec<_>>(),
+        }))
+    }
+
+    /// Upgrade every legacy receipt, `MIGRATE_BATCH` per transaction, then
+    /// the config keypair's user profile if it is legacy too.
+    fn migrate(&self) -> Result<Value> {
+        let payer = self.signer()?.pubkey();
+
+        let mut receipts = Vec::new();
+        for (address, data) in self
+            .rpc
+            .program_accounts(&PROGRAM_ID, &[(0, Receipt::DISCRIMINATOR)])?
+        {
+            let receipt = decode_receipt(&data).map_err(|e| format!("receipt {address}: {e}"))?;
+            if receipt.version < Receipt::CURRENT_VERSION {
+                receipts.push(address);
+            }
+        }
+
+        let mut transactions = Vec::new();
+        for batch in receipts.chunks(MIGRATE_BATCH) {
+            let ixs: Vec<_> = batch
+                .iter()
+                .map(|receipt| builders::migrate_receipt(&payer, receipt))
+                .collect();
+            let addresses: Vec<String> = batch.iter().map(Pubkey::to_string).collect();
+            let out = json!({ "receipts": addresses });
+            transactions.push(self.execute(&ixs, &TxArgs::default(), &payer, out)?);
+        }
+
+        // Only the owner can upgrade a profile, so other wallets' profiles
+        // are left to them.
+        let profile = pda::user_profile(&payer).0;
+        let legacy_profile = match self.rpc.account_data(&profile)? {
+            Some(data) => {
+                decode_user_profile(&data)
+                    .map_err(|e| format!("user profile {profile}: {e}"))?
+                    .version
+                    < UserProfile::CURRENT_VERSION
+            }
+            None => false,
+        };
+        if legacy_profile {
+            let ix = builders::update_user_profile(&payer);
+            let out = json!({ "user_profile": profile.to_string() });
+            transactions.push(self.execute(&[ix], &TxArgs::default(), &payer, out)?);
+        }
+
+        Ok(json!({
+            "receipts": receipts.len(),
+            "user_profile": legacy_profile.then(|| profile.to_string()),
+            "transactions": transactions,
+        }))
+    }
+
+    fn treasury(&self) -> Result<zephyon_client::accounts::Treasury> {
+        let data = self
+            .rpc
+            .account_data(&pda::treasury().0)?
+            .ok_or("treasury is not initialized")?;
+        Ok(decode_treasury(&data)?)
+    }
+}
+
+/// Sign `tx` with `keypair` if it is one of its required signers.
+fn sign_with(tx: &mut Transaction, keypair: &Keypair) -> Result<bool> {
+    let signer = keypair.pubkey();
+    if !offline::signers(tx).contains(&signer) {
+        return Ok(false);
+    }
+    let signature = keypair.sign_message(&tx.message_data());
+    offline::add_signature(tx, &signer, signature)?;
+    Ok(true)
+}
+
+/// Base64 transaction given inline or as a file path.
+fn read_transaction(arg: &str) -> Result<Transaction> {
+    let encoded = if Path::new(arg).is_file() {
+        std::fs::read_to_string(arg).map_err(|e| format!("reading {arg}: {e}"))?
+    } else {
+        arg.to_string()
+    };
+    Ok(offline::decode(&encoded)?)
+}
+
+fn parse_signer_signature(arg: &str) -> std::result::Result<(Pubkey, Signature), String> {
+    let (signer, signature) = arg.split_once('=').ok_or("expected <PUBKEY>=<SIGNATURE>")?;
+    Ok((
+        signer.parse().map_err(|e| format!("pubkey: {e}"))?,
+        signature.parse().map_err(|e| format!("signature: {e}"))?,
+    ))
+}
+
+fn parse_reference(hex: &str) -> Result<[u8; 32]> {
+    let hex = hex.trim_start_matches("0x");
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err("reference must be 64 hex characters".into());
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(out)
+}
+
+fn run(cli: Cli) -> Result<Value> {
+    let treasury = pda::treasury().0;
+
+    match &cli.command {
+        Command::InitTreasury => {
+            let s = Session::open(&cli)?;
+            let ix = builders::initialize_treasury(&s.signer()?.pubkey());
+            s.submit(ix, json!({ "treasury": treasury.to_string() }))
+        }
+
+        Command::Pause { tx } | Command::Unpause { tx } => {
+            let s = Session::open(&cli)?;
+            let paused = matches!(cli.command, Command::Pause { .. });
+            let authority = s.authority(tx)?;
+            let ix = builders::set_treasury_paused(&authority, paused);
+            s.execute(
+                &[ix],
+                tx,
+                &authority,
+                json!({ "treasury": treasury.to_string(), "paused": paused }),
+            )
+        }
+
+        Command::Pay {
+            recipient,
+            mint,
+            amount,
+            reference,
+            memo,
+            memo_cpi,
+            tx,
+        } => {
+            let s = Session::open(&cli)?;
+            let pay_count = s.treasury()?.pay_count;
+            let memo = memo.as_ref().map(|m| m.as_bytes().to_vec());
+            let memo_cpi = memo_cpi.map(MemoCpiMode::from);
+            let opts = FlowOptions {
+                memo_program: memo_cpi.is_some(),
+                ..FlowOptions::default()
+            };
+            let authority = s.authority(tx)?;
+
+            let ix = match reference.as_deref().map(parse_reference).transpose()? {
+                Some(reference) => builders::spl_pay_with_reference(
+                    &authority,
+                    recipient,
+                    mint,
+                    pay_count,
+                    args::SplPayWithReference {
+                        amount: *amount,
+                        reference,
+                        memo,
+                        memo_cpi,
+                    },
+                    opts,
+                ),
+                None => builders::spl_pay(
+                    &authority,
+                    recipient,
+                    mint,
+                    pay_count,
+                    args::SplPay {
+                        amount: *amount,
+                        reference: None,
+                        memo,
+                        memo_cpi,
+                    },
+                    opts,
+                ),
+            };
+
+            let receipt = pda::pay_receipt(&treasury, pay_count).0;
+            s.execute(
+                &[ix],
+                tx,
+                &authority,
+                json!({ "receipt": receipt.to_string(), "pay_count": pay_count }),
+            )
+        }
+
+        Command::Deposit {
+            mint,
+            amount,
+            nonce,
+            memo,
+        } => {
+            let s = Session::open(&cli)?;
+            let user = s.signer()?.pubkey();
+
+            match nonce {
+                Some(nonce) => {
+                    let ix = builders::spl_deposit_with_receipt(
+                        &user,
+                        mint,
+                        args::SplDepositWithReceipt {
+                            amount: *amount,
+                            nonce: *nonce,
+                            reference: None,
+                            memo: memo.as_ref().map(|m| m.as_bytes().to_vec()),
+                            memo_cpi: None,
+                        },
+                        FlowOptions::default(),
+                    );
+                    let receipt = pda::deposit_receipt(&user, *nonce).0;
+                    s.submit(ix, json!({ "receipt": receipt.to_string() }))
+                }
+                None => {
+                    let ix = builders::spl_deposit(&user, mint, *amount, FlowOptions::default());
+                    s.submit(ix, json!({ "user": user.to_string() }))
+                }
+            }
+        }
+
+        Command::Withdraw {
+            user,
+            mint,
+            amount,
+            tx,
+        } => {
+            let s = Session::open(&cli)?;
+            let authority = s.authority(tx)?;
+            let ix =
+                builders::spl_withdraw(&authority, user, mint, *amount, FlowOptions::default());
+            s.execute(&[ix], tx, &authority, json!({ "user": user.to_string() }))
+        }
+
+        Command::ShowTreasury => {
+            let s = Session::open(&cli)?;
+            Ok(treasury_json(&treasury, &s.treasury()?))
+        }
+
+        Command::ListReceipts {
+            user,
+            direction,
+            limit,
+        } => {
+            let s = Session::open(&cli)?;
+
+            // Layout: discriminator (8) | user (32) | direction (1) | ...
+            let mut memcmp: Vec<(usize, &[u8])> = vec![(0, Receipt::DISCRIMINATOR)];
+            if let Some(user) = user {
+                memcmp.push((8, user.as_ref()));
+            }
+            let direction_byte = direction.map(|d| [d]);
+            if let Some(d) = direction_byte.as_ref() {
+                memcmp.push((40, d));
+            }
+
+            let mut receipts = Vec::new();
+            for (address, data) in s.rpc.program_accounts(&PROGRAM_ID, &memcmp)? {
+                // Skip anything that fails to decode rather than abort the listing.
+                if let Ok(receipt) = decode_receipt(&data) {
+                    receipts.push((address, receipt));
+                }
+            }
+            receipts.sort_by(|(a, ra), (b, rb)| rb.ts.cmp(&ra.ts).then(a.cmp(b)));
+            receipts.truncate(limit.unwrap_or(usize::MAX));
+
+            let receipts: Vec<Value> = receipts
+                .iter()
+                .map(|(address, receipt)| receipt_json(address, receipt))
+                .collect();
+            Ok(json!({ "count": receipts.len(), "receipts": receipts }))
+        }
+
+        Command::DecodeReceipt { address, data } => {
+            let (address, bytes) = match (address, data) {
+                (_, Some(data)) => (address.unwrap_or_default(), STANDARD.decode(data.trim())?),
+                (Some(address), None) => {
+                    let s = Session::open(&cli)?;
+                    let bytes = s
+                        .rpc
+                        .account_data(address)?
+                        .ok_or_else(|| format!("account {address} not found"))?;
+                    (*address, bytes)
+                }
+                (None, None) => unreachable!("clap requires an address or --data"),
+            };
+            Ok(receipt_json(&address, &decode_receipt(&bytes)?))
+        }
+
+        Command::Migrate => Session::open(&cli)?.migrate(),
+
+        Command::OpenLedger { mint, tx } => {
+            let s = Session::open(&cli)?;
+            let authority = s.authority(tx)?;
+            let ix = builders::open_mint_ledger(&authority, mint);
+            s.execute(
+                &[ix],
+                tx,
+                &authority,
+                json!({
+                    "mint": mint.to_string(),
+                    "mint_ledger": pda::mint_ledger(&treasury, mint).0.to_string(),
+                }),
+            )
+        }
+
+        Command::AssertSolvency { mint } => {
+            let s = Session::open(&cli)?;
+            s.submit(
+                builders::assert_solvency(mint),
+                json!({ "mint": mint.to_string() }),
+            )
+        }
+
+        Command::Reconcile { mints } => {
+            let s = Session::open(&cli)?;
+            let report = s.reconcile(mints)?;
+            if report["balanced"] != json!(true) {
+                print(&report);
+                return Err("treasury books and chain disagree".into());
+            }
+            Ok(report)
+        }
+
+        Command::Sign {
+            transaction,
+            keypair,
+        } => {
+            let mut tx = read_transaction(transaction)?;
+            let keypair = match keypair {
+                Some(path) => read_keypair(path)?,
+                None => Config::load(&cli.config)?.signer()?,
+            };
+            if !sign_with(&mut tx, &keypair)? {
+                return Err(format!("{} is not a required signer", keypair.pubkey()).into());
+            }
+
+            let index = offline::signers(&tx)
+                .iter()
+                .position(|key| *key == keypair.pubkey())
+                .expect("signed above");
+            Ok(json!({
+                "transaction": transaction_json(&tx),
+                "signer": keypair.pubkey().to_string(),
+                "signature": tx.signatures[index].to_string(),
+            }))
+        }
+
+        Command::Nonce { command } => {
+            let s = Session::open(&cli)?;
+            match command {
+                NonceCommand::Create { keypair, authority } => {
+                    let payer = s.signer()?;
+                    let account = if keypair.exists() {
+                        read_keypair(keypair)?
+                    } else {
+                        let account = Keypair::new();
+                        write_keypair_file(&account, keypair)
+                            .map_err(|e| format!("writing {}: {e}", keypair.display()))?;
+                        account
+                    };
+                    let authority = authority.unwrap_or(payer.pubkey());
+                    let lamports = s
+                        .rpc
+                        .minimum_balance_for_rent_exemption(nonce::NONCE_ACCOUNT_LEN)?;
+
+                    let ixs =
+                        nonce::create(&payer.pubkey(), &account.pubkey(), &authority, lamports);
+                    let blockhash = s.rpc.latest_blockhash()?;
+                    let mut tx =
+                        offline::unsigned(&ixs, &payer.pubkey(), &Lifetime::Blockhash(blockhash));
+                    sign_with(&mut tx, &payer)?;
+                    sign_with(&mut tx, &account)?;
+                    s.send(
+                        &tx,
+                        json!({
+                            "nonce_account": account.pubkey().to_string(),
+                            "authority": authority.to_string(),
+                            "lamports": lamports,
+                        }),
+                    )
+                }
+                NonceCommand::Show { account } => Ok(nonce_json(account, &s.nonce(account)?)),
+                NonceCommand::Advance { account } => {
+                    let ix = nonce::advance(account, &s.signer()?.pubkey());
+                    s.submit(ix, json!({ "nonce_account": account.to_string() }))
+                }
+            }
+        }
+
+        Command::Submit {
+            transaction,
+            signatures,
+        } => {
+            let s = Session::open(&cli)?;
+            let mut tx = read_transaction(transaction)?;
+            for (signer, signature) in signatures {
+                offline::add_signature(&mut tx, signer, *signature)?;
+            }
+            let signer = s.signer()?;
+            if offline::missing_signers(&tx).contains(&signer.pubkey()) {
+                sign_with(&mut tx, &signer)?;
+            }
+            s.send(&tx, json!({ "transaction": transaction_json(&tx) }))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(value) => {
+            print(&value);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", json!({ "error": err.to_string() }));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! JSON rendering of program accounts and transactions.
+
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+use zephyon_client::accounts::{Receipt, ReceiptV2Ext, Treasury};
+use zephyon_client::nonce::NonceAccount;
+use zephyon_client::offline;
+use zephyon_client::reconcile::{MintReport, ReceiptIssue};
+
+pub fn print(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+    );
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn treasury_json(address: &Pubkey, treasury: &Treasury) -> Value {
+    json!({
+        "address": address.to_string(),
+        "authority": treasury.authority.to_string(),
+        "paused": treasury.paused,
+        "pay_count": treasury.pay_count,
+        "bump": treasury.bump,
+    })
+}
+
+pub fn nonce_json(address: &Pubkey, nonce: &NonceAccount) -> Value {
+    json!({
+        "address": address.to_string(),
+        "authority": nonce.authority.to_string(),
+        "nonce": nonce.nonce.to_string(),
+        "lamports_per_signature": nonce.lamports_per_signature,
+    })
+}
+
+fn direction_name(direction: u8) -> &'static str {
+    match direction {
+        Receipt::DIR_DEPOSIT => "deposit",
+        Receipt::DIR_WITHDRAW => "withdraw",
+        Receipt::DIR_PAY => "pay",
+        Receipt::DIR_DISPUTE => "dispute",
+        Receipt::DIR_MERCHANT_PAY => "merchant_pay",
+        _ => "unknown",
+    }
+}
+
+pub fn receipt_json(address: &Pubkey, receipt: &Receipt) -> Value {
+    let v2 = &receipt.v2;
+    let reference = (v2.flags & ReceiptV2Ext::FLAG_HAS_REFERENCE != 0).then(|| hex(&v2.reference));
+    let memo = (v2.flags & ReceiptV2Ext::FLAG_HAS_MEMO != 0).then(|| {
+        let len = (v2.memo_len as usize).min(v2.memo.len());
+        String::from_utf8_lossy(&v2.memo[..len]).into_owned()
+    });
+
+    json!({
+        "address": address.to_string(),
+        "version": receipt.version,
+        "user": receipt.user.to_string(),
+        "direction": receipt.direction,
+        "direction_name": direction_name(receipt.direction),
+        "asset_kind": receipt.asset_kind,
+        "mint": receipt.mint.to_string(),
+        "amount": receipt.amount,
+        "fee": receipt.fee,
+        "ts": receipt.ts,
+        "tx_count": receipt.tx_count,
+        "flags": v2.flags,
+        "risk_flags": v2.risk_flags(),
+        "reference": reference,
+        "memo": memo,
+    })
+}
+
+/// Reviewable summary of a (possibly unsigned) transaction.
+pub fn transaction_json(tx: &Transaction) -> Value {
+    let message = &tx.message;
+    let key = |index: u8| {
+        message
+            .account_keys
+            .get(usize::from(index))
+            .map_or_else(|| "<out of range>".to_string(), Pubkey::to_string)
+    };
+
+    let instructions: Vec<Value> = message
+        .instructions
+        .iter()
+        .map(|ix| {
+            json!({
+                "program_id": key(ix.program_id_index),
+                "accounts": ix.accounts.iter().map(|i| key(*i)).collect::<Vec<_>>(),
+                "data": STANDARD.encode(&ix.data),
+            })
+        })
+        .collect();
+
+    json!({
+        "fee_payer": offline::signers(tx).first().map(Pubkey::to_string),
+        "recent_blockhash": message.recent_blockhash.to_string(),
+        "signers": offline::signers(tx).iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+        "missing_signers": offline::missing_signers(tx)
+            .iter()
+            .map(Pubkey::to_string)
+            .collect::<Vec<_>>(),
+        "instructions": instructions,
+    })
+}
+
+/// Signed amounts as JSON numbers, or strings beyond the i64 range.
+fn signed(value: i128) -> Value {
+    i64::try_from(value).map_or_else(|_| json!(value.to_string()), |v| json!(v))
+}
+
+/// One mint of a reconciliation; unbalanced mints list every receipt
+/// counted into their books.
+pub fn mint_report_json(report: &MintReport) -> Value {
+    let books = &report.books;
+    let balanced = report.is_balanced();
+
+    let issues: Vec<Value> = report
+        .receipt_issues
+        .iter()
+        .map(|(receipt, issue)| match issue {
+            ReceiptIssue::Missing { event_amount } => json!({
+                "receipt": receipt.to_string(),
+                "issue": "missing",
+                "event_amount": event_amount,
+            }),
+            ReceiptIssue::Mismatch {
+                event_amount,
+                receipt_amount,
+            } => json!({
+                "receipt": receipt.to_string(),
+                "issue": "mismatch",
+                "event_amount": event_amount,
+                "receipt_amount": receipt_amount,
+            }),
+        })
+        .collect();
+
+    json!({
+        "mint": report.mint.to_string(),
+        "treasury_ata": report.treasury_ata.to_string(),
+        "balanced": balanced,
+        "ata_balance": report.ata_balance,
+        "books": {
+            "deposited": books.deposited,
+            "withdrawn": books.withdrawn,
+            "paid": books.paid,
+            "fees": books.fees,
+            "refunded": books.refunded,
+            "balance": signed(books.balance()),
+            "receipt_count": books.receipts.len(),
+            "unreceipted_flows": books.unreceipted_flows,
+        },
+        "discrepancy": signed(report.discrepancy),
+        "ledger": report.ledger.as_ref().map(|ledger| json!({
+            "expected_balance": ledger.expected,
+            "discrepancy": ledger.discrepancy.map(signed),
+        })),
+        "receipt_issues": issues,
+        "receipts": (!balanced).then(|| {
+            books.receipts.iter().map(Pubkey::to_string).collect::<Vec<_>>()
+        }),
+    })
+}
@@ -0,0 +1,270 @@
+//! Minimal Solana JSON-RPC client (the handful of methods the CLI uses).
+
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_transaction::Transaction;
+
+use crate::Result;
+
+/// How long `send_and_confirm` waits for the requested commitment.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL: Duration = Duration::from_millis(500);
+
+/// Page size of `getSignaturesForAddress` (the RPC maximum).
+const SIGNATURE_PAGE: usize = 1000;
+
+pub struct Rpc {
+    url: String,
+    commitment: String,
+}
+
+impl Rpc {
+    pub fn new(url: &str, commitment: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            commitment: commitment.to_string(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))?
+            .into_json()?;
+
+        if let Some(err) = response.get("error") {
+            return Err(format!("{method}: {err}").into());
+        }
+        Ok(response["result"].clone())
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call(
+            "getLatestBlockhash",
+            json!([{ "commitment": self.commitment }]),
+        )?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or("getLatestBlockhash: missing blockhash")?;
+        Ok(blockhash.parse()?)
+    }
+
+    /// Lamports an account of `len` data bytes needs to be rent exempt.
+    pub fn minimum_balance_for_rent_exemption(&self, len: usize) -> Result<u64> {
+        self.call(
+            "getMinimumBalanceForRentExemption",
+            json!([len, { "commitment": self.commitment }]),
+        )?
+        .as_u64()
+        .ok_or_else(|| "getMinimumBalanceForRentExemption: expected a number".into())
+    }
+
+    /// Account data, or `None` if the account does not exist.
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": self.commitment }]),
+        )?;
+        match result["value"]["data"][0].as_str() {
+            Some(data) => Ok(Some(STANDARD.decode(data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `(address, data)` of every account owned by `program` matching the
+    /// given `memcmp` filters (`(offset, bytes)`).
+    pub fn program_accounts(
+        &self,
+        program: &Pubkey,
+        memcmp: &[(usize, &[u8])],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let filters: Vec<Value> = memcmp
+            .iter()
+            .map(|(offset, bytes)| {
+                json!({ "memcmp": { "offset": offset, "bytes": STANDARD.encode(bytes), "encoding": "base64" } })
+            })
+            .collect();
+
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program.to_string(), {
+                "encoding": "base64",
+                "commitment": self.commitment,
+                "filters": filters,
+            }]),
+        )?;
+
+        let mut accounts = Vec::new();
+        for entry in result
+            .as_array()
+            .ok_or("getProgramAccounts: expected an array")?
+        {
+            let address: Pubkey = entry["pubkey"]
+                .as_str()
+                .ok_or("getProgramAccounts: missing pubkey")?
+                .parse()?;
+            let data = entry["account"]["data"][0]
+                .as_str()
+                .ok_or("getProgramAccounts: missing data")?;
+            accounts.push((address, STANDARD.decode(data)?));
+        }
+        Ok(accounts)
+    }
+
+    /// `(address, data)` of every token account `owner` holds under
+    /// `token_program`.
+    pub fn token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getTokenAccountsByOwner",
+            json!([
+                owner.to_string(),
+                { "programId": token_program.to_string() },
+                { "encoding": "base64", "commitment": self.commitment },
+            ]),
+        )?;
+
+        let mut accounts = Vec::new();
+        for entry in result["value"]
+            .as_array()
+            .ok_or("getTokenAccountsByOwner: expected an array")?
+        {
+            let address: Pubkey = entry["pubkey"]
+                .as_str()
+                .ok_or("getTokenAccountsByOwner: missing pubkey")?
+                .parse()?;
+            let data = entry["account"]["data"][0]
+                .as_str()
+                .ok_or("getTokenAccountsByOwner: missing data")?;
+            accounts.push((address, STANDARD.decode(data)?));
+        }
+        Ok(accounts)
+    }
+
+    /// Signatures of every successful transaction that touched `address`,
+    /// newest first.
+    pub fn successful_signatures(&self, address: &Pubkey) -> Result<Vec<String>> {
+        let mut signatures = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            let mut options =
+                json!({ "limit": SIGNATURE_PAGE, "commitment": self.history_commitment() });
+            if let Some(before) = &before {
+                options["before"] = json!(before);
+            }
+            let page = self.call(
+                "getSignaturesForAddress",
+                json!([address.to_string(), options]),
+            )?;
+            let page = page
+                .as_array()
+                .ok_or("getSignaturesForAddress: expected an array")?;
+
+            for entry in page {
+                let signature = entry["signature"]
+                    .as_str()
+                    .ok_or("getSignaturesForAddress: missing signature")?;
+                if entry["err"].is_null() {
+                    signatures.push(signature.to_string());
+                }
+                before = Some(signature.to_string());
+            }
+            if page.len() < SIGNATURE_PAGE {
+                return Ok(signatures);
+            }
+        }
+    }
+
+    /// Log messages of a confirmed transaction.
+    pub fn transaction_logs(&self, signature: &str) -> Result<Vec<String>> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "maxSupportedTransactionVersion": 0,
+                "commitment": self.history_commitment(),
+            }]),
+        )?;
+        let logs = result["meta"]["logMessages"]
+            .as_array()
+            .ok_or_else(|| format!("getTransaction {signature}: missing logMessages"))?;
+        Ok(logs
+            .iter()
+            .filter_map(|line| line.as_str().map(str::to_string))
+            .collect())
+    }
+
+    /// History methods reject `processed`.
+    fn history_commitment(&self) -> &str {
+        match self.commitment.as_str() {
+            "processed" => "confirmed",
+            commitment => commitment,
+        }
+    }
+
+    /// Simulate a signed transaction; returns the RPC `value` object
+    /// (`err`, `logs`, `unitsConsumed`).
+    pub fn simulate(&self, tx: &Transaction) -> Result<Value> {
+        let result = self.call(
+            "simulateTransaction",
+            json!([encode(tx)?, { "encoding": "base64", "commitment": self.commitment }]),
+        )?;
+        Ok(result["value"].clone())
+    }
+
+    /// Submit a signed transaction and wait for the configured commitment.
+    pub fn send_and_confirm(&self, tx: &Transaction) -> Result<String> {
+        let signature = self
+            .call(
+                "sendTransaction",
+                json!([encode(tx)?, { "encoding": "base64", "preflightCommitment": self.commitment }]),
+            )?
+            .as_str()
+            .ok_or("sendTransaction: missing signature")?
+            .to_string();
+
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("transaction {signature} failed: {}", status["err"]).into());
+                }
+                if reached(status["confirmationStatus"].as_str(), &self.commitment) {
+                    return Ok(signature);
+                }
+            }
+            sleep(CONFIRM_POLL);
+        }
+        Err(format!("transaction {signature} not confirmed within {CONFIRM_TIMEOUT:?}").into())
+    }
+}
+
+fn encode(tx: &Transaction) -> Result<String> {
+    Ok(STANDARD.encode(bincode::serialize(tx)?))
+}
+
+/// Whether `status` satisfies `commitment`.
+fn reached(status: Option<&str>, commitment: &str) -> bool {
+    let rank = |level: &str| match level {
+        "processed" => 0,
+        "confirmed" => 1,
+        "finalized" => 2,
+        _ => 1,
+    };
+    status.is_some_and(|s| rank(s) >= rank(commitment))
+}
@@ -0,0 +1,30 @@
+//! Account decoders.
+//!
+//! Input is the raw account data as fetched from RPC, discriminator
+//! included. Receipts and user profiles go through the program's versioned
+//! readers, so accounts written by older layouts still decode.
+
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use protocol::state::{MintLedger, Receipt, ReceiptV2Ext, Treasury, UserProfile};
+
+/// Decode the treasury PDA.
+pub fn decode_treasury(data: &[u8]) -> Result<Treasury> {
+    Treasury::try_deserialize(&mut &data[..])
+}
+
+/// Decode a receipt of any schema version (upgraded to the current layout
+/// in memory; see `Receipt::try_deserialize_versioned`).
+pub fn decode_receipt(data: &[u8]) -> Result<Receipt> {
+    Receipt::try_deserialize_versioned(data)
+}
+
+/// Decode a user profile of any layout version.
+pub fn decode_user_profile(data: &[u8]) -> Result<UserProfile> {
+    UserProfile::try_deserialize_versioned(data)
+}
+
+/// Decode any other program account type (`TreasuryConfig`, `Merchant`, ...).
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
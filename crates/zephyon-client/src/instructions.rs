@@ -0,0 +1,899 @@
+//! Instruction builders, one per `#[program]` handler.
+//!
+//! Builders take the wallets and mints a caller has to choose and derive
+//! everything else (PDAs, ATAs, program ids). Accounts that are always
+//! required so settings cannot be skipped (treasury config, limits, denylist,
+//! KYC) are always passed; optional accounts are opted into through
+//! [`FlowOptions`] or a `bool` argument, and otherwise passed as "None".
+//!
+//! Value-moving flows with many arguments take the Anchor-generated
+//! instruction data struct (`crate::args::*`) instead of loose arguments.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::{memo, token};
+use protocol::accounts as acc;
+use protocol::instruction as ix;
+
+use crate::pda;
+use crate::{RiskConfigParams, TravelRuleParams, PROGRAM_ID};
+
+/// Optional accounts a value-moving flow can carry.
+///
+/// The default passes none of them; the flow then skips the feature (no XP,
+/// no trust credit, no memo CPI, no travel-rule record).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlowOptions {
+    /// Pass the SPL Memo program (required when `memo_cpi` is set).
+    pub memo_program: bool,
+
+    /// Pass the wallet's user profile (activity + XP).
+    pub user_profile: bool,
+
+    /// Pass the wallet's trust score (credit + limit tier lift).
+    pub trust_score: bool,
+
+    /// Travel-rule record to consume (see `pda::travel_rule`).
+    pub travel_rule: Option<Pubkey>,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn optional(include: bool, address: Pubkey) -> Option<Pubkey> {
+    include.then_some(address)
+}
+
+fn treasury() -> Pubkey {
+    pda::treasury().0
+}
+
+fn compliance(include: bool, treasury: &Pubkey) -> Option<Pubkey> {
+    optional(include, pda::compliance_config(treasury).0)
+}
+
+pub fn initialize_treasury(authority: &Pubkey) -> Instruction {
+    build(
+        acc::InitializeTreasury {
+            authority: *authority,
+            treasury: treasury(),
+            system_program: system_program::ID,
+        },
+        ix::InitializeTreasury {},
+    )
+}
+
+pub fn set_treasury_paused(authority: &Pubkey, paused: bool) -> Instruction {
+    build(
+        acc::SetTreasuryPaused {
+            treasury: treasury(),
+            treasury_authority: *authority,
+        },
+        ix::SetTreasuryPaused { paused },
+    )
+}
+
+pub fn spl_deposit(user: &Pubkey, mint: &Pubkey, amount: u64, opts: FlowOptions) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SplDeposit {
+            user: *user,
+            treasury,
+            mint: *mint,
+            user_ata: get_associated_token_address(user, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            user_profile: optional(opts.user_profile, pda::user_profile(user).0),
+            treasury_config: pda::treasury_config(&treasury).0,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, user, mint).0,
+            denylist_entry: pda::denylist_entry(&treasury, user).0,
+            trust_score: optional(opts.trust_score, pda::trust_score(user).0),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        ix::SplDeposit { amount },
+    )
+}
+
+/// Deposit with a receipt at `pda::deposit_receipt(user, args.nonce)`.
+pub fn spl_deposit_with_receipt(
+    user: &Pubkey,
+    mint: &Pubkey,
+    args: ix::SplDepositWithReceipt,
+    opts: FlowOptions,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SplDepositWithReceipt {
+            user: *user,
+            treasury,
+            mint: *mint,
+            user_ata: get_associated_token_address(user, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            receipt: pda::deposit_receipt(user, args.nonce).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            memo_program: optional(opts.memo_program, memo::ID),
+            user_profile: optional(opts.user_profile, pda::user_profile(user).0),
+            treasury_config: pda::treasury_config(&treasury).0,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, user, mint).0,
+            denylist_entry: pda::denylist_entry(&treasury, user).0,
+            trust_score: optional(opts.trust_score, pda::trust_score(user).0),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        args,
+    )
+}
+
+pub fn spl_withdraw(
+    authority: &Pubkey,
+    user: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    opts: FlowOptions,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SplWithdraw {
+            treasury_authority: *authority,
+            user: *user,
+            treasury,
+            mint: *mint,
+            user_ata: get_associated_token_address(user, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            user_profile: optional(opts.user_profile, pda::user_profile(user).0),
+            treasury_config: pda::treasury_config(&treasury).0,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, user, mint).0,
+            denylist_entry: pda::denylist_entry(&treasury, user).0,
+            kyc_attestation: pda::kyc_attestation(&treasury, user).0,
+            travel_rule: opts.travel_rule,
+            trust_score: optional(opts.trust_score, pda::trust_score(user).0),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        ix::SplWithdraw { amount },
+    )
+}
+
+/// Withdrawal with a receipt at `pda::withdraw_receipt(user, profile_tx_count)`.
+///
+/// The user profile is required by this flow, so `opts.user_profile` and
+/// `opts.memo_program` are ignored.
+pub fn spl_withdraw_with_receipt(
+    user: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    profile_tx_count: u64,
+    args: ix::SplWithdrawWithReceipt,
+    opts: FlowOptions,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SplWithdrawWithReceipt {
+            user: *user,
+            treasury_authority: *authority,
+            user_profile: pda::user_profile(user).0,
+            treasury,
+            mint: *mint,
+            user_ata: get_associated_token_address(user, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            receipt: pda::withdraw_receipt(user, profile_tx_count).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            treasury_config: pda::treasury_config(&treasury).0,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, user, mint).0,
+            denylist_entry: pda::denylist_entry(&treasury, user).0,
+            kyc_attestation: pda::kyc_attestation(&treasury, user).0,
+            travel_rule: opts.travel_rule,
+            trust_score: optional(opts.trust_score, pda::trust_score(user).0),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        args,
+    )
+}
+
+/// Treasury payout with a receipt at `pda::pay_receipt(treasury, pay_count)`;
+/// `pay_count` is the treasury's current `pay_count`. `args.reference` must be
+/// `None`; use [`spl_pay_with_reference`] for referenced payouts.
+pub fn spl_pay(
+    authority: &Pubkey,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    pay_count: u64,
+    args: ix::SplPay,
+    opts: FlowOptions,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SplPay {
+            treasury_authority: *authority,
+            recipient: *recipient,
+            treasury,
+            mint: *mint,
+            recipient_ata: get_associated_token_address(recipient, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            receipt: pda::pay_receipt(&treasury, pay_count).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            memo_program: optional(opts.memo_program, memo::ID),
+            user_profile: optional(opts.user_profile, pda::user_profile(recipient).0),
+            treasury_config: pda::treasury_config(&treasury).0,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, recipient, mint).0,
+            denylist_entry: pda::denylist_entry(&treasury, recipient).0,
+            kyc_attestation: pda::kyc_attestation(&treasury, recipient).0,
+            travel_rule: opts.travel_rule,
+            trust_score: optional(opts.trust_score, pda::trust_score(recipient).0),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        args,
+    )
+}
+
+/// Like [`spl_pay`], also creating the reference index for `args.reference`.
+pub fn spl_pay_with_reference(
+    authority: &Pubkey,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    pay_count: u64,
+    args: ix::SplPayWithReference,
+    opts: FlowOptions,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SplPayWithReference {
+            treasury_authority: *authority,
+            recipient: *recipient,
+            treasury,
+            mint: *mint,
+            recipient_ata: get_associated_token_address(recipient, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            receipt: pda::pay_receipt(&treasury, pay_count).0,
+            reference_index: pda::reference_index(&treasury, &args.reference).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            memo_program: optional(opts.memo_program, memo::ID),
+            user_profile: optional(opts.user_profile, pda::user_profile(recipient).0),
+            treasury_config: pda::treasury_config(&treasury).0,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, recipient, mint).0,
+            denylist_entry: pda::denylist_entry(&treasury, recipient).0,
+            kyc_attestation: pda::kyc_attestation(&treasury, recipient).0,
+            travel_rule: opts.travel_rule,
+            trust_score: optional(opts.trust_score, pda::trust_score(recipient).0),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        args,
+    )
+}
+
+pub fn migrate_receipt(payer: &Pubkey, receipt: &Pubkey) -> Instruction {
+    build(
+        acc::MigrateReceipt {
+            payer: *payer,
+            receipt: *receipt,
+            system_program: system_program::ID,
+        },
+        ix::MigrateReceipt {},
+    )
+}
+
+pub fn set_receipt_attestor(authority: &Pubkey, attestor: Pubkey) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetReceiptAttestor {
+            treasury_authority: *authority,
+            treasury,
+            attestor_config: pda::attestor_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetReceiptAttestor { attestor },
+    )
+}
+
+/// Must directly follow the Ed25519 program instruction carrying the
+/// attestation (see `protocol::attestation`).
+pub fn verify_receipt_attestation(receipt: &Pubkey, attestor_config: bool) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::VerifyReceiptAttestation {
+            treasury,
+            receipt: *receipt,
+            attestor_config: optional(attestor_config, pda::attestor_config(&treasury).0),
+            instructions: sysvar::instructions::ID,
+        },
+        ix::VerifyReceiptAttestation {},
+    )
+}
+
+pub fn init_user_profile(user: &Pubkey) -> Instruction {
+    build(
+        acc::InitUserProfile {
+            user: *user,
+            user_profile: pda::user_profile(user).0,
+            system_program: system_program::ID,
+        },
+        ix::InitUserProfile {},
+    )
+}
+
+pub fn close_user_profile(user: &Pubkey) -> Instruction {
+    build(
+        acc::CloseUserProfile {
+            user: *user,
+            user_profile: pda::user_profile(user).0,
+        },
+        ix::CloseUserProfile {},
+    )
+}
+
+pub fn update_user_profile(user: &Pubkey) -> Instruction {
+    build(
+        acc::UpdateUserProfile {
+            user: *user,
+            user_profile: pda::user_profile(user).0,
+            system_program: system_program::ID,
+        },
+        ix::UpdateUserProfile {},
+    )
+}
+
+pub fn set_xp_config(authority: &Pubkey, args: ix::SetXpConfig) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetXpConfig {
+            treasury_authority: *authority,
+            treasury,
+            treasury_config: pda::treasury_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+pub fn set_risk_config(authority: &Pubkey, params: RiskConfigParams) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetRiskConfig {
+            treasury_authority: *authority,
+            treasury,
+            treasury_config: pda::treasury_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetRiskConfig { params },
+    )
+}
+
+pub fn set_limit_tier(authority: &Pubkey, mint: &Pubkey, args: ix::SetLimitTier) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetLimitTier {
+            treasury_authority: *authority,
+            treasury,
+            mint: *mint,
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+pub fn set_user_limit_tier(
+    authority: &Pubkey,
+    user: &Pubkey,
+    mint: &Pubkey,
+    tier: u8,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetUserLimitTier {
+            treasury_authority: *authority,
+            treasury,
+            user: *user,
+            mint: *mint,
+            user_limits: pda::user_limits(&treasury, user, mint).0,
+            system_program: system_program::ID,
+        },
+        ix::SetUserLimitTier { tier },
+    )
+}
+
+pub fn set_compliance_authority(authority: &Pubkey, compliance_authority: Pubkey) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetComplianceAuthority {
+            treasury_authority: *authority,
+            treasury,
+            compliance_config: pda::compliance_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetComplianceAuthority { compliance_authority },
+    )
+}
+
+/// `compliance_config` must be set when `authority` is the designated
+/// compliance authority rather than the treasury authority; the same holds
+/// for every compliance-gated builder.
+pub fn add_denylist_entry(
+    authority: &Pubkey,
+    wallet: &Pubkey,
+    reason_code: u16,
+    expires_at: i64,
+    compliance_config: bool,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::AddDenylistEntry {
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            wallet: *wallet,
+            denylist_entry: pda::denylist_entry(&treasury, wallet).0,
+            system_program: system_program::ID,
+        },
+        ix::AddDenylistEntry {
+            reason_code,
+            expires_at,
+        },
+    )
+}
+
+pub fn remove_denylist_entry(
+    authority: &Pubkey,
+    wallet: &Pubkey,
+    compliance_config: bool,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::RemoveDenylistEntry {
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            wallet: *wallet,
+            denylist_entry: pda::denylist_entry(&treasury, wallet).0,
+        },
+        ix::RemoveDenylistEntry {},
+    )
+}
+
+pub fn set_kyc_config(authority: &Pubkey, min_level: u8, threshold: u64) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetKycConfig {
+            treasury_authority: *authority,
+            treasury,
+            treasury_config: pda::treasury_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetKycConfig {
+            min_level,
+            threshold,
+        },
+    )
+}
+
+pub fn add_kyc_issuer(authority: &Pubkey, issuer: &Pubkey, compliance_config: bool) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::AddKycIssuer {
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            issuer: *issuer,
+            kyc_issuer: pda::kyc_issuer(&treasury, issuer).0,
+            system_program: system_program::ID,
+        },
+        ix::AddKycIssuer {},
+    )
+}
+
+pub fn remove_kyc_issuer(
+    authority: &Pubkey,
+    issuer: &Pubkey,
+    compliance_config: bool,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::RemoveKycIssuer {
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            kyc_issuer: pda::kyc_issuer(&treasury, issuer).0,
+        },
+        ix::RemoveKycIssuer {},
+    )
+}
+
+pub fn issue_kyc_attestation(
+    issuer: &Pubkey,
+    wallet: &Pubkey,
+    level: u8,
+    jurisdiction: [u8; 2],
+    expires_at: i64,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::IssueKycAttestation {
+            issuer: *issuer,
+            treasury,
+            kyc_issuer: pda::kyc_issuer(&treasury, issuer).0,
+            wallet: *wallet,
+            kyc_attestation: pda::kyc_attestation(&treasury, wallet).0,
+            system_program: system_program::ID,
+        },
+        ix::IssueKycAttestation {
+            level,
+            jurisdiction,
+            expires_at,
+        },
+    )
+}
+
+pub fn revoke_kyc_attestation(
+    authority: &Pubkey,
+    wallet: &Pubkey,
+    compliance_config: bool,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::RevokeKycAttestation {
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            kyc_attestation: pda::kyc_attestation(&treasury, wallet).0,
+        },
+        ix::RevokeKycAttestation {},
+    )
+}
+
+pub fn set_travel_rule_config(authority: &Pubkey, threshold: u64) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetTravelRuleConfig {
+            treasury_authority: *authority,
+            treasury,
+            treasury_config: pda::treasury_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetTravelRuleConfig { threshold },
+    )
+}
+
+pub fn record_travel_rule(authority: &Pubkey, params: TravelRuleParams) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::RecordTravelRule {
+            treasury_authority: *authority,
+            treasury,
+            travel_rule: pda::travel_rule(&treasury, &params.record_id).0,
+            system_program: system_program::ID,
+        },
+        ix::RecordTravelRule { params },
+    )
+}
+
+pub fn set_trust_config(
+    authority: &Pubkey,
+    tier_scores: [u16; 4],
+    min_amount: u64,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetTrustConfig {
+            treasury_authority: *authority,
+            treasury,
+            treasury_config: pda::treasury_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetTrustConfig {
+            tier_scores,
+            min_amount,
+        },
+    )
+}
+
+pub fn init_trust_score(wallet: &Pubkey) -> Instruction {
+    build(
+        acc::InitTrustScore {
+            wallet: *wallet,
+            trust_score: pda::trust_score(wallet).0,
+            system_program: system_program::ID,
+        },
+        ix::InitTrustScore {},
+    )
+}
+
+pub fn refresh_trust_score(wallet: &Pubkey) -> Instruction {
+    build(
+        acc::RefreshTrustScore {
+            trust_score: pda::trust_score(wallet).0,
+        },
+        ix::RefreshTrustScore {},
+    )
+}
+
+pub fn set_dispute_config(authority: &Pubkey, arbiter: Pubkey, window_secs: i64) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetDisputeConfig {
+            treasury_authority: *authority,
+            treasury,
+            dispute_config: pda::dispute_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetDisputeConfig {
+            arbiter,
+            window_secs,
+        },
+    )
+}
+
+/// Open a dispute on `receipt`; merchant payment receipts also take the
+/// owner of the merchant that issued them.
+pub fn open_dispute(
+    opener: &Pubkey,
+    receipt: &Pubkey,
+    merchant_owner: Option<&Pubkey>,
+    reason_code: u16,
+    evidence: [u8; 32],
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::OpenDispute {
+            opener: *opener,
+            treasury,
+            dispute_config: pda::dispute_config(&treasury).0,
+            receipt: *receipt,
+            dispute: pda::dispute(receipt).0,
+            system_program: system_program::ID,
+            merchant: merchant_owner.map(|owner| pda::merchant(&treasury, owner).0),
+        },
+        ix::OpenDispute {
+            reason_code,
+            evidence,
+        },
+    )
+}
+
+pub fn submit_dispute_evidence(
+    party: &Pubkey,
+    dispute: &Pubkey,
+    evidence: [u8; 32],
+) -> Instruction {
+    build(
+        acc::SubmitDisputeEvidence {
+            party: *party,
+            dispute: *dispute,
+        },
+        ix::SubmitDisputeEvidence { evidence },
+    )
+}
+
+/// Optional accounts of [`resolve_dispute`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DisputeOptions {
+    /// Pass the respondent's trust score (records the outcome).
+    pub respondent_trust: bool,
+
+    /// Pass the payer's profile (a refund earns XP).
+    pub payer_profile: bool,
+}
+
+/// Resolve a dispute: refund `refund_amount` (> 0) to `payer` or reject (0).
+///
+/// The refund token accounts and the merchant (owned by `respondent`) are
+/// passed only for refunds.
+pub fn resolve_dispute(
+    arbiter: &Pubkey,
+    dispute: &Pubkey,
+    payer: &Pubkey,
+    respondent: &Pubkey,
+    mint: &Pubkey,
+    refund_amount: u64,
+    opts: DisputeOptions,
+) -> Instruction {
+    let treasury = treasury();
+    let refund = refund_amount > 0;
+    build(
+        acc::ResolveDispute {
+            arbiter: *arbiter,
+            treasury,
+            dispute_config: pda::dispute_config(&treasury).0,
+            dispute: *dispute,
+            resolution_receipt: pda::dispute_receipt(dispute).0,
+            mint: *mint,
+            payer: *payer,
+            payer_ata: optional(refund, get_associated_token_address(payer, mint)),
+            treasury_ata: optional(refund, get_associated_token_address(&treasury, mint)),
+            respondent_trust: optional(opts.respondent_trust, pda::trust_score(respondent).0),
+            token_program: token::ID,
+            system_program: system_program::ID,
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+            treasury_config: pda::treasury_config(&treasury).0,
+            payer_profile: optional(opts.payer_profile, pda::user_profile(payer).0),
+            merchant: optional(refund, pda::merchant(&treasury, respondent).0),
+            payer_denylist_entry: pda::denylist_entry(&treasury, payer).0,
+        },
+        ix::ResolveDispute { refund_amount },
+    )
+}
+
+pub fn set_merchant_fee_tiers(authority: &Pubkey, fee_bps: [u16; 4]) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetMerchantFeeTiers {
+            treasury_authority: *authority,
+            treasury,
+            treasury_config: pda::treasury_config(&treasury).0,
+            system_program: system_program::ID,
+        },
+        ix::SetMerchantFeeTiers { fee_bps },
+    )
+}
+
+pub fn register_merchant(
+    owner: &Pubkey,
+    authority: &Pubkey,
+    payout_wallet: Pubkey,
+    settlement_mints: Vec<Pubkey>,
+    fee_tier: u8,
+    compliance_config: bool,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::RegisterMerchant {
+            owner: *owner,
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            merchant: pda::merchant(&treasury, owner).0,
+            system_program: system_program::ID,
+        },
+        ix::RegisterMerchant {
+            payout_wallet,
+            settlement_mints,
+            fee_tier,
+        },
+    )
+}
+
+pub fn update_merchant(
+    owner: &Pubkey,
+    payout_wallet: Pubkey,
+    settlement_mints: Vec<Pubkey>,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::UpdateMerchant {
+            owner: *owner,
+            treasury,
+            merchant: pda::merchant(&treasury, owner).0,
+        },
+        ix::UpdateMerchant {
+            payout_wallet,
+            settlement_mints,
+        },
+    )
+}
+
+pub fn set_merchant_status(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    status: u8,
+    fee_tier: u8,
+    compliance_config: bool,
+) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::SetMerchantStatus {
+            authority: *authority,
+            treasury,
+            compliance_config: compliance(compliance_config, &treasury),
+            merchant: pda::merchant(&treasury, owner).0,
+        },
+        ix::SetMerchantStatus { status, fee_tier },
+    )
+}
+
+/// Merchant payment with a receipt at
+/// `pda::merchant_receipt(merchant, merchant_pay_count)`; `payout_wallet`
+/// must be the merchant's current payout wallet. `opts.user_profile` and
+/// `opts.trust_score` pass the payer's profile and trust score;
+/// `opts.travel_rule` names the payment's travel-rule record.
+pub fn pay_merchant(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    payout_wallet: &Pubkey,
+    mint: &Pubkey,
+    merchant_pay_count: u64,
+    args: ix::PayMerchant,
+    opts: FlowOptions,
+) -> Instruction {
+    let treasury = treasury();
+    let merchant = pda::merchant(&treasury, owner).0;
+    build(
+        acc::PayMerchant {
+            payer: *payer,
+            treasury,
+            merchant,
+            payout_wallet: *payout_wallet,
+            mint: *mint,
+            payer_ata: get_associated_token_address(payer, mint),
+            payout_ata: get_associated_token_address(payout_wallet, mint),
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            receipt: pda::merchant_receipt(&merchant, merchant_pay_count).0,
+            treasury_config: pda::treasury_config(&treasury).0,
+            denylist_entry: pda::denylist_entry(&treasury, payer).0,
+            payout_denylist_entry: pda::denylist_entry(&treasury, payout_wallet).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+            user_profile: optional(opts.user_profile, pda::user_profile(payer).0),
+            mint_limits: pda::mint_limits(&treasury, mint).0,
+            user_limits: pda::user_limits(&treasury, payer, mint).0,
+            kyc_attestation: pda::kyc_attestation(&treasury, payer).0,
+            travel_rule: opts.travel_rule,
+            trust_score: optional(opts.trust_score, pda::trust_score(payer).0),
+        },
+        args,
+    )
+}
+
+/// Open the ledger of `mint` (authority only); the current treasury ATA
+/// balance becomes its opening balance.
+pub fn open_mint_ledger(authority: &Pubkey, mint: &Pubkey) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::OpenMintLedger {
+            treasury_authority: *authority,
+            treasury,
+            mint: *mint,
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::OpenMintLedger {},
+    )
+}
+
+/// Fails unless the treasury ATA of `mint` covers its ledger balance.
+pub fn assert_solvency(mint: &Pubkey) -> Instruction {
+    let treasury = treasury();
+    build(
+        acc::AssertSolvency {
+            treasury,
+            mint: *mint,
+            treasury_ata: get_associated_token_address(&treasury, mint),
+            mint_ledger: pda::mint_ledger(&treasury, mint).0,
+        },
+        ix::AssertSolvency {},
+    )
+}
@@ -0,0 +1,35 @@
+//! Rust client SDK for the Zephyon protocol program.
+//!
+//! - [`pda`]: PDA derivation for every account the program derives
+//! - [`instructions`]: one builder per `#[program]` handler
+//! - [`accounts`]: decoders for the core account types
+//! - [`events`]: decoders for the program's events
+//! - [`offline`]: unsigned transactions for offline signing
+//! - [`nonce`]: durable nonce accounts for transactions that wait on approval
+//! - [`reconcile`]: treasury books versus on-chain balances, per mint
+//!
+//! Everything is built on the `protocol` crate itself (linked with
+//! `no-entrypoint`): seeds, account layouts, instruction data and events are
+//! the program's own types, so the client cannot drift from the program.
+
+pub mod accounts;
+pub mod instructions;
+pub mod nonce;
+pub mod offline;
+pub mod pda;
+pub mod reconcile;
+
+/// Event decoding (the `zephyon-events` crate).
+pub use zephyon_events as events;
+
+/// Program id the client targets.
+pub use protocol::ID as PROGRAM_ID;
+
+/// Anchor-generated instruction data structs, one per handler (used as the
+/// argument bundle of the value-moving builders).
+pub use protocol::instruction as args;
+
+/// Program-side parameter types referenced by instruction arguments.
+pub use protocol::memo::MemoCpiMode;
+pub use protocol::risk::RiskConfigParams;
+pub use protocol::travel_rule::TravelRuleParams;
@@ -0,0 +1,146 @@
+//! Durable nonce accounts.
+//!
+//! A transaction built against a nonce account (see
+//! [`Lifetime::Nonce`](crate::offline::Lifetime)) stays valid until the
+//! nonce advances, instead of expiring with its blockhash after about 150
+//! slots. That is what privileged transactions awaiting a human approval
+//! should use:
+//!
+//! 1. [`create`] a nonce account whose authority signs the approval;
+//! 2. [`decode`] it and build with [`lifetime`];
+//! 3. before submitting, fetch it again and [`check`] the transaction still
+//!    matches the stored nonce (any other use of the nonce, or an explicit
+//!    [`advance`], invalidates every transaction signed against it).
+
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use solana_hash::Hash;
+use solana_nonce::state::State;
+use solana_nonce::versions::Versions;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction::{uses_durable_nonce, Transaction};
+
+use crate::offline::{self, Lifetime};
+
+/// Nonce account data length (system program owned).
+pub const NONCE_ACCOUNT_LEN: usize = State::size();
+
+/// Initialized nonce account state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonceAccount {
+    /// Signs `advance` and every transaction built against this nonce.
+    pub authority: Pubkey,
+
+    /// Value to use as the transaction's blockhash.
+    pub nonce: Hash,
+
+    /// Fee rate recorded when the nonce last advanced.
+    pub lamports_per_signature: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NonceError {
+    /// Account data is not a nonce account.
+    Malformed,
+
+    /// The account exists but was never initialized as a nonce.
+    Uninitialized,
+
+    /// The transaction does not start with `advance_nonce_account` on this
+    /// account.
+    NotNonceTransaction(Pubkey),
+
+    /// The nonce advanced since the transaction was built; it can no longer
+    /// land and must be rebuilt and re-signed.
+    Advanced { signed: Hash, stored: Hash },
+
+    /// The nonce authority is not a signer of the transaction.
+    AuthorityNotSigner(Pubkey),
+}
+
+impl fmt::Display for NonceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "not a nonce account"),
+            Self::Uninitialized => write!(f, "nonce account is not initialized"),
+            Self::NotNonceTransaction(account) => {
+                write!(f, "transaction does not advance nonce {account}")
+            }
+            Self::Advanced { signed, stored } => write!(
+                f,
+                "nonce advanced since signing (signed against {signed}, account holds {stored})"
+            ),
+            Self::AuthorityNotSigner(authority) => {
+                write!(f, "nonce authority {authority} is not a signer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NonceError {}
+
+/// Create and initialize `nonce` (which must sign) with `authority`.
+/// `lamports` should cover rent exemption for [`NONCE_ACCOUNT_LEN`].
+pub fn create(
+    payer: &Pubkey,
+    nonce: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    system_instruction::create_nonce_account(payer, nonce, authority, lamports)
+}
+
+/// Advance the nonce, invalidating every transaction signed against the
+/// current value.
+pub fn advance(nonce: &Pubkey, authority: &Pubkey) -> Instruction {
+    system_instruction::advance_nonce_account(nonce, authority)
+}
+
+/// Decode nonce account data as fetched from RPC.
+pub fn decode(data: &[u8]) -> Result<NonceAccount, NonceError> {
+    let versions: Versions = bincode::deserialize(data).map_err(|_| NonceError::Malformed)?;
+    match versions.state() {
+        State::Uninitialized => Err(NonceError::Uninitialized),
+        State::Initialized(data) => Ok(NonceAccount {
+            authority: data.authority,
+            nonce: data.blockhash(),
+            lamports_per_signature: data.fee_calculator.lamports_per_signature,
+        }),
+    }
+}
+
+/// Lifetime for a transaction built against `account` in its current state.
+pub fn lifetime(account: &Pubkey, state: &NonceAccount) -> Lifetime {
+    Lifetime::Nonce {
+        account: *account,
+        authority: state.authority,
+        value: state.nonce,
+    }
+}
+
+/// Nonce account a transaction is built against, if any.
+pub fn nonce_account(tx: &Transaction) -> Option<Pubkey> {
+    let ix = uses_durable_nonce(tx)?;
+    let index = *ix.accounts.first()?;
+    tx.message.account_keys.get(usize::from(index)).copied()
+}
+
+/// Check that `tx`, built against `account`, can still land given the
+/// account's current `state`.
+pub fn check(tx: &Transaction, account: &Pubkey, state: &NonceAccount) -> Result<(), NonceError> {
+    if nonce_account(tx) != Some(*account) {
+        return Err(NonceError::NotNonceTransaction(*account));
+    }
+    if tx.message.recent_blockhash != state.nonce {
+        return Err(NonceError::Advanced {
+            signed: tx.message.recent_blockhash,
+            stored: state.nonce,
+        });
+    }
+    if !offline::signers(tx).contains(&state.authority) {
+        return Err(NonceError::AuthorityNotSigner(state.authority));
+    }
+    Ok(())
+}
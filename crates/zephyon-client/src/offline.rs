@@ -0,0 +1,154 @@
+//! Unsigned transactions for offline (air-gapped) signing.
+//!
+//! 1. The online machine builds the transaction with [`unsigned`], against a
+//!    recent blockhash or a durable nonce ([`Lifetime`]), and exports it with
+//!    [`encode`].
+//! 2. The offline machine [`decode`]s it, reviews it and signs
+//!    `tx.message_data()` with the authority key.
+//! 3. The online machine [`add_signature`]s each returned signature, checks
+//!    [`missing_signers`] is empty and submits.
+//!
+//! A blockhash expires after about 150 slots, so anything that waits for a
+//! human approval should use a durable nonce (see [`crate::nonce`]).
+
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_hash::Hash;
+use solana_signature::Signature;
+use solana_system_interface::instruction::advance_nonce_account;
+use solana_transaction::Transaction;
+
+/// What keeps an exported transaction valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lifetime {
+    /// A recent blockhash (valid for about 150 slots).
+    Blockhash(Hash),
+
+    /// A durable nonce: `advance_nonce_account` is prepended and the stored
+    /// nonce value is used as the blockhash. Valid until the nonce advances.
+    Nonce {
+        account: Pubkey,
+        authority: Pubkey,
+        value: Hash,
+    },
+}
+
+impl Lifetime {
+    /// Value that goes into the message's `recent_blockhash`.
+    pub fn blockhash(&self) -> Hash {
+        match self {
+            Self::Blockhash(hash) => *hash,
+            Self::Nonce { value, .. } => *value,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OfflineError {
+    /// The transaction could not be serialized.
+    Encode(String),
+
+    /// Input is not base64 of a bincode-serialized transaction.
+    Malformed(String),
+
+    /// The pubkey is not one of the transaction's required signers.
+    NotASigner(Pubkey),
+
+    /// The signature does not verify against the message for this pubkey.
+    InvalidSignature(Pubkey),
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(reason) => write!(f, "cannot encode transaction: {reason}"),
+            Self::Malformed(reason) => write!(f, "malformed transaction: {reason}"),
+            Self::NotASigner(key) => write!(f, "{key} is not a required signer"),
+            Self::InvalidSignature(key) => write!(f, "signature for {key} does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+/// Build an unsigned transaction for `instructions`.
+pub fn unsigned(
+    instructions: &[Instruction],
+    fee_payer: &Pubkey,
+    lifetime: &Lifetime,
+) -> Transaction {
+    let mut ixs = Vec::with_capacity(instructions.len() + 1);
+    if let Lifetime::Nonce {
+        account, authority, ..
+    } = lifetime
+    {
+        // The runtime only accepts a nonce blockhash when the advance is the
+        // first instruction.
+        ixs.push(advance_nonce_account(account, authority));
+    }
+    ixs.extend_from_slice(instructions);
+
+    let mut tx = Transaction::new_with_payer(&ixs, Some(fee_payer));
+    tx.message.recent_blockhash = lifetime.blockhash();
+    tx
+}
+
+/// Base64 of the bincode wire format (signatures included, unset ones zeroed).
+pub fn encode(tx: &Transaction) -> Result<String, OfflineError> {
+    let bytes = bincode::serialize(tx).map_err(|e| OfflineError::Encode(e.to_string()))?;
+    Ok(STANDARD.encode(bytes))
+}
+
+pub fn decode(encoded: &str) -> Result<Transaction, OfflineError> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| OfflineError::Malformed(e.to_string()))?;
+    let tx: Transaction =
+        bincode::deserialize(&bytes).map_err(|e| OfflineError::Malformed(e.to_string()))?;
+
+    if tx.signatures.len() != usize::from(tx.message.header.num_required_signatures)
+        || tx.message.account_keys.len() < tx.signatures.len()
+    {
+        return Err(OfflineError::Malformed(
+            "signature count does not match the message".into(),
+        ));
+    }
+    Ok(tx)
+}
+
+/// Required signers, in signature order.
+pub fn signers(tx: &Transaction) -> &[Pubkey] {
+    &tx.message.account_keys[..tx.signatures.len()]
+}
+
+/// Required signers whose signature is still unset.
+pub fn missing_signers(tx: &Transaction) -> Vec<Pubkey> {
+    signers(tx)
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, sig)| **sig == Signature::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Place a signature produced offline, after checking it signs this message.
+pub fn add_signature(
+    tx: &mut Transaction,
+    signer: &Pubkey,
+    signature: Signature,
+) -> Result<(), OfflineError> {
+    let index = signers(tx)
+        .iter()
+        .position(|key| key == signer)
+        .ok_or(OfflineError::NotASigner(*signer))?;
+
+    if !signature.verify(signer.as_ref(), &tx.message_data()) {
+        return Err(OfflineError::InvalidSignature(*signer));
+    }
+    tx.signatures[index] = signature;
+    Ok(())
+}
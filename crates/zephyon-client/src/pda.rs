@@ -0,0 +1,131 @@
+//! PDA derivation.
+//!
+//! Each helper mirrors the `seeds = [...]` of the instructions that use the
+//! account and reads its seed constant from the program crate. All return
+//! `(address, bump)` for `crate::PROGRAM_ID`.
+
+use anchor_lang::prelude::Pubkey;
+use protocol::state::{
+    AttestorConfig, ComplianceConfig, DenylistEntry, Dispute, DisputeConfig, KycAttestation,
+    KycIssuer, Merchant, MintLedger, MintLimits, Receipt, ReferenceIndex, TravelRuleRecord, Treasury,
+    TreasuryConfig, TrustScore, UserLimits, UserProfile,
+};
+
+use crate::PROGRAM_ID;
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &PROGRAM_ID)
+}
+
+/// ["treasury"]
+pub fn treasury() -> (Pubkey, u8) {
+    find(&[Treasury::SEED])
+}
+
+/// ["treasury_config", treasury]
+pub fn treasury_config(treasury: &Pubkey) -> (Pubkey, u8) {
+    find(&[TreasuryConfig::SEED, treasury.as_ref()])
+}
+
+/// ["user_profile", user]
+pub fn user_profile(user: &Pubkey) -> (Pubkey, u8) {
+    find(&[UserProfile::SEED, user.as_ref()])
+}
+
+/// SPL pay receipt (`spl_pay`, `spl_pay_with_reference`):
+/// ["receipt", treasury, pay_count_before]
+pub fn pay_receipt(treasury: &Pubkey, pay_count: u64) -> (Pubkey, u8) {
+    find(&[Receipt::RECEIPT_SEED, treasury.as_ref(), &pay_count.to_le_bytes()])
+}
+
+/// Deposit receipt (`spl_deposit_with_receipt`): ["receipt", user, nonce]
+pub fn deposit_receipt(user: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    find(&[Receipt::RECEIPT_SEED, user.as_ref(), &nonce.to_le_bytes()])
+}
+
+/// Withdrawal receipt (`spl_withdraw_with_receipt`):
+/// ["receipt", user, user_profile.tx_count]
+pub fn withdraw_receipt(user: &Pubkey, profile_tx_count: u64) -> (Pubkey, u8) {
+    find(&[Receipt::RECEIPT_SEED, user.as_ref(), &profile_tx_count.to_le_bytes()])
+}
+
+/// Dispute resolution receipt: ["receipt", dispute]
+pub fn dispute_receipt(dispute: &Pubkey) -> (Pubkey, u8) {
+    find(&[Receipt::RECEIPT_SEED, dispute.as_ref()])
+}
+
+/// Merchant payment receipt: ["receipt", merchant, merchant.pay_count]
+pub fn merchant_receipt(merchant: &Pubkey, pay_count: u64) -> (Pubkey, u8) {
+    find(&[Receipt::RECEIPT_SEED, merchant.as_ref(), &pay_count.to_le_bytes()])
+}
+
+/// ["reference", treasury, reference]
+pub fn reference_index(treasury: &Pubkey, reference: &[u8; 32]) -> (Pubkey, u8) {
+    find(&[ReferenceIndex::SEED, treasury.as_ref(), reference])
+}
+
+/// ["attestor", treasury]
+pub fn attestor_config(treasury: &Pubkey) -> (Pubkey, u8) {
+    find(&[AttestorConfig::SEED, treasury.as_ref()])
+}
+
+/// ["compliance", treasury]
+pub fn compliance_config(treasury: &Pubkey) -> (Pubkey, u8) {
+    find(&[ComplianceConfig::SEED, treasury.as_ref()])
+}
+
+/// ["mint_limits", treasury, mint]
+pub fn mint_limits(treasury: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[MintLimits::SEED, treasury.as_ref(), mint.as_ref()])
+}
+
+/// ["mint_ledger", treasury, mint]
+pub fn mint_ledger(treasury: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[MintLedger::SEED, treasury.as_ref(), mint.as_ref()])
+}
+
+/// ["user_limits", treasury, user, mint]
+pub fn user_limits(treasury: &Pubkey, user: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[UserLimits::SEED, treasury.as_ref(), user.as_ref(), mint.as_ref()])
+}
+
+/// ["denylist", treasury, wallet]
+pub fn denylist_entry(treasury: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    find(&[DenylistEntry::SEED, treasury.as_ref(), wallet.as_ref()])
+}
+
+/// ["kyc_issuer", treasury, issuer]
+pub fn kyc_issuer(treasury: &Pubkey, issuer: &Pubkey) -> (Pubkey, u8) {
+    find(&[KycIssuer::SEED, treasury.as_ref(), issuer.as_ref()])
+}
+
+/// ["kyc", treasury, wallet]
+pub fn kyc_attestation(treasury: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    find(&[KycAttestation::SEED, treasury.as_ref(), wallet.as_ref()])
+}
+
+/// ["travel_rule", treasury, record_id]; receipt flows use the receipt
+/// address as `record_id`.
+pub fn travel_rule(treasury: &Pubkey, record_id: &[u8; 32]) -> (Pubkey, u8) {
+    find(&[TravelRuleRecord::SEED, treasury.as_ref(), record_id])
+}
+
+/// ["trust_score", wallet]
+pub fn trust_score(wallet: &Pubkey) -> (Pubkey, u8) {
+    find(&[TrustScore::SEED, wallet.as_ref()])
+}
+
+/// ["dispute_config", treasury]
+pub fn dispute_config(treasury: &Pubkey) -> (Pubkey, u8) {
+    find(&[DisputeConfig::SEED, treasury.as_ref()])
+}
+
+/// ["dispute", receipt]
+pub fn dispute(receipt: &Pubkey) -> (Pubkey, u8) {
+    find(&[Dispute::SEED, receipt.as_ref()])
+}
+
+/// ["merchant", treasury, owner]
+pub fn merchant(treasury: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    find(&[Merchant::SEED, treasury.as_ref(), owner.as_ref()])
+}
@@ -0,0 +1,261 @@
+//! Treasury reconciliation.
+//!
+//! [`Books`] rebuilds per-mint totals from the program's receipts and
+//! events, and [`reconcile`] checks them against the treasury ATA balances
+//! and, where one is open, the on-chain [`MintLedger`]:
+//!
+//! - receipted flows are counted from their receipt accounts
+//! - receipt-less deposits and withdrawals are counted from their events
+//! - the events of receipted flows only cross-check their receipt
+//!
+//! A mint is expected to hold `deposited - withdrawn - paid + fees -
+//! refunded`. The books are only complete when every receipt and every
+//! event since the treasury ATA was created has been added.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use protocol::state::{MintLedger, Receipt};
+
+use crate::events::ProtocolEvent;
+use crate::pda;
+
+/// Totals of one mint.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MintBooks {
+    pub deposited: u64,
+    pub withdrawn: u64,
+    pub paid: u64,
+    pub fees: u64,
+    pub refunded: u64,
+
+    /// Receipts counted into the totals.
+    pub receipts: Vec<Pubkey>,
+
+    /// Receipt-less flows counted from their events.
+    pub unreceipted_flows: u64,
+}
+
+impl MintBooks {
+    /// Balance the treasury ATA should hold (negative if the books are
+    /// missing inflows).
+    pub fn balance(&self) -> i128 {
+        i128::from(self.deposited) - i128::from(self.withdrawn) - i128::from(self.paid)
+            + i128::from(self.fees)
+            - i128::from(self.refunded)
+    }
+}
+
+/// Why a receipt disagrees with the events.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReceiptIssue {
+    /// An event references the receipt, but it was not added.
+    Missing { event_amount: u64 },
+
+    /// The receipt amount differs from the amount of its event.
+    Mismatch {
+        event_amount: u64,
+        receipt_amount: u64,
+    },
+}
+
+/// Receipts and events collected for reconciliation.
+#[derive(Debug, Default)]
+pub struct Books {
+    mints: BTreeMap<Pubkey, MintBooks>,
+
+    /// Added receipts: address -> (mint, amount).
+    receipts: BTreeMap<Pubkey, (Pubkey, u64)>,
+
+    /// Receipts referenced by events: address -> (mint if the event names
+    /// one, amount).
+    referenced: BTreeMap<Pubkey, (Option<Pubkey>, u64)>,
+}
+
+impl Books {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count an SPL receipt (SOL receipts are ignored). Adding the same
+    /// address twice counts it once.
+    pub fn add_receipt(&mut self, address: Pubkey, receipt: &Receipt) {
+        if receipt.asset_kind != Receipt::ASSET_SPL || self.receipts.contains_key(&address) {
+            return;
+        }
+
+        let books = self.mints.entry(receipt.mint).or_default();
+        let total = match receipt.direction {
+            Receipt::DIR_DEPOSIT => &mut books.deposited,
+            Receipt::DIR_WITHDRAW => &mut books.withdrawn,
+            Receipt::DIR_PAY => &mut books.paid,
+            Receipt::DIR_DISPUTE => &mut books.refunded,
+            Receipt::DIR_MERCHANT_PAY => &mut books.fees,
+            _ => return,
+        };
+        // Merchant payment receipts hold the gross amount; only the fee
+        // reaches the treasury.
+        let value = if receipt.direction == Receipt::DIR_MERCHANT_PAY {
+            receipt.fee
+        } else {
+            receipt.amount
+        };
+        *total = total.saturating_add(value);
+        books.receipts.push(address);
+
+        self.receipts
+            .insert(address, (receipt.mint, receipt.amount));
+    }
+
+    /// Count a receipt-less flow, or remember which receipt a receipted
+    /// flow wrote. Events of failed transactions must not be added.
+    pub fn add_event(&mut self, event: &ProtocolEvent) {
+        let none = Pubkey::default();
+        match event {
+            ProtocolEvent::Deposit(e) if e.receipt == none => {
+                let books = self.mints.entry(e.mint).or_default();
+                books.deposited = books.deposited.saturating_add(e.amount);
+                books.unreceipted_flows += 1;
+            }
+            ProtocolEvent::Withdraw(e) if e.receipt == none => {
+                let books = self.mints.entry(e.mint).or_default();
+                books.withdrawn = books.withdrawn.saturating_add(e.amount);
+                books.unreceipted_flows += 1;
+            }
+            ProtocolEvent::Deposit(e) => self.reference(e.receipt, Some(e.mint), e.amount),
+            ProtocolEvent::Withdraw(e) => self.reference(e.receipt, Some(e.mint), e.amount),
+            ProtocolEvent::SplPay(e) => self.reference(e.receipt, Some(e.mint), e.amount),
+            ProtocolEvent::MerchantPaid(e) => self.reference(e.receipt, Some(e.mint), e.amount),
+            ProtocolEvent::DisputeResolved(e) if e.refund_amount > 0 => {
+                self.reference(e.resolution_receipt, None, e.refund_amount)
+            }
+            _ => {}
+        }
+    }
+
+    fn reference(&mut self, receipt: Pubkey, mint: Option<Pubkey>, amount: u64) {
+        self.referenced.insert(receipt, (mint, amount));
+    }
+
+    /// Totals of `mint`, if any receipt or event touched it.
+    pub fn mint(&self, mint: &Pubkey) -> Option<&MintBooks> {
+        self.mints.get(mint)
+    }
+
+    /// Every mint a receipt or event touched.
+    pub fn mints(&self) -> impl Iterator<Item = &Pubkey> {
+        self.mints.keys()
+    }
+
+    /// Receipts of `mint` that disagree with their events. Missing receipts
+    /// are only attributed to a mint when their event names it (dispute
+    /// refunds do not).
+    pub fn receipt_issues(&self, mint: &Pubkey) -> Vec<(Pubkey, ReceiptIssue)> {
+        self.referenced
+            .iter()
+            .filter_map(|(address, (event_mint, event_amount))| {
+                let event_amount = *event_amount;
+                match self.receipts.get(address) {
+                    Some((receipt_mint, receipt_amount)) => (receipt_mint == mint
+                        && *receipt_amount != event_amount)
+                        .then_some(ReceiptIssue::Mismatch {
+                            event_amount,
+                            receipt_amount: *receipt_amount,
+                        }),
+                    None => (event_mint.as_ref() == Some(mint))
+                        .then_some(ReceiptIssue::Missing { event_amount }),
+                }
+                .map(|issue| (*address, issue))
+            })
+            .collect()
+    }
+}
+
+/// The on-chain ledger of a mint, checked against the ATA.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerCheck {
+    /// `MintLedger::expected_balance` (`None` if the totals overflow).
+    pub expected: Option<u64>,
+
+    /// ATA balance minus `expected`; negative means insolvent.
+    pub discrepancy: Option<i128>,
+}
+
+/// Reconciliation result of one mint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintReport {
+    pub mint: Pubkey,
+    pub treasury_ata: Pubkey,
+
+    /// On-chain ATA balance (0 if the ATA does not exist).
+    pub ata_balance: u64,
+
+    pub books: MintBooks,
+
+    /// ATA balance minus `books.balance()`.
+    pub discrepancy: i128,
+
+    /// Present when the mint has a ledger.
+    pub ledger: Option<LedgerCheck>,
+
+    /// Receipts that disagree with their events.
+    pub receipt_issues: Vec<(Pubkey, ReceiptIssue)>,
+}
+
+impl MintReport {
+    /// Whether books, ledger and chain all agree.
+    pub fn is_balanced(&self) -> bool {
+        self.discrepancy == 0
+            && self.receipt_issues.is_empty()
+            && self
+                .ledger
+                .as_ref()
+                .is_none_or(|ledger| ledger.discrepancy == Some(0))
+    }
+}
+
+/// Check the books of every mint against its treasury ATA balance
+/// (`balances`, by mint) and ledger (`ledgers`, by mint).
+///
+/// Covers every mint that appears in any of the three inputs, in mint
+/// order.
+pub fn reconcile(
+    books: &Books,
+    balances: &BTreeMap<Pubkey, u64>,
+    ledgers: &BTreeMap<Pubkey, MintLedger>,
+) -> Vec<MintReport> {
+    let treasury = pda::treasury().0;
+    let mints: BTreeSet<&Pubkey> = books
+        .mints()
+        .chain(balances.keys())
+        .chain(ledgers.keys())
+        .collect();
+
+    mints
+        .into_iter()
+        .map(|mint| {
+            let mint_books = books.mint(mint).cloned().unwrap_or_default();
+            let ata_balance = balances.get(mint).copied().unwrap_or(0);
+
+            let ledger = ledgers.get(mint).map(|ledger| {
+                let expected = ledger.expected_balance().ok();
+                LedgerCheck {
+                    expected,
+                    discrepancy: expected
+                        .map(|expected| i128::from(ata_balance) - i128::from(expected)),
+                }
+            });
+
+            MintReport {
+                mint: *mint,
+                treasury_ata: get_associated_token_address(&treasury, mint),
+                ata_balance,
+                discrepancy: i128::from(ata_balance) - mint_books.balance(),
+                books: mint_books,
+                ledger,
+                receipt_issues: books.receipt_issues(mint),
+            }
+        })
+        .collect()
+}
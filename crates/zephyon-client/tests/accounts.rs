@@ -0,0 +1,35 @@
+//! Account decoding across layout versions.
+
+use anchor_lang::Discriminator;
+use protocol::state::{Merchant, TreasuryConfig};
+use zephyon_client::accounts::decode;
+
+/// A config as version 6 wrote it: everything up to `merchant_fee_bps`,
+/// then zeroed `reserved`.
+fn v6_config(merchant_fee_bps: [u16; Merchant::MAX_FEE_TIERS]) -> Vec<u8> {
+    let fees_len = 2 * Merchant::MAX_FEE_TIERS;
+    let before_fees = TreasuryConfig::LEN - TreasuryConfig::RESERVED_LEN - 8 - fees_len;
+
+    let mut data = TreasuryConfig::DISCRIMINATOR.to_vec();
+    data.resize(8 + before_fees, 0);
+    data[8 + 32 + 1] = TreasuryConfig::VERSION_6;
+    data.extend(merchant_fee_bps.iter().flat_map(|bps| bps.to_le_bytes()));
+    data.resize(TreasuryConfig::SPACE, 0);
+    data
+}
+
+#[test]
+fn v6_treasury_configs_decode_under_the_current_layout() {
+    let mut fees = [0; Merchant::MAX_FEE_TIERS];
+    fees[0] = 150;
+    fees[Merchant::MAX_FEE_TIERS - 1] = 25;
+
+    let config: TreasuryConfig = decode(&v6_config(fees)).unwrap();
+    assert_eq!(config.version, TreasuryConfig::VERSION_6);
+    assert_eq!(config.merchant_fee_bps, fees);
+    assert_eq!(config.merchant_fee_bps_for(0), 150);
+
+    // Settings added since read as disabled.
+    assert_eq!(config.trust_min_amount, 0);
+    assert_eq!(config.reserved, [0; TreasuryConfig::RESERVED_LEN]);
+}
@@ -0,0 +1,173 @@
+//! Durable nonce accounts: decoding, lifetimes and pre-submit checks.
+
+use anchor_lang::prelude::Pubkey;
+use solana_hash::Hash;
+use solana_keypair::Keypair;
+use solana_nonce::state::{Data, DurableNonce, State};
+use solana_nonce::versions::Versions;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use zephyon_client::args;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::nonce::{self, NonceAccount, NonceError};
+use zephyon_client::offline::{self, Lifetime};
+
+/// Nonce account data as the system program stores it.
+fn account_data(state: State) -> Vec<u8> {
+    bincode::serialize(&Versions::new(state)).unwrap()
+}
+
+fn initialized(authority: &Pubkey, blockhash: &Hash) -> Vec<u8> {
+    let durable = DurableNonce::from_blockhash(blockhash);
+    account_data(State::Initialized(Data::new(*authority, durable, 5_000)))
+}
+
+/// A profile creation by `authority`, built against the nonce `account`.
+fn nonce_tx(account: &Pubkey, state: &NonceAccount) -> Transaction {
+    let init = ix::init_user_profile(&state.authority);
+    offline::unsigned(&[init], &state.authority, &nonce::lifetime(account, state))
+}
+
+#[test]
+fn decode_reads_initialized_nonce_accounts() {
+    let authority = Pubkey::new_unique();
+    let state = nonce::decode(&initialized(&authority, &Hash::new_unique())).unwrap();
+
+    assert_eq!(state.authority, authority);
+    assert_eq!(state.lamports_per_signature, 5_000);
+    assert_ne!(state.nonce, Hash::default());
+
+    assert_eq!(
+        nonce::decode(&account_data(State::Uninitialized)),
+        Err(NonceError::Uninitialized)
+    );
+    assert_eq!(nonce::decode(&[7; 3]), Err(NonceError::Malformed));
+}
+
+#[test]
+fn nonce_lifetimes_prepend_the_advance() {
+    let authority = Keypair::new();
+    let account = Pubkey::new_unique();
+    let state = nonce::decode(&initialized(&authority.pubkey(), &Hash::new_unique())).unwrap();
+
+    let lifetime = nonce::lifetime(&account, &state);
+    assert_eq!(
+        lifetime,
+        Lifetime::Nonce {
+            account,
+            authority: authority.pubkey(),
+            value: state.nonce,
+        }
+    );
+
+    let tx = nonce_tx(&account, &state);
+    assert_eq!(tx.message.recent_blockhash, state.nonce);
+    assert_eq!(tx.message.instructions.len(), 2);
+    assert_eq!(nonce::nonce_account(&tx), Some(account));
+    assert_eq!(offline::missing_signers(&tx), vec![authority.pubkey()]);
+}
+
+#[test]
+fn check_accepts_a_transaction_against_the_current_nonce() {
+    let account = Pubkey::new_unique();
+    let state = nonce::decode(&initialized(&Pubkey::new_unique(), &Hash::new_unique())).unwrap();
+    let tx = nonce_tx(&account, &state);
+
+    assert_eq!(nonce::check(&tx, &account, &state), Ok(()));
+}
+
+#[test]
+fn check_rejects_an_advanced_nonce() {
+    let account = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let signed = nonce::decode(&initialized(&authority, &Hash::new_unique())).unwrap();
+    let tx = nonce_tx(&account, &signed);
+
+    let stored = nonce::decode(&initialized(&authority, &Hash::new_unique())).unwrap();
+    assert_eq!(
+        nonce::check(&tx, &account, &stored),
+        Err(NonceError::Advanced {
+            signed: signed.nonce,
+            stored: stored.nonce,
+        })
+    );
+}
+
+#[test]
+fn check_rejects_transactions_not_built_against_the_nonce() {
+    let account = Pubkey::new_unique();
+    let state = nonce::decode(&initialized(&Pubkey::new_unique(), &Hash::new_unique())).unwrap();
+
+    // A blockhash transaction, and one advancing another nonce account.
+    let init = ix::init_user_profile(&state.authority);
+    let blockhash = offline::unsigned(&[init], &state.authority, &Lifetime::Blockhash(state.nonce));
+    let other = nonce_tx(&Pubkey::new_unique(), &state);
+
+    for tx in [blockhash, other] {
+        assert_eq!(nonce::nonce_account(&tx).filter(|a| *a == account), None);
+        assert_eq!(
+            nonce::check(&tx, &account, &state),
+            Err(NonceError::NotNonceTransaction(account))
+        );
+    }
+}
+
+#[test]
+fn check_requires_the_nonce_authority_to_sign() {
+    let account = Pubkey::new_unique();
+    let state = nonce::decode(&initialized(&Pubkey::new_unique(), &Hash::new_unique())).unwrap();
+    let tx = nonce_tx(&account, &state);
+
+    // Same nonce value, different authority on the account.
+    let rotated = NonceAccount {
+        authority: Pubkey::new_unique(),
+        ..state
+    };
+    assert_eq!(
+        nonce::check(&tx, &account, &rotated),
+        Err(NonceError::AuthorityNotSigner(rotated.authority))
+    );
+}
+
+#[test]
+fn stale_nonce_treasury_payouts_are_rejected() {
+    let authority = Keypair::new();
+    let account = Pubkey::new_unique();
+    let signed = nonce::decode(&initialized(&authority.pubkey(), &Hash::new_unique())).unwrap();
+
+    // A payout signed by the treasury authority, held for approval.
+    let payout = ix::spl_pay(
+        &authority.pubkey(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        0,
+        args::SplPay {
+            amount: 1_000,
+            reference: None,
+            memo: None,
+            memo_cpi: None,
+        },
+        FlowOptions::default(),
+    );
+    let mut tx = offline::unsigned(
+        &[payout],
+        &authority.pubkey(),
+        &nonce::lifetime(&account, &signed),
+    );
+    let signature = authority.sign_message(&tx.message_data());
+    offline::add_signature(&mut tx, &authority.pubkey(), signature).unwrap();
+    assert!(offline::missing_signers(&tx).is_empty());
+    let held = offline::encode(&tx).unwrap();
+    assert_eq!(nonce::check(&tx, &account, &signed), Ok(()));
+
+    // Another transaction advances the nonce before the payout is submitted.
+    let advanced = nonce::decode(&initialized(&authority.pubkey(), &Hash::new_unique())).unwrap();
+    let tx = offline::decode(&held).unwrap();
+    assert_eq!(
+        nonce::check(&tx, &account, &advanced),
+        Err(NonceError::Advanced {
+            signed: signed.nonce,
+            stored: advanced.nonce,
+        })
+    );
+}
@@ -0,0 +1,95 @@
+//! Offline signing: export, import and signature placement.
+
+use solana_hash::Hash;
+use solana_keypair::Keypair;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use zephyon_client::instructions as ix;
+use zephyon_client::offline::{self, Lifetime, OfflineError};
+
+/// A profile creation paid by `payer` and signed by `wallet`: two signers.
+fn two_signer_tx(payer: &Keypair, wallet: &Keypair) -> Transaction {
+    let init = ix::init_user_profile(&wallet.pubkey());
+    offline::unsigned(
+        &[init],
+        &payer.pubkey(),
+        &Lifetime::Blockhash(Hash::new_unique()),
+    )
+}
+
+#[test]
+fn unsigned_transactions_round_trip_and_collect_signatures() {
+    let (payer, wallet) = (Keypair::new(), Keypair::new());
+    let tx = two_signer_tx(&payer, &wallet);
+
+    let encoded = offline::encode(&tx).unwrap();
+    let mut imported = offline::decode(&encoded).unwrap();
+    assert_eq!(imported, tx);
+    assert_eq!(offline::signers(&imported)[0], payer.pubkey());
+    assert_eq!(
+        offline::missing_signers(&imported),
+        vec![payer.pubkey(), wallet.pubkey()]
+    );
+
+    // Signed offline against the imported message.
+    for key in [&wallet, &payer] {
+        let signature = key.sign_message(&imported.message_data());
+        offline::add_signature(&mut imported, &key.pubkey(), signature).unwrap();
+    }
+    assert!(offline::missing_signers(&imported).is_empty());
+    imported.verify().unwrap();
+
+    // Signatures survive another export.
+    let reimported = offline::decode(&offline::encode(&imported).unwrap()).unwrap();
+    assert_eq!(reimported.signatures, imported.signatures);
+}
+
+#[test]
+fn add_signature_rejects_bad_signatures_and_non_signers() {
+    let (payer, wallet) = (Keypair::new(), Keypair::new());
+    let mut tx = two_signer_tx(&payer, &wallet);
+
+    // The payer's key over the wallet's slot, and a signature over another
+    // message.
+    let wrong_key = payer.sign_message(&tx.message_data());
+    assert_eq!(
+        offline::add_signature(&mut tx, &wallet.pubkey(), wrong_key),
+        Err(OfflineError::InvalidSignature(wallet.pubkey()))
+    );
+    let wrong_message = wallet.sign_message(b"something else");
+    assert_eq!(
+        offline::add_signature(&mut tx, &wallet.pubkey(), wrong_message),
+        Err(OfflineError::InvalidSignature(wallet.pubkey()))
+    );
+
+    let outsider = Keypair::new();
+    let signature = outsider.sign_message(&tx.message_data());
+    assert_eq!(
+        offline::add_signature(&mut tx, &outsider.pubkey(), signature),
+        Err(OfflineError::NotASigner(outsider.pubkey()))
+    );
+
+    assert!(tx.signatures.iter().all(|s| *s == Signature::default()));
+    assert_eq!(offline::missing_signers(&tx).len(), 2);
+}
+
+#[test]
+fn decode_rejects_malformed_input() {
+    assert!(matches!(
+        offline::decode("not base64!"),
+        Err(OfflineError::Malformed(_))
+    ));
+    assert!(matches!(
+        offline::decode("AAAA"),
+        Err(OfflineError::Malformed(_))
+    ));
+
+    // A signature list that does not match the header.
+    let mut tx = two_signer_tx(&Keypair::new(), &Keypair::new());
+    tx.signatures.pop();
+    assert!(matches!(
+        offline::decode(&offline::encode(&tx).unwrap()),
+        Err(OfflineError::Malformed(_))
+    ));
+}
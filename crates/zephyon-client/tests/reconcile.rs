@@ -0,0 +1,237 @@
+//! Off-chain reconciliation: books from receipts and events against the
+//! treasury ATA and ledger.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::borsh::BorshDeserialize;
+use anchor_lang::prelude::Pubkey;
+use protocol::state::{MintLedger, Receipt, ReceiptV2Ext};
+use zephyon_client::events::{
+    DepositEvent, DisputeResolvedEvent, MerchantPaidEvent, ProtocolEvent, WithdrawEvent,
+};
+use zephyon_client::reconcile::{reconcile, Books, ReceiptIssue};
+
+fn receipt(direction: u8, mint: Pubkey, amount: u64, fee: u64) -> Receipt {
+    Receipt {
+        user: Pubkey::new_unique(),
+        direction,
+        asset_kind: Receipt::ASSET_SPL,
+        mint,
+        amount,
+        fee,
+        pre_balance: 0,
+        post_balance: 0,
+        ts: 1_700_000_000,
+        tx_count: 0,
+        bump: 255,
+        v2: ReceiptV2Ext::spl(mint),
+        version: Receipt::CURRENT_VERSION,
+    }
+}
+
+/// An event with every field zeroed.
+fn zeroed<T: BorshDeserialize>() -> T {
+    T::deserialize(&mut &[0u8; 1024][..]).unwrap()
+}
+
+fn deposit(mint: Pubkey, amount: u64, receipt: Pubkey) -> ProtocolEvent {
+    ProtocolEvent::Deposit(DepositEvent {
+        mint,
+        amount,
+        receipt,
+        ..zeroed()
+    })
+}
+
+fn withdraw(mint: Pubkey, amount: u64, receipt: Pubkey) -> ProtocolEvent {
+    ProtocolEvent::Withdraw(WithdrawEvent {
+        mint,
+        amount,
+        receipt,
+        ..zeroed()
+    })
+}
+
+fn ledger(mint: Pubkey, deposited: u64, withdrawn: u64, paid: u64) -> MintLedger {
+    MintLedger {
+        treasury: Pubkey::new_unique(),
+        mint,
+        bump: 255,
+        version: MintLedger::CURRENT_VERSION,
+        opening_balance: 0,
+        deposited,
+        withdrawn,
+        paid,
+        fees: 0,
+        refunded: 0,
+        opened_slot: 1,
+        last_slot: 1,
+    }
+}
+
+/// A receipted deposit of 100, a receipt-less withdrawal of 30 and a
+/// payout of 20: the treasury should hold 50.
+fn flows(mint: Pubkey) -> (Books, [Pubkey; 2]) {
+    let (deposit_receipt, pay_receipt) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut books = Books::new();
+    books.add_receipt(
+        deposit_receipt,
+        &receipt(Receipt::DIR_DEPOSIT, mint, 100, 0),
+    );
+    books.add_receipt(pay_receipt, &receipt(Receipt::DIR_PAY, mint, 20, 0));
+    books.add_event(&deposit(mint, 100, deposit_receipt));
+    books.add_event(&withdraw(mint, 30, Pubkey::default()));
+    (books, [deposit_receipt, pay_receipt])
+}
+
+#[test]
+fn balanced_books_reconcile() {
+    let mint = Pubkey::new_unique();
+    let (mut books, receipts) = flows(mint);
+
+    // Receipts and events added twice are counted once.
+    books.add_receipt(receipts[0], &receipt(Receipt::DIR_DEPOSIT, mint, 100, 0));
+    books.add_event(&deposit(mint, 100, receipts[0]));
+
+    let books_of_mint = books.mint(&mint).unwrap();
+    assert_eq!(
+        (
+            books_of_mint.deposited,
+            books_of_mint.withdrawn,
+            books_of_mint.paid
+        ),
+        (100, 30, 20)
+    );
+    assert_eq!(books_of_mint.unreceipted_flows, 1);
+    assert_eq!(books_of_mint.balance(), 50);
+
+    let reports = reconcile(
+        &books,
+        &BTreeMap::from([(mint, 50)]),
+        &BTreeMap::from([(mint, ledger(mint, 100, 30, 20))]),
+    );
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert!(report.is_balanced(), "{report:#?}");
+    assert_eq!(report.discrepancy, 0);
+    assert_eq!(report.ledger.as_ref().unwrap().expected, Some(50));
+}
+
+#[test]
+fn discrepancies_are_reported_per_mint_with_their_receipts() {
+    let (short, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut books, receipts) = flows(short);
+    let other_receipt = Pubkey::new_unique();
+    books.add_receipt(other_receipt, &receipt(Receipt::DIR_DEPOSIT, other, 7, 0));
+
+    // `short` is 10 below its books and its ledger; `other` balances.
+    let balances = BTreeMap::from([(short, 40), (other, 7)]);
+    let ledgers = BTreeMap::from([(short, ledger(short, 100, 30, 20))]);
+    let reports = reconcile(&books, &balances, &ledgers);
+
+    assert_eq!(reports.len(), 2);
+    let report = |mint| reports.iter().find(|r| r.mint == mint).unwrap();
+    let (short_report, other_report) = (report(short), report(other));
+
+    assert!(!short_report.is_balanced());
+    assert_eq!(short_report.discrepancy, -10);
+    assert_eq!(short_report.ledger.as_ref().unwrap().discrepancy, Some(-10));
+    assert_eq!(short_report.books.receipts, receipts);
+
+    assert!(other_report.is_balanced());
+    assert_eq!(other_report.books.receipts, [other_receipt]);
+    assert!(other_report.ledger.is_none());
+
+    // A mint only the chain knows about is reported too.
+    let stray = Pubkey::new_unique();
+    let reports = reconcile(
+        &Books::new(),
+        &BTreeMap::from([(stray, 3)]),
+        &BTreeMap::new(),
+    );
+    assert_eq!((reports[0].mint, reports[0].discrepancy), (stray, 3));
+}
+
+#[test]
+fn receipts_that_disagree_with_their_event_are_reported() {
+    let mint = Pubkey::new_unique();
+    let (mut books, _) = flows(mint);
+    let address = Pubkey::new_unique();
+    books.add_receipt(address, &receipt(Receipt::DIR_WITHDRAW, mint, 15, 0));
+    books.add_event(&withdraw(mint, 12, address));
+
+    assert_eq!(
+        books.receipt_issues(&mint),
+        [(
+            address,
+            ReceiptIssue::Mismatch {
+                event_amount: 12,
+                receipt_amount: 15,
+            }
+        )]
+    );
+    // The receipt is what counts.
+    assert_eq!(books.mint(&mint).unwrap().withdrawn, 45);
+
+    let report = &reconcile(&books, &BTreeMap::from([(mint, 35)]), &BTreeMap::new())[0];
+    assert_eq!(report.discrepancy, 0);
+    assert!(!report.is_balanced());
+}
+
+#[test]
+fn events_without_their_receipt_are_reported_as_missing() {
+    let mint = Pubkey::new_unique();
+    let (mut books, _) = flows(mint);
+    let missing = Pubkey::new_unique();
+    books.add_event(&deposit(mint, 60, missing));
+
+    // Dispute refunds do not name their mint, so they are not attributed.
+    books.add_event(&ProtocolEvent::DisputeResolved(DisputeResolvedEvent {
+        resolution_receipt: Pubkey::new_unique(),
+        refund_amount: 5,
+        ..zeroed()
+    }));
+
+    assert_eq!(
+        books.receipt_issues(&mint),
+        [(missing, ReceiptIssue::Missing { event_amount: 60 })]
+    );
+    // Not counted until the receipt is added.
+    assert_eq!(books.mint(&mint).unwrap().deposited, 100);
+
+    books.add_receipt(missing, &receipt(Receipt::DIR_DEPOSIT, mint, 60, 0));
+    assert!(books.receipt_issues(&mint).is_empty());
+    assert_eq!(books.mint(&mint).unwrap().deposited, 160);
+}
+
+#[test]
+fn merchant_fees_and_refunds_enter_the_balance() {
+    let mint = Pubkey::new_unique();
+    let (mut books, _) = flows(mint);
+
+    // The payment settles 975 to the merchant; only the fee reaches the
+    // treasury, which then fronts a refund of 10.
+    let payment = Pubkey::new_unique();
+    books.add_receipt(
+        payment,
+        &receipt(Receipt::DIR_MERCHANT_PAY, mint, 1_000, 25),
+    );
+    books.add_event(&ProtocolEvent::MerchantPaid(MerchantPaidEvent {
+        receipt: payment,
+        mint,
+        amount: 1_000,
+        fee: 25,
+        ..zeroed()
+    }));
+    let refund = Pubkey::new_unique();
+    books.add_receipt(refund, &receipt(Receipt::DIR_DISPUTE, mint, 10, 0));
+
+    let totals = books.mint(&mint).unwrap();
+    assert_eq!((totals.fees, totals.refunded), (25, 10));
+    // deposited - withdrawn - paid + fees - refunded
+    assert_eq!(totals.balance(), 100 - 30 - 20 + 25 - 10);
+    assert!(books.receipt_issues(&mint).is_empty());
+
+    let report = &reconcile(&books, &BTreeMap::from([(mint, 65)]), &BTreeMap::new())[0];
+    assert!(report.is_balanced(), "{report:#?}");
+}
@@ -0,0 +1,208 @@
+//! Typed protocol event enum.
+
+use anchor_lang::prelude::borsh::BorshDeserialize;
+use anchor_lang::Discriminator;
+use protocol::events::*;
+use protocol::state::ReceiptCreated;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{Map, Value};
+
+use crate::json::ToJson;
+use crate::DecodeError;
+
+macro_rules! protocol_events {
+    ($($variant:ident($event:ident) { $($field:ident),* $(,)? },)*) => {
+        /// Any event emitted by the protocol program.
+        pub enum ProtocolEvent {
+            $($variant($event),)*
+        }
+
+        /// Names of every known event, as reported by `ProtocolEvent::name`.
+        pub const EVENT_NAMES: &[&str] = &[$(stringify!($variant),)*];
+
+        impl ProtocolEvent {
+            pub(crate) fn decode(disc: &[u8; 8], mut body: &[u8]) -> Result<Self, DecodeError> {
+                $(
+                    if disc == $event::DISCRIMINATOR {
+                        return $event::deserialize(&mut body)
+                            .map(Self::$variant)
+                            .map_err(|_| DecodeError::Malformed(stringify!($variant)));
+                    }
+                )*
+                Err(DecodeError::UnknownDiscriminator(*disc))
+            }
+
+            /// Event name without the `Event` suffix (e.g. `SplPay`).
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($variant),)*
+                }
+            }
+
+            pub fn discriminator(&self) -> &'static [u8] {
+                match self {
+                    $(Self::$variant(_) => $event::DISCRIMINATOR,)*
+                }
+            }
+
+            /// The event's fields as a JSON object.
+            pub fn fields_json(&self) -> Value {
+                let mut map = Map::new();
+                match self {
+                    $(
+                        // Exhaustive pattern: a field added to the event
+                        // fails to compile until it is listed below.
+                        Self::$variant($event { $($field),* }) => {
+                            $(map.insert(stringify!($field).to_string(), $field.to_json());)*
+                        }
+                    )*
+                }
+                Value::Object(map)
+            }
+        }
+    };
+}
+
+// One row per `#[event]` in the program, with every field.
+protocol_events! {
+    Deposit(DepositEvent) {
+        user, mint, amount, treasury, direction, asset_kind, receipt, nonce_or_tx, xp_delta,
+        risk_flags, slot, has_reference, reference, has_memo, memo_len
+    },
+    Withdraw(WithdrawEvent) {
+        authority, user, mint, amount, treasury, direction, asset_kind, receipt,
+        nonce_or_tx, xp_delta, risk_flags, slot, has_reference, reference, has_memo,
+        memo_len
+    },
+    TreasuryInitialized(TreasuryInitializedEvent) {
+        treasury, authority, paused, bump, pay_count, slot, unix_timestamp
+    },
+    TreasuryPausedSet(TreasuryPausedSetEvent) {
+        treasury, authority, paused, slot, unix_timestamp
+    },
+    SplPay(SplPayEvent) {
+        pay_count, treasury, treasury_authority, recipient, receipt, direction, asset_kind,
+        mint, amount, has_reference, reference, has_memo, memo_len, slot, unix_timestamp,
+        xp_delta, risk_flags
+    },
+    ReceiptMigrated(ReceiptMigratedEvent) {
+        receipt, from_version, to_version, payer, slot
+    },
+    ReceiptAttestorSet(ReceiptAttestorSetEvent) {
+        treasury, attestor, authority, slot
+    },
+    ReceiptAttestationVerified(ReceiptAttestationVerifiedEvent) {
+        receipt, attestor, attested_slot, slot
+    },
+    UserProfileInitialized(UserProfileInitializedEvent) {
+        user, profile, version, slot
+    },
+    UserProfileUpdated(UserProfileUpdatedEvent) {
+        user, profile, from_version, to_version, slot
+    },
+    UserProfileClosed(UserProfileClosedEvent) {
+        user, profile, slot
+    },
+    XpConfigSet(XpConfigSetEvent) {
+        treasury, authority, xp_per_deposit, xp_per_withdraw, xp_per_pay, xp_min_amount,
+        xp_epoch_cap, slot
+    },
+    RiskConfigSet(RiskConfigSetEvent) {
+        treasury, authority, rules_mask, blocking_mask, default_threshold,
+        mint_threshold_count, new_profile_secs, velocity_window_slots, velocity_max_count,
+        round_whole_units, slot
+    },
+    LimitTierSet(LimitTierSetEvent) {
+        treasury, mint, authority, tier, period_secs, max_count, max_amount, slot
+    },
+    UserLimitTierSet(UserLimitTierSetEvent) {
+        treasury, user, mint, authority, tier, slot
+    },
+    ComplianceAuthoritySet(ComplianceAuthoritySetEvent) {
+        treasury, compliance_authority, authority, slot
+    },
+    DenylistEntryAdded(DenylistEntryAddedEvent) {
+        treasury, wallet, authority, reason_code, expires_at, slot
+    },
+    DenylistEntryRemoved(DenylistEntryRemovedEvent) {
+        treasury, wallet, authority, reason_code, slot
+    },
+    KycConfigSet(KycConfigSetEvent) {
+        treasury, authority, min_level, threshold, slot
+    },
+    KycIssuerAdded(KycIssuerAddedEvent) {
+        treasury, issuer, authority, slot
+    },
+    KycIssuerRemoved(KycIssuerRemovedEvent) {
+        treasury, issuer, authority, slot
+    },
+    KycAttestationIssued(KycAttestationIssuedEvent) {
+        treasury, wallet, issuer, level, jurisdiction, expires_at, slot
+    },
+    KycAttestationRevoked(KycAttestationRevokedEvent) {
+        treasury, wallet, issuer, authority, slot
+    },
+    TravelRuleConfigSet(TravelRuleConfigSetEvent) {
+        treasury, authority, threshold, slot
+    },
+    TravelRuleRecorded(TravelRuleRecordedEvent) {
+        treasury, record, authority, record_id, beneficiary, mint, amount, slot
+    },
+    TrustConfigSet(TrustConfigSetEvent) {
+        treasury, authority, tier_scores, min_amount, slot
+    },
+    TrustScoreInitialized(TrustScoreInitializedEvent) {
+        wallet, trust_score, slot
+    },
+    TrustScoreUpdated(TrustScoreUpdatedEvent) {
+        wallet, trust_score, score, successful_flows, disputes_lost, slot
+    },
+    DisputeConfigSet(DisputeConfigSetEvent) {
+        treasury, authority, arbiter, window_secs, slot
+    },
+    DisputeOpened(DisputeOpenedEvent) {
+        treasury, dispute, receipt, payer, respondent, mint, amount, reason_code, slot
+    },
+    DisputeEvidenceSubmitted(DisputeEvidenceSubmittedEvent) {
+        dispute, party, evidence, slot
+    },
+    DisputeResolved(DisputeResolvedEvent) {
+        treasury, dispute, receipt, resolution_receipt, arbiter, status, refund_amount, xp_delta,
+        slot
+    },
+    MerchantFeeTiersSet(MerchantFeeTiersSetEvent) {
+        treasury, authority, fee_bps, slot
+    },
+    MerchantRegistered(MerchantRegisteredEvent) {
+        treasury, merchant, owner, payout_wallet, authority, fee_tier, slot
+    },
+    MerchantUpdated(MerchantUpdatedEvent) {
+        merchant, owner, payout_wallet, settlement_mints, slot
+    },
+    MerchantStatusSet(MerchantStatusSetEvent) {
+        treasury, merchant, authority, status, fee_tier, slot
+    },
+    MerchantPaid(MerchantPaidEvent) {
+        treasury, merchant, payer, payout_wallet, receipt, mint, amount, fee, pay_index,
+        has_reference, reference, xp_delta, risk_flags, slot
+    },
+    MintLedgerOpened(MintLedgerOpenedEvent) {
+        treasury, mint, authority, opening_balance, slot
+    },
+    SolvencyChecked(SolvencyCheckedEvent) {
+        treasury, mint, ledger_balance, ata_balance, slot
+    },
+    ReceiptCreated(ReceiptCreated) {
+        user, direction, asset_kind, mint, amount, fee, pre_balance, post_balance, ts,
+        tx_count
+    },
+}
+
+impl Serialize for ProtocolEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("event", self.name())?;
+        map.serialize_entry("data", &self.fields_json())?;
+        map.end()
+    }
+}
@@ -0,0 +1,74 @@
+//! JSON rendering of event field types.
+
+use anchor_lang::prelude::Pubkey;
+use protocol::events::{AssetKind, PayDirection};
+use serde_json::{json, Value};
+
+/// JSON form of an event field.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+macro_rules! to_json_number {
+    ($($ty:ty),*) => {
+        $(impl ToJson for $ty {
+            fn to_json(&self) -> Value {
+                json!(self)
+            }
+        })*
+    };
+}
+
+to_json_number!(bool, u8, u16, u32, u64, i64);
+
+impl ToJson for Pubkey {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+/// References, hashes and other opaque byte strings: hex.
+impl ToJson for [u8; 32] {
+    fn to_json(&self) -> Value {
+        Value::String(self.iter().map(|b| format!("{b:02x}")).collect())
+    }
+}
+
+/// Two-letter codes (KYC jurisdiction): ASCII.
+impl ToJson for [u8; 2] {
+    fn to_json(&self) -> Value {
+        Value::String(String::from_utf8_lossy(self).into_owned())
+    }
+}
+
+impl ToJson for [u16; 4] {
+    fn to_json(&self) -> Value {
+        json!(self)
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl ToJson for PayDirection {
+    fn to_json(&self) -> Value {
+        let name = match self {
+            PayDirection::TreasuryToRecipient => "treasury_to_recipient",
+            PayDirection::RecipientToTreasury => "recipient_to_treasury",
+            PayDirection::UserToTreasury => "user_to_treasury",
+            PayDirection::TreasuryToUser => "treasury_to_user",
+        };
+        Value::String(name.to_string())
+    }
+}
+
+impl ToJson for AssetKind {
+    fn to_json(&self) -> Value {
+        match self {
+            AssetKind::SPL => Value::String("spl".to_string()),
+        }
+    }
+}
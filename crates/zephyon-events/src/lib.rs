@@ -0,0 +1,91 @@
+//! Decoder for the protocol program's events.
+//!
+//! Anchor writes events two ways:
+//! - `emit!`: a `Program data: <base64>` log line whose payload is the
+//!   event's 8-byte discriminator followed by its Borsh encoding;
+//! - `emit_cpi!`: a self-CPI whose instruction data is
+//!   [`EVENT_IX_TAG`] followed by the same payload.
+//!
+//! [`decode_event`] turns a payload into a typed [`ProtocolEvent`]; [`logs`]
+//! walks a transaction's log messages (attributing each data line to the
+//! program that wrote it) and inner instructions. Payloads that carry an
+//! unknown discriminator are rejected rather than skipped, so an indexer
+//! built on this crate notices when the program gains events it does not
+//! know yet.
+//!
+//! [`ProtocolEvent`] serializes to JSON as
+//! `{"event": "<name>", "data": {<fields>}}`; pubkeys are base58, byte
+//! arrays hex.
+
+mod event;
+mod json;
+pub mod logs;
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+pub use event::{ProtocolEvent, EVENT_NAMES};
+pub use json::ToJson;
+pub use protocol::events::*;
+pub use protocol::state::ReceiptCreated;
+
+/// Instruction data prefix of `emit_cpi!` event instructions.
+pub const EVENT_IX_TAG: &[u8] = anchor_lang::event::EVENT_IX_TAG_LE;
+
+/// Log prefix Anchor's `emit!` writes event payloads under.
+pub const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Payload shorter than a discriminator.
+    Truncated,
+
+    /// Data line payload is not valid base64.
+    NotBase64,
+
+    /// Discriminator of no known protocol event.
+    UnknownDiscriminator([u8; 8]),
+
+    /// Discriminator matches the named event but the body does not decode.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "event payload shorter than a discriminator"),
+            Self::NotBase64 => write!(f, "event payload is not base64"),
+            Self::UnknownDiscriminator(disc) => {
+                write!(f, "unknown event discriminator ")?;
+                disc.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            Self::Malformed(name) => write!(f, "malformed {name} event"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decode one event payload (discriminator + Borsh body).
+pub fn decode_event(data: &[u8]) -> Result<ProtocolEvent, DecodeError> {
+    let (disc, body) = data.split_at_checked(8).ok_or(DecodeError::Truncated)?;
+    ProtocolEvent::decode(disc.try_into().expect("8 bytes"), body)
+}
+
+/// Decode the payload of a `Program data:` log line (prefix stripped).
+pub fn decode_base64_event(payload: &str) -> Result<ProtocolEvent, DecodeError> {
+    let data = STANDARD
+        .decode(payload.trim())
+        .map_err(|_| DecodeError::NotBase64)?;
+    decode_event(&data)
+}
+
+/// Decode the data of an `emit_cpi!` event instruction.
+///
+/// Returns `None` when `ix_data` does not start with [`EVENT_IX_TAG`] (an
+/// ordinary instruction, not an event).
+pub fn decode_cpi_event(ix_data: &[u8]) -> Option<Result<ProtocolEvent, DecodeError>> {
+    ix_data.strip_prefix(EVENT_IX_TAG).map(decode_event)
+}
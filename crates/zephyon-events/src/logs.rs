@@ -0,0 +1,147 @@
+//! Transaction-level event extraction.
+//!
+//! Log messages are walked with the invoke stack in mind, so only data lines
+//! written by the protocol program itself are decoded (another program CPI'd
+//! into, or invoking the protocol, may log its own `Program data:`).
+//!
+//! Failed transactions still carry the log lines of events that were rolled
+//! back; callers should only pass logs of successful transactions.
+
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use serde::Serialize;
+
+use crate::{
+    decode_base64_event, decode_cpi_event, DecodeError, ProtocolEvent, PROGRAM_DATA_PREFIX,
+};
+
+/// Where an event was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    /// `Program data:` log line (`emit!`).
+    Log,
+    /// Self-CPI event instruction (`emit_cpi!`).
+    Cpi,
+}
+
+#[derive(Serialize)]
+pub struct ParsedEvent {
+    pub source: EventSource,
+
+    /// Log line index, or inner instruction index, the event was read from.
+    pub index: usize,
+
+    #[serde(flatten)]
+    pub event: ProtocolEvent,
+}
+
+/// A protocol payload that failed to decode, and where it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub source: EventSource,
+    pub index: usize,
+    pub error: DecodeError,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.source {
+            EventSource::Log => "log line",
+            EventSource::Cpi => "inner instruction",
+        };
+        write!(f, "{what} {}: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Decode every event the protocol program (`program_id`) logged, in order.
+pub fn parse_logs<S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+) -> Result<Vec<ParsedEvent>, ParseError> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for (index, line) in logs.iter().enumerate() {
+        let line = line.as_ref();
+
+        if let Some(payload) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if stack.last() == Some(&program_id.as_str()) {
+                let event = decode_base64_event(payload).map_err(|error| ParseError {
+                    source: EventSource::Log,
+                    index,
+                    error,
+                })?;
+                events.push(ParsedEvent {
+                    source: EventSource::Log,
+                    index,
+                    event,
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => stack.push(program),
+                (Some(_), Some("success" | "failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        } else if line == "Log truncated" {
+            break;
+        }
+    }
+
+    Ok(events)
+}
+
+/// Decode every `emit_cpi!` event among a transaction's inner instructions,
+/// given as `(program id, instruction data)` in order.
+pub fn parse_inner_instructions<'a, I>(
+    program_id: &Pubkey,
+    instructions: I,
+) -> Result<Vec<ParsedEvent>, ParseError>
+where
+    I: IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+{
+    let mut events = Vec::new();
+
+    for (index, (program, data)) in instructions.into_iter().enumerate() {
+        if program != program_id {
+            continue;
+        }
+        if let Some(decoded) = decode_cpi_event(data) {
+            let event = decoded.map_err(|error| ParseError {
+                source: EventSource::Cpi,
+                index,
+                error,
+            })?;
+            events.push(ParsedEvent {
+                source: EventSource::Cpi,
+                index,
+                event,
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+/// Events of one transaction: logged events first, then CPI events.
+pub fn parse_transaction<'a, S, I>(
+    program_id: &Pubkey,
+    logs: &[S],
+    inner_instructions: I,
+) -> Result<Vec<ParsedEvent>, ParseError>
+where
+    S: AsRef<str>,
+    I: IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+{
+    let mut events = parse_logs(program_id, logs)?;
+    events.extend(parse_inner_instructions(program_id, inner_instructions)?);
+    Ok(events)
+}
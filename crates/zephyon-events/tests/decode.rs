@@ -0,0 +1,147 @@
+//! Event payload decoding: every event round-trips, unknown ones are rejected.
+
+use anchor_lang::prelude::borsh::{self, BorshDeserialize};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{Discriminator, Event};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use zephyon_events::*;
+
+/// Encode the all-zero instance of each event (valid for every field type),
+/// decode it back and check it lands in its own variant with the same body.
+macro_rules! round_trip {
+    ($($variant:ident($event:ident),)*) => {{
+        let mut names = Vec::new();
+        $(
+            let original = $event::deserialize(&mut &[0u8; 1024][..]).unwrap();
+            let data = original.data();
+            assert_eq!(&data[..8], $event::DISCRIMINATOR);
+
+            let ProtocolEvent::$variant(decoded) = decode_event(&data).unwrap() else {
+                panic!("{} decoded as another event", stringify!($variant));
+            };
+            assert_eq!(borsh::to_vec(&decoded).unwrap(), data[8..]);
+            names.push(stringify!($variant));
+        )*
+        names
+    }};
+}
+
+#[test]
+fn every_event_round_trips() {
+    let names = round_trip! {
+        Deposit(DepositEvent),
+        Withdraw(WithdrawEvent),
+        TreasuryInitialized(TreasuryInitializedEvent),
+        TreasuryPausedSet(TreasuryPausedSetEvent),
+        SplPay(SplPayEvent),
+        ReceiptMigrated(ReceiptMigratedEvent),
+        ReceiptAttestorSet(ReceiptAttestorSetEvent),
+        ReceiptAttestationVerified(ReceiptAttestationVerifiedEvent),
+        UserProfileInitialized(UserProfileInitializedEvent),
+        UserProfileUpdated(UserProfileUpdatedEvent),
+        UserProfileClosed(UserProfileClosedEvent),
+        XpConfigSet(XpConfigSetEvent),
+        RiskConfigSet(RiskConfigSetEvent),
+        LimitTierSet(LimitTierSetEvent),
+        UserLimitTierSet(UserLimitTierSetEvent),
+        ComplianceAuthoritySet(ComplianceAuthoritySetEvent),
+        DenylistEntryAdded(DenylistEntryAddedEvent),
+        DenylistEntryRemoved(DenylistEntryRemovedEvent),
+        KycConfigSet(KycConfigSetEvent),
+        KycIssuerAdded(KycIssuerAddedEvent),
+        KycIssuerRemoved(KycIssuerRemovedEvent),
+        KycAttestationIssued(KycAttestationIssuedEvent),
+        KycAttestationRevoked(KycAttestationRevokedEvent),
+        TravelRuleConfigSet(TravelRuleConfigSetEvent),
+        TravelRuleRecorded(TravelRuleRecordedEvent),
+        TrustConfigSet(TrustConfigSetEvent),
+        TrustScoreInitialized(TrustScoreInitializedEvent),
+        TrustScoreUpdated(TrustScoreUpdatedEvent),
+        DisputeConfigSet(DisputeConfigSetEvent),
+        DisputeOpened(DisputeOpenedEvent),
+        DisputeEvidenceSubmitted(DisputeEvidenceSubmittedEvent),
+        DisputeResolved(DisputeResolvedEvent),
+        MerchantFeeTiersSet(MerchantFeeTiersSetEvent),
+        MerchantRegistered(MerchantRegisteredEvent),
+        MerchantUpdated(MerchantUpdatedEvent),
+        MerchantStatusSet(MerchantStatusSetEvent),
+        MerchantPaid(MerchantPaidEvent),
+        MintLedgerOpened(MintLedgerOpenedEvent),
+        SolvencyChecked(SolvencyCheckedEvent),
+        ReceiptCreated(ReceiptCreated),
+    };
+
+    // A new event must be added above too.
+    assert_eq!(names, EVENT_NAMES);
+}
+
+fn spl_pay(amount: u64) -> SplPayEvent {
+    SplPayEvent {
+        pay_count: 3,
+        treasury: Pubkey::new_unique(),
+        treasury_authority: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        receipt: Pubkey::new_unique(),
+        direction: PayDirection::TreasuryToRecipient,
+        asset_kind: AssetKind::SPL,
+        mint: Pubkey::new_unique(),
+        amount,
+        has_reference: true,
+        reference: [0xab; 32],
+        has_memo: false,
+        memo_len: 0,
+        slot: 42,
+        unix_timestamp: 1_700_000_000,
+        xp_delta: 5,
+        risk_flags: 1,
+    }
+}
+
+#[test]
+fn decoded_events_serialize_their_fields() {
+    let event = spl_pay(250);
+    let encoded = STANDARD.encode(event.data());
+    let decoded = decode_base64_event(&encoded).unwrap();
+
+    assert_eq!(decoded.name(), "SplPay");
+    assert_eq!(decoded.discriminator(), SplPayEvent::DISCRIMINATOR);
+
+    let json = serde_json::to_value(&decoded).unwrap();
+    assert_eq!(json["event"], "SplPay");
+    assert_eq!(json["data"]["amount"], 250);
+    assert_eq!(json["data"]["recipient"], event.recipient.to_string());
+    assert_eq!(json["data"]["reference"], "ab".repeat(32));
+    assert_eq!(json["data"]["direction"], "treasury_to_recipient");
+}
+
+#[test]
+fn unknown_and_malformed_payloads_are_rejected() {
+    let mut unknown = spl_pay(1).data();
+    unknown[..8].copy_from_slice(&[0xff; 8]);
+    assert_eq!(
+        decode_event(&unknown).err(),
+        Some(DecodeError::UnknownDiscriminator([0xff; 8]))
+    );
+
+    let truncated = &spl_pay(1).data()[..20];
+    assert_eq!(
+        decode_event(truncated).err(),
+        Some(DecodeError::Malformed("SplPay"))
+    );
+    assert_eq!(decode_event(&[1, 2, 3]).err(), Some(DecodeError::Truncated));
+    assert_eq!(
+        decode_base64_event("%%%").err(),
+        Some(DecodeError::NotBase64)
+    );
+}
+
+#[test]
+fn cpi_event_data_needs_the_event_tag() {
+    let event = spl_pay(7).data();
+    assert!(decode_cpi_event(&event).is_none());
+
+    let ix_data = [EVENT_IX_TAG, &event].concat();
+    let decoded = decode_cpi_event(&ix_data).unwrap().unwrap();
+    assert_eq!(decoded.name(), "SplPay");
+}
@@ -0,0 +1,134 @@
+//! Log walking: data lines are attributed to the program that wrote them.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use zephyon_events::logs::{parse_logs, parse_transaction, EventSource, ParseError};
+use zephyon_events::*;
+
+fn paused(paused: bool) -> String {
+    let event = TreasuryPausedSetEvent {
+        treasury: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        paused,
+        slot: 9,
+        unix_timestamp: 1_700_000_000,
+    };
+    format!("{PROGRAM_DATA_PREFIX}{}", STANDARD.encode(event.data()))
+}
+
+fn invoke(program: &Pubkey, depth: u8) -> String {
+    format!("Program {program} invoke [{depth}]")
+}
+
+fn success(program: &Pubkey) -> String {
+    format!("Program {program} success")
+}
+
+fn names(events: &[logs::ParsedEvent]) -> Vec<&'static str> {
+    events.iter().map(|e| e.event.name()).collect()
+}
+
+#[test]
+fn parse_logs_reads_the_programs_own_data_lines() {
+    let program = protocol::ID;
+    let logs = [
+        invoke(&program, 1),
+        "Program log: Instruction: SetTreasuryPaused".into(),
+        paused(true),
+        format!("Program {program} consumed 5000 of 200000 compute units"),
+        success(&program),
+    ];
+
+    let events = parse_logs(&program, &logs).unwrap();
+    assert_eq!(names(&events), ["TreasuryPausedSet"]);
+    assert_eq!(events[0].source, EventSource::Log);
+    assert_eq!(events[0].index, 2);
+}
+
+#[test]
+fn parse_logs_skips_data_lines_of_other_programs() {
+    let program = protocol::ID;
+    let other = Pubkey::new_unique();
+    let logs = [
+        // A program that invokes the protocol.
+        invoke(&other, 1),
+        "Program data: AAAA".into(),
+        invoke(&program, 2),
+        paused(true),
+        // A program the protocol CPIs into, logging an unrelated payload.
+        invoke(&other, 3),
+        "Program data: //////////8=".into(),
+        success(&other),
+        paused(false),
+        success(&program),
+        "Program data: AAAA".into(),
+        success(&other),
+    ];
+
+    let events = parse_logs(&program, &logs).unwrap();
+    assert_eq!(names(&events), ["TreasuryPausedSet", "TreasuryPausedSet"]);
+    assert_eq!(events.iter().map(|e| e.index).collect::<Vec<_>>(), [3, 7]);
+}
+
+#[test]
+fn parse_logs_stops_at_log_truncation() {
+    let program = protocol::ID;
+    let logs = [
+        invoke(&program, 1),
+        paused(true),
+        "Log truncated".into(),
+        paused(false),
+    ];
+
+    // Only what was logged before the cut is returned.
+    let events = parse_logs(&program, &logs).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].index, 1);
+}
+
+#[test]
+fn parse_logs_reports_where_a_payload_failed() {
+    let program = protocol::ID;
+    let logs = [
+        invoke(&program, 1),
+        paused(true),
+        "Program data: //////////8=".into(),
+    ];
+
+    let error = parse_logs(&program, &logs).err().unwrap();
+    assert_eq!(
+        error,
+        ParseError {
+            source: EventSource::Log,
+            index: 2,
+            error: DecodeError::UnknownDiscriminator([0xff; 8]),
+        }
+    );
+}
+
+#[test]
+fn parse_transaction_appends_cpi_events() {
+    let program = protocol::ID;
+    let other = Pubkey::new_unique();
+    let logs = [invoke(&program, 1), paused(true), success(&program)];
+
+    let event = TreasuryPausedSetEvent {
+        treasury: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        paused: false,
+        slot: 10,
+        unix_timestamp: 1_700_000_001,
+    };
+    let cpi = [EVENT_IX_TAG, &event.data()].concat();
+    let inner = [
+        (&other, &cpi[..]),
+        (&program, &[1u8, 2, 3][..]),
+        (&program, &cpi[..]),
+    ];
+
+    let events = parse_transaction(&program, &logs, inner).unwrap();
+    let sources: Vec<_> = events.iter().map(|e| (e.source, e.index)).collect();
+    assert_eq!(sources, [(EventSource::Log, 1), (EventSource::Cpi, 2)]);
+}
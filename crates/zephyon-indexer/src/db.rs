@@ -0,0 +1,506 @@
+//! SQLite store.
+//!
+//! Each transaction is indexed inside one SQLite transaction together with
+//! the checkpoint, so an interrupted run resumes exactly where it stopped.
+//! Re-indexing a transaction that is already stored is a no-op.
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde_json::Value;
+use zephyon_client::accounts::{decode_receipt, ReceiptV2Ext};
+use zephyon_events::logs::{parse_transaction, EventSource, ParsedEvent};
+
+use crate::source::TxRecord;
+use crate::Result;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id        INTEGER PRIMARY KEY CHECK (id = 1),
+    slot      INTEGER NOT NULL,
+    signature TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    signature  TEXT PRIMARY KEY,
+    slot       INTEGER NOT NULL,
+    block_time INTEGER,
+    failed     INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    idx       INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    source    TEXT NOT NULL,
+    name      TEXT NOT NULL,
+    data      TEXT NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+CREATE TABLE IF NOT EXISTS receipts (
+    address      TEXT PRIMARY KEY,
+    user         TEXT NOT NULL,
+    direction    INTEGER NOT NULL,
+    asset_kind   INTEGER NOT NULL,
+    mint         TEXT NOT NULL,
+    amount       INTEGER NOT NULL,
+    fee          INTEGER NOT NULL,
+    pre_balance  INTEGER NOT NULL,
+    post_balance INTEGER NOT NULL,
+    ts           INTEGER NOT NULL,
+    tx_count     INTEGER NOT NULL,
+    flags        INTEGER NOT NULL,
+    reference    TEXT,
+    memo         TEXT,
+    version      INTEGER NOT NULL,
+    slot         INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS deposits (
+    signature TEXT NOT NULL,
+    idx       INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    user      TEXT NOT NULL,
+    mint      TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    receipt   TEXT,
+    nonce     INTEGER NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature TEXT NOT NULL,
+    idx       INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    authority TEXT NOT NULL,
+    user      TEXT NOT NULL,
+    mint      TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    receipt   TEXT,
+    tx_count  INTEGER NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+CREATE TABLE IF NOT EXISTS pays (
+    signature TEXT NOT NULL,
+    idx       INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    pay_count INTEGER NOT NULL,
+    authority TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    mint      TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    receipt   TEXT,
+    reference TEXT,
+    PRIMARY KEY (signature, idx)
+);
+
+CREATE TABLE IF NOT EXISTS merchant_pays (
+    signature     TEXT NOT NULL,
+    idx           INTEGER NOT NULL,
+    slot          INTEGER NOT NULL,
+    merchant      TEXT NOT NULL,
+    payer         TEXT NOT NULL,
+    payout_wallet TEXT NOT NULL,
+    mint          TEXT NOT NULL,
+    amount        INTEGER NOT NULL,
+    fee           INTEGER NOT NULL,
+    receipt       TEXT NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+CREATE TABLE IF NOT EXISTS governance_actions (
+    signature TEXT NOT NULL,
+    idx       INTEGER NOT NULL,
+    slot      INTEGER NOT NULL,
+    action    TEXT NOT NULL,
+    authority TEXT,
+    data      TEXT NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+-- Treasury-side token flow per mint: derived from the flow tables by
+-- `Store::refresh_balances`. Dispute refunds are attributed through the
+-- resolution receipt's mint, so they count once that receipt is indexed.
+CREATE TABLE IF NOT EXISTS mint_balances (
+    mint          TEXT PRIMARY KEY,
+    deposited     INTEGER NOT NULL,
+    withdrawn     INTEGER NOT NULL,
+    paid          INTEGER NOT NULL,
+    merchant_fees INTEGER NOT NULL,
+    refunded      INTEGER NOT NULL,
+    balance       INTEGER NOT NULL
+);
+";
+
+const REFRESH_BALANCES: &str = "
+DELETE FROM mint_balances;
+INSERT INTO mint_balances (mint, deposited, withdrawn, paid, merchant_fees, refunded, balance)
+SELECT mint, SUM(dep), SUM(wd), SUM(paid), SUM(fees), SUM(ref),
+       SUM(dep) - SUM(wd) - SUM(paid) + SUM(fees) - SUM(ref)
+FROM (
+    SELECT mint, amount AS dep, 0 AS wd, 0 AS paid, 0 AS fees, 0 AS ref FROM deposits
+    UNION ALL SELECT mint, 0, amount, 0, 0, 0 FROM withdrawals
+    UNION ALL SELECT mint, 0, 0, amount, 0, 0 FROM pays
+    UNION ALL SELECT mint, 0, 0, 0, fee, 0 FROM merchant_pays
+    UNION ALL SELECT r.mint, 0, 0, 0, 0, json_extract(e.data, '$.refund_amount')
+        FROM events e
+        JOIN receipts r ON r.address = json_extract(e.data, '$.resolution_receipt')
+        WHERE e.name = 'DisputeResolved'
+)
+GROUP BY mint;
+";
+
+/// Configuration and authority changes, indexed into `governance_actions`.
+const GOVERNANCE_EVENTS: &[&str] = &[
+    "TreasuryInitialized",
+    "TreasuryPausedSet",
+    "ReceiptAttestorSet",
+    "XpConfigSet",
+    "RiskConfigSet",
+    "LimitTierSet",
+    "UserLimitTierSet",
+    "ComplianceAuthoritySet",
+    "DenylistEntryAdded",
+    "DenylistEntryRemoved",
+    "KycConfigSet",
+    "KycIssuerAdded",
+    "KycIssuerRemoved",
+    "TravelRuleConfigSet",
+    "TrustConfigSet",
+    "DisputeConfigSet",
+    "MerchantFeeTiersSet",
+    "MerchantStatusSet",
+    "MintLedgerOpened",
+];
+
+pub struct Checkpoint {
+    pub slot: u64,
+    pub signature: String,
+}
+
+/// What indexing one transaction produced.
+#[derive(Default)]
+pub struct Indexed {
+    /// Whether the transaction was new.
+    pub stored: bool,
+    pub events: usize,
+
+    /// Receipt accounts the transaction's events reference.
+    pub receipts: Vec<Pubkey>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn checkpoint(&self) -> Result<Option<Checkpoint>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT slot, signature FROM checkpoint WHERE id = 1",
+                [],
+                |row| {
+                    Ok(Checkpoint {
+                        slot: row.get::<_, i64>(0)? as u64,
+                        signature: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Index one transaction of `program_id`.
+    pub fn index_transaction(&mut self, program_id: &Pubkey, tx: &TxRecord) -> Result<Indexed> {
+        let db = self.conn.transaction()?;
+
+        let known: bool = db.query_row(
+            "SELECT EXISTS (SELECT 1 FROM transactions WHERE signature = ?1)",
+            [&tx.signature],
+            |row| row.get(0),
+        )?;
+        if known {
+            return Ok(Indexed::default());
+        }
+
+        db.execute(
+            "INSERT INTO transactions (signature, slot, block_time, failed) VALUES (?1, ?2, ?3, ?4)",
+            params![tx.signature, sql_int(tx.slot)?, tx.block_time, tx.failed],
+        )?;
+
+        // Events of failed transactions were rolled back with them.
+        let events = if tx.failed {
+            Vec::new()
+        } else {
+            let inner = tx
+                .inner_instructions
+                .iter()
+                .map(|(program, data)| (program, data.as_slice()));
+            parse_transaction(program_id, &tx.logs, inner)
+                .map_err(|e| format!("{}: {e}", tx.signature))?
+        };
+
+        let mut indexed = Indexed {
+            stored: true,
+            events: events.len(),
+            receipts: Vec::new(),
+        };
+        for (idx, event) in events.iter().enumerate() {
+            let data = event.event.fields_json();
+            insert_event(&db, tx, idx, event, &data)?;
+
+            for key in ["receipt", "resolution_receipt"] {
+                if let Some(receipt) = pubkey_field(&data, key).filter(|k| *k != Pubkey::default())
+                {
+                    indexed.receipts.push(receipt);
+                }
+            }
+        }
+
+        db.execute(
+            "INSERT INTO checkpoint (id, slot, signature) VALUES (1, ?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET slot = excluded.slot, signature = excluded.signature
+             WHERE excluded.slot >= checkpoint.slot",
+            params![sql_int(tx.slot)?, tx.signature],
+        )?;
+
+        db.commit()?;
+        Ok(indexed)
+    }
+
+    /// Store a receipt account; returns `false` if `data` is not a receipt.
+    pub fn upsert_receipt(&self, address: &Pubkey, data: &[u8], slot: Option<u64>) -> Result<bool> {
+        let Ok(receipt) = decode_receipt(data) else {
+            return Ok(false);
+        };
+        let v2 = &receipt.v2;
+        let reference =
+            (v2.flags & ReceiptV2Ext::FLAG_HAS_REFERENCE != 0).then(|| hex(&v2.reference));
+        let memo = (v2.flags & ReceiptV2Ext::FLAG_HAS_MEMO != 0).then(|| {
+            let len = usize::from(v2.memo_len).min(v2.memo.len());
+            String::from_utf8_lossy(&v2.memo[..len]).into_owned()
+        });
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO receipts (address, user, direction, asset_kind, mint, amount,
+                 fee, pre_balance, post_balance, ts, tx_count, flags, reference, memo, version, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                address.to_string(),
+                receipt.user.to_string(),
+                receipt.direction,
+                receipt.asset_kind,
+                receipt.mint.to_string(),
+                sql_int(receipt.amount)?,
+                sql_int(receipt.fee)?,
+                sql_int(receipt.pre_balance)?,
+                sql_int(receipt.post_balance)?,
+                receipt.ts,
+                sql_int(receipt.tx_count)?,
+                v2.flags,
+                reference,
+                memo,
+                receipt.version,
+                slot.map(sql_int).transpose()?,
+            ],
+        )?;
+        Ok(true)
+    }
+
+    /// Recompute `mint_balances` from the flow tables.
+    pub fn refresh_balances(&self) -> Result<()> {
+        self.conn.execute_batch(REFRESH_BALANCES)?;
+        Ok(())
+    }
+
+    /// Row count of every table.
+    pub fn counts(&self) -> Result<Vec<(&'static str, i64)>> {
+        let tables = [
+            "transactions",
+            "events",
+            "receipts",
+            "deposits",
+            "withdrawals",
+            "pays",
+            "merchant_pays",
+            "governance_actions",
+            "mint_balances",
+        ];
+        tables
+            .into_iter()
+            .map(|table| {
+                let count =
+                    self.conn
+                        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                            row.get(0)
+                        })?;
+                Ok((table, count))
+            })
+            .collect()
+    }
+
+    pub fn balances(&self) -> Result<Vec<Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT mint, deposited, withdrawn, paid, merchant_fees, refunded, balance
+             FROM mint_balances ORDER BY mint",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(serde_json::json!({
+                "mint": row.get::<_, String>(0)?,
+                "deposited": row.get::<_, i64>(1)?,
+                "withdrawn": row.get::<_, i64>(2)?,
+                "paid": row.get::<_, i64>(3)?,
+                "merchant_fees": row.get::<_, i64>(4)?,
+                "refunded": row.get::<_, i64>(5)?,
+                "balance": row.get::<_, i64>(6)?,
+            }))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// Store the event and, for value flows and governance actions, its row in
+/// the matching table.
+fn insert_event(
+    db: &Transaction,
+    tx: &TxRecord,
+    idx: usize,
+    event: &ParsedEvent,
+    data: &Value,
+) -> Result<()> {
+    let name = event.event.name();
+    let slot = sql_int(tx.slot)?;
+    let source = match event.source {
+        EventSource::Log => "log",
+        EventSource::Cpi => "cpi",
+    };
+
+    db.execute(
+        "INSERT INTO events (signature, idx, slot, source, name, data) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![tx.signature, idx, slot, source, name, data.to_string()],
+    )?;
+
+    let text = |key: &str| data[key].as_str().map(str::to_string);
+    let int = |key: &str| -> Result<i64> {
+        let value = data[key]
+            .as_u64()
+            .ok_or_else(|| format!("{name}: missing {key}"))?;
+        sql_int(value)
+    };
+    let receipt = pubkey_field(data, "receipt")
+        .filter(|k| *k != Pubkey::default())
+        .map(|k| k.to_string());
+
+    match name {
+        "Deposit" => {
+            db.execute(
+                "INSERT INTO deposits (signature, idx, slot, user, mint, amount, receipt, nonce)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    tx.signature,
+                    idx,
+                    slot,
+                    text("user"),
+                    text("mint"),
+                    int("amount")?,
+                    receipt,
+                    int("nonce_or_tx")?
+                ],
+            )?;
+        }
+        "Withdraw" => {
+            db.execute(
+                "INSERT INTO withdrawals (signature, idx, slot, authority, user, mint, amount, receipt, tx_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    tx.signature,
+                    idx,
+                    slot,
+                    text("authority"),
+                    text("user"),
+                    text("mint"),
+                    int("amount")?,
+                    receipt,
+                    int("nonce_or_tx")?
+                ],
+            )?;
+        }
+        "SplPay" => {
+            let reference = (data["has_reference"] == Value::Bool(true))
+                .then(|| text("reference"))
+                .flatten();
+            db.execute(
+                "INSERT INTO pays (signature, idx, slot, pay_count, authority, recipient, mint, amount, receipt, reference)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    tx.signature,
+                    idx,
+                    slot,
+                    int("pay_count")?,
+                    text("treasury_authority"),
+                    text("recipient"),
+                    text("mint"),
+                    int("amount")?,
+                    receipt,
+                    reference
+                ],
+            )?;
+        }
+        "MerchantPaid" => {
+            db.execute(
+                "INSERT INTO merchant_pays (signature, idx, slot, merchant, payer, payout_wallet, mint, amount, fee, receipt)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    tx.signature,
+                    idx,
+                    slot,
+                    text("merchant"),
+                    text("payer"),
+                    text("payout_wallet"),
+                    text("mint"),
+                    int("amount")?,
+                    int("fee")?,
+                    text("receipt")
+                ],
+            )?;
+        }
+        _ if GOVERNANCE_EVENTS.contains(&name) => {
+            db.execute(
+                "INSERT INTO governance_actions (signature, idx, slot, action, authority, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    tx.signature,
+                    idx,
+                    slot,
+                    name,
+                    text("authority"),
+                    data.to_string()
+                ],
+            )?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn pubkey_field(data: &Value, key: &str) -> Option<Pubkey> {
+    data[key].as_str()?.parse().ok()
+}
+
+/// SQLite integers are signed 64-bit.
+fn sql_int(value: u64) -> Result<i64> {
+    i64::try_from(value).map_err(|_| format!("{value} does not fit an SQLite integer").into())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,243 @@
+//! `zephyon-indexer` — materializes protocol transactions, events and
+//! receipts into SQLite.
+//!
+//! Sources:
+//! - `rpc`: transactions mentioning the program since the last checkpoint,
+//!   plus every receipt account their events reference;
+//! - `file`: a dump of the same RPC JSON, one object per line: `getTransaction`
+//!   results (`json` encoding) and `{ "pubkey", "account" }` program accounts
+//!   (`base64` encoding). `rpc --dump` writes this format, so a run can be
+//!   replayed offline.
+//!
+//! Progress is checkpointed per transaction (see `db`); running either
+//! command again only indexes what is new.
+
+mod db;
+mod rpc;
+mod source;
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+use zephyon_client::PROGRAM_ID;
+
+use crate::db::Store;
+use crate::rpc::Rpc;
+use crate::source::{Record, TxRecord};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "zephyon-indexer",
+    version,
+    about = "Index Zephyon protocol transactions, events and receipts into SQLite"
+)]
+struct Cli {
+    /// SQLite database (created if missing).
+    #[arg(long, global = true, default_value = "zephyon-index.sqlite")]
+    db: PathBuf,
+
+    /// Program to index.
+    #[arg(long, global = true, default_value_t = PROGRAM_ID)]
+    program: Pubkey,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index new transactions from an RPC node.
+    Rpc {
+        #[arg(long, env = "ZEPHYON_RPC_URL")]
+        url: String,
+        #[arg(long, default_value = "confirmed")]
+        commitment: String,
+        /// Also append everything fetched to this dump file.
+        #[arg(long)]
+        dump: Option<PathBuf>,
+    },
+
+    /// Index a dump file (no network access).
+    File { path: PathBuf },
+
+    /// Print the checkpoint, table sizes and per-mint balances.
+    Status,
+}
+
+#[derive(Default)]
+struct Summary {
+    transactions: usize,
+    skipped: usize,
+    events: usize,
+    receipts: usize,
+}
+
+impl Summary {
+    fn add(&mut self, indexed: &db::Indexed) {
+        if indexed.stored {
+            self.transactions += 1;
+            self.events += indexed.events;
+        } else {
+            self.skipped += 1;
+        }
+    }
+
+    fn json(&self, store: &Store) -> Result<Value> {
+        Ok(json!({
+            "transactions": self.transactions,
+            "skipped": self.skipped,
+            "events": self.events,
+            "receipts": self.receipts,
+            "checkpoint": checkpoint_json(store)?,
+        }))
+    }
+}
+
+fn checkpoint_json(store: &Store) -> Result<Value> {
+    Ok(match store.checkpoint()? {
+        Some(c) => json!({ "slot": c.slot, "signature": c.signature }),
+        None => Value::Null,
+    })
+}
+
+fn index_rpc(
+    store: &mut Store,
+    program: &Pubkey,
+    rpc: &Rpc,
+    dump: Option<File>,
+) -> Result<Summary> {
+    let mut dump = dump;
+    let mut summary = Summary::default();
+    let until = store.checkpoint()?.map(|c| c.signature);
+
+    for signature in rpc.signatures_since(program, until.as_deref())? {
+        let Some(raw) = rpc.transaction(&signature)? else {
+            return Err(format!("transaction {signature} not found").into());
+        };
+        if let Some(dump) = dump.as_mut() {
+            writeln!(dump, "{raw}")?;
+        }
+
+        let tx = TxRecord::parse(&raw)?;
+        let indexed = store.index_transaction(program, &tx)?;
+        summary.add(&indexed);
+
+        for receipt in &indexed.receipts {
+            let Some(account) = rpc.account(receipt)? else {
+                continue;
+            };
+            if let Some(dump) = dump.as_mut() {
+                writeln!(
+                    dump,
+                    "{}",
+                    json!({ "pubkey": receipt.to_string(), "account": account })
+                )?;
+            }
+            let record = source::AccountRecord::parse(&json!(receipt.to_string()), &account)?;
+            if store.upsert_receipt(receipt, &record.data, Some(tx.slot))? {
+                summary.receipts += 1;
+            }
+        }
+    }
+
+    store.refresh_balances()?;
+    Ok(summary)
+}
+
+fn index_file(store: &mut Store, program: &Pubkey, path: &PathBuf) -> Result<Summary> {
+    let file = File::open(path).map_err(|e| format!("opening {}: {e}", path.display()))?;
+    let mut summary = Summary::default();
+    let mut transactions = Vec::new();
+
+    // Accounts first: dispute refunds resolve their mint through receipts.
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(&line)
+            .map_err(|e| format!("{}:{}: {e}", path.display(), number + 1))?;
+        match Record::parse(&value)
+            .map_err(|e| format!("{}:{}: {e}", path.display(), number + 1))?
+        {
+            Record::Transaction(tx) => transactions.push(tx),
+            Record::Account(account) => {
+                if store.upsert_receipt(&account.address, &account.data, None)? {
+                    summary.receipts += 1;
+                }
+            }
+        }
+    }
+
+    let resume_slot = store.checkpoint()?.map_or(0, |c| c.slot);
+    transactions.sort_by_key(|tx| tx.slot);
+    for tx in transactions.iter().filter(|tx| tx.slot >= resume_slot) {
+        summary.add(&store.index_transaction(program, tx)?);
+    }
+
+    store.refresh_balances()?;
+    Ok(summary)
+}
+
+fn run(cli: Cli) -> Result<Value> {
+    let mut store = Store::open(&cli.db)?;
+
+    match &cli.command {
+        Command::Rpc {
+            url,
+            commitment,
+            dump,
+        } => {
+            let dump = dump
+                .as_ref()
+                .map(|path| {
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .map_err(|e| format!("opening {}: {e}", path.display()))
+                })
+                .transpose()?;
+            let summary = index_rpc(&mut store, &cli.program, &Rpc::new(url, commitment), dump)?;
+            summary.json(&store)
+        }
+
+        Command::File { path } => index_file(&mut store, &cli.program, path)?.json(&store),
+
+        Command::Status => {
+            let counts: serde_json::Map<String, Value> = store
+                .counts()?
+                .into_iter()
+                .map(|(table, count)| (table.to_string(), json!(count)))
+                .collect();
+            Ok(json!({
+                "checkpoint": checkpoint_json(&store)?,
+                "tables": counts,
+                "mint_balances": store.balances()?,
+            }))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(value) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", json!({ "error": err.to_string() }));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! The few Solana JSON-RPC methods the indexer reads.
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::{json, Value};
+
+use crate::Result;
+
+/// `getSignaturesForAddress` page size (the RPC maximum).
+const PAGE: usize = 1_000;
+
+pub struct Rpc {
+    url: String,
+    commitment: String,
+}
+
+impl Rpc {
+    pub fn new(url: &str, commitment: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            commitment: commitment.to_string(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))?
+            .into_json()?;
+
+        if let Some(err) = response.get("error") {
+            return Err(format!("{method}: {err}").into());
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Signatures of every transaction mentioning `address` after `until`
+    /// (all of them without one), oldest first.
+    pub fn signatures_since(&self, address: &Pubkey, until: Option<&str>) -> Result<Vec<String>> {
+        let mut signatures = Vec::new();
+        let mut before: Option<String> = None;
+
+        loop {
+            let page = self.call(
+                "getSignaturesForAddress",
+                json!([address.to_string(), {
+                    "limit": PAGE,
+                    "before": before,
+                    "until": until,
+                    "commitment": self.commitment,
+                }]),
+            )?;
+            let page = page
+                .as_array()
+                .ok_or("getSignaturesForAddress: expected an array")?;
+
+            for entry in page {
+                let signature = entry["signature"]
+                    .as_str()
+                    .ok_or("getSignaturesForAddress: missing signature")?;
+                signatures.push(signature.to_string());
+            }
+
+            if page.len() < PAGE {
+                break;
+            }
+            before = signatures.last().cloned();
+        }
+
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    /// `getTransaction` result (`json` encoding), or `None` if unknown.
+    pub fn transaction(&self, signature: &str) -> Result<Option<Value>> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": self.commitment,
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )?;
+        Ok((!result.is_null()).then_some(result))
+    }
+
+    /// `getAccountInfo` value (`base64` encoding), or `None` if the account
+    /// does not exist.
+    pub fn account(&self, address: &Pubkey) -> Result<Option<Value>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": self.commitment }]),
+        )?;
+        let value = &result["value"];
+        Ok((!value.is_null()).then(|| value.clone()))
+    }
+}
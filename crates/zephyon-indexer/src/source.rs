@@ -0,0 +1,126 @@
+//! Transaction and account records, as returned by RPC (`getTransaction`
+//! with `json` encoding, `getProgramAccounts` / `getAccountInfo` with
+//! `base64` encoding) or read back from a dump file of the same JSON.
+
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+
+use crate::Result;
+
+pub struct TxRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub logs: Vec<String>,
+
+    /// Inner instructions in execution order, as (program id, data).
+    pub inner_instructions: Vec<(Pubkey, Vec<u8>)>,
+}
+
+pub struct AccountRecord {
+    pub address: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// One line of a dump file.
+pub enum Record {
+    Transaction(TxRecord),
+    Account(AccountRecord),
+}
+
+impl Record {
+    pub fn parse(value: &Value) -> Result<Self> {
+        if value.get("transaction").is_some() {
+            TxRecord::parse(value).map(Self::Transaction)
+        } else if let (Some(address), Some(account)) = (value.get("pubkey"), value.get("account")) {
+            AccountRecord::parse(address, account).map(Self::Account)
+        } else {
+            Err("neither a transaction nor a program account".into())
+        }
+    }
+}
+
+impl TxRecord {
+    /// Parse a `getTransaction` result.
+    pub fn parse(value: &Value) -> Result<Self> {
+        let signature = value["transaction"]["signatures"][0]
+            .as_str()
+            .ok_or("transaction: missing signature")?
+            .to_string();
+        let slot = value["slot"].as_u64().ok_or("transaction: missing slot")?;
+        let meta = &value["meta"];
+
+        let logs = meta["logMessages"]
+            .as_array()
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(|line| line.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Static keys, then the address-table lookups of v0 messages.
+        let mut keys = Vec::new();
+        for key in value["transaction"]["message"]["accountKeys"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .chain(
+                meta["loadedAddresses"]["writable"]
+                    .as_array()
+                    .into_iter()
+                    .flatten(),
+            )
+            .chain(
+                meta["loadedAddresses"]["readonly"]
+                    .as_array()
+                    .into_iter()
+                    .flatten(),
+            )
+        {
+            keys.push(
+                key.as_str()
+                    .ok_or("transaction: bad account key")?
+                    .parse::<Pubkey>()?,
+            );
+        }
+
+        let mut inner_instructions = Vec::new();
+        for group in meta["innerInstructions"].as_array().into_iter().flatten() {
+            for ix in group["instructions"].as_array().into_iter().flatten() {
+                let program = ix["programIdIndex"]
+                    .as_u64()
+                    .and_then(|i| keys.get(i as usize))
+                    .ok_or("transaction: bad programIdIndex")?;
+                let data = bs58::decode(ix["data"].as_str().unwrap_or_default()).into_vec()?;
+                inner_instructions.push((*program, data));
+            }
+        }
+
+        Ok(Self {
+            signature,
+            slot,
+            block_time: value["blockTime"].as_i64(),
+            failed: !meta["err"].is_null(),
+            logs,
+            inner_instructions,
+        })
+    }
+}
+
+impl AccountRecord {
+    /// Parse an `{ pubkey, account }` pair (`account` as returned by
+    /// `getAccountInfo` with `base64` encoding).
+    pub fn parse(address: &Value, account: &Value) -> Result<Self> {
+        let address = address.as_str().ok_or("account: bad pubkey")?.parse()?;
+        let data = account["data"][0].as_str().ok_or("account: missing data")?;
+        Ok(Self {
+            address,
+            data: STANDARD.decode(data)?,
+        })
+    }
+}
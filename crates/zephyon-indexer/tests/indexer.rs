@@ -0,0 +1,350 @@
+//! Runs the indexer binary over dump files and checks the SQLite it writes.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, Event};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use zephyon_client::accounts::{Receipt, ReceiptV2Ext};
+use zephyon_client::PROGRAM_ID;
+use zephyon_events::{AssetKind, DepositEvent, PayDirection, TreasuryPausedSetEvent, EVENT_IX_TAG};
+
+/// A scratch directory per test, removed on drop.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("zephyon-indexer-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn db(&self) -> PathBuf {
+        self.0.join("index.sqlite")
+    }
+
+    /// Write `records` as a dump file, one JSON object per line.
+    fn dump(&self, name: &str, records: &[Value]) -> PathBuf {
+        let path = self.0.join(name);
+        let lines: Vec<String> = records.iter().map(Value::to_string).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        path
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Run the indexer; returns its JSON output, or the error message.
+fn indexer(db: &Path, args: &[&str]) -> Result<Value, String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_zephyon-indexer"))
+        .arg("--db")
+        .arg(db)
+        .args(args)
+        .output()
+        .unwrap();
+    if output.status.success() {
+        Ok(serde_json::from_slice(&output.stdout).unwrap())
+    } else {
+        let error: Value = serde_json::from_slice(&output.stderr).unwrap();
+        Err(error["error"].as_str().unwrap().to_string())
+    }
+}
+
+fn index_file(db: &Path, dump: &Path) -> Result<Value, String> {
+    indexer(db, &["file", dump.to_str().unwrap()])
+}
+
+fn deposit_log(user: Pubkey, mint: Pubkey, amount: u64, receipt: Pubkey) -> String {
+    let event = DepositEvent {
+        user,
+        mint,
+        amount,
+        treasury: Pubkey::new_unique(),
+        direction: PayDirection::UserToTreasury,
+        asset_kind: AssetKind::SPL,
+        receipt,
+        nonce_or_tx: 1,
+        xp_delta: 0,
+        risk_flags: 0,
+        slot: 0,
+        has_reference: false,
+        reference: [0; 32],
+        has_memo: false,
+        memo_len: 0,
+    };
+    format!("Program data: {}", STANDARD.encode(event.data()))
+}
+
+/// A `getTransaction` result (`json` encoding) of one protocol instruction.
+fn transaction(signature: &str, slot: u64, logs: &[String], inner: &[Vec<u8>]) -> Value {
+    let program = PROGRAM_ID.to_string();
+    let mut lines = vec![format!("Program {program} invoke [1]")];
+    lines.extend_from_slice(logs);
+    lines.push(format!("Program {program} success"));
+
+    let inner: Vec<Value> = inner
+        .iter()
+        .map(|data| {
+            let data = bs58::encode(data).into_string();
+            json!({ "programIdIndex": 1, "accounts": [], "data": data })
+        })
+        .collect();
+
+    json!({
+        "slot": slot,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "signatures": [signature],
+            "message": { "accountKeys": [Pubkey::new_unique().to_string(), program] },
+        },
+        "meta": {
+            "err": null,
+            "logMessages": lines,
+            "innerInstructions": [{ "index": 0, "instructions": inner }],
+        },
+    })
+}
+
+fn deposit_tx(signature: &str, slot: u64, mint: Pubkey, amount: u64) -> Value {
+    let log = deposit_log(Pubkey::new_unique(), mint, amount, Pubkey::default());
+    transaction(signature, slot, &[log], &[])
+}
+
+fn failed_tx(signature: &str, slot: u64, mint: Pubkey) -> Value {
+    let mut tx = deposit_tx(signature, slot, mint, 1_000);
+    tx["meta"]["err"] = json!({ "InstructionError": [0, { "Custom": 1 }] });
+    tx
+}
+
+fn receipt_account(address: Pubkey, mint: Pubkey) -> Value {
+    let receipt = Receipt {
+        user: Pubkey::new_unique(),
+        direction: Receipt::DIR_DEPOSIT,
+        asset_kind: Receipt::ASSET_SPL,
+        mint,
+        amount: 75,
+        fee: 0,
+        pre_balance: 0,
+        post_balance: 0,
+        ts: 1_700_000_000,
+        tx_count: 1,
+        bump: 255,
+        v2: ReceiptV2Ext::spl_with_meta(mint, Some([0xcd; 32]), Some(b"invoice 7")),
+        version: Receipt::CURRENT_VERSION,
+    };
+    let mut data = Vec::new();
+    receipt.try_serialize(&mut data).unwrap();
+    json!({
+        "pubkey": address.to_string(),
+        "account": { "data": [STANDARD.encode(data), "base64"], "owner": PROGRAM_ID.to_string() },
+    })
+}
+
+fn count(db: &Path, sql: &str) -> i64 {
+    Connection::open(db)
+        .unwrap()
+        .query_row(sql, [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn a_fresh_database_gets_the_schema() {
+    let scratch = Scratch::new("schema");
+
+    let status = indexer(&scratch.db(), &["status"]).unwrap();
+    assert_eq!(status["checkpoint"], Value::Null);
+    assert_eq!(status["mint_balances"], json!([]));
+    for (table, rows) in status["tables"].as_object().unwrap() {
+        assert_eq!(rows, 0, "{table}");
+    }
+
+    let tables = count(
+        &scratch.db(),
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN
+            ('checkpoint', 'transactions', 'events', 'receipts', 'deposits', 'withdrawals',
+             'pays', 'merchant_pays', 'governance_actions', 'mint_balances')",
+    );
+    assert_eq!(tables, 10);
+
+    // Opening again keeps the schema (and the data).
+    indexer(&scratch.db(), &["status"]).unwrap();
+}
+
+#[test]
+fn the_file_source_indexes_transactions_events_and_receipts() {
+    let scratch = Scratch::new("file");
+    let mint = Pubkey::new_unique();
+    let receipt = Pubkey::new_unique();
+    let deposit = deposit_log(Pubkey::new_unique(), mint, 75, receipt);
+
+    // A line that is neither record kind is reported with its line number.
+    let bad = scratch.dump(
+        "bad.jsonl",
+        &[
+            transaction("deposit", 11, std::slice::from_ref(&deposit), &[]),
+            json!({}),
+        ],
+    );
+    let error = index_file(&scratch.db(), &bad).unwrap_err();
+    assert!(error.contains("bad.jsonl:2"), "{error}");
+
+    let dump = scratch.dump(
+        "dump.jsonl",
+        &[
+            transaction("deposit", 11, &[deposit], &[]),
+            receipt_account(receipt, mint),
+            failed_tx("failed", 12, mint),
+        ],
+    );
+    let summary = index_file(&scratch.db(), &dump).unwrap();
+    assert_eq!(summary["transactions"], 2);
+    assert_eq!(summary["events"], 1);
+    assert_eq!(summary["receipts"], 1);
+
+    // Failed transactions are recorded without their rolled-back events.
+    assert_eq!(
+        count(
+            &scratch.db(),
+            "SELECT COUNT(*) FROM transactions WHERE failed"
+        ),
+        1
+    );
+    assert_eq!(count(&scratch.db(), "SELECT SUM(amount) FROM deposits"), 75);
+
+    let (memo, reference): (String, String) = Connection::open(scratch.db())
+        .unwrap()
+        .query_row("SELECT memo, reference FROM receipts", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(memo, "invoice 7");
+    assert_eq!(reference, "cd".repeat(32));
+}
+
+#[test]
+fn cpi_events_are_indexed_after_logged_ones() {
+    let scratch = Scratch::new("cpi");
+    let paused = TreasuryPausedSetEvent {
+        treasury: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        paused: true,
+        slot: 11,
+        unix_timestamp: 1_700_000_000,
+    };
+    let cpi_event = [EVENT_IX_TAG, &paused.data()].concat();
+    let log = deposit_log(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        5,
+        Pubkey::default(),
+    );
+
+    let dump = scratch.dump(
+        "dump.jsonl",
+        &[transaction("both", 11, &[log], &[cpi_event])],
+    );
+    index_file(&scratch.db(), &dump).unwrap();
+
+    let conn = Connection::open(scratch.db()).unwrap();
+    let mut stmt = conn
+        .prepare("SELECT source, name FROM events ORDER BY idx")
+        .unwrap();
+    let events: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        events,
+        [
+            ("log".to_string(), "Deposit".to_string()),
+            ("cpi".to_string(), "TreasuryPausedSet".to_string())
+        ]
+    );
+    assert_eq!(
+        count(&scratch.db(), "SELECT COUNT(*) FROM governance_actions"),
+        1
+    );
+}
+
+#[test]
+fn re_ingesting_a_dump_is_a_no_op() {
+    let scratch = Scratch::new("idempotent");
+    let mint = Pubkey::new_unique();
+    let dump = scratch.dump(
+        "dump.jsonl",
+        &[
+            deposit_tx("a", 10, mint, 100),
+            deposit_tx("b", 20, mint, 50),
+        ],
+    );
+
+    let first = index_file(&scratch.db(), &dump).unwrap();
+    assert_eq!(
+        (first["transactions"].clone(), first["skipped"].clone()),
+        (json!(2), json!(0))
+    );
+
+    // The same signatures again (the last one is at the checkpoint slot).
+    let second = index_file(&scratch.db(), &dump).unwrap();
+    assert_eq!(second["transactions"], 0);
+    assert_eq!(second["skipped"], 1);
+
+    assert_eq!(count(&scratch.db(), "SELECT COUNT(*) FROM transactions"), 2);
+    assert_eq!(count(&scratch.db(), "SELECT COUNT(*) FROM deposits"), 2);
+    let status = indexer(&scratch.db(), &["status"]).unwrap();
+    assert_eq!(status["mint_balances"][0]["deposited"], 150);
+}
+
+#[test]
+fn indexing_resumes_from_the_slot_checkpoint() {
+    let scratch = Scratch::new("resume");
+    let mint = Pubkey::new_unique();
+
+    let first = scratch.dump("first.jsonl", &[deposit_tx("a", 10, mint, 100)]);
+    index_file(&scratch.db(), &first).unwrap();
+    let status = indexer(&scratch.db(), &["status"]).unwrap();
+    assert_eq!(
+        status["checkpoint"],
+        json!({ "slot": 10, "signature": "a" })
+    );
+
+    // Transactions below the checkpoint slot are not revisited; ones at or
+    // above it are, in slot order.
+    let second = scratch.dump(
+        "second.jsonl",
+        &[
+            deposit_tx("c", 30, mint, 3),
+            deposit_tx("old", 5, mint, 1_000),
+            deposit_tx("b", 10, mint, 2),
+        ],
+    );
+    let summary = index_file(&scratch.db(), &second).unwrap();
+    assert_eq!(summary["transactions"], 2);
+    assert_eq!(
+        summary["checkpoint"],
+        json!({ "slot": 30, "signature": "c" })
+    );
+
+    assert_eq!(
+        count(
+            &scratch.db(),
+            "SELECT COUNT(*) FROM transactions WHERE signature = 'old'"
+        ),
+        0
+    );
+    assert_eq!(
+        count(&scratch.db(), "SELECT SUM(amount) FROM deposits"),
+        105
+    );
+}
@@ -0,0 +1,26 @@
+//! Detects the deployed program the integration tests load.
+//!
+//! The tests run `target/deploy/protocol.so` and are compiled only when it
+//! exists (`cfg(protocol_so)`); otherwise the build says how to produce it
+//! and the suite is skipped.
+
+use std::path::Path;
+
+fn main() {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).ancestors().nth(2);
+    let program = workspace
+        .expect("workspace root")
+        .join("target/deploy/protocol.so");
+    println!("cargo::rustc-env=ZEPHYON_PROTOCOL_SO={}", program.display());
+    println!("cargo::rustc-check-cfg=cfg(protocol_so)");
+    println!("cargo::rerun-if-changed={}", program.display());
+
+    if program.exists() {
+        println!("cargo::rustc-cfg=protocol_so");
+    } else {
+        println!(
+            "cargo::warning=zephyon-svm tests skipped: {} not found; run `anchor build` first",
+            program.display()
+        );
+    }
+}
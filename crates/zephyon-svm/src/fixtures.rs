@@ -0,0 +1,336 @@
+//! Test fixtures: a chain with an initialized treasury, mints and token
+//! accounts, and the everyday flows as one-call transactions.
+//!
+//! Everything else is built with the `zephyon-client` builders and sent
+//! with [`Protocol::send`].
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token::spl_token;
+use protocol::state::Treasury;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_system_interface::instruction as system_instruction;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda};
+
+use crate::{Account, FailedTransaction, Svm, TransactionResult};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Decimals of fixture mints.
+pub const DECIMALS: u8 = 6;
+
+/// Associated token account of `owner` for `mint`.
+pub fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, mint)
+}
+
+/// The treasury PDA.
+pub fn treasury() -> Pubkey {
+    pda::treasury().0
+}
+
+/// The treasury's associated token account for `mint`.
+pub fn treasury_ata(mint: &Pubkey) -> Pubkey {
+    ata(&treasury(), mint)
+}
+
+/// Unwrap a failed transaction, asserting its custom error code (protocol,
+/// Anchor or SPL error).
+#[track_caller]
+pub fn expect_error(result: TransactionResult, code: impl Into<u32>) -> FailedTransaction {
+    let code = code.into();
+    match result {
+        Ok(meta) => panic!(
+            "expected error {code}, transaction succeeded: {:#?}",
+            meta.logs
+        ),
+        Err(failed) => {
+            assert_eq!(
+                failed.custom_code(),
+                Some(code),
+                "unexpected error {:?}: {:#?}",
+                failed.err,
+                failed.logs
+            );
+            failed
+        }
+    }
+}
+
+/// Ed25519 program instruction in the self-contained form (key, signature
+/// and message inside the instruction), signed by `signer`.
+pub fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    use ed25519_dalek::{Signer as _, SigningKey};
+
+    let secret: [u8; 32] = signer.to_bytes()[..32].try_into().expect("secret key");
+    let key = SigningKey::from_bytes(&secret);
+    let signature = key.sign(message).to_bytes();
+
+    const PUBKEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBKEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+    let offsets = [
+        SIGNATURE_OFFSET,
+        u16::MAX,
+        PUBKEY_OFFSET,
+        u16::MAX,
+        MESSAGE_OFFSET,
+        message.len() as u16,
+        u16::MAX,
+    ];
+
+    let mut data = vec![1, 0];
+    data.extend(offsets.iter().flat_map(|v| v.to_le_bytes()));
+    data.extend_from_slice(&key.verifying_key().to_bytes());
+    data.extend_from_slice(&signature);
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: solana_sdk_ids::ed25519_program::ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+/// A chain with the protocol deployed and its treasury initialized
+/// (unpaused, `authority` as treasury authority).
+pub struct Protocol {
+    pub svm: Svm,
+
+    /// Treasury authority.
+    pub authority: Keypair,
+
+    /// Mint authority of every fixture mint.
+    pub mint_authority: Keypair,
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Protocol {
+    pub fn new() -> Self {
+        let mut svm = Svm::new();
+        let authority = Keypair::new();
+        let mint_authority = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 100 * LAMPORTS_PER_SOL);
+        svm.airdrop(&mint_authority.pubkey(), 100 * LAMPORTS_PER_SOL);
+
+        let mut protocol = Self {
+            svm,
+            authority,
+            mint_authority,
+        };
+        let init = ix::initialize_treasury(&protocol.authority.pubkey());
+        protocol
+            .send(&[init], &[&protocol.authority.insecure_clone()])
+            .expect("initialize_treasury");
+        protocol
+    }
+
+    /// Execute `instructions` as one transaction.
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        self.svm.send_transaction(instructions, signers)
+    }
+
+    /// Execute `instructions` signed by the treasury authority (plus
+    /// `signers`).
+    pub fn send_as_authority(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let authority = self.authority.insecure_clone();
+        let mut all = vec![&authority];
+        all.extend_from_slice(signers);
+        self.svm.send_transaction(instructions, &all)
+    }
+
+    /// A new wallet holding 10 SOL.
+    pub fn user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        self.svm.airdrop(&user.pubkey(), 10 * LAMPORTS_PER_SOL);
+        user
+    }
+
+    /// Decode an Anchor account, `None` if it does not exist or does not
+    /// decode as `T`.
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        let account = self.svm.account(address)?;
+        T::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    pub fn exists(&self, address: &Pubkey) -> bool {
+        self.svm.account(address).is_some()
+    }
+
+    pub fn treasury_state(&self) -> Treasury {
+        self.fetch(&treasury()).expect("treasury")
+    }
+
+    pub fn pay_count(&self) -> u64 {
+        self.treasury_state().pay_count
+    }
+
+    /// A new mint with [`DECIMALS`] decimals and no supply.
+    pub fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let space = spl_token::state::Mint::LEN;
+        let payer = self.mint_authority.insecure_clone();
+        let instructions = [
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                self.svm.rent().minimum_balance(space),
+                space as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .expect("initialize_mint2"),
+        ];
+        self.send(&instructions, &[&payer, &mint])
+            .expect("create mint");
+        mint.pubkey()
+    }
+
+    /// The ATA of `owner` for `mint`, created if missing.
+    pub fn create_ata(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let payer = self.mint_authority.insecure_clone();
+        let create =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                owner,
+                mint,
+                &spl_token::ID,
+            );
+        self.send(&[create], &[&payer]).expect("create ATA");
+        ata(owner, mint)
+    }
+
+    /// Mint `amount` into the ATA of `owner` (created if missing).
+    pub fn mint_to(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let account = self.create_ata(owner, mint);
+        let authority = self.mint_authority.insecure_clone();
+        let mint_to = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            mint,
+            &account,
+            &authority.pubkey(),
+            &[],
+            amount,
+        )
+        .expect("mint_to");
+        self.send(&[mint_to], &[&authority]).expect("mint_to");
+        account
+    }
+
+    /// Mint `amount` into the treasury ATA of `mint` (created if missing).
+    pub fn fund_treasury(&mut self, mint: &Pubkey, amount: u64) -> Pubkey {
+        self.mint_to(&treasury(), mint, amount)
+    }
+
+    /// Write a token account directly, bypassing the token program (fake
+    /// or tampered accounts for adversarial tests).
+    pub fn set_token_account(
+        &mut self,
+        address: Pubkey,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) {
+        let state = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        state.pack_into_slice(&mut data);
+        self.svm.set_account(
+            address,
+            Account {
+                lamports: self.svm.rent().minimum_balance(data.len()),
+                data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    /// Token balance of `account` (0 if it does not exist).
+    pub fn balance(&self, account: &Pubkey) -> u64 {
+        self.svm
+            .account(account)
+            .filter(|a| a.owner == spl_token::ID)
+            .and_then(|a| spl_token::state::Account::unpack(&a.data).ok())
+            .map_or(0, |a| a.amount)
+    }
+
+    /// Token balance of the treasury ATA for `mint`.
+    pub fn treasury_balance(&self, mint: &Pubkey) -> u64 {
+        self.balance(&treasury_ata(mint))
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> TransactionResult {
+        let set = ix::set_treasury_paused(&self.authority.pubkey(), paused);
+        self.send_as_authority(&[set], &[])
+    }
+
+    /// `spl_deposit` of `amount` from the user's ATA.
+    pub fn deposit(&mut self, user: &Keypair, mint: &Pubkey, amount: u64) -> TransactionResult {
+        let deposit = ix::spl_deposit(&user.pubkey(), mint, amount, FlowOptions::default());
+        self.send(&[deposit], &[user])
+    }
+
+    /// `spl_withdraw` of `amount` to the user's ATA, signed by the treasury
+    /// authority.
+    pub fn withdraw(&mut self, user: &Pubkey, mint: &Pubkey, amount: u64) -> TransactionResult {
+        let withdraw = ix::spl_withdraw(
+            &self.authority.pubkey(),
+            user,
+            mint,
+            amount,
+            FlowOptions::default(),
+        );
+        self.send_as_authority(&[withdraw], &[])
+    }
+
+    /// `spl_pay` of `amount` to the recipient's ATA at the current
+    /// `pay_count`, signed by the treasury authority.
+    pub fn pay(&mut self, recipient: &Pubkey, mint: &Pubkey, amount: u64) -> TransactionResult {
+        let pay = ix::spl_pay(
+            &self.authority.pubkey(),
+            recipient,
+            mint,
+            self.pay_count(),
+            args::SplPay {
+                amount,
+                memo: None,
+                reference: None,
+                memo_cpi: None,
+            },
+            FlowOptions::default(),
+        );
+        self.send_as_authority(&[pay], &[])
+    }
+}
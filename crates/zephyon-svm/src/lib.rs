@@ -0,0 +1,264 @@
+//! LiteSVM harness for the Zephyon protocol program.
+//!
+//! [`Svm`] loads the deployed program, `target/deploy/protocol.so` (built by
+//! `anchor build`), into [LiteSVM](https://github.com/LiteSVM/litesvm) and
+//! executes real transactions against it. The SBF loader, the System, SPL
+//! Token, Associated Token Account and SPL Memo programs and the Ed25519
+//! precompile are LiteSVM's, so CPIs, compute limits and signature checks
+//! behave as on a validator. The integration tests are only compiled once
+//! the program is built; until then `cargo test` skips them with a warning
+//! (see `build.rs`).
+//!
+//! [`fixtures`] sets up a treasury, mints and token accounts, and wraps the
+//! `zephyon-client` builders the tests use.
+//!
+//! [`substitution`] runs instructions with their accounts swapped for other
+//! known accounts and checks that each one is rejected or harmless; the
+//! substitution tests and the `fuzz/` target drive it.
+//!
+//! Fees are paid by a dedicated fee payer, so wallet balances only move by
+//! what the program does. [`TransactionMeta::logs`] holds the full program
+//! output: `msg!` lines and the `Program data:` lines written by Anchor's
+//! `emit!`, which `zephyon-events` decodes.
+
+pub mod fixtures;
+pub mod substitution;
+
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::{Clock, Pubkey, Rent};
+use litesvm::types::{FailedTransactionMetadata, TransactionMetadata};
+use litesvm::LiteSVM;
+use solana_instruction::error::InstructionError;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+pub use solana_account::Account;
+
+/// The program `anchor build` writes for `programs/protocol`.
+pub const PROGRAM_PATH: &str = env!("ZEPHYON_PROTOCOL_SO");
+
+/// Slot and time of a fresh [`Svm`].
+const GENESIS_SLOT: u64 = 1;
+const GENESIS_UNIX_TIMESTAMP: i64 = 1_700_000_000;
+
+/// Lamports of the fee payer; enough for any test run.
+const FEE_PAYER_LAMPORTS: u64 = 1_000_000 * 1_000_000_000;
+
+/// Runtime logs of a successful transaction.
+#[derive(Clone, Debug)]
+pub struct TransactionMeta {
+    pub logs: Vec<String>,
+}
+
+/// A failed transaction: its error and the logs up to the failure.
+#[derive(Clone, Debug)]
+pub struct FailedTransaction {
+    pub err: TransactionError,
+    pub logs: Vec<String>,
+}
+
+impl FailedTransaction {
+    /// The error of the failing instruction.
+    pub fn instruction_error(&self) -> Option<&InstructionError> {
+        match &self.err {
+            TransactionError::InstructionError(_, err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Custom program error code (Anchor and protocol errors, SPL errors).
+    pub fn custom_code(&self) -> Option<u32> {
+        match self.instruction_error() {
+            Some(InstructionError::Custom(code)) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+pub type TransactionResult = Result<TransactionMeta, FailedTransaction>;
+
+/// A LiteSVM chain with the protocol deployed.
+///
+/// Cloning snapshots the whole chain.
+pub struct Svm {
+    svm: LiteSVM,
+
+    /// Pays the fees of every transaction.
+    fee_payer: Keypair,
+
+    /// Every address set up directly or named by a transaction; the
+    /// accounts [`Svm::accounts`] walks.
+    known: BTreeSet<Pubkey>,
+}
+
+impl Clone for Svm {
+    fn clone(&self) -> Self {
+        Self {
+            svm: self.svm.clone(),
+            fee_payer: self.fee_payer.insecure_clone(),
+            known: self.known.clone(),
+        }
+    }
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    /// A fresh chain with [`PROGRAM_PATH`] deployed at `protocol::ID`.
+    ///
+    /// Panics if the program has not been built.
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(protocol::ID, PROGRAM_PATH)
+            .unwrap_or_else(|err| {
+                panic!("cannot load {PROGRAM_PATH} ({err:?}); run `anchor build`")
+            });
+
+        let clock = Clock {
+            slot: GENESIS_SLOT,
+            unix_timestamp: GENESIS_UNIX_TIMESTAMP,
+            epoch_start_timestamp: GENESIS_UNIX_TIMESTAMP,
+            ..Clock::default()
+        };
+        svm.set_sysvar(&clock);
+
+        let fee_payer = Keypair::new();
+        let funds = Account {
+            lamports: FEE_PAYER_LAMPORTS,
+            ..Account::default()
+        };
+        svm.set_account(fee_payer.pubkey(), funds)
+            .expect("fund the fee payer");
+
+        Self {
+            svm,
+            fee_payer,
+            known: BTreeSet::new(),
+        }
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.svm.get_account(address)
+    }
+
+    /// Every account set up with [`Svm::set_account`] or [`Svm::airdrop`],
+    /// or named by a transaction, that exists; in address order. The fee
+    /// payer is left out.
+    pub fn accounts(&self) -> impl Iterator<Item = (Pubkey, Account)> + '_ {
+        self.known
+            .iter()
+            .filter_map(|address| Some((*address, self.account(address)?)))
+    }
+
+    /// Create or overwrite an account (fixtures and adversarial setups).
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.svm.set_account(address, account).expect("set_account");
+        self.known.insert(address);
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_balance(address).unwrap_or(0)
+    }
+
+    /// Credit `lamports` to `address`, creating a system account if needed.
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        let mut account = self.account(address).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(*address, account);
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.svm.get_sysvar()
+    }
+
+    pub fn rent(&self) -> Rent {
+        self.svm.get_sysvar()
+    }
+
+    /// Move the clock to `slot` (time is unchanged).
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        let mut clock = self.clock();
+        clock.slot = slot;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// Move the clock forward by `seconds` (the slot is unchanged).
+    pub fn advance_time(&mut self, seconds: i64) {
+        let mut clock = self.clock();
+        clock.unix_timestamp += seconds;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// Execute `instructions` as one atomic transaction signed by `signers`.
+    ///
+    /// Keypairs the instructions do not ask for are ignored; a signer that
+    /// is not passed in fails the transaction with `SignatureFailure`.
+    pub fn send_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let tx = self.transaction(instructions, signers);
+        self.known.extend(
+            tx.message
+                .account_keys
+                .iter()
+                .filter(|key| **key != self.fee_payer.pubkey()),
+        );
+        let result = self.svm.send_transaction(tx);
+        // The same instructions may be sent again; give them a new
+        // signature instead of `AlreadyProcessed`.
+        self.svm.expire_blockhash();
+        match result {
+            Ok(meta) => Ok(Self::meta(meta)),
+            Err(failed) => Err(Self::failure(failed)),
+        }
+    }
+
+    /// Execute `instructions` without committing anything.
+    pub fn simulate_transaction(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let tx = self.transaction(instructions, signers);
+        match self.svm.simulate_transaction(tx) {
+            Ok(info) => Ok(Self::meta(info.meta)),
+            Err(failed) => Err(Self::failure(failed)),
+        }
+    }
+
+    fn transaction(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let mut tx = Transaction::new_with_payer(instructions, Some(&self.fee_payer.pubkey()));
+        let required = tx.message.signer_keys();
+        let mut keypairs = vec![&self.fee_payer];
+        for signer in signers {
+            let key = signer.pubkey();
+            if required.contains(&&key) && keypairs.iter().all(|k| k.pubkey() != key) {
+                keypairs.push(*signer);
+            }
+        }
+        tx.try_partial_sign(&keypairs, self.svm.latest_blockhash())
+            .expect("sign transaction");
+        tx
+    }
+
+    fn meta(meta: TransactionMetadata) -> TransactionMeta {
+        TransactionMeta { logs: meta.logs }
+    }
+
+    fn failure(failed: FailedTransactionMetadata) -> FailedTransaction {
+        FailedTransaction {
+            err: failed.err,
+            logs: failed.meta.logs,
+        }
+    }
+}
@@ -0,0 +1,657 @@
+//! Account substitution attacks.
+//!
+//! A [`Scenario`] is a chain where every value-moving handler has something
+//! to steal: two mints with funded user and treasury ATAs and open ledgers
+//! (the second one short by one token), a registered merchant, existing
+//! receipts, an open merchant payment dispute, and look-alike token accounts at
+//! non-ATA addresses owned by the treasury, the user and the attacker.
+//!
+//! [`Scenario::run`] builds a valid instruction for one [`Target`], rewrites
+//! some of its account metas with other known accounts (signer slots only
+//! take wallets whose keys the scenario holds), executes it and checks the
+//! outcome. A rejected transaction must leave the chain untouched; an
+//! accepted one must keep:
+//!
+//! - every mint's supply
+//! - tokens leaving only accounts whose owner signed, or the canonical
+//!   treasury ATA with the treasury authority signing
+//! - each ledger moving exactly as much as its treasury ATA
+//! - existing receipts unchanged, `pay_count` moving by at most one and the
+//!   pause flag unchanged
+//! - for a single substitution, the balances the unmodified instruction
+//!   produces
+//! - `assert_solvency` passing only for a solvent mint
+//!
+//! Indices in [`Attack`] wrap around, so any fuzzer input is a valid attack.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::token::spl_token;
+use protocol::state::{Merchant, MintLedger, Receipt, Treasury, UserProfile};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, DisputeOptions, FlowOptions};
+use zephyon_client::{args, pda};
+
+use crate::fixtures::{ata, treasury, treasury_ata, Protocol};
+use crate::{FailedTransaction, Svm};
+
+const USER_FUNDS: u64 = 10_000;
+const TREASURY_FUNDS: u64 = 10_000;
+const AMOUNT: u64 = 100;
+const DISPUTE_WINDOW: i64 = 7 * 86_400;
+/// Deposit receipt nonces start far above any withdrawal count, whose
+/// receipts share the ["receipt", user, n] seeds.
+const FIRST_NONCE: u64 = 1_000;
+
+/// Handler an attack is aimed at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Deposit,
+    DepositWithReceipt,
+    Withdraw,
+    WithdrawWithReceipt,
+    Pay,
+    PayWithReference,
+    PayMerchant,
+    ResolveDispute,
+    AssertSolvency,
+}
+
+impl Target {
+    pub const ALL: [Target; 9] = [
+        Target::Deposit,
+        Target::DepositWithReceipt,
+        Target::Withdraw,
+        Target::WithdrawWithReceipt,
+        Target::Pay,
+        Target::PayWithReference,
+        Target::PayMerchant,
+        Target::ResolveDispute,
+        Target::AssertSolvency,
+    ];
+}
+
+/// Replace the account in `slot` with candidate `candidate` (both wrap).
+#[derive(Clone, Copy, Debug)]
+pub struct Substitution {
+    pub slot: usize,
+    pub candidate: usize,
+}
+
+/// One instruction for `Target::ALL[target]` on mint `mint` (both wrap),
+/// with its accounts rewritten.
+#[derive(Clone, Debug)]
+pub struct Attack {
+    pub target: usize,
+    pub mint: usize,
+    pub substitutions: Vec<Substitution>,
+}
+
+/// Result of an attack that kept every invariant.
+#[derive(Debug)]
+pub enum Outcome {
+    Rejected(FailedTransaction),
+    Accepted,
+}
+
+/// An invariant broken by an attack.
+#[derive(Debug)]
+pub struct Violation {
+    pub target: Target,
+    pub instruction: Instruction,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?}: {}", self.target, self.reason)?;
+        for (slot, meta) in self.instruction.accounts.iter().enumerate() {
+            writeln!(
+                f,
+                "  {slot:>2} {} signer={} writable={}",
+                meta.pubkey, meta.is_signer, meta.is_writable
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// The attacked chain; see the module docs.
+pub struct Scenario {
+    pub p: Protocol,
+    pub user: Keypair,
+    pub recipient: Keypair,
+    pub attacker: Keypair,
+    pub merchant_owner: Keypair,
+    pub payout: Pubkey,
+    pub mints: [Pubkey; 2],
+    /// Open dispute on the user's merchant payment receipt (first mint).
+    pub dispute: Pubkey,
+    /// Accounts substituted into non-signer slots.
+    pub candidates: Vec<Pubkey>,
+    /// Keys available for signer slots.
+    signers: Vec<Keypair>,
+    nonce: u64,
+    genesis: Svm,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        let mut p = Protocol::new();
+        let authority = p.authority.pubkey();
+        let user = p.user();
+        let recipient = p.user();
+        let attacker = p.user();
+        let merchant_owner = p.user();
+        let payout = p.user().pubkey();
+        let mints = [p.create_mint(), p.create_mint()];
+
+        for mint in &mints {
+            p.mint_to(&user.pubkey(), mint, USER_FUNDS);
+            p.mint_to(&recipient.pubkey(), mint, USER_FUNDS);
+            p.mint_to(&attacker.pubkey(), mint, USER_FUNDS);
+            p.create_ata(&payout, mint);
+            p.fund_treasury(mint, TREASURY_FUNDS);
+            p.send_as_authority(&[ix::open_mint_ledger(&authority, mint)], &[])
+                .expect("open_mint_ledger");
+        }
+
+        let setup = [
+            ix::init_user_profile(&user.pubkey()),
+            ix::init_trust_score(&user.pubkey()),
+            ix::init_trust_score(&recipient.pubkey()),
+        ];
+        p.send(&setup, &[&user, &recipient]).expect("profiles");
+
+        let fee_tiers = ix::set_merchant_fee_tiers(&authority, [0, 250, 500, 1_000]);
+        let register = ix::register_merchant(
+            &merchant_owner.pubkey(),
+            &authority,
+            payout,
+            mints.to_vec(),
+            1,
+            false,
+        );
+        let disputes = ix::set_dispute_config(&authority, Pubkey::default(), DISPUTE_WINDOW);
+        p.send_as_authority(&[fee_tiers, register, disputes], &[&merchant_owner])
+            .expect("merchant and dispute config");
+
+        // A deposit receipt, a pay receipt and a disputed merchant receipt.
+        let deposit = ix::spl_deposit_with_receipt(
+            &user.pubkey(),
+            &mints[0],
+            args::SplDepositWithReceipt {
+                amount: 500,
+                nonce: FIRST_NONCE,
+                reference: None,
+                memo: None,
+                memo_cpi: None,
+            },
+            FlowOptions::default(),
+        );
+        let deposit_receipt = pda::deposit_receipt(&user.pubkey(), FIRST_NONCE).0;
+        p.send(&[deposit], &[&user]).expect("deposit");
+        p.pay(&recipient.pubkey(), &mints[0], AMOUNT).expect("pay");
+        let merchant_pay = ix::pay_merchant(
+            &user.pubkey(),
+            &merchant_owner.pubkey(),
+            &payout,
+            &mints[0],
+            0,
+            args::PayMerchant {
+                amount: AMOUNT,
+                reference: None,
+            },
+            FlowOptions::default(),
+        );
+        let merchant = pda::merchant(&treasury(), &merchant_owner.pubkey()).0;
+        let merchant_receipt = pda::merchant_receipt(&merchant, 0).0;
+        let open = ix::open_dispute(
+            &user.pubkey(),
+            &merchant_receipt,
+            Some(&merchant_owner.pubkey()),
+            1,
+            [1; 32],
+        );
+        p.send(&[merchant_pay, open], &[&user])
+            .expect("pay_merchant and dispute");
+
+        // Look-alike token accounts and an insolvent second mint.
+        let mut fakes = Vec::new();
+        for mint in &mints {
+            for (owner, amount) in [
+                (treasury(), 1_000_000),
+                (user.pubkey(), USER_FUNDS),
+                (attacker.pubkey(), 0),
+            ] {
+                let fake = Pubkey::new_unique();
+                p.set_token_account(fake, mint, &owner, amount);
+                fakes.push(fake);
+            }
+        }
+        let shortfall = p.treasury_balance(&mints[1]) - 1;
+        p.set_token_account(treasury_ata(&mints[1]), &mints[1], &treasury(), shortfall);
+
+        let dispute = pda::dispute(&merchant_receipt).0;
+        let wallets = [
+            authority,
+            user.pubkey(),
+            recipient.pubkey(),
+            attacker.pubkey(),
+            merchant_owner.pubkey(),
+            payout,
+            treasury(),
+        ];
+        let mut candidates = wallets.to_vec();
+        for mint in &mints {
+            candidates.push(*mint);
+            candidates.extend(wallets.iter().map(|wallet| ata(wallet, mint)));
+            candidates.push(pda::mint_ledger(&treasury(), mint).0);
+        }
+        candidates.extend(fakes);
+        candidates.extend([
+            deposit_receipt,
+            pda::deposit_receipt(&user.pubkey(), FIRST_NONCE + 1).0,
+            pda::withdraw_receipt(&user.pubkey(), 0).0,
+            pda::pay_receipt(&treasury(), 0).0,
+            pda::pay_receipt(&treasury(), 1).0,
+            merchant_receipt,
+            pda::merchant_receipt(&merchant, 1).0,
+            dispute,
+            pda::dispute_receipt(&dispute).0,
+            merchant,
+            pda::user_profile(&user.pubkey()).0,
+            pda::trust_score(&user.pubkey()).0,
+            pda::trust_score(&recipient.pubkey()).0,
+            pda::treasury_config(&treasury()).0,
+            pda::dispute_config(&treasury()).0,
+            pda::denylist_entry(&treasury(), &attacker.pubkey()).0,
+            spl_token::ID,
+            anchor_spl::associated_token::ID,
+            anchor_spl::memo::ID,
+            solana_sdk_ids::system_program::ID,
+            solana_sdk_ids::sysvar::rent::ID,
+            protocol::ID,
+            Pubkey::new_unique(),
+        ]);
+
+        let signers = vec![
+            p.authority.insecure_clone(),
+            user.insecure_clone(),
+            recipient.insecure_clone(),
+            attacker.insecure_clone(),
+            merchant_owner.insecure_clone(),
+        ];
+        let genesis = p.svm.clone();
+        Self {
+            p,
+            user,
+            recipient,
+            attacker,
+            merchant_owner,
+            payout,
+            mints,
+            dispute,
+            candidates,
+            signers,
+            nonce: FIRST_NONCE + 1,
+            genesis,
+        }
+    }
+
+    /// Restore the chain to its state right after setup.
+    pub fn reset(&mut self) {
+        self.p.svm = self.genesis.clone();
+        self.nonce = FIRST_NONCE + 1;
+    }
+
+    /// A valid instruction for `target` on `mint` against the current state.
+    pub fn instruction(&self, target: Target, mint: &Pubkey) -> Instruction {
+        let authority = self.p.authority.pubkey();
+        let user = self.user.pubkey();
+        let recipient = self.recipient.pubkey();
+        let opts = FlowOptions {
+            user_profile: true,
+            trust_score: true,
+            ..FlowOptions::default()
+        };
+        match target {
+            Target::Deposit => ix::spl_deposit(&user, mint, AMOUNT, opts),
+            Target::DepositWithReceipt => ix::spl_deposit_with_receipt(
+                &user,
+                mint,
+                args::SplDepositWithReceipt {
+                    amount: AMOUNT,
+                    nonce: self.nonce,
+                    reference: None,
+                    memo: None,
+                    memo_cpi: None,
+                },
+                FlowOptions {
+                    memo_program: true,
+                    ..opts
+                },
+            ),
+            Target::Withdraw => ix::spl_withdraw(&authority, &user, mint, AMOUNT, opts),
+            Target::WithdrawWithReceipt => {
+                let tx_count = self
+                    .fetch::<UserProfile>(&pda::user_profile(&user).0)
+                    .map_or(0, |profile| profile.tx_count);
+                ix::spl_withdraw_with_receipt(
+                    &user,
+                    &authority,
+                    mint,
+                    tx_count,
+                    args::SplWithdrawWithReceipt {
+                        amount: AMOUNT,
+                        reference: None,
+                        memo: None,
+                    },
+                    opts,
+                )
+            }
+            Target::Pay => ix::spl_pay(
+                &authority,
+                &recipient,
+                mint,
+                self.treasury_state().pay_count,
+                args::SplPay {
+                    amount: AMOUNT,
+                    memo: None,
+                    reference: None,
+                    memo_cpi: None,
+                },
+                FlowOptions {
+                    user_profile: false,
+                    ..opts
+                },
+            ),
+            Target::PayWithReference => ix::spl_pay_with_reference(
+                &authority,
+                &recipient,
+                mint,
+                self.treasury_state().pay_count,
+                args::SplPayWithReference {
+                    amount: AMOUNT,
+                    reference: self.nonce.to_le_bytes().repeat(4).try_into().unwrap(),
+                    memo: None,
+                    memo_cpi: None,
+                },
+                FlowOptions {
+                    user_profile: false,
+                    ..opts
+                },
+            ),
+            Target::PayMerchant => {
+                let merchant = pda::merchant(&treasury(), &self.merchant_owner.pubkey()).0;
+                let pay_count = self
+                    .fetch::<Merchant>(&merchant)
+                    .map_or(0, |merchant| merchant.pay_count);
+                ix::pay_merchant(
+                    &user,
+                    &self.merchant_owner.pubkey(),
+                    &self.payout,
+                    mint,
+                    pay_count,
+                    args::PayMerchant {
+                        amount: AMOUNT,
+                        reference: None,
+                    },
+                    FlowOptions::default(),
+                )
+            }
+            Target::ResolveDispute => ix::resolve_dispute(
+                &authority,
+                &self.dispute,
+                &user,
+                &self.merchant_owner.pubkey(),
+                mint,
+                AMOUNT,
+                DisputeOptions::default(),
+            ),
+            Target::AssertSolvency => ix::assert_solvency(mint),
+        }
+    }
+
+    /// Run `attack` and check the outcome.
+    pub fn run(&mut self, attack: &Attack) -> Result<Outcome, Violation> {
+        let target = Target::ALL[attack.target % Target::ALL.len()];
+        let mint = self.mints[attack.mint % self.mints.len()];
+        let original = self.instruction(target, &mint);
+
+        let mut instruction = original.clone();
+        for substitution in &attack.substitutions {
+            let slot = substitution.slot % instruction.accounts.len();
+            let meta = &mut instruction.accounts[slot];
+            meta.pubkey = if meta.is_signer {
+                self.signers[substitution.candidate % self.signers.len()].pubkey()
+            } else {
+                self.candidates[substitution.candidate % self.candidates.len()]
+            };
+        }
+        self.nonce += 1;
+
+        let before = self.p.svm.clone();
+        let signers = self.signers_of(&instruction);
+        let result = self.p.send(
+            std::slice::from_ref(&instruction),
+            &signers.iter().collect::<Vec<_>>(),
+        );
+
+        let violation = |reason: String| Violation {
+            target,
+            instruction: instruction.clone(),
+            reason,
+        };
+        match result {
+            Err(failed) => {
+                if !same_accounts(&before, &self.p.svm) {
+                    return Err(violation("rejected transaction changed state".into()));
+                }
+                Ok(Outcome::Rejected(failed))
+            }
+            Ok(_) => {
+                self.check(target, &mint, &before, &original, &instruction)
+                    .map_err(violation)?;
+                Ok(Outcome::Accepted)
+            }
+        }
+    }
+
+    fn check(
+        &self,
+        target: Target,
+        mint: &Pubkey,
+        before: &Svm,
+        original: &Instruction,
+        instruction: &Instruction,
+    ) -> Result<(), String> {
+        let after = &self.p.svm;
+        let (old, new) = (token_accounts(before), token_accounts(after));
+        let signed: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| meta.pubkey)
+            .collect();
+
+        // Supply per mint.
+        if supply(&old) != supply(&new) {
+            return Err(format!(
+                "supply changed: {:?} -> {:?}",
+                supply(&old),
+                supply(&new)
+            ));
+        }
+
+        // Tokens only leave accounts whose owner signed.
+        for (address, account) in &old {
+            if amount(&new, address) >= account.amount {
+                continue;
+            }
+            let authorized = signed.contains(&account.owner)
+                || (account.owner == treasury()
+                    && *address == treasury_ata(&account.mint)
+                    && signed.contains(&self.p.authority.pubkey()));
+            if !authorized {
+                return Err(format!(
+                    "{address} (owner {}) lost {} tokens without its owner signing",
+                    account.owner,
+                    account.amount - amount(&new, address)
+                ));
+            }
+        }
+
+        // Ledgers move with their treasury ATA.
+        for mint in &self.mints {
+            let ledger = pda::mint_ledger(&treasury(), mint).0;
+            let expected = |svm: &Svm| {
+                fetch::<MintLedger>(svm, &ledger)
+                    .map_or(0, |l| l.expected_balance().unwrap() as i128)
+            };
+            let ata = treasury_ata(mint);
+            let books = expected(after) - expected(before);
+            let held = amount(&new, &ata) as i128 - amount(&old, &ata) as i128;
+            if books != held {
+                return Err(format!(
+                    "ledger of {mint} moved {books}, treasury ATA moved {held}"
+                ));
+            }
+        }
+
+        // Receipts are immutable; one pay at most; pause untouched.
+        for (address, account) in before.accounts() {
+            if account.owner == protocol::ID
+                && account.data.starts_with(Receipt::DISCRIMINATOR)
+                && after.account(&address).map(|a| a.data) != Some(account.data)
+            {
+                return Err(format!("receipt {address} changed"));
+            }
+        }
+        let (t0, t1) = (treasury_of(before), treasury_of(after));
+        if t1.pay_count > t0.pay_count + 1 || t1.pay_count < t0.pay_count || t1.paused != t0.paused
+        {
+            return Err(format!(
+                "treasury moved from pay_count {} paused {} to {} {}",
+                t0.pay_count, t0.paused, t1.pay_count, t1.paused
+            ));
+        }
+
+        // A single substitution must not change what the instruction does.
+        let changed = original
+            .accounts
+            .iter()
+            .zip(&instruction.accounts)
+            .filter(|(a, b)| a.pubkey != b.pubkey)
+            .count();
+        if changed == 1 {
+            let mut replay = before.clone();
+            let signers = self.signers_of(original);
+            if let Err(failed) = replay.send_transaction(
+                std::slice::from_ref(original),
+                &signers.iter().collect::<Vec<_>>(),
+            ) {
+                return Err(format!(
+                    "substitution made a failing instruction succeed ({:?})",
+                    failed.err
+                ));
+            }
+            let expected = token_accounts(&replay);
+            for address in expected.keys().chain(new.keys()) {
+                if amount(&expected, address) != amount(&new, address) {
+                    return Err(format!(
+                        "{address} holds {} instead of {}",
+                        amount(&new, address),
+                        amount(&expected, address)
+                    ));
+                }
+            }
+        }
+
+        // The solvency check only passes for a solvent mint.
+        if target == Target::AssertSolvency {
+            let ledger = fetch::<MintLedger>(after, &pda::mint_ledger(&treasury(), mint).0);
+            let expected = ledger.map_or(0, |l| l.expected_balance().unwrap());
+            let held = amount(&new, &treasury_ata(mint));
+            if held < expected {
+                return Err(format!(
+                    "assert_solvency passed with {held} held, {expected} owed"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn signers_of(&self, instruction: &Instruction) -> Vec<Keypair> {
+        let mut signers: Vec<Keypair> = Vec::new();
+        for meta in instruction.accounts.iter().filter(|meta| meta.is_signer) {
+            if signers.iter().any(|signer| signer.pubkey() == meta.pubkey) {
+                continue;
+            }
+            if let Some(signer) = self.signers.iter().find(|s| s.pubkey() == meta.pubkey) {
+                signers.push(signer.insecure_clone());
+            }
+        }
+        signers
+    }
+
+    fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        fetch(&self.p.svm, address)
+    }
+
+    fn treasury_state(&self) -> Treasury {
+        treasury_of(&self.p.svm)
+    }
+}
+
+fn fetch<T: AccountDeserialize>(svm: &Svm, address: &Pubkey) -> Option<T> {
+    let account = svm.account(address)?;
+    T::try_deserialize(&mut account.data.as_slice()).ok()
+}
+
+fn treasury_of(svm: &Svm) -> Treasury {
+    fetch(svm, &treasury()).expect("treasury")
+}
+
+fn same_accounts(a: &Svm, b: &Svm) -> bool {
+    let a: HashMap<_, _> = a.accounts().collect();
+    let b: HashMap<_, _> = b.accounts().collect();
+    a == b
+}
+
+/// Every SPL token account on the chain.
+fn token_accounts(svm: &Svm) -> BTreeMap<Pubkey, spl_token::state::Account> {
+    svm.accounts()
+        .filter(|(_, account)| account.owner == spl_token::ID)
+        .filter_map(|(address, account)| {
+            let state = spl_token::state::Account::unpack(&account.data).ok()?;
+            Some((address, state))
+        })
+        .collect()
+}
+
+fn amount(accounts: &BTreeMap<Pubkey, spl_token::state::Account>, address: &Pubkey) -> u64 {
+    accounts.get(address).map_or(0, |account| account.amount)
+}
+
+fn supply(accounts: &BTreeMap<Pubkey, spl_token::state::Account>) -> BTreeMap<Pubkey, u128> {
+    let mut supply = BTreeMap::new();
+    for account in accounts.values() {
+        *supply.entry(account.mint).or_default() += u128::from(account.amount);
+    }
+    supply
+}
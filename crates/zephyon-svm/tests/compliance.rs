@@ -0,0 +1,410 @@
+//! Risk rules, spending limits, denylist, KYC and the travel rule.
+
+#![cfg(protocol_so)]
+
+use anchor_lang::prelude::Pubkey;
+use protocol::errors::ErrorCode;
+use protocol::risk::{RISK_HIGH_VELOCITY, RISK_LARGE_AMOUNT};
+use protocol::state::{
+    DenylistEntry, KycAttestation, Receipt, ReceiptV2Ext, TravelRuleRecord, UserLimits,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda, RiskConfigParams, TravelRuleParams};
+use zephyon_svm::fixtures::*;
+
+const DAY: i64 = 86_400;
+
+#[test]
+fn set_risk_config_blocks_flagged_flows() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+
+    let params = RiskConfigParams {
+        rules_mask: RISK_LARGE_AMOUNT,
+        blocking_mask: RISK_LARGE_AMOUNT,
+        default_threshold: 100,
+        ..RiskConfigParams::default()
+    };
+    p.send_as_authority(&[ix::set_risk_config(&p.authority.pubkey(), params)], &[])
+        .unwrap();
+
+    p.deposit(&user, &mint, 100).unwrap();
+    expect_error(p.deposit(&user, &mint, 101), ErrorCode::RiskFlagBlocked);
+    assert_eq!(p.treasury_balance(&mint), 100);
+}
+
+#[test]
+fn blocking_velocity_requires_the_profile() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.send(&[ix::init_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+
+    let params = RiskConfigParams {
+        rules_mask: RISK_HIGH_VELOCITY,
+        blocking_mask: RISK_HIGH_VELOCITY,
+        velocity_window_slots: 10_000,
+        velocity_max_count: 2,
+        ..RiskConfigParams::default()
+    };
+    p.send_as_authority(&[ix::set_risk_config(&p.authority.pubkey(), params)], &[])
+        .unwrap();
+
+    // Leaving the profile out cannot skip the velocity window.
+    expect_error(p.deposit(&user, &mint, 10), ErrorCode::RiskFlagBlocked);
+
+    let opts = FlowOptions {
+        user_profile: true,
+        ..FlowOptions::default()
+    };
+    let deposit = |amount| ix::spl_deposit(&user.pubkey(), &mint, amount, opts);
+    p.send(&[deposit(10)], &[&user]).unwrap();
+    p.send(&[deposit(11)], &[&user]).unwrap();
+    expect_error(p.send(&[deposit(12)], &[&user]), ErrorCode::RiskFlagBlocked);
+    assert_eq!(p.treasury_balance(&mint), 21);
+}
+
+#[test]
+fn set_risk_config_rejects_blocking_outside_the_rules() {
+    let mut p = Protocol::new();
+
+    let params = RiskConfigParams {
+        rules_mask: 0,
+        blocking_mask: RISK_LARGE_AMOUNT,
+        ..RiskConfigParams::default()
+    };
+    let set = ix::set_risk_config(&p.authority.pubkey(), params);
+    expect_error(
+        p.send_as_authority(&[set], &[]),
+        ErrorCode::InvalidRiskConfig,
+    );
+}
+
+#[test]
+fn limit_tiers_cap_count_and_amount_per_window() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 10_000);
+
+    let tiers = [(0, 2, 0), (1, 0, 1_000)];
+    for (tier, max_count, max_amount) in tiers {
+        let set = ix::set_limit_tier(
+            &p.authority.pubkey(),
+            &mint,
+            args::SetLimitTier {
+                tier,
+                period_secs: DAY,
+                max_count,
+                max_amount,
+            },
+        );
+        p.send_as_authority(&[set], &[]).unwrap();
+    }
+
+    p.deposit(&user, &mint, 1).unwrap();
+    p.deposit(&user, &mint, 1).unwrap();
+    expect_error(p.deposit(&user, &mint, 1), ErrorCode::LimitCountExceeded);
+
+    let promote = ix::set_user_limit_tier(&p.authority.pubkey(), &user.pubkey(), &mint, 1);
+    p.send_as_authority(&[promote], &[]).unwrap();
+    let limits: UserLimits = p
+        .fetch(&pda::user_limits(&treasury(), &user.pubkey(), &mint).0)
+        .unwrap();
+    assert_eq!(limits.tier, 1);
+
+    p.deposit(&user, &mint, 998).unwrap();
+    expect_error(p.deposit(&user, &mint, 1), ErrorCode::LimitAmountExceeded);
+
+    // Two windows later the allowance is back.
+    p.svm.advance_time(2 * DAY);
+    p.deposit(&user, &mint, 1_000).unwrap();
+}
+
+#[test]
+fn limit_tiers_reject_out_of_range_tiers() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+
+    let set = ix::set_limit_tier(
+        &p.authority.pubkey(),
+        &mint,
+        args::SetLimitTier {
+            tier: 4,
+            period_secs: DAY,
+            max_count: 1,
+            max_amount: 0,
+        },
+    );
+    expect_error(
+        p.send_as_authority(&[set], &[]),
+        ErrorCode::InvalidLimitTier,
+    );
+
+    let assign = ix::set_user_limit_tier(&p.authority.pubkey(), &user.pubkey(), &mint, 4);
+    expect_error(
+        p.send_as_authority(&[assign], &[]),
+        ErrorCode::InvalidLimitTier,
+    );
+}
+
+#[test]
+fn denylist_blocks_deposits_and_payouts_until_removed() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 100);
+    p.fund_treasury(&mint, 100);
+
+    let officer = p.user();
+    let delegate = ix::set_compliance_authority(&p.authority.pubkey(), officer.pubkey());
+    p.send_as_authority(&[delegate], &[]).unwrap();
+
+    let add = ix::add_denylist_entry(&officer.pubkey(), &user.pubkey(), 7, 0, true);
+    p.send(&[add], &[&officer]).unwrap();
+    let entry: DenylistEntry = p
+        .fetch(&pda::denylist_entry(&treasury(), &user.pubkey()).0)
+        .unwrap();
+    assert_eq!((entry.reason_code, entry.added_by), (7, officer.pubkey()));
+
+    expect_error(p.deposit(&user, &mint, 1), ErrorCode::WalletDenylisted);
+    expect_error(
+        p.pay(&user.pubkey(), &mint, 1),
+        ErrorCode::RecipientDenylisted,
+    );
+
+    let remove = ix::remove_denylist_entry(&officer.pubkey(), &user.pubkey(), true);
+    p.send(&[remove], &[&officer]).unwrap();
+    p.deposit(&user, &mint, 1).unwrap();
+    p.pay(&user.pubkey(), &mint, 1).unwrap();
+}
+
+#[test]
+fn denylist_entries_expire() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 100);
+
+    let now = p.svm.clock().unix_timestamp;
+    let stale = ix::add_denylist_entry(&p.authority.pubkey(), &user.pubkey(), 1, now, false);
+    expect_error(
+        p.send_as_authority(&[stale], &[]),
+        ErrorCode::InvalidDenylistEntry,
+    );
+
+    let add = ix::add_denylist_entry(&p.authority.pubkey(), &user.pubkey(), 1, now + 60, false);
+    p.send_as_authority(&[add], &[]).unwrap();
+    expect_error(p.deposit(&user, &mint, 1), ErrorCode::WalletDenylisted);
+
+    p.svm.advance_time(60);
+    p.deposit(&user, &mint, 1).unwrap();
+}
+
+#[test]
+fn compliance_actions_reject_outsiders() {
+    let mut p = Protocol::new();
+    let outsider = p.user();
+    let wallet = Pubkey::new_unique();
+
+    let add = ix::add_denylist_entry(&outsider.pubkey(), &wallet, 1, 0, false);
+    expect_error(
+        p.send(&[add], &[&outsider]),
+        ErrorCode::UnauthorizedCompliance,
+    );
+
+    let issuer = ix::add_kyc_issuer(&outsider.pubkey(), &outsider.pubkey(), false);
+    expect_error(
+        p.send(&[issuer], &[&outsider]),
+        ErrorCode::UnauthorizedCompliance,
+    );
+
+    let delegate = ix::set_compliance_authority(&outsider.pubkey(), outsider.pubkey());
+    expect_error(
+        p.send(&[delegate], &[&outsider]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+}
+
+#[test]
+fn kyc_gates_large_withdrawals() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    let issuer = p.user();
+    let config = ix::set_kyc_config(&p.authority.pubkey(), KycAttestation::LEVEL_ENHANCED, 100);
+    let add = ix::add_kyc_issuer(&p.authority.pubkey(), &issuer.pubkey(), false);
+    p.send_as_authority(&[config, add], &[]).unwrap();
+
+    p.withdraw(&user.pubkey(), &mint, 100).unwrap();
+    expect_error(
+        p.withdraw(&user.pubkey(), &mint, 101),
+        ErrorCode::KycAttestationRequired,
+    );
+
+    let expires_at = p.svm.clock().unix_timestamp + DAY;
+    let issue = |level| {
+        ix::issue_kyc_attestation(&issuer.pubkey(), &user.pubkey(), level, *b"US", expires_at)
+    };
+    p.send(&[issue(KycAttestation::LEVEL_BASIC)], &[&issuer])
+        .unwrap();
+    expect_error(
+        p.withdraw(&user.pubkey(), &mint, 101),
+        ErrorCode::KycLevelTooLow,
+    );
+
+    // The issuer revokes and re-issues at a higher level.
+    let revoke = ix::revoke_kyc_attestation(&issuer.pubkey(), &user.pubkey(), false);
+    p.send(&[revoke, issue(KycAttestation::LEVEL_ENHANCED)], &[&issuer])
+        .unwrap();
+    p.withdraw(&user.pubkey(), &mint, 101).unwrap();
+
+    p.svm.advance_time(DAY);
+    expect_error(
+        p.withdraw(&user.pubkey(), &mint, 101),
+        ErrorCode::KycAttestationRequired,
+    );
+}
+
+#[test]
+fn kyc_issuers_and_attestations_are_validated() {
+    let mut p = Protocol::new();
+    let issuer = p.user();
+    let wallet = Pubkey::new_unique();
+    let expires_at = p.svm.clock().unix_timestamp + DAY;
+
+    let config = ix::set_kyc_config(&p.authority.pubkey(), KycAttestation::MAX_LEVEL + 1, 0);
+    expect_error(
+        p.send_as_authority(&[config], &[]),
+        ErrorCode::InvalidKycAttestation,
+    );
+
+    let add = ix::add_kyc_issuer(&p.authority.pubkey(), &issuer.pubkey(), false);
+    p.send_as_authority(&[add], &[]).unwrap();
+
+    for (level, jurisdiction, expires_at) in [
+        (0, *b"US", expires_at),
+        (KycAttestation::MAX_LEVEL + 1, *b"US", expires_at),
+        (1, *b"us", expires_at),
+        (1, *b"US", p.svm.clock().unix_timestamp),
+    ] {
+        let issue =
+            ix::issue_kyc_attestation(&issuer.pubkey(), &wallet, level, jurisdiction, expires_at);
+        expect_error(
+            p.send(&[issue], &[&issuer]),
+            ErrorCode::InvalidKycAttestation,
+        );
+    }
+
+    // A removed issuer can no longer attest.
+    let remove = ix::remove_kyc_issuer(&p.authority.pubkey(), &issuer.pubkey(), false);
+    p.send_as_authority(&[remove], &[]).unwrap();
+    assert!(!p.exists(&pda::kyc_issuer(&treasury(), &issuer.pubkey()).0));
+    let issue = ix::issue_kyc_attestation(&issuer.pubkey(), &wallet, 1, *b"US", expires_at);
+    assert!(p.send(&[issue], &[&issuer]).is_err());
+}
+
+fn travel_rule_params(
+    record_id: [u8; 32],
+    beneficiary: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+) -> TravelRuleParams {
+    TravelRuleParams {
+        record_id,
+        beneficiary,
+        mint,
+        amount,
+        originator_vasp: [1; 32],
+        beneficiary_vasp: [2; 32],
+        pii_hash: [3; 32],
+    }
+}
+
+#[test]
+fn travel_rule_records_are_required_and_consumed_once() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    let config = ix::set_travel_rule_config(&p.authority.pubkey(), 100);
+    p.send_as_authority(&[config], &[]).unwrap();
+    expect_error(
+        p.pay(&recipient.pubkey(), &mint, 101),
+        ErrorCode::TravelRuleRecordRequired,
+    );
+
+    // Records are keyed by the receipt the transfer will create.
+    let receipt = pda::pay_receipt(&treasury(), p.pay_count()).0;
+    let record_id = receipt.to_bytes();
+    let params = travel_rule_params(record_id, recipient.pubkey(), mint, 101);
+    let record = ix::record_travel_rule(&p.authority.pubkey(), params);
+    p.send_as_authority(&[record], &[]).unwrap();
+
+    let pay = |amount| {
+        ix::spl_pay(
+            &p.authority.pubkey(),
+            &recipient.pubkey(),
+            &mint,
+            0,
+            args::SplPay {
+                amount,
+                memo: None,
+                reference: None,
+                memo_cpi: None,
+            },
+            FlowOptions {
+                travel_rule: Some(pda::travel_rule(&treasury(), &record_id).0),
+                ..FlowOptions::default()
+            },
+        )
+    };
+    let wrong_amount = pay(102);
+    let exact = pay(101);
+    expect_error(
+        p.send_as_authority(&[wrong_amount], &[]),
+        ErrorCode::TravelRuleRecordMismatch,
+    );
+    p.send_as_authority(&[exact], &[]).unwrap();
+
+    let record: TravelRuleRecord = p
+        .fetch(&pda::travel_rule(&treasury(), &record_id).0)
+        .unwrap();
+    assert_eq!(record.receipt, receipt);
+    assert!(record.consumed_at > 0);
+    let receipt: Receipt = p.fetch(&receipt).unwrap();
+    assert_ne!(receipt.v2.flags & ReceiptV2Ext::FLAG_HAS_TRAVEL_RULE, 0);
+}
+
+#[test]
+fn record_travel_rule_validates_params() {
+    let mut p = Protocol::new();
+    let mint = Pubkey::new_unique();
+
+    let mut params = travel_rule_params([9; 32], Pubkey::new_unique(), mint, 1);
+    params.pii_hash = [0; 32];
+    let record = ix::record_travel_rule(&p.authority.pubkey(), params);
+    expect_error(
+        p.send_as_authority(&[record], &[]),
+        ErrorCode::InvalidTravelRuleRecord,
+    );
+
+    let outsider = Keypair::new();
+    p.svm.airdrop(&outsider.pubkey(), LAMPORTS_PER_SOL);
+    let params = travel_rule_params([9; 32], Pubkey::new_unique(), mint, 1);
+    let record = ix::record_travel_rule(&outsider.pubkey(), params);
+    expect_error(
+        p.send(&[record], &[&outsider]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+}
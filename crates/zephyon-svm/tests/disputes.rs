@@ -0,0 +1,544 @@
+//! Disputes against merchant payment and pay receipts.
+
+#![cfg(protocol_so)]
+
+use anchor_lang::prelude::Pubkey;
+use protocol::errors::ErrorCode;
+use protocol::state::{Dispute, Merchant, Receipt, TrustScore, UserProfile};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, DisputeOptions, FlowOptions};
+use zephyon_client::{args, pda};
+use zephyon_svm::fixtures::*;
+
+const WINDOW: i64 = 7 * 86_400;
+
+struct Paid {
+    p: Protocol,
+    customer: Keypair,
+    owner: Keypair,
+    mint: Pubkey,
+    merchant: Pubkey,
+    receipt: Pubkey,
+}
+
+/// A customer payment of 500 to a fee-free merchant, a treasury holding
+/// 1_000 of the mint, and disputes enabled.
+fn paid() -> Paid {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let owner = p.user();
+    let customer = p.user();
+    p.mint_to(&customer.pubkey(), &mint, 1_000);
+    p.fund_treasury(&mint, 1_000);
+
+    let register = ix::register_merchant(
+        &owner.pubkey(),
+        &p.authority.pubkey(),
+        owner.pubkey(),
+        vec![mint],
+        0,
+        false,
+    );
+    let config = ix::set_dispute_config(&p.authority.pubkey(), Pubkey::default(), WINDOW);
+    p.send_as_authority(&[register, config], &[&owner]).unwrap();
+
+    let pay = ix::pay_merchant(
+        &customer.pubkey(),
+        &owner.pubkey(),
+        &owner.pubkey(),
+        &mint,
+        0,
+        args::PayMerchant {
+            amount: 500,
+            reference: None,
+        },
+        FlowOptions::default(),
+    );
+    p.send(&[pay], &[&customer]).unwrap();
+
+    let merchant = pda::merchant(&treasury(), &owner.pubkey()).0;
+    let receipt = pda::merchant_receipt(&merchant, 0).0;
+    Paid {
+        p,
+        customer,
+        owner,
+        mint,
+        merchant,
+        receipt,
+    }
+}
+
+#[test]
+fn merchant_disputes_refund_the_payer_from_the_treasury() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        mint,
+        merchant,
+        receipt,
+    } = paid();
+    let dispute = pda::dispute(&receipt).0;
+
+    let open = ix::open_dispute(
+        &customer.pubkey(),
+        &receipt,
+        Some(&owner.pubkey()),
+        3,
+        [1; 32],
+    );
+    p.send(&[open], &[&customer]).unwrap();
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.status, Dispute::STATUS_OPEN);
+    assert_eq!(state.payer, customer.pubkey());
+    assert_eq!(state.respondent, owner.pubkey());
+    assert_eq!((state.amount, state.reason_code), (500, 3));
+
+    let answer = ix::submit_dispute_evidence(&owner.pubkey(), &dispute, [2; 32]);
+    p.send(&[answer], &[&owner]).unwrap();
+
+    p.send(&[ix::init_trust_score(&owner.pubkey())], &[&owner])
+        .unwrap();
+    let resolve = ix::resolve_dispute(
+        &p.authority.pubkey(),
+        &dispute,
+        &customer.pubkey(),
+        &owner.pubkey(),
+        &mint,
+        200,
+        DisputeOptions {
+            respondent_trust: true,
+            ..DisputeOptions::default()
+        },
+    );
+    p.send_as_authority(&[resolve], &[]).unwrap();
+
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.status, Dispute::STATUS_REFUNDED);
+    assert_eq!(state.respondent_evidence, [2; 32]);
+    assert_eq!(state.resolution_receipt, pda::dispute_receipt(&dispute).0);
+    let resolution: Receipt = p.fetch(&state.resolution_receipt).unwrap();
+    assert_eq!(resolution.direction, Receipt::DIR_DISPUTE);
+    assert_eq!(resolution.amount, 200);
+    assert_eq!(resolution.v2.reference, receipt.to_bytes());
+
+    // The treasury fronts the refund and books it against the merchant.
+    assert_eq!(p.balance(&ata(&customer.pubkey(), &mint)), 700);
+    assert_eq!(p.balance(&ata(&owner.pubkey(), &mint)), 500);
+    assert_eq!(p.treasury_balance(&mint), 800);
+    let state: Merchant = p.fetch(&merchant).unwrap();
+    assert_eq!(state.settlements[0].refunded, 200);
+    let trust: TrustScore = p.fetch(&pda::trust_score(&owner.pubkey()).0).unwrap();
+    assert_eq!(trust.disputes_lost, 1);
+    assert_eq!(trust.dispute_penalty, TrustScore::DISPUTE_LOST_PENALTY);
+
+    // Resolved disputes are final.
+    let again = ix::submit_dispute_evidence(&customer.pubkey(), &dispute, [3; 32]);
+    expect_error(p.send(&[again], &[&customer]), ErrorCode::DisputeNotOpen);
+}
+
+#[test]
+fn refunds_to_denylisted_payers_are_rejected() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        mint,
+        receipt,
+        ..
+    } = paid();
+    let dispute = pda::dispute(&receipt).0;
+    let open = ix::open_dispute(
+        &customer.pubkey(),
+        &receipt,
+        Some(&owner.pubkey()),
+        3,
+        [1; 32],
+    );
+    p.send(&[open], &[&customer]).unwrap();
+
+    // Listed after paying.
+    let add = ix::add_denylist_entry(&p.authority.pubkey(), &customer.pubkey(), 1, 0, false);
+    p.send_as_authority(&[add], &[]).unwrap();
+
+    let authority = p.authority.pubkey();
+    let resolve = |refund| {
+        ix::resolve_dispute(
+            &authority,
+            &dispute,
+            &customer.pubkey(),
+            &owner.pubkey(),
+            &mint,
+            refund,
+            DisputeOptions::default(),
+        )
+    };
+    let refund = resolve(200);
+    expect_error(
+        p.send_as_authority(&[refund], &[]),
+        ErrorCode::RecipientDenylisted,
+    );
+    assert_eq!(p.treasury_balance(&mint), 1_000);
+    assert_eq!(p.balance(&ata(&customer.pubkey(), &mint)), 500);
+
+    // The dispute can still be rejected.
+    let reject = resolve(0);
+    p.send_as_authority(&[reject], &[]).unwrap();
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.status, Dispute::STATUS_REJECTED);
+}
+
+#[test]
+fn refunds_credit_the_payer_xp() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        mint,
+        receipt,
+        ..
+    } = paid();
+    let dispute = pda::dispute(&receipt).0;
+    p.send(&[ix::init_user_profile(&customer.pubkey())], &[&customer])
+        .unwrap();
+    let config = ix::set_xp_config(
+        &p.authority.pubkey(),
+        args::SetXpConfig {
+            xp_per_deposit: 0,
+            xp_per_withdraw: 9,
+            xp_per_pay: 0,
+            xp_min_amount: 0,
+            xp_epoch_cap: 0,
+        },
+    );
+    p.send_as_authority(&[config], &[]).unwrap();
+
+    let open = ix::open_dispute(
+        &customer.pubkey(),
+        &receipt,
+        Some(&owner.pubkey()),
+        1,
+        [1; 32],
+    );
+    p.send(&[open], &[&customer]).unwrap();
+    let resolve = ix::resolve_dispute(
+        &p.authority.pubkey(),
+        &dispute,
+        &customer.pubkey(),
+        &owner.pubkey(),
+        &mint,
+        50,
+        DisputeOptions {
+            payer_profile: true,
+            ..DisputeOptions::default()
+        },
+    );
+    p.send_as_authority(&[resolve], &[]).unwrap();
+
+    let profile: UserProfile = p.fetch(&pda::user_profile(&customer.pubkey()).0).unwrap();
+    assert_eq!(profile.xp, 9);
+}
+
+#[test]
+fn open_dispute_is_limited_to_the_payer_within_the_window() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        receipt,
+        ..
+    } = paid();
+    let owner = owner.pubkey();
+
+    let outsider = p.user();
+    let open = ix::open_dispute(&outsider.pubkey(), &receipt, Some(&owner), 1, [1; 32]);
+    expect_error(
+        p.send(&[open], &[&outsider]),
+        ErrorCode::UnauthorizedDispute,
+    );
+
+    let open = ix::open_dispute(&customer.pubkey(), &receipt, Some(&owner), 1, [0; 32]);
+    expect_error(
+        p.send(&[open], &[&customer]),
+        ErrorCode::InvalidDisputeEvidence,
+    );
+
+    p.svm.advance_time(WINDOW + 1);
+    let open = ix::open_dispute(&customer.pubkey(), &receipt, Some(&owner), 1, [1; 32]);
+    expect_error(
+        p.send(&[open], &[&customer]),
+        ErrorCode::DisputeWindowClosed,
+    );
+}
+
+#[test]
+fn merchant_disputes_require_the_issuing_merchant() {
+    let Paid {
+        mut p,
+        customer,
+        mint,
+        receipt,
+        ..
+    } = paid();
+
+    let open = ix::open_dispute(&customer.pubkey(), &receipt, None, 1, [1; 32]);
+    expect_error(p.send(&[open], &[&customer]), ErrorCode::MerchantRequired);
+
+    let other = p.user();
+    let register = ix::register_merchant(
+        &other.pubkey(),
+        &p.authority.pubkey(),
+        other.pubkey(),
+        vec![mint],
+        0,
+        false,
+    );
+    p.send_as_authority(&[register], &[&other]).unwrap();
+    let open = ix::open_dispute(
+        &customer.pubkey(),
+        &receipt,
+        Some(&other.pubkey()),
+        1,
+        [1; 32],
+    );
+    expect_error(p.send(&[open], &[&customer]), ErrorCode::MerchantRequired);
+}
+
+#[test]
+fn open_dispute_requires_enabled_disputes_and_a_disputable_receipt() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        mint,
+        receipt,
+        ..
+    } = paid();
+
+    let disable = ix::set_dispute_config(&p.authority.pubkey(), Pubkey::default(), 0);
+    p.send_as_authority(&[disable], &[]).unwrap();
+    let open = ix::open_dispute(
+        &customer.pubkey(),
+        &receipt,
+        Some(&owner.pubkey()),
+        1,
+        [1; 32],
+    );
+    expect_error(p.send(&[open], &[&customer]), ErrorCode::DisputesDisabled);
+
+    let enable = ix::set_dispute_config(&p.authority.pubkey(), Pubkey::default(), WINDOW);
+    let negative = ix::set_dispute_config(&p.authority.pubkey(), Pubkey::default(), -1);
+    p.send_as_authority(&[enable], &[]).unwrap();
+    expect_error(
+        p.send_as_authority(&[negative], &[]),
+        ErrorCode::InvalidDisputeConfig,
+    );
+
+    // Deposits and withdrawals cannot be disputed.
+    let deposit = ix::spl_deposit_with_receipt(
+        &customer.pubkey(),
+        &mint,
+        args::SplDepositWithReceipt {
+            amount: 10,
+            nonce: 1,
+            reference: None,
+            memo: None,
+            memo_cpi: None,
+        },
+        FlowOptions::default(),
+    );
+    let withdraw = ix::spl_withdraw_with_receipt(
+        &customer.pubkey(),
+        &p.authority.pubkey(),
+        &mint,
+        0,
+        args::SplWithdrawWithReceipt {
+            amount: 1,
+            reference: None,
+            memo: None,
+        },
+        FlowOptions::default(),
+    );
+    p.send_as_authority(&[deposit, withdraw], &[&customer])
+        .unwrap();
+    for receipt in [
+        pda::deposit_receipt(&customer.pubkey(), 1).0,
+        pda::withdraw_receipt(&customer.pubkey(), 0).0,
+    ] {
+        let open = ix::open_dispute(&customer.pubkey(), &receipt, None, 1, [1; 32]);
+        expect_error(
+            p.send(&[open], &[&customer]),
+            ErrorCode::ReceiptNotDisputable,
+        );
+    }
+}
+
+#[test]
+fn submit_dispute_evidence_is_limited_to_the_parties() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        receipt,
+        ..
+    } = paid();
+    let dispute = pda::dispute(&receipt).0;
+    p.send(
+        &[ix::open_dispute(
+            &customer.pubkey(),
+            &receipt,
+            Some(&owner.pubkey()),
+            1,
+            [1; 32],
+        )],
+        &[&customer],
+    )
+    .unwrap();
+
+    let outsider = p.user();
+    let submit = ix::submit_dispute_evidence(&outsider.pubkey(), &dispute, [9; 32]);
+    expect_error(
+        p.send(&[submit], &[&outsider]),
+        ErrorCode::UnauthorizedDispute,
+    );
+
+    let submit = ix::submit_dispute_evidence(&customer.pubkey(), &dispute, [4; 32]);
+    p.send(&[submit], &[&customer]).unwrap();
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.payer_evidence, [4; 32]);
+}
+
+#[test]
+fn resolve_dispute_enforces_the_arbiter_and_refund_bounds() {
+    let Paid {
+        mut p,
+        customer,
+        owner,
+        mint,
+        receipt,
+        ..
+    } = paid();
+    let dispute = pda::dispute(&receipt).0;
+    p.send(
+        &[ix::open_dispute(
+            &customer.pubkey(),
+            &receipt,
+            Some(&owner.pubkey()),
+            1,
+            [1; 32],
+        )],
+        &[&customer],
+    )
+    .unwrap();
+
+    let resolve = ix::resolve_dispute(
+        &customer.pubkey(),
+        &dispute,
+        &customer.pubkey(),
+        &owner.pubkey(),
+        &mint,
+        500,
+        DisputeOptions::default(),
+    );
+    expect_error(
+        p.send(&[resolve], &[&customer]),
+        ErrorCode::UnauthorizedDispute,
+    );
+
+    let arbiter = p.user();
+    let config = ix::set_dispute_config(&p.authority.pubkey(), arbiter.pubkey(), WINDOW);
+    p.send_as_authority(&[config], &[]).unwrap();
+
+    let resolve = |amount| {
+        ix::resolve_dispute(
+            &arbiter.pubkey(),
+            &dispute,
+            &customer.pubkey(),
+            &owner.pubkey(),
+            &mint,
+            amount,
+            DisputeOptions::default(),
+        )
+    };
+    let (too_much, paused, full) = (resolve(501), resolve(500), resolve(500));
+    expect_error(
+        p.send(&[too_much], &[&arbiter]),
+        ErrorCode::InvalidRefundAmount,
+    );
+
+    p.set_paused(true).unwrap();
+    expect_error(p.send(&[paused], &[&arbiter]), ErrorCode::ProtocolPaused);
+    assert_eq!(p.treasury_balance(&mint), 1_000);
+
+    p.set_paused(false).unwrap();
+    p.send(&[full], &[&arbiter]).unwrap();
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.resolved_by, arbiter.pubkey());
+    assert_eq!(p.treasury_balance(&mint), 500);
+}
+
+#[test]
+fn pay_disputes_resolve_without_a_refund() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 100);
+    p.send(&[ix::init_trust_score(&recipient.pubkey())], &[&recipient])
+        .unwrap();
+    let config = ix::set_dispute_config(&p.authority.pubkey(), Pubkey::default(), WINDOW);
+    p.send_as_authority(&[config], &[]).unwrap();
+    p.pay(&recipient.pubkey(), &mint, 40).unwrap();
+
+    // Only the treasury, which paid, can dispute a payout.
+    let receipt = pda::pay_receipt(&treasury(), 0).0;
+    let dispute = pda::dispute(&receipt).0;
+    let open = ix::open_dispute(&recipient.pubkey(), &receipt, None, 1, [1; 32]);
+    expect_error(
+        p.send(&[open], &[&recipient]),
+        ErrorCode::UnauthorizedDispute,
+    );
+    let open = ix::open_dispute(&p.authority.pubkey(), &receipt, None, 1, [1; 32]);
+    p.send_as_authority(&[open], &[]).unwrap();
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.respondent, recipient.pubkey());
+
+    // Even when the recipient runs a merchant, a payout is not refundable.
+    let authority = p.authority.pubkey();
+    let register = ix::register_merchant(
+        &recipient.pubkey(),
+        &authority,
+        recipient.pubkey(),
+        vec![mint],
+        0,
+        false,
+    );
+    p.send_as_authority(&[register], &[&recipient]).unwrap();
+    p.create_ata(&authority, &mint);
+    let resolve = |amount| {
+        ix::resolve_dispute(
+            &authority,
+            &dispute,
+            &authority,
+            &recipient.pubkey(),
+            &mint,
+            amount,
+            DisputeOptions {
+                respondent_trust: true,
+                ..DisputeOptions::default()
+            },
+        )
+    };
+    let (refund, reject) = (resolve(40), resolve(0));
+    expect_error(
+        p.send_as_authority(&[refund], &[]),
+        ErrorCode::InvalidRefundAmount,
+    );
+
+    p.send_as_authority(&[reject], &[]).unwrap();
+    let state: Dispute = p.fetch(&dispute).unwrap();
+    assert_eq!(state.status, Dispute::STATUS_REJECTED);
+    let trust: TrustScore = p.fetch(&pda::trust_score(&recipient.pubkey()).0).unwrap();
+    assert_eq!((trust.disputes_won, trust.disputes_lost), (1, 0));
+    assert_eq!(p.treasury_balance(&mint), 60);
+}
@@ -0,0 +1,350 @@
+//! Value-moving flows: deposits, withdrawals and payouts, with and without
+//! receipts.
+
+#![cfg(protocol_so)]
+
+use anchor_spl::memo;
+use protocol::errors::ErrorCode;
+use protocol::memo::MemoCpiMode;
+use protocol::state::{Receipt, ReferenceIndex, UserProfile};
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda};
+use zephyon_svm::fixtures::*;
+
+#[test]
+fn spl_deposit_moves_tokens_into_the_treasury() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    let user_ata = p.mint_to(&user.pubkey(), &mint, 1_000);
+
+    p.deposit(&user, &mint, 400).unwrap();
+
+    assert_eq!(p.balance(&user_ata), 600);
+    assert_eq!(p.treasury_balance(&mint), 400);
+}
+
+#[test]
+fn spl_deposit_rejects_zero_and_overdrafts() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 100);
+
+    expect_error(p.deposit(&user, &mint, 0), ErrorCode::InvalidAmount);
+    expect_error(p.deposit(&user, &mint, 101), ErrorCode::InsufficientFunds);
+    assert_eq!(p.treasury_balance(&mint), 0);
+}
+
+#[test]
+fn spl_deposit_records_profile_activity() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 100);
+    p.send(&[ix::init_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+
+    let opts = FlowOptions {
+        user_profile: true,
+        ..FlowOptions::default()
+    };
+    let deposit = ix::spl_deposit(&user.pubkey(), &mint, 40, opts);
+    p.send(&[deposit], &[&user]).unwrap();
+
+    let profile: UserProfile = p.fetch(&pda::user_profile(&user.pubkey()).0).unwrap();
+    assert_eq!(profile.tx_count, 1);
+    assert_eq!(profile.deposit_count, 1);
+}
+
+#[test]
+fn spl_deposit_with_receipt_writes_the_receipt_and_memo() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.create_ata(&treasury(), &mint);
+
+    let deposit = ix::spl_deposit_with_receipt(
+        &user.pubkey(),
+        &mint,
+        args::SplDepositWithReceipt {
+            amount: 250,
+            nonce: 7,
+            reference: Some([9; 32]),
+            memo: Some(b"invoice 42".to_vec()),
+            memo_cpi: Some(MemoCpiMode::Raw),
+        },
+        FlowOptions {
+            memo_program: true,
+            ..FlowOptions::default()
+        },
+    );
+    let meta = p.send(&[deposit], &[&user]).unwrap();
+    let memo_invoke = format!("Program {} invoke [2]", memo::ID);
+    assert!(meta.logs.contains(&memo_invoke));
+
+    let receipt: Receipt = p.fetch(&pda::deposit_receipt(&user.pubkey(), 7).0).unwrap();
+    assert_eq!(receipt.direction, Receipt::DIR_DEPOSIT);
+    assert_eq!(receipt.user, user.pubkey());
+    assert_eq!(receipt.amount, 250);
+    assert_eq!(receipt.tx_count, 7);
+    assert_eq!(receipt.version, Receipt::CURRENT_VERSION);
+    assert_eq!(receipt.v2.reference, [9; 32]);
+    assert_eq!(p.treasury_balance(&mint), 250);
+}
+
+#[test]
+fn spl_deposit_with_receipt_rejects_a_reused_nonce() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.create_ata(&treasury(), &mint);
+
+    let deposit = |amount| {
+        ix::spl_deposit_with_receipt(
+            &user.pubkey(),
+            &mint,
+            args::SplDepositWithReceipt {
+                amount,
+                nonce: 1,
+                reference: None,
+                memo: None,
+                memo_cpi: None,
+            },
+            FlowOptions::default(),
+        )
+    };
+    p.send(&[deposit(10)], &[&user]).unwrap();
+    assert!(p.send(&[deposit(20)], &[&user]).is_err());
+    assert_eq!(p.treasury_balance(&mint), 10);
+}
+
+#[test]
+fn spl_deposit_with_receipt_requires_the_memo_program_for_memo_cpi() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.create_ata(&treasury(), &mint);
+
+    let deposit = ix::spl_deposit_with_receipt(
+        &user.pubkey(),
+        &mint,
+        args::SplDepositWithReceipt {
+            amount: 1,
+            nonce: 1,
+            reference: None,
+            memo: None,
+            memo_cpi: Some(MemoCpiMode::ReceiptLink),
+        },
+        FlowOptions::default(),
+    );
+    expect_error(p.send(&[deposit], &[&user]), ErrorCode::MemoProgramRequired);
+}
+
+#[test]
+fn spl_withdraw_is_authority_only() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.fund_treasury(&mint, 500);
+
+    p.withdraw(&user.pubkey(), &mint, 200).unwrap();
+    assert_eq!(p.balance(&ata(&user.pubkey(), &mint)), 200);
+    assert_eq!(p.treasury_balance(&mint), 300);
+
+    let withdraw = ix::spl_withdraw(
+        &user.pubkey(),
+        &user.pubkey(),
+        &mint,
+        1,
+        FlowOptions::default(),
+    );
+    expect_error(
+        p.send(&[withdraw], &[&user]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+    assert_eq!(p.treasury_balance(&mint), 300);
+}
+
+#[test]
+fn spl_withdraw_with_receipt_advances_the_profile() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.fund_treasury(&mint, 500);
+
+    for (tx_count, amount) in [(0, 100), (1, 50)] {
+        let withdraw = ix::spl_withdraw_with_receipt(
+            &user.pubkey(),
+            &p.authority.pubkey(),
+            &mint,
+            tx_count,
+            args::SplWithdrawWithReceipt {
+                amount,
+                reference: None,
+                memo: Some(b"payout".to_vec()),
+            },
+            FlowOptions::default(),
+        );
+        p.send_as_authority(&[withdraw], &[&user]).unwrap();
+
+        let receipt: Receipt = p
+            .fetch(&pda::withdraw_receipt(&user.pubkey(), tx_count).0)
+            .unwrap();
+        assert_eq!(receipt.direction, Receipt::DIR_WITHDRAW);
+        assert_eq!(receipt.amount, amount);
+        assert_eq!(receipt.tx_count, tx_count);
+    }
+
+    let profile: UserProfile = p.fetch(&pda::user_profile(&user.pubkey()).0).unwrap();
+    assert_eq!(profile.tx_count, 2);
+    assert_eq!(profile.withdraw_count, 2);
+    assert_eq!(p.treasury_balance(&mint), 350);
+}
+
+#[test]
+fn spl_withdraw_with_receipt_rejects_an_unauthorized_cosigner() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    let impostor = p.user();
+    p.fund_treasury(&mint, 500);
+
+    let withdraw = ix::spl_withdraw_with_receipt(
+        &user.pubkey(),
+        &impostor.pubkey(),
+        &mint,
+        0,
+        args::SplWithdrawWithReceipt {
+            amount: 100,
+            reference: None,
+            memo: None,
+        },
+        FlowOptions::default(),
+    );
+    expect_error(
+        p.send(&[withdraw], &[&user, &impostor]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+    assert_eq!(p.treasury_balance(&mint), 500);
+}
+
+#[test]
+fn spl_pay_writes_a_receipt_per_pay_count() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    for n in 0..3 {
+        p.pay(&recipient.pubkey(), &mint, 10 + n).unwrap();
+        let receipt: Receipt = p.fetch(&pda::pay_receipt(&treasury(), n).0).unwrap();
+        assert_eq!(receipt.direction, Receipt::DIR_PAY);
+        assert_eq!(receipt.user, recipient.pubkey());
+        assert_eq!(receipt.amount, 10 + n);
+        assert_eq!(receipt.tx_count, n);
+    }
+
+    assert_eq!(p.pay_count(), 3);
+    assert_eq!(p.balance(&ata(&recipient.pubkey(), &mint)), 33);
+    assert_eq!(p.treasury_balance(&mint), 967);
+}
+
+#[test]
+fn spl_pay_rejects_an_oversized_memo() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    let pay = ix::spl_pay(
+        &p.authority.pubkey(),
+        &recipient.pubkey(),
+        &mint,
+        0,
+        args::SplPay {
+            amount: 1,
+            memo: Some(vec![b'x'; 65]),
+            reference: None,
+            memo_cpi: None,
+        },
+        FlowOptions::default(),
+    );
+    expect_error(p.send_as_authority(&[pay], &[]), ErrorCode::MemoTooLong);
+    assert_eq!(p.pay_count(), 0);
+}
+
+#[test]
+fn spl_pay_rejects_an_unindexed_reference() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    let pay = |reference| {
+        ix::spl_pay(
+            &p.authority.pubkey(),
+            &recipient.pubkey(),
+            &mint,
+            0,
+            args::SplPay {
+                amount: 25,
+                memo: None,
+                reference,
+                memo_cpi: None,
+            },
+            FlowOptions::default(),
+        )
+    };
+    let (referenced, plain) = (pay(Some([3; 32])), pay(None));
+    expect_error(
+        p.send_as_authority(&[referenced], &[]),
+        ErrorCode::UnindexedReference,
+    );
+    assert_eq!(p.treasury_balance(&mint), 1_000);
+
+    p.send_as_authority(&[plain], &[]).unwrap();
+    assert_eq!(p.pay_count(), 1);
+}
+
+#[test]
+fn spl_pay_with_reference_indexes_the_reference_once() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    let pay = |pay_count| {
+        ix::spl_pay_with_reference(
+            &p.authority.pubkey(),
+            &recipient.pubkey(),
+            &mint,
+            pay_count,
+            args::SplPayWithReference {
+                amount: 25,
+                reference: [3; 32],
+                memo: None,
+                memo_cpi: None,
+            },
+            FlowOptions::default(),
+        )
+    };
+    let first = pay(0);
+    let retry = pay(1);
+    p.send_as_authority(&[first], &[]).unwrap();
+
+    let index: ReferenceIndex = p
+        .fetch(&pda::reference_index(&treasury(), &[3; 32]).0)
+        .unwrap();
+    assert_eq!(index.receipt, pda::pay_receipt(&treasury(), 0).0);
+    assert_eq!(index.pay_count, 0);
+
+    // A client retry with the same reference cannot pay twice.
+    assert!(p.send_as_authority(&[retry], &[]).is_err());
+    assert_eq!(p.pay_count(), 1);
+    assert_eq!(p.treasury_balance(&mint), 975);
+}
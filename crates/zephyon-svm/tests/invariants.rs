@@ -0,0 +1,500 @@
+//! Property-based check of the treasury accounting invariants.
+//!
+//! Random sequences of deposits, withdrawals, pays, pauses and unauthorized
+//! calls run against a fresh chain next to a plain-Rust model of the token
+//! balances. After every step:
+//!
+//! - the outcome matches the model (a flow succeeds iff unpaused, non-zero
+//!   and funded) and every tracked balance equals the model's
+//! - `pay_count` moves by exactly one per successful pay
+//! - receipts are created once, at their expected address, and never change
+//! - per-mint supply is conserved and matches the mint ledger
+//! - nothing moves while paused, and unauthorized calls never succeed
+//!
+//! Failing sequences are shrunk by proptest to a minimal reproduction and
+//! saved to `invariants.proptest-regressions`, which later runs replay first.
+
+#![cfg(protocol_so)]
+
+use std::collections::HashMap;
+
+use anchor_lang::prelude::Pubkey;
+use proptest::prelude::*;
+use protocol::state::{MintLedger, UserProfile};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda};
+use zephyon_svm::fixtures::*;
+use zephyon_svm::FailedTransaction;
+
+const USERS: usize = 3;
+const MINTS: usize = 2;
+const USER_FUNDS: u64 = 1_000;
+const TREASURY_FUNDS: u64 = 1_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Deposit {
+        user: usize,
+        mint: usize,
+        amount: u64,
+    },
+    DepositWithReceipt {
+        user: usize,
+        mint: usize,
+        amount: u64,
+        nonce: u64,
+    },
+    Withdraw {
+        user: usize,
+        mint: usize,
+        amount: u64,
+    },
+    WithdrawWithReceipt {
+        user: usize,
+        mint: usize,
+        amount: u64,
+    },
+    Pay {
+        user: usize,
+        mint: usize,
+        amount: u64,
+    },
+    Pause,
+    Unpause,
+    Unauthorized {
+        attack: Attack,
+        mint: usize,
+        amount: u64,
+    },
+}
+
+/// Calls made by a wallet that is not the treasury authority.
+#[derive(Clone, Copy, Debug)]
+enum Attack {
+    Withdraw,
+    WithdrawWithReceipt,
+    Pay,
+    Pause,
+    Unpause,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let user = 0..USERS;
+    let mint = 0..MINTS;
+    // Mostly in range, sometimes zero or more than any balance.
+    let amount = prop_oneof![8 => 1..400u64, 1 => Just(0u64), 1 => 1_000..5_000u64];
+    let attack = prop_oneof![
+        Just(Attack::Withdraw),
+        Just(Attack::WithdrawWithReceipt),
+        Just(Attack::Pay),
+        Just(Attack::Pause),
+        Just(Attack::Unpause),
+    ];
+    prop_oneof![
+        3 => (user.clone(), mint.clone(), amount.clone())
+            .prop_map(|(user, mint, amount)| Op::Deposit { user, mint, amount }),
+        2 => (user.clone(), mint.clone(), amount.clone(), 0..4u64).prop_map(
+            |(user, mint, amount, nonce)| Op::DepositWithReceipt { user, mint, amount, nonce }
+        ),
+        2 => (user.clone(), mint.clone(), amount.clone())
+            .prop_map(|(user, mint, amount)| Op::Withdraw { user, mint, amount }),
+        2 => (user.clone(), mint.clone(), amount.clone())
+            .prop_map(|(user, mint, amount)| Op::WithdrawWithReceipt { user, mint, amount }),
+        3 => (user, mint.clone(), amount.clone())
+            .prop_map(|(user, mint, amount)| Op::Pay { user, mint, amount }),
+        1 => Just(Op::Pause),
+        1 => Just(Op::Unpause),
+        2 => (attack, mint, amount)
+            .prop_map(|(attack, mint, amount)| Op::Unauthorized { attack, mint, amount }),
+    ]
+}
+
+/// What the model predicts for one operation.
+struct Expected {
+    succeeds: bool,
+    /// Model accounts the flow moves tokens between, and the amount.
+    transfer: Option<(Pubkey, Pubkey, u64)>,
+    /// Receipt the flow writes.
+    receipt: Option<Pubkey>,
+}
+
+/// Chain under test plus the model it is checked against.
+struct Harness {
+    p: Protocol,
+    users: Vec<Keypair>,
+    attacker: Keypair,
+    mints: Vec<Pubkey>,
+    /// Expected balance of every token account that may hold tokens.
+    balances: HashMap<Pubkey, u64>,
+    paused: bool,
+    pay_count: u64,
+    /// Every receipt written so far, with its data at creation.
+    receipts: HashMap<Pubkey, Vec<u8>>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut p = Protocol::new();
+        let users: Vec<_> = (0..USERS).map(|_| p.user()).collect();
+        let attacker = p.user();
+        let mints: Vec<_> = (0..MINTS).map(|_| p.create_mint()).collect();
+
+        let mut balances = HashMap::new();
+        for mint in &mints {
+            for user in &users {
+                let account = p.mint_to(&user.pubkey(), mint, USER_FUNDS);
+                balances.insert(account, USER_FUNDS);
+            }
+            balances.insert(p.fund_treasury(mint, TREASURY_FUNDS), TREASURY_FUNDS);
+            balances.insert(ata(&attacker.pubkey(), mint), 0);
+            let open = ix::open_mint_ledger(&p.authority.pubkey(), mint);
+            p.send_as_authority(&[open], &[]).unwrap();
+        }
+
+        Self {
+            p,
+            users,
+            attacker,
+            mints,
+            balances,
+            paused: false,
+            pay_count: 0,
+            receipts: HashMap::new(),
+        }
+    }
+
+    fn supply(&self) -> u64 {
+        (USERS as u64 * USER_FUNDS + TREASURY_FUNDS) * MINTS as u64
+    }
+
+    /// Moves `amount` between two model accounts if the flow should succeed.
+    fn expect_transfer(&self, from: &Pubkey, amount: u64) -> bool {
+        !self.paused && amount > 0 && self.balances[from] >= amount
+    }
+
+    fn apply_transfer(&mut self, from: Pubkey, to: Pubkey, amount: u64) {
+        *self.balances.get_mut(&from).unwrap() -= amount;
+        *self.balances.get_mut(&to).unwrap() += amount;
+    }
+
+    fn profile_tx_count(&self, user: &Pubkey) -> u64 {
+        self.p
+            .fetch::<UserProfile>(&pda::user_profile(user).0)
+            .map_or(0, |profile| profile.tx_count)
+    }
+
+    /// Runs one operation and checks every invariant afterwards.
+    fn step(&mut self, op: Op) -> Result<(), TestCaseError> {
+        let paused_before = self.paused;
+        let balances_before = self.balances.clone();
+
+        let (result, expected) = match op {
+            Op::Deposit { user, mint, amount } => {
+                let user = self.users[user].insecure_clone();
+                let (from, to) = (
+                    ata(&user.pubkey(), &self.mints[mint]),
+                    treasury_ata(&self.mints[mint]),
+                );
+                let succeeds = self.expect_transfer(&from, amount);
+                let result = self.p.deposit(&user, &self.mints[mint], amount);
+                (
+                    result,
+                    Expected {
+                        succeeds,
+                        transfer: Some((from, to, amount)),
+                        receipt: None,
+                    },
+                )
+            }
+            Op::DepositWithReceipt {
+                user,
+                mint,
+                amount,
+                nonce,
+            } => {
+                let user = self.users[user].insecure_clone();
+                let (from, to) = (
+                    ata(&user.pubkey(), &self.mints[mint]),
+                    treasury_ata(&self.mints[mint]),
+                );
+                let address = pda::deposit_receipt(&user.pubkey(), nonce).0;
+                let succeeds =
+                    self.expect_transfer(&from, amount) && !self.receipts.contains_key(&address);
+                let deposit = ix::spl_deposit_with_receipt(
+                    &user.pubkey(),
+                    &self.mints[mint],
+                    args::SplDepositWithReceipt {
+                        amount,
+                        nonce,
+                        reference: None,
+                        memo: None,
+                        memo_cpi: None,
+                    },
+                    FlowOptions::default(),
+                );
+                let result = self.p.send(&[deposit], &[&user]);
+                (
+                    result,
+                    Expected {
+                        succeeds,
+                        transfer: Some((from, to, amount)),
+                        receipt: Some(address),
+                    },
+                )
+            }
+            Op::Withdraw { user, mint, amount } => {
+                let user = self.users[user].pubkey();
+                let (from, to) = (
+                    treasury_ata(&self.mints[mint]),
+                    ata(&user, &self.mints[mint]),
+                );
+                let succeeds = self.expect_transfer(&from, amount);
+                let result = self.p.withdraw(&user, &self.mints[mint], amount);
+                (
+                    result,
+                    Expected {
+                        succeeds,
+                        transfer: Some((from, to, amount)),
+                        receipt: None,
+                    },
+                )
+            }
+            Op::WithdrawWithReceipt { user, mint, amount } => {
+                let user = self.users[user].insecure_clone();
+                let (from, to) = (
+                    treasury_ata(&self.mints[mint]),
+                    ata(&user.pubkey(), &self.mints[mint]),
+                );
+                let tx_count = self.profile_tx_count(&user.pubkey());
+                // Deposit nonces and withdrawal counts share the
+                // ["receipt", user, n] seeds, so a deposit with nonce n blocks
+                // the user's n-th receipted withdrawal.
+                let address = pda::withdraw_receipt(&user.pubkey(), tx_count).0;
+                let succeeds =
+                    self.expect_transfer(&from, amount) && !self.receipts.contains_key(&address);
+                let withdraw = ix::spl_withdraw_with_receipt(
+                    &user.pubkey(),
+                    &self.p.authority.pubkey(),
+                    &self.mints[mint],
+                    tx_count,
+                    args::SplWithdrawWithReceipt {
+                        amount,
+                        reference: None,
+                        memo: None,
+                    },
+                    FlowOptions::default(),
+                );
+                let result = self.p.send_as_authority(&[withdraw], &[&user]);
+                (
+                    result,
+                    Expected {
+                        succeeds,
+                        transfer: Some((from, to, amount)),
+                        receipt: Some(address),
+                    },
+                )
+            }
+            Op::Pay { user, mint, amount } => {
+                let user = self.users[user].pubkey();
+                let (from, to) = (
+                    treasury_ata(&self.mints[mint]),
+                    ata(&user, &self.mints[mint]),
+                );
+                let address = pda::pay_receipt(&treasury(), self.pay_count).0;
+                let succeeds = self.expect_transfer(&from, amount);
+                let result = self.p.pay(&user, &self.mints[mint], amount);
+                (
+                    result,
+                    Expected {
+                        succeeds,
+                        transfer: Some((from, to, amount)),
+                        receipt: Some(address),
+                    },
+                )
+            }
+            Op::Pause | Op::Unpause => {
+                let result = self.p.set_paused(matches!(op, Op::Pause));
+                (
+                    result,
+                    Expected {
+                        succeeds: true,
+                        transfer: None,
+                        receipt: None,
+                    },
+                )
+            }
+            Op::Unauthorized {
+                attack,
+                mint,
+                amount,
+            } => {
+                let result = self.attack(attack, mint, amount);
+                (
+                    result,
+                    Expected {
+                        succeeds: false,
+                        transfer: None,
+                        receipt: None,
+                    },
+                )
+            }
+        };
+
+        // --- Outcome matches the model ---
+        prop_assert_eq!(
+            result.is_ok(),
+            expected.succeeds,
+            "{:?} (paused: {}): {:?}",
+            op,
+            paused_before,
+            result.as_ref().err()
+        );
+        if result.is_ok() {
+            match op {
+                Op::Pause => self.paused = true,
+                Op::Unpause => self.paused = false,
+                _ => {}
+            }
+            if let Some((from, to, amount)) = expected.transfer {
+                self.apply_transfer(from, to, amount);
+            }
+            if matches!(op, Op::Pay { .. }) {
+                self.pay_count += 1;
+            }
+            if let Some(address) = expected.receipt {
+                prop_assert!(
+                    !self.receipts.contains_key(&address),
+                    "receipt {} reused",
+                    address
+                );
+                let data = self.p.svm.account(&address).map(|a| a.data);
+                prop_assert!(data.is_some(), "receipt {} missing after {:?}", address, op);
+                self.receipts.insert(address, data.unwrap());
+            }
+        }
+
+        self.check(paused_before, &balances_before)
+    }
+
+    fn attack(
+        &mut self,
+        attack: Attack,
+        mint: usize,
+        amount: u64,
+    ) -> Result<zephyon_svm::TransactionMeta, FailedTransaction> {
+        let attacker = self.attacker.insecure_clone();
+        let mint = self.mints[mint];
+        let instruction = match attack {
+            Attack::Withdraw => ix::spl_withdraw(
+                &attacker.pubkey(),
+                &attacker.pubkey(),
+                &mint,
+                amount,
+                FlowOptions::default(),
+            ),
+            Attack::WithdrawWithReceipt => ix::spl_withdraw_with_receipt(
+                &attacker.pubkey(),
+                &attacker.pubkey(),
+                &mint,
+                self.profile_tx_count(&attacker.pubkey()),
+                args::SplWithdrawWithReceipt {
+                    amount,
+                    reference: None,
+                    memo: None,
+                },
+                FlowOptions::default(),
+            ),
+            Attack::Pay => ix::spl_pay(
+                &attacker.pubkey(),
+                &attacker.pubkey(),
+                &mint,
+                self.pay_count,
+                args::SplPay {
+                    amount,
+                    memo: None,
+                    reference: None,
+                    memo_cpi: None,
+                },
+                FlowOptions::default(),
+            ),
+            Attack::Pause => ix::set_treasury_paused(&attacker.pubkey(), true),
+            Attack::Unpause => ix::set_treasury_paused(&attacker.pubkey(), false),
+        };
+        self.p.send(&[instruction], &[&attacker])
+    }
+
+    fn check(
+        &self,
+        paused_before: bool,
+        balances_before: &HashMap<Pubkey, u64>,
+    ) -> Result<(), TestCaseError> {
+        let state = self.p.treasury_state();
+        prop_assert_eq!(state.paused, self.paused);
+        prop_assert_eq!(state.pay_count, self.pay_count);
+
+        // Balances match the model, and nothing moved while paused.
+        for (account, expected) in &self.balances {
+            prop_assert_eq!(self.p.balance(account), *expected, "balance of {}", account);
+        }
+        if paused_before && self.paused {
+            prop_assert_eq!(&self.balances, balances_before);
+        }
+
+        // Supply is conserved per mint and the books agree with the ATA.
+        let mut total = 0;
+        for mint in &self.mints {
+            let held: u64 = self
+                .balances
+                .keys()
+                .filter(|account| {
+                    self.p
+                        .svm
+                        .account(account)
+                        .is_some_and(|a| a.data.get(..32) == Some(&mint.to_bytes()[..]))
+                })
+                .map(|account| self.p.balance(account))
+                .sum();
+            prop_assert_eq!(held, self.supply() / MINTS as u64, "supply of {}", mint);
+            total += held;
+
+            let ledger: MintLedger = self
+                .p
+                .fetch(&pda::mint_ledger(&treasury(), mint).0)
+                .unwrap();
+            prop_assert_eq!(
+                ledger.expected_balance().unwrap(),
+                self.p.treasury_balance(mint)
+            );
+        }
+        prop_assert_eq!(total, self.supply());
+
+        // Receipts never change once written.
+        for (address, data) in &self.receipts {
+            let account = self.p.svm.account(address);
+            prop_assert_eq!(
+                account.as_ref().map(|a| &a.data),
+                Some(data),
+                "receipt {} changed",
+                address
+            );
+        }
+        Ok(())
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 64,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn treasury_accounting_invariants_hold(ops in prop::collection::vec(op(), 1..48)) {
+        let mut harness = Harness::new();
+        for op in ops {
+            harness.step(op)?;
+        }
+    }
+}
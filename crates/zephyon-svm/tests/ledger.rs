@@ -0,0 +1,79 @@
+//! Per-mint treasury ledger and solvency checks.
+
+#![cfg(protocol_so)]
+
+use protocol::errors::ErrorCode;
+use protocol::state::MintLedger;
+use solana_signer::Signer;
+use zephyon_client::instructions as ix;
+use zephyon_client::pda;
+use zephyon_svm::fixtures::*;
+
+#[test]
+fn mint_ledger_tracks_every_treasury_flow() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.fund_treasury(&mint, 300);
+
+    let open = ix::open_mint_ledger(&p.authority.pubkey(), &mint);
+    p.send_as_authority(&[open], &[]).unwrap();
+
+    p.deposit(&user, &mint, 500).unwrap();
+    p.withdraw(&user.pubkey(), &mint, 120).unwrap();
+    p.pay(&user.pubkey(), &mint, 80).unwrap();
+
+    let ledger: MintLedger = p.fetch(&pda::mint_ledger(&treasury(), &mint).0).unwrap();
+    assert_eq!(ledger.opening_balance, 300);
+    assert_eq!(
+        (ledger.deposited, ledger.withdrawn, ledger.paid),
+        (500, 120, 80)
+    );
+    assert_eq!(
+        ledger.expected_balance().unwrap(),
+        p.treasury_balance(&mint)
+    );
+    p.send(&[ix::assert_solvency(&mint)], &[]).unwrap();
+}
+
+#[test]
+fn open_mint_ledger_is_authority_only_and_opens_once() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let outsider = p.user();
+
+    let open = ix::open_mint_ledger(&outsider.pubkey(), &mint);
+    expect_error(
+        p.send(&[open], &[&outsider]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+
+    // Opening creates an empty treasury ATA when there is none.
+    let authority = p.authority.pubkey();
+    p.send_as_authority(&[ix::open_mint_ledger(&authority, &mint)], &[])
+        .unwrap();
+    assert!(p.exists(&treasury_ata(&mint)));
+    let reopen = ix::open_mint_ledger(&authority, &mint);
+    assert!(p.send_as_authority(&[reopen], &[]).is_err());
+}
+
+#[test]
+fn assert_solvency_detects_a_drained_treasury() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let treasury_ata = p.fund_treasury(&mint, 1_000);
+    let open = ix::open_mint_ledger(&p.authority.pubkey(), &mint);
+    p.send_as_authority(&[open], &[]).unwrap();
+
+    // A surplus (tokens sent around the program) is fine.
+    p.mint_to(&treasury(), &mint, 50);
+    p.send(&[ix::assert_solvency(&mint)], &[]).unwrap();
+
+    // Tokens leaving outside the program are not.
+    p.set_token_account(treasury_ata, &mint, &treasury(), 999);
+    expect_error(
+        p.send(&[ix::assert_solvency(&mint)], &[]),
+        ErrorCode::TreasuryInsolvent,
+    );
+}
@@ -0,0 +1,590 @@
+//! Merchant registry and merchant payments.
+
+#![cfg(protocol_so)]
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use anchor_lang::prelude::Pubkey;
+use protocol::errors::ErrorCode;
+use protocol::risk::RISK_NEW_PROFILE;
+use protocol::state::{
+    KycAttestation, Merchant, Receipt, ReceiptV2Ext, TravelRuleRecord, TreasuryConfig,
+    TrustScore, UserProfile,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda, RiskConfigParams, TravelRuleParams};
+use zephyon_svm::fixtures::*;
+
+struct Shop {
+    p: Protocol,
+    owner: Keypair,
+    payout: Pubkey,
+    mint: Pubkey,
+    merchant: Pubkey,
+}
+
+/// A merchant on fee tier 1 (2.5%) accepting one mint.
+fn shop() -> Shop {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let owner = p.user();
+    let payout = Pubkey::new_unique();
+
+    let tiers = ix::set_merchant_fee_tiers(&p.authority.pubkey(), [0, 250, 500, 1_000]);
+    let register = ix::register_merchant(
+        &owner.pubkey(),
+        &p.authority.pubkey(),
+        payout,
+        vec![mint],
+        1,
+        false,
+    );
+    p.send_as_authority(&[tiers, register], &[&owner]).unwrap();
+    p.create_ata(&treasury(), &mint);
+
+    let merchant = pda::merchant(&treasury(), &owner.pubkey()).0;
+    Shop {
+        p,
+        owner,
+        payout,
+        mint,
+        merchant,
+    }
+}
+
+#[test]
+fn set_merchant_fee_tiers_validates_and_is_authority_only() {
+    let mut p = Protocol::new();
+
+    let set = ix::set_merchant_fee_tiers(&p.authority.pubkey(), [0, 100, 200, 10_000]);
+    p.send_as_authority(&[set], &[]).unwrap();
+    let config: TreasuryConfig = p.fetch(&pda::treasury_config(&treasury()).0).unwrap();
+    assert_eq!(config.merchant_fee_bps, [0, 100, 200, 10_000]);
+
+    let set = ix::set_merchant_fee_tiers(&p.authority.pubkey(), [0, 0, 0, 10_001]);
+    expect_error(
+        p.send_as_authority(&[set], &[]),
+        ErrorCode::InvalidMerchantConfig,
+    );
+
+    let outsider = p.user();
+    let set = ix::set_merchant_fee_tiers(&outsider.pubkey(), [0; 4]);
+    expect_error(
+        p.send(&[set], &[&outsider]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+}
+
+#[test]
+fn register_merchant_validates_the_registration() {
+    let mut p = Protocol::new();
+    let owner = p.user();
+    let mint = Pubkey::new_unique();
+    let authority = p.authority.pubkey();
+
+    let outsider = p.user();
+    let register = ix::register_merchant(
+        &owner.pubkey(),
+        &outsider.pubkey(),
+        owner.pubkey(),
+        vec![mint],
+        0,
+        false,
+    );
+    expect_error(
+        p.send(&[register], &[&owner, &outsider]),
+        ErrorCode::UnauthorizedCompliance,
+    );
+
+    for (payout, mints, fee_tier) in [
+        (Pubkey::default(), vec![mint], 0),
+        (owner.pubkey(), vec![], 0),
+        (owner.pubkey(), vec![mint, mint], 0),
+        (owner.pubkey(), vec![Pubkey::default()], 0),
+        (owner.pubkey(), vec![mint; 5], 0),
+        (owner.pubkey(), vec![mint], 4),
+    ] {
+        let register =
+            ix::register_merchant(&owner.pubkey(), &authority, payout, mints, fee_tier, false);
+        expect_error(
+            p.send_as_authority(&[register], &[&owner]),
+            ErrorCode::InvalidMerchantConfig,
+        );
+    }
+
+    let register = ix::register_merchant(
+        &owner.pubkey(),
+        &authority,
+        owner.pubkey(),
+        vec![mint],
+        0,
+        false,
+    );
+    p.send_as_authority(&[register], &[&owner]).unwrap();
+    let merchant: Merchant = p
+        .fetch(&pda::merchant(&treasury(), &owner.pubkey()).0)
+        .unwrap();
+    assert_eq!(merchant.owner, owner.pubkey());
+    assert_eq!(merchant.status, Merchant::STATUS_ACTIVE);
+    assert_eq!(merchant.settlements[0].mint, mint);
+}
+
+#[test]
+fn pay_merchant_splits_the_fee_and_writes_a_receipt() {
+    let Shop {
+        mut p,
+        payout,
+        mint,
+        merchant,
+        owner,
+    } = shop();
+    let payer = p.user();
+    let payer_ata = p.mint_to(&payer.pubkey(), &mint, 1_000);
+
+    let pay = ix::pay_merchant(
+        &payer.pubkey(),
+        &owner.pubkey(),
+        &payout,
+        &mint,
+        0,
+        args::PayMerchant {
+            amount: 400,
+            reference: Some([5; 32]),
+        },
+        FlowOptions::default(),
+    );
+    p.send(&[pay], &[&payer]).unwrap();
+
+    assert_eq!(p.balance(&payer_ata), 600);
+    assert_eq!(p.balance(&ata(&payout, &mint)), 390);
+    assert_eq!(p.treasury_balance(&mint), 10);
+
+    let receipt: Receipt = p.fetch(&pda::merchant_receipt(&merchant, 0).0).unwrap();
+    assert_eq!(receipt.direction, Receipt::DIR_MERCHANT_PAY);
+    assert_eq!(receipt.amount, 400);
+    let state: Merchant = p.fetch(&merchant).unwrap();
+    assert_eq!(state.pay_count, 1);
+    assert_eq!(
+        (state.settlements[0].gross_volume, state.settlements[0].fees),
+        (400, 10)
+    );
+
+    // The receipt slot is bound to the merchant's pay count.
+    let stale = ix::pay_merchant(
+        &payer.pubkey(),
+        &owner.pubkey(),
+        &payout,
+        &mint,
+        0,
+        args::PayMerchant {
+            amount: 1,
+            reference: None,
+        },
+        FlowOptions::default(),
+    );
+    assert!(p.send(&[stale], &[&payer]).is_err());
+    assert_eq!(p.balance(&payer_ata), 600);
+}
+
+#[test]
+fn pay_merchant_credits_the_payer_xp() {
+    let Shop {
+        mut p,
+        owner,
+        payout,
+        mint,
+        ..
+    } = shop();
+    let payer = p.user();
+    p.mint_to(&payer.pubkey(), &mint, 1_000);
+    p.send(&[ix::init_user_profile(&payer.pubkey())], &[&payer])
+        .unwrap();
+    let config = ix::set_xp_config(
+        &p.authority.pubkey(),
+        args::SetXpConfig {
+            xp_per_deposit: 0,
+            xp_per_withdraw: 0,
+            xp_per_pay: 12,
+            xp_min_amount: 0,
+            xp_epoch_cap: 0,
+        },
+    );
+    p.send_as_authority(&[config], &[]).unwrap();
+
+    let pay = ix::pay_merchant(
+        &payer.pubkey(),
+        &owner.pubkey(),
+        &payout,
+        &mint,
+        0,
+        args::PayMerchant {
+            amount: 100,
+            reference: None,
+        },
+        FlowOptions {
+            user_profile: true,
+            ..FlowOptions::default()
+        },
+    );
+    p.send(&[pay], &[&payer]).unwrap();
+
+    let profile: UserProfile = p.fetch(&pda::user_profile(&payer.pubkey()).0).unwrap();
+    assert_eq!(profile.xp, 12);
+}
+
+#[test]
+fn pay_merchant_records_the_payer_activity() {
+    let Shop {
+        mut p,
+        owner,
+        payout,
+        mint,
+        merchant,
+    } = shop();
+    let payer = p.user();
+    p.mint_to(&payer.pubkey(), &mint, 1_000);
+    p.send(&[ix::init_user_profile(&payer.pubkey())], &[&payer])
+        .unwrap();
+    let params = RiskConfigParams {
+        rules_mask: RISK_NEW_PROFILE,
+        new_profile_secs: 60,
+        ..RiskConfigParams::default()
+    };
+    p.send_as_authority(&[ix::set_risk_config(&p.authority.pubkey(), params)], &[])
+        .unwrap();
+
+    let pay = |pay_index| {
+        ix::pay_merchant(
+            &payer.pubkey(),
+            &owner.pubkey(),
+            &payout,
+            &mint,
+            pay_index,
+            args::PayMerchant {
+                amount: 100,
+                reference: None,
+            },
+            FlowOptions {
+                user_profile: true,
+                ..FlowOptions::default()
+            },
+        )
+    };
+    let risk_flags = |p: &Protocol, pay_index| {
+        let receipt: Receipt = p
+            .fetch(&pda::merchant_receipt(&merchant, pay_index).0)
+            .unwrap();
+        receipt.v2.risk_flags()
+    };
+
+    p.send(&[pay(0)], &[&payer]).unwrap();
+    assert_eq!(risk_flags(&p, 0), RISK_NEW_PROFILE);
+
+    // The first payment started the profile's history.
+    p.svm.advance_time(61);
+    p.send(&[pay(1)], &[&payer]).unwrap();
+    assert_eq!(risk_flags(&p, 1), 0);
+
+    let profile: UserProfile = p.fetch(&pda::user_profile(&payer.pubkey()).0).unwrap();
+    assert_eq!(profile.tx_count, 2);
+    assert_eq!(profile.deposit_count + profile.withdraw_count, 0);
+
+    // A profile with activity cannot be closed (and its history wiped).
+    expect_error(
+        p.send(&[ix::close_user_profile(&payer.pubkey())], &[&payer]),
+        ErrorCode::UserProfileHasActivity,
+    );
+}
+
+#[test]
+fn pay_merchant_enforces_the_payer_limits_and_kyc() {
+    let Shop {
+        mut p,
+        owner,
+        payout,
+        mint,
+        ..
+    } = shop();
+    let payer = p.user();
+    let payer_ata = p.mint_to(&payer.pubkey(), &mint, 1_000);
+
+    let limit = ix::set_limit_tier(
+        &p.authority.pubkey(),
+        &mint,
+        args::SetLimitTier {
+            tier: 0,
+            period_secs: 86_400,
+            max_count: 1,
+            max_amount: 0,
+        },
+    );
+    let kyc = ix::set_kyc_config(&p.authority.pubkey(), KycAttestation::LEVEL_BASIC, 100);
+    p.send_as_authority(&[limit, kyc], &[]).unwrap();
+
+    let pay = |pay_count, amount| {
+        ix::pay_merchant(
+            &payer.pubkey(),
+            &owner.pubkey(),
+            &payout,
+            &mint,
+            pay_count,
+            args::PayMerchant {
+                amount,
+                reference: None,
+            },
+            FlowOptions::default(),
+        )
+    };
+    let (large, first, second) = (pay(0, 101), pay(0, 100), pay(1, 100));
+    expect_error(
+        p.send(&[large], &[&payer]),
+        ErrorCode::KycAttestationRequired,
+    );
+    p.send(&[first], &[&payer]).unwrap();
+    expect_error(p.send(&[second], &[&payer]), ErrorCode::LimitCountExceeded);
+    assert_eq!(p.balance(&payer_ata), 900);
+}
+
+#[test]
+fn pay_merchant_consumes_travel_rule_records_and_credits_trust() {
+    let Shop {
+        mut p,
+        owner,
+        payout,
+        mint,
+        merchant,
+    } = shop();
+    let payer = p.user();
+    p.mint_to(&payer.pubkey(), &mint, 1_000);
+    p.send(&[ix::init_trust_score(&payer.pubkey())], &[&payer])
+        .unwrap();
+    let config = ix::set_travel_rule_config(&p.authority.pubkey(), 100);
+    p.send_as_authority(&[config], &[]).unwrap();
+
+    // The record names the payout wallet and is keyed by the receipt.
+    let receipt = pda::merchant_receipt(&merchant, 0).0;
+    let record_id = receipt.to_bytes();
+    let record = ix::record_travel_rule(
+        &p.authority.pubkey(),
+        TravelRuleParams {
+            record_id,
+            beneficiary: payout,
+            mint,
+            amount: 200,
+            originator_vasp: [1; 32],
+            beneficiary_vasp: [2; 32],
+            pii_hash: [3; 32],
+        },
+    );
+    p.send_as_authority(&[record], &[]).unwrap();
+
+    let pay = |opts| {
+        ix::pay_merchant(
+            &payer.pubkey(),
+            &owner.pubkey(),
+            &payout,
+            &mint,
+            0,
+            args::PayMerchant {
+                amount: 200,
+                reference: None,
+            },
+            opts,
+        )
+    };
+    let bare = pay(FlowOptions::default());
+    let recorded = pay(FlowOptions {
+        trust_score: true,
+        travel_rule: Some(pda::travel_rule(&treasury(), &record_id).0),
+        ..FlowOptions::default()
+    });
+    expect_error(
+        p.send(&[bare], &[&payer]),
+        ErrorCode::TravelRuleRecordRequired,
+    );
+    p.send(&[recorded], &[&payer]).unwrap();
+
+    let record: TravelRuleRecord = p
+        .fetch(&pda::travel_rule(&treasury(), &record_id).0)
+        .unwrap();
+    assert_eq!(record.receipt, receipt);
+    let receipt: Receipt = p.fetch(&receipt).unwrap();
+    assert_ne!(receipt.v2.flags & ReceiptV2Ext::FLAG_HAS_TRAVEL_RULE, 0);
+
+    let trust: TrustScore = p.fetch(&pda::trust_score(&payer.pubkey()).0).unwrap();
+    assert_eq!(trust.successful_flows, 1);
+}
+
+#[test]
+fn pay_merchant_rejects_unaccepted_mints_and_foreign_payout_wallets() {
+    let Shop {
+        mut p,
+        owner,
+        payout,
+        mint,
+        ..
+    } = shop();
+    let payer = p.user();
+    let other_mint = p.create_mint();
+    p.mint_to(&payer.pubkey(), &mint, 1_000);
+    p.mint_to(&payer.pubkey(), &other_mint, 1_000);
+    p.create_ata(&treasury(), &other_mint);
+
+    let pay = ix::pay_merchant(
+        &payer.pubkey(),
+        &owner.pubkey(),
+        &payout,
+        &other_mint,
+        0,
+        args::PayMerchant {
+            amount: 10,
+            reference: None,
+        },
+        FlowOptions::default(),
+    );
+    expect_error(
+        p.send(&[pay], &[&payer]),
+        ErrorCode::MerchantMintNotAccepted,
+    );
+
+    let thief = p.user();
+    let pay = ix::pay_merchant(
+        &payer.pubkey(),
+        &owner.pubkey(),
+        &thief.pubkey(),
+        &mint,
+        0,
+        args::PayMerchant {
+            amount: 10,
+            reference: None,
+        },
+        FlowOptions::default(),
+    );
+    expect_error(p.send(&[pay], &[&payer]), ErrorCode::InvalidMerchantConfig);
+    assert_eq!(p.balance(&ata(&thief.pubkey(), &mint)), 0);
+}
+
+#[test]
+fn update_merchant_is_owner_only() {
+    let Shop {
+        mut p,
+        owner,
+        mint,
+        merchant,
+        ..
+    } = shop();
+    let new_payout = Pubkey::new_unique();
+    let extra_mint = Pubkey::new_unique();
+
+    let update = ix::update_merchant(&owner.pubkey(), new_payout, vec![extra_mint, mint]);
+    p.send(&[update], &[&owner]).unwrap();
+    let state: Merchant = p.fetch(&merchant).unwrap();
+    assert_eq!(state.payout_wallet, new_payout);
+    assert_eq!(state.settlements[0].mint, extra_mint);
+    assert_eq!(state.settlements[1].mint, mint);
+
+    let update = ix::update_merchant(&owner.pubkey(), new_payout, vec![]);
+    expect_error(
+        p.send(&[update], &[&owner]),
+        ErrorCode::InvalidMerchantConfig,
+    );
+
+    // Another wallet's merchant PDA does not exist.
+    let outsider = p.user();
+    let update = ix::update_merchant(&outsider.pubkey(), outsider.pubkey(), vec![mint]);
+    expect_error(
+        p.send(&[update], &[&outsider]),
+        AnchorError::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn set_merchant_status_suspends_payments() {
+    let Shop {
+        mut p,
+        owner,
+        payout,
+        mint,
+        merchant,
+    } = shop();
+    let payer = p.user();
+    p.mint_to(&payer.pubkey(), &mint, 1_000);
+
+    let officer = p.user();
+    let delegate = ix::set_compliance_authority(&p.authority.pubkey(), officer.pubkey());
+    p.send_as_authority(&[delegate], &[]).unwrap();
+
+    let suspend = ix::set_merchant_status(
+        &officer.pubkey(),
+        &owner.pubkey(),
+        Merchant::STATUS_SUSPENDED,
+        3,
+        true,
+    );
+    p.send(&[suspend], &[&officer]).unwrap();
+    let state: Merchant = p.fetch(&merchant).unwrap();
+    assert_eq!(
+        (state.status, state.fee_tier),
+        (Merchant::STATUS_SUSPENDED, 3)
+    );
+
+    let pay = |amount| {
+        ix::pay_merchant(
+            &payer.pubkey(),
+            &owner.pubkey(),
+            &payout,
+            &mint,
+            0,
+            args::PayMerchant {
+                amount,
+                reference: None,
+            },
+            FlowOptions::default(),
+        )
+    };
+    expect_error(p.send(&[pay(100)], &[&payer]), ErrorCode::MerchantSuspended);
+
+    for (status, fee_tier) in [(0, 0), (3, 0), (Merchant::STATUS_ACTIVE, 4)] {
+        let set =
+            ix::set_merchant_status(&officer.pubkey(), &owner.pubkey(), status, fee_tier, true);
+        expect_error(
+            p.send(&[set], &[&officer]),
+            ErrorCode::InvalidMerchantConfig,
+        );
+    }
+    let set = ix::set_merchant_status(
+        &owner.pubkey(),
+        &owner.pubkey(),
+        Merchant::STATUS_ACTIVE,
+        0,
+        true,
+    );
+    expect_error(p.send(&[set], &[&owner]), ErrorCode::UnauthorizedCompliance);
+
+    let resume = ix::set_merchant_status(
+        &officer.pubkey(),
+        &owner.pubkey(),
+        Merchant::STATUS_ACTIVE,
+        3,
+        true,
+    );
+    p.send(&[resume], &[&officer]).unwrap();
+    p.send(&[pay(100)], &[&payer]).unwrap();
+    assert_eq!(p.treasury_balance(&mint), 10);
+
+    p.set_paused(true).unwrap();
+    let paused = ix::pay_merchant(
+        &payer.pubkey(),
+        &owner.pubkey(),
+        &payout,
+        &mint,
+        1,
+        args::PayMerchant {
+            amount: 100,
+            reference: None,
+        },
+        FlowOptions::default(),
+    );
+    expect_error(p.send(&[paused], &[&payer]), ErrorCode::ProtocolPaused);
+}
@@ -0,0 +1,281 @@
+//! User profiles, XP and trust scores.
+
+#![cfg(protocol_so)]
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use protocol::errors::ErrorCode;
+use protocol::state::{TreasuryConfig, TrustScore, UserProfile, UserProfileV1};
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda};
+use zephyon_svm::fixtures::*;
+use zephyon_svm::Account;
+
+const DAY: i64 = 86_400;
+
+#[test]
+fn init_and_close_user_profile() {
+    let mut p = Protocol::new();
+    let user = p.user();
+    let profile = pda::user_profile(&user.pubkey()).0;
+    let lamports = p.svm.lamports(&user.pubkey());
+
+    p.send(&[ix::init_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+    let state: UserProfile = p.fetch(&profile).unwrap();
+    assert_eq!(state.authority, user.pubkey());
+    assert_eq!(state.version, UserProfile::CURRENT_VERSION);
+
+    p.send(&[ix::close_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+    assert!(!p.exists(&profile));
+    assert_eq!(p.svm.lamports(&user.pubkey()), lamports);
+}
+
+#[test]
+fn close_user_profile_keeps_profiles_with_activity() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 10);
+    p.send(&[ix::init_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+
+    let opts = FlowOptions {
+        user_profile: true,
+        ..FlowOptions::default()
+    };
+    p.send(&[ix::spl_deposit(&user.pubkey(), &mint, 1, opts)], &[&user])
+        .unwrap();
+
+    let close = ix::close_user_profile(&user.pubkey());
+    expect_error(
+        p.send(&[close], &[&user]),
+        ErrorCode::UserProfileHasActivity,
+    );
+}
+
+#[test]
+fn update_user_profile_upgrades_a_v1_profile() {
+    let mut p = Protocol::new();
+    let user = p.user();
+    let (address, bump) = pda::user_profile(&user.pubkey());
+
+    let mut data = UserProfile::DISCRIMINATOR.to_vec();
+    UserProfileV1 {
+        authority: user.pubkey(),
+        tx_count: 3,
+        bump,
+    }
+    .serialize(&mut data)
+    .unwrap();
+    data.resize(UserProfile::SPACE_V1, 0);
+    p.svm.set_account(
+        address,
+        Account {
+            lamports: p.svm.rent().minimum_balance(data.len()),
+            data,
+            owner: protocol::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    p.send(&[ix::update_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+
+    let profile: UserProfile = p.fetch(&address).unwrap();
+    assert_eq!(profile.version, UserProfile::CURRENT_VERSION);
+    assert_eq!(profile.tx_count, 3);
+    assert_eq!(profile.authority, user.pubkey());
+    assert_eq!(
+        p.svm.account(&address).unwrap().data.len(),
+        UserProfile::SPACE
+    );
+
+    // Another wallet cannot touch it.
+    let other = p.user();
+    assert!(p
+        .send(&[ix::update_user_profile(&other.pubkey())], &[&other])
+        .is_err());
+}
+
+#[test]
+fn set_xp_config_credits_xp_up_to_the_epoch_cap() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.send(&[ix::init_user_profile(&user.pubkey())], &[&user])
+        .unwrap();
+
+    let config = ix::set_xp_config(
+        &p.authority.pubkey(),
+        args::SetXpConfig {
+            xp_per_deposit: 10,
+            xp_per_withdraw: 0,
+            xp_per_pay: 0,
+            xp_min_amount: 5,
+            xp_epoch_cap: 25,
+        },
+    );
+    p.send_as_authority(&[config], &[]).unwrap();
+
+    let opts = FlowOptions {
+        user_profile: true,
+        ..FlowOptions::default()
+    };
+    for amount in [4, 5, 5, 5, 5] {
+        p.send(
+            &[ix::spl_deposit(&user.pubkey(), &mint, amount, opts)],
+            &[&user],
+        )
+        .unwrap();
+    }
+
+    // 4 is below the minimum; then 10 + 10 + 5 (capped) + 0.
+    let profile: UserProfile = p.fetch(&pda::user_profile(&user.pubkey()).0).unwrap();
+    assert_eq!(profile.xp, 25);
+}
+
+#[test]
+fn set_xp_config_is_authority_only() {
+    let mut p = Protocol::new();
+    let outsider = p.user();
+
+    let config = ix::set_xp_config(
+        &outsider.pubkey(),
+        args::SetXpConfig {
+            xp_per_deposit: 1_000,
+            xp_per_withdraw: 0,
+            xp_per_pay: 0,
+            xp_min_amount: 0,
+            xp_epoch_cap: 0,
+        },
+    );
+    expect_error(
+        p.send(&[config], &[&outsider]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+}
+
+#[test]
+fn trust_score_grows_with_receipted_flows_and_age() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.fund_treasury(&mint, 1_000);
+    let trust = pda::trust_score(&user.pubkey()).0;
+
+    p.send(&[ix::init_trust_score(&user.pubkey())], &[&user])
+        .unwrap();
+    let opts = FlowOptions {
+        trust_score: true,
+        ..FlowOptions::default()
+    };
+    let pay = ix::spl_pay(
+        &p.authority.pubkey(),
+        &user.pubkey(),
+        &mint,
+        0,
+        args::SplPay {
+            amount: 1,
+            memo: None,
+            reference: None,
+            memo_cpi: None,
+        },
+        opts,
+    );
+    p.send_as_authority(&[pay], &[]).unwrap();
+
+    let state: TrustScore = p.fetch(&trust).unwrap();
+    assert_eq!(state.successful_flows, 1);
+    assert_eq!(u32::from(state.score), TrustScore::FLOW_POINTS);
+
+    p.svm.advance_time(10 * DAY);
+    p.send(&[ix::refresh_trust_score(&user.pubkey())], &[])
+        .unwrap();
+    let state: TrustScore = p.fetch(&trust).unwrap();
+    assert_eq!(
+        u64::from(state.score),
+        u64::from(TrustScore::FLOW_POINTS) + 10 * TrustScore::AGE_POINTS_PER_DAY
+    );
+}
+
+#[test]
+fn trust_points_skip_dust_and_cap_per_window() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.fund_treasury(&mint, 1_000);
+    let trust = pda::trust_score(&user.pubkey()).0;
+
+    p.send(&[ix::init_trust_score(&user.pubkey())], &[&user])
+        .unwrap();
+    let config = ix::set_trust_config(&p.authority.pubkey(), [0; 4], 10);
+    p.send_as_authority(&[config], &[]).unwrap();
+
+    let opts = FlowOptions {
+        trust_score: true,
+        ..FlowOptions::default()
+    };
+    let pay = |p: &mut Protocol, amount| {
+        let pay_count = p.pay_count();
+        let pay = ix::spl_pay(
+            &p.authority.pubkey(),
+            &user.pubkey(),
+            &mint,
+            pay_count,
+            args::SplPay {
+                amount,
+                memo: None,
+                reference: None,
+                memo_cpi: None,
+            },
+            opts,
+        );
+        p.send_as_authority(&[pay], &[]).unwrap();
+    };
+
+    // Dust counts as a flow but earns nothing.
+    pay(&mut p, 9);
+    let state: TrustScore = p.fetch(&trust).unwrap();
+    assert_eq!((state.successful_flows, state.activity_points), (1, 0));
+
+    let cap = TrustScore::MAX_CREDITED_FLOWS_PER_WINDOW;
+    for _ in 0..=cap {
+        pay(&mut p, 10);
+    }
+    let state: TrustScore = p.fetch(&trust).unwrap();
+    assert_eq!(state.successful_flows, u64::from(cap) + 2);
+    assert_eq!(state.activity_points, cap * TrustScore::FLOW_POINTS);
+
+    // A new window credits again.
+    p.svm.advance_time(TrustScore::CREDIT_WINDOW_SECS);
+    pay(&mut p, 10);
+    let state: TrustScore = p.fetch(&trust).unwrap();
+    assert_eq!(state.activity_points, (cap + 1) * TrustScore::FLOW_POINTS);
+}
+
+#[test]
+fn set_trust_config_validates_tiers() {
+    let mut p = Protocol::new();
+
+    let set = ix::set_trust_config(&p.authority.pubkey(), [0, 100, 500, 2_000], 10);
+    p.send_as_authority(&[set], &[]).unwrap();
+    let config: TreasuryConfig = p.fetch(&pda::treasury_config(&treasury()).0).unwrap();
+    assert_eq!(config.trust_tier_scores, [0, 100, 500, 2_000]);
+    assert_eq!(config.trust_min_amount, 10);
+
+    for invalid in [
+        [1, 0, 0, 0],
+        [0, 500, 100, 0],
+        [0, 0, 0, TrustScore::MAX_SCORE + 1],
+    ] {
+        let set = ix::set_trust_config(&p.authority.pubkey(), invalid, 0);
+        expect_error(
+            p.send_as_authority(&[set], &[]),
+            ErrorCode::InvalidTrustConfig,
+        );
+    }
+}
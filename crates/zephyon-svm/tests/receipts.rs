@@ -0,0 +1,174 @@
+//! Receipt migration and signed receipt attestations.
+
+#![cfg(protocol_so)]
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use anchor_lang::prelude::Pubkey;
+use protocol::attestation::receipt_attestation_message;
+use protocol::errors::ErrorCode;
+use protocol::state::{AttestorConfig, Receipt};
+use solana_instruction::error::InstructionError;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction_error::TransactionError;
+use zephyon_client::instructions as ix;
+use zephyon_client::pda;
+use zephyon_svm::fixtures::*;
+
+/// A chain with one pay receipt (pay_count 0).
+fn with_pay_receipt() -> (Protocol, Pubkey) {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 100);
+    p.pay(&recipient.pubkey(), &mint, 10).unwrap();
+    (p, pda::pay_receipt(&treasury(), 0).0)
+}
+
+/// Ed25519 + `verify_receipt_attestation` for `receipt` signed by `signer`.
+fn attest(
+    p: &Protocol,
+    signer: &Keypair,
+    receipt: &Pubkey,
+    attestor_config: bool,
+) -> [solana_instruction::Instruction; 2] {
+    let state: Receipt = p.fetch(receipt).unwrap();
+    let message =
+        receipt_attestation_message(&protocol::ID, receipt, p.svm.clock().slot, &state).unwrap();
+    [
+        ed25519_instruction(signer, &message),
+        ix::verify_receipt_attestation(receipt, attestor_config),
+    ]
+}
+
+#[test]
+fn migrate_receipt_upgrades_a_v1_receipt() {
+    let (mut p, address) = with_pay_receipt();
+    let current = p.svm.account(&address).unwrap();
+
+    // Version 1 is the current layout without the trailing version byte.
+    let mut legacy = current.clone();
+    legacy.data.truncate(Receipt::SPACE_V1);
+    legacy.lamports = p.svm.rent().minimum_balance(Receipt::SPACE_V1);
+    p.svm.set_account(address, legacy);
+
+    let payer = p.user();
+    p.send(&[ix::migrate_receipt(&payer.pubkey(), &address)], &[&payer])
+        .unwrap();
+
+    let migrated = p.svm.account(&address).unwrap();
+    assert_eq!(migrated.data, current.data);
+    assert_eq!(
+        migrated.lamports,
+        p.svm.rent().minimum_balance(Receipt::SPACE)
+    );
+}
+
+#[test]
+fn migrate_receipt_is_a_no_op_on_current_receipts() {
+    let (mut p, address) = with_pay_receipt();
+    let before = p.svm.account(&address).unwrap();
+
+    let payer = p.user();
+    p.send(&[ix::migrate_receipt(&payer.pubkey(), &address)], &[&payer])
+        .unwrap();
+    assert_eq!(p.svm.account(&address).unwrap(), before);
+}
+
+#[test]
+fn migrate_receipt_rejects_foreign_accounts() {
+    let mut p = Protocol::new();
+    let payer = p.user();
+    let foreign = p.user();
+
+    let migrate = ix::migrate_receipt(&payer.pubkey(), &foreign.pubkey());
+    expect_error(p.send(&[migrate], &[&payer]), AnchorError::ConstraintOwner);
+}
+
+#[test]
+fn set_receipt_attestor_is_authority_only() {
+    let mut p = Protocol::new();
+    let attestor = Keypair::new();
+
+    let set = ix::set_receipt_attestor(&p.authority.pubkey(), attestor.pubkey());
+    p.send_as_authority(&[set], &[]).unwrap();
+    let config: AttestorConfig = p.fetch(&pda::attestor_config(&treasury()).0).unwrap();
+    assert_eq!(config.attestor, attestor.pubkey());
+
+    let outsider = p.user();
+    let set = ix::set_receipt_attestor(&outsider.pubkey(), outsider.pubkey());
+    expect_error(
+        p.send(&[set], &[&outsider]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+}
+
+#[test]
+fn verify_receipt_attestation_accepts_the_authority_and_designated_attestor() {
+    let (mut p, receipt) = with_pay_receipt();
+
+    let authority = p.authority.insecure_clone();
+    let attest_by_authority = attest(&p, &authority, &receipt, false);
+    p.send(&attest_by_authority, &[]).unwrap();
+
+    let attestor = Keypair::new();
+    let set = ix::set_receipt_attestor(&p.authority.pubkey(), attestor.pubkey());
+    p.send_as_authority(&[set], &[]).unwrap();
+    let attest_by_attestor = attest(&p, &attestor, &receipt, true);
+    p.send(&attest_by_attestor, &[]).unwrap();
+}
+
+#[test]
+fn verify_receipt_attestation_rejects_unknown_signers() {
+    let (mut p, receipt) = with_pay_receipt();
+
+    let stranger = Keypair::new();
+    let attestation = attest(&p, &stranger, &receipt, false);
+    expect_error(
+        p.send(&attestation, &[]),
+        ErrorCode::AttestationSignerUnauthorized,
+    );
+}
+
+#[test]
+fn verify_receipt_attestation_binds_the_receipt() {
+    let (mut p, receipt) = with_pay_receipt();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 100);
+    p.pay(&recipient.pubkey(), &mint, 10).unwrap();
+    let other = pda::pay_receipt(&treasury(), 1).0;
+
+    // A valid attestation of one receipt presented for another.
+    let authority = p.authority.insecure_clone();
+    let [signature, _] = attest(&p, &authority, &receipt, false);
+    let verify_other = ix::verify_receipt_attestation(&other, false);
+    expect_error(
+        p.send(&[signature, verify_other], &[]),
+        ErrorCode::AttestationMismatch,
+    );
+}
+
+#[test]
+fn verify_receipt_attestation_requires_the_signature_instruction() {
+    let (mut p, receipt) = with_pay_receipt();
+
+    let verify = ix::verify_receipt_attestation(&receipt, false);
+    expect_error(p.send(&[verify], &[]), ErrorCode::AttestationMissing);
+}
+
+#[test]
+fn forged_signatures_fail_before_the_program_runs() {
+    let (mut p, receipt) = with_pay_receipt();
+
+    let authority = p.authority.insecure_clone();
+    let [mut signature, verify] = attest(&p, &authority, &receipt, false);
+    let last = signature.data.len() - 1;
+    signature.data[last] ^= 1;
+
+    let failed = p.send(&[signature, verify], &[]).unwrap_err();
+    assert!(matches!(
+        failed.err,
+        TransactionError::InstructionError(0, InstructionError::Custom(_))
+    ));
+}
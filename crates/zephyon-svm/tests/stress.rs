@@ -0,0 +1,356 @@
+//! Stress scenarios ported from the `stress_*` specs: pause races, stale
+//! receipts, multi-mint isolation and adversarial token accounts.
+
+#![cfg(protocol_so)]
+
+use anchor_lang::prelude::Pubkey;
+use protocol::errors::ErrorCode;
+use solana_instruction::Instruction;
+use solana_signer::Signer;
+use zephyon_client::instructions::{self as ix, FlowOptions};
+use zephyon_client::{args, pda};
+use zephyon_svm::fixtures::*;
+use zephyon_svm::Account;
+
+fn pay_ix(
+    p: &Protocol,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    pay_count: u64,
+    amount: u64,
+) -> Instruction {
+    ix::spl_pay(
+        &p.authority.pubkey(),
+        recipient,
+        mint,
+        pay_count,
+        args::SplPay {
+            amount,
+            memo: None,
+            reference: None,
+            memo_cpi: None,
+        },
+        FlowOptions::default(),
+    )
+}
+
+/// Replaces every occurrence of `from` in the instruction's accounts.
+fn substitute(mut ix: Instruction, from: &Pubkey, to: Pubkey) -> Instruction {
+    for meta in ix.accounts.iter_mut().filter(|m| m.pubkey == *from) {
+        meta.pubkey = to;
+    }
+    ix
+}
+
+fn snapshot(p: &Protocol, addresses: &[Pubkey]) -> Vec<Option<Account>> {
+    addresses
+        .iter()
+        .map(|a| p.svm.account(a))
+        .collect()
+}
+
+#[test]
+fn pause_in_the_same_transaction_orders_with_the_flow() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipient = p.user();
+    p.fund_treasury(&mint, 1_000);
+    let authority = p.authority.pubkey();
+
+    // Pay lands before the pause.
+    let pay = pay_ix(&p, &recipient.pubkey(), &mint, 0, 10);
+    p.send_as_authority(&[pay, ix::set_treasury_paused(&authority, true)], &[])
+        .unwrap();
+    assert!(p.treasury_state().paused);
+    assert_eq!(p.pay_count(), 1);
+
+    // Unpause and pay atomically while paused.
+    let pay = pay_ix(&p, &recipient.pubkey(), &mint, 1, 10);
+    p.send_as_authority(&[ix::set_treasury_paused(&authority, false), pay], &[])
+        .unwrap();
+    assert_eq!(p.pay_count(), 2);
+
+    // A pause ahead of the pay fails the pay and rolls back the pause.
+    let pay = pay_ix(&p, &recipient.pubkey(), &mint, 2, 10);
+    let failed = p
+        .send_as_authority(&[ix::set_treasury_paused(&authority, true), pay], &[])
+        .unwrap_err();
+    assert_eq!(
+        failed.custom_code(),
+        Some(u32::from(ErrorCode::ProtocolPaused))
+    );
+    assert!(!p.treasury_state().paused);
+    assert_eq!(p.pay_count(), 2);
+    assert_eq!(p.treasury_balance(&mint), 980);
+}
+
+#[test]
+fn pause_windows_gate_deposits_and_pays_without_drift() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let users: Vec<_> = (0..4).map(|_| p.user()).collect();
+    for user in &users {
+        p.mint_to(&user.pubkey(), &mint, 10_000);
+    }
+    p.fund_treasury(&mint, 10_000);
+    let total = 10_000 * 5;
+
+    let (mut paused, mut window, mut pays) = (false, 0, 0);
+    for step in 0u64..120 {
+        if window == 0 {
+            paused = !paused;
+            window = 3 + step % 7;
+            p.set_paused(paused).unwrap();
+        }
+        window -= 1;
+
+        let user = &users[(step % 4) as usize];
+        let amount = 1 + step * 7 % 50;
+        let result = if step % 2 == 0 {
+            p.deposit(user, &mint, amount)
+        } else {
+            p.pay(&user.pubkey(), &mint, amount)
+        };
+        if paused {
+            expect_error(result, ErrorCode::ProtocolPaused);
+        } else {
+            result.unwrap();
+            pays += step % 2;
+        }
+
+        let held: u64 = users
+            .iter()
+            .map(|u| p.balance(&ata(&u.pubkey(), &mint)))
+            .sum();
+        assert_eq!(held + p.treasury_balance(&mint), total);
+        assert_eq!(p.pay_count(), pays);
+    }
+}
+
+#[test]
+fn failed_pays_are_idempotent() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let other_mint = p.create_mint();
+    let recipient = p.user();
+    let attacker = p.user();
+    p.fund_treasury(&mint, 1_000);
+    p.create_ata(&recipient.pubkey(), &mint);
+
+    let watched = [
+        treasury(),
+        treasury_ata(&mint),
+        ata(&recipient.pubkey(), &mint),
+        pda::pay_receipt(&treasury(), 0).0,
+    ];
+    let before = snapshot(&p, &watched);
+
+    for _ in 0..10 {
+        // Wrong authority.
+        let pay = substitute(
+            pay_ix(&p, &recipient.pubkey(), &mint, 0, 10),
+            &p.authority.pubkey(),
+            attacker.pubkey(),
+        );
+        expect_error(
+            p.send(&[pay], &[&attacker]),
+            ErrorCode::UnauthorizedWithdraw,
+        );
+
+        // Recipient ATA of another mint.
+        let pay = substitute(
+            pay_ix(&p, &recipient.pubkey(), &mint, 0, 10),
+            &ata(&recipient.pubkey(), &mint),
+            ata(&recipient.pubkey(), &other_mint),
+        );
+        assert!(p.send_as_authority(&[pay], &[]).is_err());
+    }
+    p.set_paused(true).unwrap();
+    for _ in 0..10 {
+        expect_error(
+            p.pay(&recipient.pubkey(), &mint, 10),
+            ErrorCode::ProtocolPaused,
+        );
+    }
+    p.set_paused(false).unwrap();
+
+    assert_eq!(snapshot(&p, &watched), before);
+}
+
+#[test]
+fn stale_prebuilt_receipts_are_rejected() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let first = p.user();
+    let second = p.user();
+    p.fund_treasury(&mint, 1_000);
+
+    // Both prebuilt against pay_count 0; only one can land.
+    let pay_first = pay_ix(&p, &first.pubkey(), &mint, 0, 10);
+    let pay_second = pay_ix(&p, &second.pubkey(), &mint, 0, 20);
+    let ahead = pay_ix(&p, &second.pubkey(), &mint, 5, 20);
+    p.send_as_authority(&[pay_first], &[]).unwrap();
+
+    let watched = [
+        treasury(),
+        treasury_ata(&mint),
+        ata(&second.pubkey(), &mint),
+    ];
+    let before = snapshot(&p, &watched);
+    assert!(p.send_as_authority(&[pay_second], &[]).is_err());
+    assert!(p.send_as_authority(&[ahead], &[]).is_err());
+    assert_eq!(snapshot(&p, &watched), before);
+    assert!(!p.exists(&pda::pay_receipt(&treasury(), 5).0));
+
+    // Rebuilt against the current count it goes through.
+    p.pay(&second.pubkey(), &mint, 20).unwrap();
+    assert_eq!(p.pay_count(), 2);
+    assert_eq!(p.treasury_balance(&mint), 970);
+}
+
+#[test]
+fn mints_stay_isolated_under_an_alternating_pay_stream() {
+    let mut p = Protocol::new();
+    let mints = [p.create_mint(), p.create_mint()];
+    let recipients: Vec<_> = (0..3).map(|_| p.user()).collect();
+    for mint in &mints {
+        p.fund_treasury(mint, 5_000);
+    }
+
+    let mut paid = [0u64; 2];
+    for step in 0u64..60 {
+        let m = (step % 2) as usize;
+        let recipient = recipients[(step % 3) as usize].pubkey();
+        let amount = 1 + step % 13;
+        p.pay(&recipient, &mints[m], amount).unwrap();
+        paid[m] += amount;
+
+        for (mint, paid) in mints.iter().zip(paid) {
+            let received: u64 = recipients
+                .iter()
+                .map(|r| p.balance(&ata(&r.pubkey(), mint)))
+                .sum();
+            assert_eq!(received, paid);
+            assert_eq!(p.treasury_balance(mint), 5_000 - paid);
+        }
+    }
+
+    // A pay of one mint cannot draw on the other mint's treasury ATA.
+    let pay = substitute(
+        pay_ix(&p, &recipients[0].pubkey(), &mints[0], p.pay_count(), 1),
+        &treasury_ata(&mints[0]),
+        treasury_ata(&mints[1]),
+    );
+    assert!(p.send_as_authority(&[pay], &[]).is_err());
+    assert_eq!(p.pay_count(), 60);
+}
+
+#[test]
+fn adversarial_token_accounts_are_rejected() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    let attacker = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.fund_treasury(&mint, 1_000);
+    let attacker_ata = p.create_ata(&attacker.pubkey(), &mint);
+
+    // A token account owned by the treasury, but not its ATA.
+    let fake_treasury_ata = Pubkey::new_unique();
+    p.set_token_account(fake_treasury_ata, &mint, &treasury(), 1_000_000);
+    // A token account claiming to be the recipient's, owned by the attacker.
+    let fake_recipient_ata = Pubkey::new_unique();
+    p.set_token_account(fake_recipient_ata, &mint, &attacker.pubkey(), 0);
+
+    let deposit = || ix::spl_deposit(&user.pubkey(), &mint, 100, FlowOptions::default());
+    let deposit_with_receipt = || {
+        ix::spl_deposit_with_receipt(
+            &user.pubkey(),
+            &mint,
+            args::SplDepositWithReceipt {
+                amount: 100,
+                nonce: 1,
+                reference: None,
+                memo: None,
+                memo_cpi: None,
+            },
+            FlowOptions::default(),
+        )
+    };
+    let pay = pay_ix(&p, &user.pubkey(), &mint, 0, 100);
+    let withdraw = ix::spl_withdraw(
+        &p.authority.pubkey(),
+        &user.pubkey(),
+        &mint,
+        100,
+        FlowOptions::default(),
+    );
+
+    let user_ata = ata(&user.pubkey(), &mint);
+    let attacks = [
+        // Deposits diverted to the attacker or a look-alike treasury account.
+        (deposit(), treasury_ata(&mint), attacker_ata),
+        (deposit(), treasury_ata(&mint), fake_treasury_ata),
+        (deposit_with_receipt(), treasury_ata(&mint), attacker_ata),
+        (
+            deposit_with_receipt(),
+            treasury_ata(&mint),
+            fake_treasury_ata,
+        ),
+        // Payouts redirected to the attacker.
+        (pay.clone(), user_ata, attacker_ata),
+        (pay.clone(), user_ata, fake_recipient_ata),
+        (withdraw.clone(), user_ata, attacker_ata),
+        // Payouts drawn from a look-alike treasury account.
+        (pay, treasury_ata(&mint), fake_treasury_ata),
+        (withdraw, treasury_ata(&mint), fake_treasury_ata),
+    ];
+
+    let watched = [
+        user_ata,
+        attacker_ata,
+        treasury_ata(&mint),
+        fake_treasury_ata,
+        fake_recipient_ata,
+    ];
+    let before = snapshot(&p, &watched);
+    for (ix, from, to) in attacks {
+        let attack = substitute(ix, &from, to);
+        let signer_is_user = attack
+            .accounts
+            .iter()
+            .any(|m| m.pubkey == user.pubkey() && m.is_signer);
+        let result = if signer_is_user {
+            p.send(&[attack], &[&user])
+        } else {
+            p.send_as_authority(&[attack], &[])
+        };
+        assert!(result.is_err(), "{from} -> {to} was accepted");
+    }
+    assert_eq!(snapshot(&p, &watched), before);
+    assert_eq!(p.pay_count(), 0);
+}
+
+#[test]
+fn pay_storm_across_recipients_conserves_value() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let recipients: Vec<_> = (0..12).map(|_| p.user()).collect();
+    p.fund_treasury(&mint, 1_000_000);
+
+    let mut total = 0;
+    for n in 0u64..200 {
+        let amount = 1 + n * 31 % 250;
+        p.pay(&recipients[(n % 12) as usize].pubkey(), &mint, amount)
+            .unwrap();
+        total += amount;
+    }
+
+    let received: u64 = recipients
+        .iter()
+        .map(|r| p.balance(&ata(&r.pubkey(), &mint)))
+        .sum();
+    assert_eq!(received, total);
+    assert_eq!(p.treasury_balance(&mint), 1_000_000 - total);
+    assert_eq!(p.pay_count(), 200);
+}
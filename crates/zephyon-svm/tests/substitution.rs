@@ -0,0 +1,150 @@
+//! Account substitution attacks: every value-moving instruction with its
+//! accounts swapped for other known accounts (see
+//! `zephyon_svm::substitution`). The `fuzz/` target drives the same
+//! scenario with arbitrary inputs.
+
+#![cfg(protocol_so)]
+
+use proptest::prelude::*;
+use zephyon_svm::fixtures::*;
+use zephyon_svm::substitution::{Attack, Outcome, Scenario, Substitution, Target};
+
+/// Every single substitution of every slot of `target` on the given mints.
+/// Attacks on the second mint mirror the first (its accounts are candidates
+/// either way) except for the solvency check, where it is the insolvent one.
+fn sweep(target: Target, mints: &[usize]) {
+    let mut scenario = Scenario::new();
+    let index = Target::ALL.iter().position(|t| *t == target).unwrap();
+    let mut accepted = 0;
+    for &mint in mints {
+        let original = scenario.instruction(target, &scenario.mints[mint]);
+        for slot in 0..original.accounts.len() {
+            for candidate in 0..scenario.candidates.len() {
+                let attack = Attack {
+                    target: index,
+                    mint,
+                    substitutions: vec![Substitution { slot, candidate }],
+                };
+                match scenario.run(&attack) {
+                    Ok(Outcome::Accepted) => accepted += 1,
+                    Ok(Outcome::Rejected(_)) => {}
+                    Err(violation) => panic!("{violation}"),
+                }
+                scenario.reset();
+            }
+        }
+    }
+    // Leaving a slot as it was is among the attacks.
+    assert!(accepted > 0);
+}
+
+#[test]
+fn deposit_substitutions() {
+    sweep(Target::Deposit, &[0]);
+}
+
+#[test]
+fn deposit_with_receipt_substitutions() {
+    sweep(Target::DepositWithReceipt, &[0]);
+}
+
+#[test]
+fn withdraw_substitutions() {
+    sweep(Target::Withdraw, &[0]);
+}
+
+#[test]
+fn withdraw_with_receipt_substitutions() {
+    sweep(Target::WithdrawWithReceipt, &[0]);
+}
+
+#[test]
+fn pay_substitutions() {
+    sweep(Target::Pay, &[0]);
+}
+
+#[test]
+fn pay_with_reference_substitutions() {
+    sweep(Target::PayWithReference, &[0]);
+}
+
+#[test]
+fn pay_merchant_substitutions() {
+    sweep(Target::PayMerchant, &[0]);
+}
+
+#[test]
+fn resolve_dispute_substitutions() {
+    sweep(Target::ResolveDispute, &[0]);
+}
+
+#[test]
+fn assert_solvency_substitutions() {
+    sweep(Target::AssertSolvency, &[0, 1]);
+}
+
+#[test]
+fn deposit_with_receipt_only_accepts_the_canonical_treasury_ata() {
+    let mut scenario = Scenario::new();
+    let target = Target::ALL
+        .iter()
+        .position(|t| *t == Target::DepositWithReceipt)
+        .unwrap();
+    for (mint_index, mint) in scenario.mints.into_iter().enumerate() {
+        let canonical = treasury_ata(&mint);
+        let slot = scenario
+            .instruction(Target::DepositWithReceipt, &mint)
+            .accounts
+            .iter()
+            .position(|meta| meta.pubkey == canonical)
+            .unwrap();
+        for candidate in 0..scenario.candidates.len() {
+            let attack = Attack {
+                target,
+                mint: mint_index,
+                substitutions: vec![Substitution { slot, candidate }],
+            };
+            let outcome = scenario.run(&attack).unwrap_or_else(|v| panic!("{v}"));
+            let replaced = scenario.candidates[candidate];
+            assert_eq!(
+                matches!(outcome, Outcome::Accepted),
+                replaced == canonical,
+                "treasury_ata {replaced}"
+            );
+            scenario.reset();
+        }
+    }
+}
+
+fn attack() -> impl Strategy<Value = Attack> {
+    let substitution = (any::<usize>(), any::<usize>())
+        .prop_map(|(slot, candidate)| Substitution { slot, candidate });
+    (
+        0..Target::ALL.len(),
+        0..2usize,
+        prop::collection::vec(substitution, 0..4),
+    )
+        .prop_map(|(target, mint, substitutions)| Attack {
+            target,
+            mint,
+            substitutions,
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    /// Sequences of multi-account attacks on one chain: accepted attacks
+    /// change the state the next ones run against.
+    #[test]
+    fn substitution_sequences_are_rejected_or_harmless(
+        attacks in prop::collection::vec(attack(), 1..24),
+    ) {
+        let mut scenario = Scenario::new();
+        for attack in &attacks {
+            if let Err(violation) = scenario.run(attack) {
+                prop_assert!(false, "{}", violation);
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Treasury setup and the pause switch.
+
+#![cfg(protocol_so)]
+
+use protocol::errors::ErrorCode;
+use solana_signer::Signer;
+use zephyon_client::instructions as ix;
+use zephyon_svm::fixtures::*;
+
+#[test]
+fn initialize_treasury_sets_the_authority_unpaused() {
+    let p = Protocol::new();
+    let state = p.treasury_state();
+
+    assert_eq!(state.authority, p.authority.pubkey());
+    assert!(!state.paused);
+    assert_eq!(state.pay_count, 0);
+}
+
+#[test]
+fn initialize_treasury_cannot_be_repeated() {
+    let mut p = Protocol::new();
+    let attacker = p.user();
+
+    let init = ix::initialize_treasury(&attacker.pubkey());
+    assert!(p.send(&[init], &[&attacker]).is_err());
+    assert_eq!(p.treasury_state().authority, p.authority.pubkey());
+}
+
+#[test]
+fn set_treasury_paused_toggles_and_is_idempotent() {
+    let mut p = Protocol::new();
+
+    p.set_paused(true).unwrap();
+    p.set_paused(true).unwrap();
+    assert!(p.treasury_state().paused);
+
+    p.set_paused(false).unwrap();
+    assert!(!p.treasury_state().paused);
+}
+
+#[test]
+fn set_treasury_paused_rejects_other_signers() {
+    let mut p = Protocol::new();
+    let attacker = p.user();
+
+    let pause = ix::set_treasury_paused(&attacker.pubkey(), true);
+    expect_error(
+        p.send(&[pause], &[&attacker]),
+        ErrorCode::UnauthorizedWithdraw,
+    );
+    assert!(!p.treasury_state().paused);
+}
+
+#[test]
+fn pause_blocks_every_value_flow_until_unpaused() {
+    let mut p = Protocol::new();
+    let mint = p.create_mint();
+    let user = p.user();
+    p.mint_to(&user.pubkey(), &mint, 1_000);
+    p.fund_treasury(&mint, 1_000);
+
+    p.set_paused(true).unwrap();
+    expect_error(p.deposit(&user, &mint, 1), ErrorCode::ProtocolPaused);
+    expect_error(
+        p.withdraw(&user.pubkey(), &mint, 1),
+        ErrorCode::ProtocolPaused,
+    );
+    expect_error(p.pay(&user.pubkey(), &mint, 1), ErrorCode::ProtocolPaused);
+    assert_eq!(p.treasury_balance(&mint), 1_000);
+    assert_eq!(p.pay_count(), 0);
+
+    p.set_paused(false).unwrap();
+    p.deposit(&user, &mint, 1).unwrap();
+    p.withdraw(&user.pubkey(), &mint, 1).unwrap();
+    p.pay(&user.pubkey(), &mint, 1).unwrap();
+    assert_eq!(p.treasury_balance(&mint), 999);
+    assert_eq!(p.pay_count(), 1);
+}
@@ -0,0 +1,43 @@
+//! Sequences of account substitution attacks against one chain; see
+//! `zephyon_svm::substitution` for the scenario and what is checked.
+
+#![no_main]
+
+use std::cell::RefCell;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use zephyon_svm::substitution::{Attack, Scenario, Substitution};
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    /// (target, mint, [(slot, candidate)]); indices wrap.
+    attacks: Vec<(u8, u8, Vec<(u8, u8)>)>,
+}
+
+thread_local! {
+    static SCENARIO: RefCell<Scenario> = RefCell::new(Scenario::new());
+}
+
+fuzz_target!(|input: Input| {
+    SCENARIO.with_borrow_mut(|scenario| {
+        scenario.reset();
+        for (target, mint, substitutions) in input.attacks.into_iter().take(16) {
+            let attack = Attack {
+                target: target.into(),
+                mint: mint.into(),
+                substitutions: substitutions
+                    .into_iter()
+                    .take(4)
+                    .map(|(slot, candidate)| Substitution {
+                        slot: slot.into(),
+                        candidate: candidate.into(),
+                    })
+                    .collect(),
+            };
+            if let Err(violation) = scenario.run(&attack) {
+                panic!("{violation}");
+            }
+        }
+    });
+});
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use solana_sdk_ids::ed25519_program;
+
+use crate::errors::ErrorCode;
+use crate::state::Receipt;
+
+/// Domain separator prefixed to every receipt attestation message.
+///
+/// Bumped (v2, v3, ...) whenever the message layout changes, so a signature
+/// over one layout can never be replayed as another.
+pub const ATTESTATION_DOMAIN: &[u8] = b"zephyon:receipt-attestation:v1";
+
+/// Canonical attestation message length.
+pub const ATTESTATION_MESSAGE_LEN: usize = ATTESTATION_DOMAIN.len() + 32 + 32 + 8 + Receipt::LEN;
+
+/// Portable, signed proof that a receipt existed with the given content.
+///
+/// Carries everything a verifier needs besides the receipt itself: the
+/// signing key, the signature, and the slot the attestor observed the receipt at.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReceiptAttestation {
+    pub attestor: Pubkey,
+    pub signature: [u8; 64],
+    pub slot: u64,
+}
+
+/// Canonical byte encoding of a receipt for signing.
+///
+/// Layout:
+/// - ATTESTATION_DOMAIN
+/// - program_id: 32
+/// - receipt address: 32
+/// - slot: u64 LE
+/// - receipt: Borsh, current schema (`Receipt::LEN` bytes)
+///
+/// The receipt body is exactly the on-chain account data after the 8-byte
+/// discriminator, so clients can build the message from raw account bytes.
+pub fn receipt_attestation_message(
+    program_id: &Pubkey,
+    receipt_address: &Pubkey,
+    slot: u64,
+    receipt: &Receipt,
+) -> Result<Vec<u8>> {
+    let mut msg = Vec::with_capacity(ATTESTATION_MESSAGE_LEN);
+    msg.extend_from_slice(ATTESTATION_DOMAIN);
+    msg.extend_from_slice(program_id.as_ref());
+    msg.extend_from_slice(receipt_address.as_ref());
+    msg.extend_from_slice(&slot.to_le_bytes());
+    receipt
+        .serialize(&mut msg)
+        .map_err(|_| ErrorCode::AttestationMalformed)?;
+    Ok(msg)
+}
+
+/// Split a canonical message into `(program_id, receipt_address, slot, receipt body)`.
+pub fn parse_attestation_message(msg: &[u8]) -> Result<(Pubkey, Pubkey, u64, &[u8])> {
+    require!(
+        msg.len() == ATTESTATION_MESSAGE_LEN,
+        ErrorCode::AttestationMalformed
+    );
+
+    let (domain, rest) = msg.split_at(ATTESTATION_DOMAIN.len());
+    require!(domain == ATTESTATION_DOMAIN, ErrorCode::AttestationMalformed);
+
+    let (program_id, rest) = rest.split_at(32);
+    let (receipt_address, rest) = rest.split_at(32);
+    let (slot, body) = rest.split_at(8);
+
+    Ok((
+        Pubkey::try_from(program_id).map_err(|_| ErrorCode::AttestationMalformed)?,
+        Pubkey::try_from(receipt_address).map_err(|_| ErrorCode::AttestationMalformed)?,
+        u64::from_le_bytes(slot.try_into().map_err(|_| ErrorCode::AttestationMalformed)?),
+        body,
+    ))
+}
+
+/// Sign a receipt attestation (off-chain only).
+#[cfg(not(target_os = "solana"))]
+pub fn sign_receipt_attestation(
+    signing_key: &ed25519_dalek::SigningKey,
+    program_id: &Pubkey,
+    receipt_address: &Pubkey,
+    slot: u64,
+    receipt: &Receipt,
+) -> Result<ReceiptAttestation> {
+    use ed25519_dalek::Signer;
+
+    let msg = receipt_attestation_message(program_id, receipt_address, slot, receipt)?;
+    Ok(ReceiptAttestation {
+        attestor: Pubkey::new_from_array(signing_key.verifying_key().to_bytes()),
+        signature: signing_key.sign(&msg).to_bytes(),
+        slot,
+    })
+}
+
+/// Verify a receipt attestation against an expected attestor (off-chain only).
+///
+/// Fails with `AttestationSignerUnauthorized` when the attestation was made by
+/// a different key, and `AttestationInvalidSignature` when the signature does
+/// not cover this exact receipt/address/program/slot.
+#[cfg(not(target_os = "solana"))]
+pub fn verify_receipt_attestation(
+    attestation: &ReceiptAttestation,
+    expected_attestor: &Pubkey,
+    program_id: &Pubkey,
+    receipt_address: &Pubkey,
+    receipt: &Receipt,
+) -> Result<()> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    require_keys_eq!(
+        attestation.attestor,
+        *expected_attestor,
+        ErrorCode::AttestationSignerUnauthorized
+    );
+
+    let key = VerifyingKey::from_bytes(&attestation.attestor.to_bytes())
+        .map_err(|_| ErrorCode::AttestationInvalidSignature)?;
+    let msg = receipt_attestation_message(program_id, receipt_address, attestation.slot, receipt)?;
+
+    key.verify(&msg, &Signature::from_bytes(&attestation.signature))
+        .map_err(|_| error!(ErrorCode::AttestationInvalidSignature))
+}
+
+/// Ed25519 native program instruction: header + one offsets record.
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// Extract `(pubkey, message)` from an Ed25519 program instruction.
+///
+/// Only the single-signature, self-contained form is accepted: every
+/// `*_instruction_index` must be `u16::MAX` (data lives in this instruction).
+/// Signature validity itself is enforced by the runtime before the
+/// transaction executes; this only binds *what* was signed and *by whom*.
+pub fn parse_ed25519_instruction(program_id: &Pubkey, data: &[u8]) -> Result<(Pubkey, Vec<u8>)> {
+    require_keys_eq!(*program_id, ed25519_program::ID, ErrorCode::AttestationMissing);
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        ErrorCode::AttestationMalformed
+    );
+
+    let off = &data[ED25519_HEADER_LEN..ED25519_HEADER_LEN + ED25519_OFFSETS_LEN];
+    let u16_at = |i: usize| u16::from_le_bytes([off[i], off[i + 1]]);
+
+    let signature_ix = u16_at(2);
+    let pubkey_offset = u16_at(4) as usize;
+    let pubkey_ix = u16_at(6);
+    let message_offset = u16_at(8) as usize;
+    let message_size = u16_at(10) as usize;
+    let message_ix = u16_at(12);
+
+    require!(
+        signature_ix == u16::MAX && pubkey_ix == u16::MAX && message_ix == u16::MAX,
+        ErrorCode::AttestationMalformed
+    );
+
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(ErrorCode::AttestationMalformed)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::AttestationMalformed)?;
+
+    Ok((
+        Pubkey::try_from(pubkey).map_err(|_| ErrorCode::AttestationMalformed)?,
+        message.to_vec(),
+    ))
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::DenylistEntry;
+
+/// Reject the flow if `entry` holds an active denylist entry.
+///
+/// `entry` is the wallet's denylist PDA, pinned by seeds in the flow's
+/// account struct. Flows take it unconditionally rather than as an optional
+/// account, so the denylist cannot be skipped by leaving it out; it only
+/// exists while the wallet is listed. `error` names the side of the flow it
+/// belongs to (`WalletDenylisted` or `RecipientDenylisted`). Expired entries
+/// no longer apply.
+pub fn ensure_not_denylisted(entry: &AccountInfo, error: ErrorCode) -> Result<()> {
+    let Some(entry) = DenylistEntry::load(entry)? else {
+        return Ok(());
+    };
+
+    if entry.is_active(Clock::get()?.unix_timestamp) {
+        msg!(
+            "wallet {} denylisted (reason {})",
+            entry.wallet,
+            entry.reason_code
+        );
+        return Err(error.into());
+    }
+
+    Ok(())
+}
@@ -35,5 +35,148 @@ pub enum ErrorCode {
     #[msg("Memo too long.")]
     MemoTooLong,
 
+    #[msg("Memo program account is required for the requested memo CPI.")]
+    MemoProgramRequired,
+
+    #[msg("Memo CPI mode requires a non-empty memo.")]
+    MemoRequired,
+
+    #[msg("Memo must be valid UTF-8 to be written through the SPL Memo program.")]
+    MemoNotUtf8,
+
+    #[msg("Unsupported receipt schema version.")]
+    UnsupportedReceiptVersion,
+
+    #[msg("Expected an Ed25519 program instruction immediately before this one.")]
+    AttestationMissing,
+
+    #[msg("Malformed receipt attestation.")]
+    AttestationMalformed,
+
+    #[msg("Attestation was not signed by the treasury authority or designated attestor.")]
+    AttestationSignerUnauthorized,
+
+    #[msg("Invalid receipt attestation signature.")]
+    AttestationInvalidSignature,
+
+    #[msg("Attestation does not match this receipt.")]
+    AttestationMismatch,
+
+    #[msg("Invalid receipt direction.")]
+    InvalidDirection,
+
+    #[msg("Unsupported user profile schema version.")]
+    UnsupportedUserProfileVersion,
+
+    #[msg("User profile has recorded activity and cannot be closed.")]
+    UserProfileHasActivity,
+
+    #[msg("Transfer blocked by treasury risk policy.")]
+    RiskFlagBlocked,
+
+    #[msg("Invalid risk configuration.")]
+    InvalidRiskConfig,
+
+    #[msg("Spending limit exceeded: too many flows in the current window.")]
+    LimitCountExceeded,
+
+    #[msg("Spending limit exceeded: amount over the current window's allowance.")]
+    LimitAmountExceeded,
+
+    #[msg("Invalid spending limit tier.")]
+    InvalidLimitTier,
+
+    #[msg("Invalid user limits account.")]
+    InvalidUserLimits,
+
+    #[msg("Wallet is on the treasury denylist.")]
+    WalletDenylisted,
+
+    #[msg("Recipient is on the treasury denylist.")]
+    RecipientDenylisted,
+
+    #[msg("Signer is not a compliance authority for this treasury.")]
+    UnauthorizedCompliance,
+
+    #[msg("Invalid denylist entry.")]
+    InvalidDenylistEntry,
+
+    #[msg("A valid KYC attestation is required for this amount.")]
+    KycAttestationRequired,
+
+    #[msg("KYC attestation level is below the required minimum.")]
+    KycLevelTooLow,
+
+    #[msg("Invalid KYC attestation.")]
+    InvalidKycAttestation,
+
+    #[msg("A travel-rule record is required for this amount.")]
+    TravelRuleRecordRequired,
+
+    #[msg("Travel-rule record does not match this transfer.")]
+    TravelRuleRecordMismatch,
+
+    #[msg("Travel-rule record was already used by another transfer.")]
+    TravelRuleRecordConsumed,
+
+    #[msg("Invalid travel-rule record.")]
+    InvalidTravelRuleRecord,
+
+    #[msg("Invalid trust tier configuration.")]
+    InvalidTrustConfig,
+
+    #[msg("Disputes are disabled for this treasury.")]
+    DisputesDisabled,
+
+    #[msg("Dispute window for this receipt has closed.")]
+    DisputeWindowClosed,
+
+    #[msg("Receipt cannot be disputed.")]
+    ReceiptNotDisputable,
+
+    #[msg("Signer is not a party to or arbiter of this dispute.")]
+    UnauthorizedDispute,
+
+    #[msg("Dispute is not open.")]
+    DisputeNotOpen,
+
+    #[msg("Invalid dispute evidence.")]
+    InvalidDisputeEvidence,
+
+    #[msg("Invalid dispute configuration.")]
+    InvalidDisputeConfig,
+
+    #[msg("Invalid refund amount for this dispute.")]
+    InvalidRefundAmount,
+
+    #[msg("Payer and treasury token accounts and the merchant are required for a refund.")]
+    RefundAccountsRequired,
+
+    #[msg("Invalid merchant configuration.")]
+    InvalidMerchantConfig,
+
+    #[msg("Merchant is suspended.")]
+    MerchantSuspended,
+
+    #[msg("Merchant does not accept this mint.")]
+    MerchantMintNotAccepted,
+
+    #[msg("Signer is not the merchant owner.")]
+    UnauthorizedMerchant,
+
+    #[msg("Mint ledger account is invalid.")]
+    InvalidMintLedger,
+
+    #[msg("Mint ledger totals overflow.")]
+    LedgerOverflow,
+
+    #[msg("Treasury token balance is below the mint ledger balance.")]
+    TreasuryInsolvent,
+
+    #[msg("References must be paid through spl_pay_with_reference.")]
+    UnindexedReference,
+
+    #[msg("Merchant payment disputes require the merchant that issued the receipt.")]
+    MerchantRequired,
 
 }
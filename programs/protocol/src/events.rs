@@ -19,10 +19,17 @@ pub struct DepositEvent {
     pub nonce_or_tx: u64,
 
     // Telemetry-first (grant safe)
-    pub xp_delta: u32,   // start simple: 1
-    pub risk_flags: u32, // start simple: 0
+    pub xp_delta: u32,   // XP actually credited (0 without profile + config)
+    pub risk_flags: u32, // see `crate::risk` (0 without treasury config)
 
     pub slot: u64,
+
+    // Receipt metadata (APPEND ONLY).
+    // Populated by deposit-with-receipt; false/zeroed otherwise.
+    pub has_reference: bool,
+    pub reference: [u8; 32],
+    pub has_memo: bool,
+    pub memo_len: u8,
 }
 
 #[event]
@@ -43,10 +50,17 @@ pub struct WithdrawEvent {
     // For others: 0
     pub nonce_or_tx: u64,
 
-    pub xp_delta: u32,   // start simple: 1
-    pub risk_flags: u32, // start simple: 0
+    pub xp_delta: u32,   // XP actually credited (0 without profile + config)
+    pub risk_flags: u32, // see `crate::risk` (0 without treasury config)
 
     pub slot: u64,
+
+    // Receipt metadata (APPEND ONLY).
+    // Populated by withdraw-with-receipt; false/zeroed otherwise.
+    pub has_reference: bool,
+    pub reference: [u8; 32],
+    pub has_memo: bool,
+    pub memo_len: u8,
 }
 #[event]
 pub struct TreasuryInitializedEvent {
@@ -113,5 +127,403 @@ pub struct SplPayEvent {
     pub slot: u64,
 
     pub unix_timestamp: i64,
+
+    // XP credited to the recipient's profile (APPEND ONLY).
+    pub xp_delta: u32,
+
+    // Risk flags raised by the payout (see `crate::risk`).
+    pub risk_flags: u32,
+}
+
+#[event]
+pub struct ReceiptMigratedEvent {
+    pub receipt: Pubkey,
+
+    pub from_version: u8,
+    pub to_version: u8,
+
+    pub payer: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ReceiptAttestorSetEvent {
+    pub treasury: Pubkey,
+    pub attestor: Pubkey,
+
+    pub authority: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ReceiptAttestationVerifiedEvent {
+    pub receipt: Pubkey,
+    pub attestor: Pubkey,
+
+    /// Slot the attestor claims to have observed the receipt at.
+    pub attested_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UserProfileInitializedEvent {
+    pub user: Pubkey,
+    pub profile: Pubkey,
+
+    pub version: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UserProfileUpdatedEvent {
+    pub user: Pubkey,
+    pub profile: Pubkey,
+
+    pub from_version: u8,
+    pub to_version: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct UserProfileClosedEvent {
+    pub user: Pubkey,
+    pub profile: Pubkey,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct XpConfigSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub xp_per_deposit: u32,
+    pub xp_per_withdraw: u32,
+    pub xp_per_pay: u32,
+    pub xp_min_amount: u64,
+    pub xp_epoch_cap: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct RiskConfigSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub rules_mask: u32,
+    pub blocking_mask: u32,
+    pub default_threshold: u64,
+    pub mint_threshold_count: u8,
+    pub new_profile_secs: i64,
+    pub velocity_window_slots: u64,
+    pub velocity_max_count: u32,
+    pub round_whole_units: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct LimitTierSetEvent {
+    pub treasury: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+
+    pub tier: u8,
+    pub period_secs: i64,
+    pub max_count: u32,
+    pub max_amount: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct UserLimitTierSetEvent {
+    pub treasury: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+
+    pub tier: u8,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct ComplianceAuthoritySetEvent {
+    pub treasury: Pubkey,
+    pub compliance_authority: Pubkey,
+    pub authority: Pubkey,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct DenylistEntryAddedEvent {
+    pub treasury: Pubkey,
+    pub wallet: Pubkey,
+    pub authority: Pubkey,
+
+    pub reason_code: u16,
+    pub expires_at: i64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct DenylistEntryRemovedEvent {
+    pub treasury: Pubkey,
+    pub wallet: Pubkey,
+    pub authority: Pubkey,
+
+    pub reason_code: u16,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct KycConfigSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub min_level: u8,
+    pub threshold: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct KycIssuerAddedEvent {
+    pub treasury: Pubkey,
+    pub issuer: Pubkey,
+    pub authority: Pubkey,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct KycIssuerRemovedEvent {
+    pub treasury: Pubkey,
+    pub issuer: Pubkey,
+    pub authority: Pubkey,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct KycAttestationIssuedEvent {
+    pub treasury: Pubkey,
+    pub wallet: Pubkey,
+    pub issuer: Pubkey,
+
+    pub level: u8,
+    pub jurisdiction: [u8; 2],
+    pub expires_at: i64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct KycAttestationRevokedEvent {
+    pub treasury: Pubkey,
+    pub wallet: Pubkey,
+    pub issuer: Pubkey,
+    pub authority: Pubkey,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct TravelRuleConfigSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub threshold: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct TravelRuleRecordedEvent {
+    pub treasury: Pubkey,
+    pub record: Pubkey,
+    pub authority: Pubkey,
+
+    pub record_id: [u8; 32],
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct TrustConfigSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub tier_scores: [u16; 4],
+    pub min_amount: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct TrustScoreInitializedEvent {
+    pub wallet: Pubkey,
+    pub trust_score: Pubkey,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct TrustScoreUpdatedEvent {
+    pub wallet: Pubkey,
+    pub trust_score: Pubkey,
+
+    pub score: u16,
+    pub successful_flows: u64,
+    pub disputes_lost: u32,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct DisputeConfigSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub arbiter: Pubkey,
+    pub window_secs: i64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct DisputeOpenedEvent {
+    pub treasury: Pubkey,
+    pub dispute: Pubkey,
+    pub receipt: Pubkey,
+    pub payer: Pubkey,
+    pub respondent: Pubkey,
+
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct DisputeEvidenceSubmittedEvent {
+    pub dispute: Pubkey,
+    pub party: Pubkey,
+
+    pub evidence: [u8; 32],
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct DisputeResolvedEvent {
+    pub treasury: Pubkey,
+    pub dispute: Pubkey,
+    pub receipt: Pubkey,
+    pub resolution_receipt: Pubkey,
+    pub arbiter: Pubkey,
+
+    pub status: u8,
+    pub refund_amount: u64,
+    pub xp_delta: u32,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct MerchantFeeTiersSetEvent {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+
+    pub fee_bps: [u16; 4],
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct MerchantRegisteredEvent {
+    pub treasury: Pubkey,
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub payout_wallet: Pubkey,
+    pub authority: Pubkey,
+
+    pub fee_tier: u8,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct MerchantUpdatedEvent {
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub payout_wallet: Pubkey,
+
+    pub settlement_mints: Vec<Pubkey>,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct MerchantStatusSetEvent {
+    pub treasury: Pubkey,
+    pub merchant: Pubkey,
+    pub authority: Pubkey,
+
+    pub status: u8,
+    pub fee_tier: u8,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct MerchantPaidEvent {
+    pub treasury: Pubkey,
+    pub merchant: Pubkey,
+    pub payer: Pubkey,
+    pub payout_wallet: Pubkey,
+    pub receipt: Pubkey,
+
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub pay_index: u64,
+
+    pub has_reference: bool,
+    pub reference: [u8; 32],
+    pub xp_delta: u32,
+    pub risk_flags: u32,
+
+    pub slot: u64,
 }
 
+#[event]
+pub struct MintLedgerOpenedEvent {
+    pub treasury: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+
+    pub opening_balance: u64,
+
+    pub slot: u64,
+}
+
+#[event]
+pub struct SolvencyCheckedEvent {
+    pub treasury: Pubkey,
+    pub mint: Pubkey,
+
+    pub ledger_balance: u64,
+    pub ata_balance: u64,
+
+    pub slot: u64,
+}
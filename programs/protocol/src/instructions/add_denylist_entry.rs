@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::DenylistEntryAddedEvent;
+use crate::state::{ComplianceConfig, DenylistEntry, Treasury};
+
+/// Denylists (freezes) a wallet, or updates an existing entry.
+///
+/// While the entry is active the wallet cannot deposit, withdraw or receive
+/// payouts. `expires_at` is a unix timestamp in the future, or 0 for an entry
+/// that never expires.
+///
+/// Signer must be the treasury authority or the designated compliance
+/// authority.
+#[derive(Accounts)]
+pub struct AddDenylistEntry<'info> {
+    /// Treasury authority or designated compliance authority; pays rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// CHECK: Wallet being denylisted; only used as a PDA seed.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Denylist entry PDA
+    ///
+    /// ["denylist", treasury.key(), wallet.key()]
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = DenylistEntry::SPACE,
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddDenylistEntry>, reason_code: u16, expires_at: i64) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+
+    // --- Compliance gate ---
+    require!(
+        ComplianceConfig::is_authorized(
+            ctx.accounts.compliance_config.as_deref(),
+            treasury,
+            &authority.key()
+        ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    let clock = Clock::get()?;
+    require!(
+        expires_at == 0 || expires_at > clock.unix_timestamp,
+        ErrorCode::InvalidDenylistEntry
+    );
+
+    let wallet = ctx.accounts.wallet.key();
+    let entry = &mut ctx.accounts.denylist_entry;
+    entry.treasury = treasury.key();
+    entry.wallet = wallet;
+    entry.reason_code = reason_code;
+    entry.added_at = clock.unix_timestamp;
+    entry.expires_at = expires_at;
+    entry.added_by = authority.key();
+    entry.bump = ctx.bumps.denylist_entry;
+
+    emit!(DenylistEntryAddedEvent {
+        treasury: treasury.key(),
+        wallet,
+        authority: authority.key(),
+        reason_code,
+        expires_at,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
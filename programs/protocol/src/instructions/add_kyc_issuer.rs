@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::KycIssuerAddedEvent;
+use crate::state::{ComplianceConfig, KycIssuer, Treasury};
+
+/// Trusts a key to issue KYC/KYB attestations for the treasury.
+///
+/// Signer must be the treasury authority or the designated compliance
+/// authority.
+#[derive(Accounts)]
+pub struct AddKycIssuer<'info> {
+    /// Treasury authority or designated compliance authority; pays rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// CHECK: Issuer key being trusted; only used as a PDA seed.
+    pub issuer: UncheckedAccount<'info>,
+
+    /// KYC issuer PDA
+    ///
+    /// ["kyc_issuer", treasury.key(), issuer.key()]
+    #[account(
+        init,
+        payer = authority,
+        space = KycIssuer::SPACE,
+        seeds = [KycIssuer::SEED, treasury.key().as_ref(), issuer.key().as_ref()],
+        bump
+    )]
+    pub kyc_issuer: Account<'info, KycIssuer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddKycIssuer>) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+
+    // --- Compliance gate ---
+    require!(
+        ComplianceConfig::is_authorized(
+            ctx.accounts.compliance_config.as_deref(),
+            treasury,
+            &authority.key()
+        ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    let clock = Clock::get()?;
+    let issuer = ctx.accounts.issuer.key();
+    let entry = &mut ctx.accounts.kyc_issuer;
+    entry.treasury = treasury.key();
+    entry.issuer = issuer;
+    entry.added_at = clock.unix_timestamp;
+    entry.bump = ctx.bumps.kyc_issuer;
+
+    emit!(KycIssuerAddedEvent {
+        treasury: treasury.key(),
+        issuer,
+        authority: authority.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
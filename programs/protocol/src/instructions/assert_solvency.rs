@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::events::SolvencyCheckedEvent;
+use crate::state::{MintLedger, Treasury};
+
+/// Checks the treasury ATA against the mint ledger.
+///
+/// Fails with `TreasuryInsolvent` when the ATA holds less than
+/// `MintLedger::expected_balance`; otherwise emits both balances (any
+/// surplus is tokens sent to the ATA outside the program). Read-only, so
+/// anyone can run it, simulate it, or require it in the same transaction.
+#[derive(Accounts)]
+pub struct AssertSolvency<'info> {
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Mint being checked
+    pub mint: Account<'info, Mint>,
+
+    /// Treasury ATA for this mint
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_ata: Account<'info, TokenAccount>,
+
+    /// Mint ledger PDA
+    ///
+    /// ["mint_ledger", treasury.key(), mint.key()]
+    #[account(
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump = mint_ledger.bump
+    )]
+    pub mint_ledger: Account<'info, MintLedger>,
+}
+
+pub fn handler(ctx: Context<AssertSolvency>) -> Result<()> {
+    let ledger_balance = ctx.accounts.mint_ledger.expected_balance()?;
+    let ata_balance = ctx.accounts.treasury_ata.amount;
+
+    require!(ata_balance >= ledger_balance, ErrorCode::TreasuryInsolvent);
+
+    let clock = Clock::get()?;
+    emit!(SolvencyCheckedEvent {
+        treasury: ctx.accounts.treasury.key(),
+        mint: ctx.accounts.mint.key(),
+        ledger_balance,
+        ata_balance,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
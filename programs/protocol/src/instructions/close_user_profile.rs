@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::UserProfileClosedEvent;
+use crate::state::UserProfile;
+
+/// Closes the caller's user profile and returns its rent.
+///
+/// Only profiles with no recorded activity (`tx_count == 0`) may be closed:
+/// withdraw receipts are derived from `tx_count`, so recreating an active
+/// profile would restart the counter onto receipt PDAs that already exist.
+#[derive(Accounts)]
+pub struct CloseUserProfile<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == user.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+pub fn handler(ctx: Context<CloseUserProfile>) -> Result<()> {
+    let profile = &ctx.accounts.user_profile;
+
+    require!(profile.tx_count == 0, ErrorCode::UserProfileHasActivity);
+
+    let clock = Clock::get()?;
+    emit!(UserProfileClosedEvent {
+        user: ctx.accounts.user.key(),
+        profile: profile.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
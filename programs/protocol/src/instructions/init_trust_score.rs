@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::events::TrustScoreInitializedEvent;
+use crate::state::TrustScore;
+
+/// Creates the caller's trust score PDA.
+///
+/// PDA seeds:
+/// ["trust_score", wallet.key()]
+///
+/// Account age, and so the age component of the score, starts now. Flows
+/// only update the score when this account is passed to them.
+#[derive(Accounts)]
+pub struct InitTrustScore<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    #[account(
+        init,
+        payer = wallet,
+        space = TrustScore::SPACE,
+        seeds = [TrustScore::SEED, wallet.key().as_ref()],
+        bump
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitTrustScore>) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let trust = &mut ctx.accounts.trust_score;
+
+    let clock = Clock::get()?;
+    trust.initialize(wallet.key(), ctx.bumps.trust_score, clock.unix_timestamp);
+
+    emit!(TrustScoreInitializedEvent {
+        wallet: wallet.key(),
+        trust_score: trust.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::events::UserProfileInitializedEvent;
+use crate::state::UserProfile;
+
+/// Creates the caller's user profile PDA at the current schema version.
+///
+/// PDA seeds:
+/// ["user_profile", user.key()]
+///
+/// Lets apps create (and then find) a profile up front instead of relying on
+/// the `init_if_needed` side effect of `spl_withdraw_with_receipt`.
+#[derive(Accounts)]
+pub struct InitUserProfile<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = UserProfile::SPACE,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitUserProfile>) -> Result<()> {
+    let user = &ctx.accounts.user;
+    let profile = &mut ctx.accounts.user_profile;
+
+    profile.initialize(user.key(), ctx.bumps.user_profile);
+
+    let clock = Clock::get()?;
+    emit!(UserProfileInitializedEvent {
+        user: user.key(),
+        profile: profile.key(),
+        version: profile.version,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
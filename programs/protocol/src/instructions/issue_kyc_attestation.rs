@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::KycAttestationIssuedEvent;
+use crate::state::{KycAttestation, KycIssuer, Treasury};
+
+/// Writes (or re-issues) a wallet's KYC/KYB attestation.
+///
+/// Signer must be a trusted issuer of the treasury; it pays the attestation
+/// rent. `level` is one of the `KycAttestation::LEVEL_*` values,
+/// `jurisdiction` an ISO 3166-1 alpha-2 code and `expires_at` a unix
+/// timestamp in the future.
+#[derive(Accounts)]
+pub struct IssueKycAttestation<'info> {
+    /// Trusted issuer signing the attestation; pays rent
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Issuer's trust record; its existence is the issuer check
+    ///
+    /// ["kyc_issuer", treasury.key(), issuer.key()]
+    #[account(
+        seeds = [KycIssuer::SEED, treasury.key().as_ref(), issuer.key().as_ref()],
+        bump = kyc_issuer.bump
+    )]
+    pub kyc_issuer: Account<'info, KycIssuer>,
+
+    /// CHECK: Attested wallet; only used as a PDA seed.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// KYC attestation PDA
+    ///
+    /// ["kyc", treasury.key(), wallet.key()]
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = KycAttestation::SPACE,
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub kyc_attestation: Account<'info, KycAttestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<IssueKycAttestation>,
+    level: u8,
+    jurisdiction: [u8; 2],
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        (KycAttestation::LEVEL_BASIC..=KycAttestation::MAX_LEVEL).contains(&level),
+        ErrorCode::InvalidKycAttestation
+    );
+    require!(
+        KycAttestation::is_jurisdiction_code(&jurisdiction),
+        ErrorCode::InvalidKycAttestation
+    );
+    require!(
+        expires_at > clock.unix_timestamp,
+        ErrorCode::InvalidKycAttestation
+    );
+
+    let treasury = ctx.accounts.treasury.key();
+    let issuer = ctx.accounts.issuer.key();
+    let wallet = ctx.accounts.wallet.key();
+
+    let attestation = &mut ctx.accounts.kyc_attestation;
+    attestation.treasury = treasury;
+    attestation.wallet = wallet;
+    attestation.issuer = issuer;
+    attestation.level = level;
+    attestation.jurisdiction = jurisdiction;
+    attestation.issued_at = clock.unix_timestamp;
+    attestation.expires_at = expires_at;
+    attestation.bump = ctx.bumps.kyc_attestation;
+
+    emit!(KycAttestationIssuedEvent {
+        treasury,
+        wallet,
+        issuer,
+        level,
+        jurisdiction,
+        expires_at,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
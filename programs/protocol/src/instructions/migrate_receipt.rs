@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::events::ReceiptMigratedEvent;
+use crate::state::Receipt;
+
+/// Upgrades a receipt account to `Receipt::CURRENT_VERSION` in place.
+///
+/// Behavior:
+/// - reads any historical layout via `Receipt::try_deserialize_versioned`
+/// - reallocs to `Receipt::SPACE`, topping up rent from `payer`
+/// - rewrites the account with every stored field preserved
+///
+/// Permissionless: the receipt content is never changed, only its layout.
+/// Already-current receipts are left untouched (no-op), so batch migration
+/// scripts can safely re-run.
+#[derive(Accounts)]
+pub struct MigrateReceipt<'info> {
+    /// Pays for any additional rent required by the larger layout
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Receipt account to migrate
+    ///
+    /// CHECK:
+    /// Legacy layouts cannot be loaded as `Account<Receipt>`. Ownership is
+    /// enforced here; the discriminator and layout are verified in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub receipt: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateReceipt>) -> Result<()> {
+    let info = ctx.accounts.receipt.to_account_info();
+
+    let mut receipt = {
+        let data = info.try_borrow_data()?;
+        Receipt::try_deserialize_versioned(&data)?
+    };
+
+    let from_version = receipt.version;
+    if from_version == Receipt::CURRENT_VERSION {
+        return Ok(());
+    }
+
+    // --- Rent top-up for the larger layout ---
+    let required = Rent::get()?.minimum_balance(Receipt::SPACE);
+    let shortfall = required.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: info.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, shortfall)?;
+    }
+
+    info.resize(Receipt::SPACE)?;
+
+    // --- Rewrite in the current layout (discriminator included) ---
+    receipt.version = Receipt::CURRENT_VERSION;
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        receipt.try_serialize(&mut writer)?;
+    }
+
+    let clock = Clock::get()?;
+    emit!(ReceiptMigratedEvent {
+        receipt: info.key(),
+        from_version,
+        to_version: Receipt::CURRENT_VERSION,
+        payer: ctx.accounts.payer.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
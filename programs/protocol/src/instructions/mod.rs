@@ -5,7 +5,42 @@ pub mod spl_withdraw;
 pub mod spl_deposit_with_receipt;
 pub mod spl_withdraw_with_receipt;
 pub mod spl_pay;
+pub mod spl_pay_with_reference;
 pub mod set_treasury_paused;
+pub mod migrate_receipt;
+pub mod set_receipt_attestor;
+pub mod verify_receipt_attestation;
+pub mod init_user_profile;
+pub mod close_user_profile;
+pub mod update_user_profile;
+pub mod set_xp_config;
+pub mod set_risk_config;
+pub mod set_limit_tier;
+pub mod set_user_limit_tier;
+pub mod set_compliance_authority;
+pub mod add_denylist_entry;
+pub mod remove_denylist_entry;
+pub mod set_kyc_config;
+pub mod add_kyc_issuer;
+pub mod remove_kyc_issuer;
+pub mod issue_kyc_attestation;
+pub mod revoke_kyc_attestation;
+pub mod set_travel_rule_config;
+pub mod record_travel_rule;
+pub mod set_trust_config;
+pub mod init_trust_score;
+pub mod refresh_trust_score;
+pub mod set_dispute_config;
+pub mod open_dispute;
+pub mod submit_dispute_evidence;
+pub mod resolve_dispute;
+pub mod set_merchant_fee_tiers;
+pub mod register_merchant;
+pub mod update_merchant;
+pub mod set_merchant_status;
+pub mod pay_merchant;
+pub mod open_mint_ledger;
+pub mod assert_solvency;
 
 pub use initialize_treasury::InitializeTreasury;
 pub use spl_deposit::SplDeposit;
@@ -14,4 +49,39 @@ pub use spl_withdraw::SplWithdraw;
 pub use spl_deposit_with_receipt::SplDepositWithReceipt;
 pub use spl_withdraw_with_receipt::SplWithdrawWithReceipt;
 pub use spl_pay::*;
+pub use spl_pay_with_reference::SplPayWithReference;
 pub use set_treasury_paused::SetTreasuryPaused;
+pub use migrate_receipt::MigrateReceipt;
+pub use set_receipt_attestor::SetReceiptAttestor;
+pub use verify_receipt_attestation::VerifyReceiptAttestation;
+pub use init_user_profile::InitUserProfile;
+pub use close_user_profile::CloseUserProfile;
+pub use update_user_profile::UpdateUserProfile;
+pub use set_xp_config::SetXpConfig;
+pub use set_risk_config::SetRiskConfig;
+pub use set_limit_tier::SetLimitTier;
+pub use set_user_limit_tier::SetUserLimitTier;
+pub use set_compliance_authority::SetComplianceAuthority;
+pub use add_denylist_entry::AddDenylistEntry;
+pub use remove_denylist_entry::RemoveDenylistEntry;
+pub use set_kyc_config::SetKycConfig;
+pub use add_kyc_issuer::AddKycIssuer;
+pub use remove_kyc_issuer::RemoveKycIssuer;
+pub use issue_kyc_attestation::IssueKycAttestation;
+pub use revoke_kyc_attestation::RevokeKycAttestation;
+pub use set_travel_rule_config::SetTravelRuleConfig;
+pub use record_travel_rule::RecordTravelRule;
+pub use set_trust_config::SetTrustConfig;
+pub use init_trust_score::InitTrustScore;
+pub use refresh_trust_score::RefreshTrustScore;
+pub use set_dispute_config::SetDisputeConfig;
+pub use open_dispute::OpenDispute;
+pub use submit_dispute_evidence::SubmitDisputeEvidence;
+pub use resolve_dispute::ResolveDispute;
+pub use set_merchant_fee_tiers::SetMerchantFeeTiers;
+pub use register_merchant::RegisterMerchant;
+pub use update_merchant::UpdateMerchant;
+pub use set_merchant_status::SetMerchantStatus;
+pub use pay_merchant::PayMerchant;
+pub use open_mint_ledger::OpenMintLedger;
+pub use assert_solvency::AssertSolvency;
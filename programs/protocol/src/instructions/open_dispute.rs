@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::DisputeOpenedEvent;
+use crate::state::{Dispute, DisputeConfig, Merchant, Receipt, Treasury};
+
+/// Opens a dispute against a merchant payment or pay receipt.
+///
+/// The opener must be the receipt's payer: the paying customer for merchant
+/// payment receipts (the merchant owner responds), the treasury authority
+/// for pay receipts. Only allowed within the configured window after the
+/// receipt's `ts`, and once per receipt.
+///
+/// Deposits are not disputable: the depositor can withdraw through the
+/// treasury, and a deposit "refund" would only be a claw-back of the
+/// depositor's own funds.
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    /// Paying side of the receipt; pays rent for the dispute account
+    #[account(mut)]
+    pub opener: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Dispute config PDA
+    ///
+    /// ["dispute_config", treasury.key()]
+    #[account(
+        seeds = [DisputeConfig::SEED, treasury.key().as_ref()],
+        bump = dispute_config.bump
+    )]
+    pub dispute_config: Account<'info, DisputeConfig>,
+
+    /// Receipt being disputed
+    pub receipt: Account<'info, Receipt>,
+
+    /// Dispute PDA
+    ///
+    /// ["dispute", receipt.key()]
+    #[account(
+        init,
+        payer = opener,
+        space = Dispute::SPACE,
+        seeds = [Dispute::SEED, receipt.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Merchant that issued the receipt; required for merchant payment
+    /// receipts, which must sit at
+    /// ["receipt", merchant.key(), receipt.tx_count.to_le_bytes()]
+    #[account(
+        seeds = [Merchant::SEED, treasury.key().as_ref(), merchant.owner.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Option<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<OpenDispute>, reason_code: u16, evidence: [u8; 32]) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let opener = &ctx.accounts.opener;
+    let receipt = &ctx.accounts.receipt;
+    let config = &ctx.accounts.dispute_config;
+
+    // --- Payer gate: who paid, and who answers ---
+    let respondent = match receipt.direction {
+        Receipt::DIR_MERCHANT_PAY => {
+            require_keys_eq!(opener.key(), receipt.user, ErrorCode::UnauthorizedDispute);
+            let merchant = ctx
+                .accounts
+                .merchant
+                .as_ref()
+                .ok_or(ErrorCode::MerchantRequired)?;
+            require!(
+                Merchant::issued(&merchant.key(), receipt),
+                ErrorCode::MerchantRequired
+            );
+            merchant.owner
+        }
+        Receipt::DIR_PAY => {
+            require_keys_eq!(opener.key(), treasury.authority, ErrorCode::UnauthorizedDispute);
+            receipt.user
+        }
+        _ => return err!(ErrorCode::ReceiptNotDisputable),
+    };
+
+    // --- Window ---
+    require!(config.window_secs > 0, ErrorCode::DisputesDisabled);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now <= receipt.ts.saturating_add(config.window_secs),
+        ErrorCode::DisputeWindowClosed
+    );
+
+    require!(evidence != [0u8; 32], ErrorCode::InvalidDisputeEvidence);
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.treasury = treasury.key();
+    dispute.receipt = receipt.key();
+    dispute.receipt_direction = receipt.direction;
+    dispute.payer = opener.key();
+    dispute.respondent = respondent;
+    dispute.mint = receipt.mint;
+    dispute.amount = receipt.amount;
+    dispute.reason_code = reason_code;
+    dispute.payer_evidence = evidence;
+    dispute.respondent_evidence = [0u8; 32];
+    dispute.status = Dispute::STATUS_OPEN;
+    dispute.refund_amount = 0;
+    dispute.opened_at = now;
+    dispute.resolved_at = 0;
+    dispute.resolved_by = Pubkey::default();
+    dispute.resolution_receipt = Pubkey::default();
+    dispute.bump = ctx.bumps.dispute;
+
+    let clock = Clock::get()?;
+    emit!(DisputeOpenedEvent {
+        treasury: treasury.key(),
+        dispute: dispute.key(),
+        receipt: receipt.key(),
+        payer: dispute.payer,
+        respondent,
+        mint: dispute.mint,
+        amount: dispute.amount,
+        reason_code,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
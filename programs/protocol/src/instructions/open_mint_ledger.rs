@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::MintLedgerOpenedEvent;
+use crate::state::{MintLedger, Treasury};
+
+/// Opens the per-mint ledger the value flows keep from then on.
+///
+/// The current treasury ATA balance (the ATA is created if missing) becomes
+/// the ledger's opening balance, so mints that already hold funds can be
+/// brought under `assert_solvency` without replaying their history. A
+/// ledger is opened once and never reset.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct OpenMintLedger<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Mint the ledger is denominated in
+    pub mint: Account<'info, Mint>,
+
+    /// Treasury ATA for this mint (created if missing)
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_ata: Account<'info, TokenAccount>,
+
+    /// Mint ledger PDA
+    ///
+    /// ["mint_ledger", treasury.key(), mint.key()]
+    #[account(
+        init,
+        payer = treasury_authority,
+        space = MintLedger::SPACE,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: Account<'info, MintLedger>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenMintLedger>) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    let clock = Clock::get()?;
+    let opening_balance = ctx.accounts.treasury_ata.amount;
+
+    let ledger = &mut ctx.accounts.mint_ledger;
+    ledger.treasury = treasury.key();
+    ledger.mint = ctx.accounts.mint.key();
+    ledger.bump = ctx.bumps.mint_ledger;
+    ledger.version = MintLedger::CURRENT_VERSION;
+    ledger.opening_balance = opening_balance;
+    ledger.opened_slot = clock.slot;
+    ledger.last_slot = clock.slot;
+
+    emit!(MintLedgerOpenedEvent {
+        treasury: treasury.key(),
+        mint: ctx.accounts.mint.key(),
+        authority: authority.key(),
+        opening_balance,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
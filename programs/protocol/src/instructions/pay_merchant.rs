@@ -0,0 +1,381 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::MerchantPaidEvent;
+use crate::state::{
+    DenylistEntry, KycAttestation, LedgerEntry, Merchant, MintLedger, MintLimits, Receipt,
+    ReceiptV2Ext, TravelRuleRecord, Treasury, TreasuryConfig, TrustScore, UserLimits,
+    UserProfile,
+};
+use crate::denylist::ensure_not_denylisted;
+use crate::kyc::enforce_kyc;
+use crate::limits::{enforce_limits, LimitAccounts};
+use crate::risk::evaluate_user_flow;
+use crate::travel_rule::consume_travel_rule;
+use crate::trust::{credit_trust, trust_limit_tier};
+use crate::xp::{credit_xp, XpFlow};
+
+/// Customer payment to a registered merchant.
+///
+/// The payee, accepted mints and fee all come from the `Merchant` account:
+/// - the net amount goes to the merchant's `payout_wallet` ATA
+/// - the fee (`TreasuryConfig.merchant_fee_bps[merchant.fee_tier]`) goes to
+///   the treasury ATA
+///
+/// The payer goes through the same hooks as a user flow: denylist, KYC,
+/// travel rule (beneficiary = payout wallet), risk rules and the payer's
+/// spending limits, all on the gross amount. With the payer's profile and
+/// trust score supplied, the payment earns XP (`XpFlow::MerchantPay`) and
+/// trust points.
+///
+/// Receipt PDA seeds:
+/// ["receipt", merchant.key(), merchant.pay_count_before.to_le_bytes()]
+///
+/// Like SPL pay, this is a single-writer path per merchant: concurrent
+/// payments reading the same pay_count target the same receipt PDA and all
+/// but one fail.
+#[derive(Accounts)]
+#[instruction(amount: u64, reference: Option<[u8; 32]>)]
+pub struct PayMerchant<'info> {
+    /// Paying customer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+        constraint = !treasury.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Merchant PDA
+    ///
+    /// ["merchant", treasury.key(), merchant.owner]
+    #[account(
+        mut,
+        seeds = [Merchant::SEED, treasury.key().as_ref(), merchant.owner.as_ref()],
+        bump = merchant.bump,
+        constraint = merchant.is_active() @ ErrorCode::MerchantSuspended
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    /// CHECK: Merchant payout wallet, pinned to `merchant.payout_wallet`.
+    #[account(address = merchant.payout_wallet @ ErrorCode::InvalidMerchantConfig)]
+    pub payout_wallet: UncheckedAccount<'info>,
+
+    /// SPL mint being paid
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payer
+    )]
+    pub payer_ata: Account<'info, TokenAccount>,
+
+    /// Merchant payout ATA for this mint
+    ///
+    /// Created if missing, paid by the payer.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = payout_wallet
+    )]
+    pub payout_ata: Account<'info, TokenAccount>,
+
+    /// Treasury ATA for this mint; receives the fee
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_ata: Account<'info, TokenAccount>,
+
+    /// Merchant payment receipt PDA
+    ///
+    /// ["receipt", merchant.key(), merchant.pay_count_before.to_le_bytes()]
+    #[account(
+        init,
+        payer = payer,
+        space = Receipt::SPACE,
+        seeds = [
+            Receipt::RECEIPT_SEED,
+            merchant.key().as_ref(),
+            &merchant.pay_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    /// CHECK:
+    /// Treasury config PDA (merchant fee tiers); it may not exist yet, in
+    /// which case no fee is charged. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Payer denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Payout wallet denylist entry PDA; same rules as `denylist_entry`.
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), payout_wallet.key().as_ref()],
+        bump
+    )]
+    pub payout_denylist_entry: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
+
+    /// Optional payer profile; when supplied, the payment earns the payer XP.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, payer.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == payer.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Payer limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            payer.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Payer KYC attestation PDA (see `crate::kyc`).
+    #[account(
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// Optional travel-rule record for this payment; required above the
+    /// config's `travel_rule_threshold`.
+    ///
+    /// ["travel_rule", treasury.key(), receipt.key()]
+    #[account(
+        mut,
+        seeds = [TravelRuleRecord::SEED, treasury.key().as_ref(), receipt.key().as_ref()],
+        bump = travel_rule.bump
+    )]
+    pub travel_rule: Option<Account<'info, TravelRuleRecord>>,
+
+    /// Optional trust score of the payer; credited by this receipted flow, and
+    /// its score may lift the payer's limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", payer.key()]
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, payer.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+}
+
+pub fn handler(
+    ctx: Context<PayMerchant>,
+    amount: u64,
+    reference: Option<[u8; 32]>,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // --- Denylist (may reject) ---
+    ensure_not_denylisted(&ctx.accounts.denylist_entry, ErrorCode::WalletDenylisted)?;
+    ensure_not_denylisted(&ctx.accounts.payout_denylist_entry, ErrorCode::RecipientDenylisted)?;
+
+    let config = TreasuryConfig::load(&ctx.accounts.treasury_config)?;
+
+    // --- KYC (may reject) ---
+    enforce_kyc(config.as_ref(), &ctx.accounts.kyc_attestation, amount)?;
+
+    // --- Travel rule (may reject) ---
+    let has_travel_rule = consume_travel_rule(
+        config.as_ref(),
+        ctx.accounts.travel_rule.as_mut(),
+        &ctx.accounts.payout_wallet.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        ctx.accounts.receipt.key(),
+    )?;
+
+    // --- Risk rules (may reject) ---
+    let risk_flags = evaluate_user_flow(
+        config.as_ref(),
+        ctx.accounts.user_profile.as_mut(),
+        &ctx.accounts.mint.key(),
+        ctx.accounts.mint.decimals,
+        amount,
+    )?;
+
+    // --- Spending limits (may reject) ---
+    let trust_tier = trust_limit_tier(config.as_ref(), ctx.accounts.trust_score.as_deref())?;
+    enforce_limits(
+        &LimitAccounts {
+            mint_limits: &ctx.accounts.mint_limits,
+            user_limits: &ctx.accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            payer: &ctx.accounts.payer,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.treasury.key(),
+        &ctx.accounts.payer.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        trust_tier,
+    )?;
+
+    // --- Fee from the merchant's tier ---
+    let fee_bps = config
+        .as_ref()
+        .map_or(0, |c| c.merchant_fee_bps_for(ctx.accounts.merchant.fee_tier));
+    let fee = (u128::from(amount) * u128::from(fee_bps) / 10_000) as u64;
+    let net = amount - fee;
+
+    // --- Settlement stats (also rejects mints the merchant does not accept) ---
+    let mint = ctx.accounts.mint.key();
+    let clock = Clock::get()?;
+    let merchant = &mut ctx.accounts.merchant;
+    let pay_index = merchant.pay_count;
+
+    let settlement = merchant
+        .settlement_mut(&mint)
+        .ok_or(ErrorCode::MerchantMintNotAccepted)?;
+    settlement.gross_volume = settlement
+        .gross_volume
+        .checked_add(amount)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    settlement.fees = settlement
+        .fees
+        .checked_add(fee)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    merchant.pay_count = merchant
+        .pay_count
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+    merchant.last_paid_at = clock.unix_timestamp;
+
+    // --- Transfers: payer -> merchant (net), payer -> treasury (fee) ---
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let payer = ctx.accounts.payer.to_account_info();
+
+    if net > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer_ata.to_account_info(),
+            to: ctx.accounts.payout_ata.to_account_info(),
+            authority: payer.clone(),
+        };
+        token::transfer(CpiContext::new(token_program.clone(), cpi_accounts), net)?;
+    }
+
+    if fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer_ata.to_account_info(),
+            to: ctx.accounts.treasury_ata.to_account_info(),
+            authority: payer,
+        };
+        token::transfer(CpiContext::new(token_program, cpi_accounts), fee)?;
+        MintLedger::record(&ctx.accounts.mint_ledger, LedgerEntry::Fee(fee))?;
+    }
+
+    // --- Populate receipt ---
+    //
+    // For merchant payments:
+    // - `user` stores the paying wallet
+    // - `amount` is gross, `fee` the treasury's share
+    // - `tx_count` stores the merchant pay_count snapshot BEFORE increment
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.user = ctx.accounts.payer.key();
+    receipt.direction = Receipt::DIR_MERCHANT_PAY;
+    receipt.asset_kind = Receipt::ASSET_SPL;
+    receipt.mint = mint;
+    receipt.amount = amount;
+    receipt.fee = fee;
+    receipt.pre_balance = 0;
+    receipt.post_balance = 0;
+    receipt.ts = clock.unix_timestamp;
+    receipt.tx_count = pay_index;
+    receipt.bump = ctx.bumps.receipt;
+    receipt.version = Receipt::CURRENT_VERSION;
+    receipt.v2 = ReceiptV2Ext::spl_with_meta(mint, reference, None);
+    receipt.v2.set_risk_flags(risk_flags);
+    if has_travel_rule {
+        receipt.v2.flags |= ReceiptV2Ext::FLAG_HAS_TRAVEL_RULE;
+    }
+    let receipt = receipt.key();
+
+    // --- Optional payer activity, XP + trust ---
+    if let Some(profile) = ctx.accounts.user_profile.as_mut() {
+        profile.record_activity(Receipt::DIR_MERCHANT_PAY, mint, amount, clock.unix_timestamp)?;
+    }
+    let xp_delta = credit_xp(
+        ctx.accounts.user_profile.as_mut(),
+        config.as_ref(),
+        XpFlow::MerchantPay,
+        amount,
+    )?;
+    credit_trust(ctx.accounts.trust_score.as_mut(), config.as_ref(), amount)?;
+
+    emit!(MerchantPaidEvent {
+        treasury: ctx.accounts.treasury.key(),
+        merchant: ctx.accounts.merchant.key(),
+        payer: ctx.accounts.payer.key(),
+        payout_wallet: ctx.accounts.payout_wallet.key(),
+        receipt,
+        mint,
+        amount,
+        fee,
+        pay_index,
+        has_reference: reference.is_some(),
+        reference: reference.unwrap_or_default(),
+        xp_delta,
+        risk_flags,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
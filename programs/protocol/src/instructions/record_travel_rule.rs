@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::TravelRuleRecordedEvent;
+use crate::state::{TravelRuleRecord, Treasury};
+use crate::travel_rule::TravelRuleParams;
+
+/// Creates a travel-rule record for an upcoming withdrawal or payout.
+///
+/// For receipt flows `params.record_id` must be the address of the receipt
+/// the transfer will create (e.g. the next pay receipt). The record is
+/// consumed by exactly one matching transfer.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+#[instruction(params: TravelRuleParams)]
+pub struct RecordTravelRule<'info> {
+    /// Authorized signer for treasury control; pays rent
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Travel-rule record PDA
+    ///
+    /// ["travel_rule", treasury.key(), params.record_id]
+    #[account(
+        init,
+        payer = treasury_authority,
+        space = TravelRuleRecord::SPACE,
+        seeds = [TravelRuleRecord::SEED, treasury.key().as_ref(), params.record_id.as_ref()],
+        bump
+    )]
+    pub travel_rule: Account<'info, TravelRuleRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RecordTravelRule>, params: TravelRuleParams) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    params.validate()?;
+
+    let clock = Clock::get()?;
+    let record_key = ctx.accounts.travel_rule.key();
+    let record = &mut ctx.accounts.travel_rule;
+    record.treasury = treasury.key();
+    record.record_id = params.record_id;
+    record.beneficiary = params.beneficiary;
+    record.mint = params.mint;
+    record.amount = params.amount;
+    record.originator_vasp = params.originator_vasp;
+    record.beneficiary_vasp = params.beneficiary_vasp;
+    record.pii_hash = params.pii_hash;
+    record.created_at = clock.unix_timestamp;
+    record.consumed_at = 0;
+    record.receipt = Pubkey::default();
+    record.bump = ctx.bumps.travel_rule;
+
+    emit!(TravelRuleRecordedEvent {
+        treasury: treasury.key(),
+        record: record_key,
+        authority: authority.key(),
+        record_id: params.record_id,
+        beneficiary: params.beneficiary,
+        mint: params.mint,
+        amount: params.amount,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
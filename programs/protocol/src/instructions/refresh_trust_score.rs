@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::TrustScore;
+use crate::trust::emit_trust_updated;
+
+/// Permissionless: applies decay and account age to a trust score.
+///
+/// PDA seeds:
+/// ["trust_score", trust_score.wallet]
+///
+/// Lets external programs that read the stored `score` directly (instead of
+/// calling `TrustScore::score_at`) see an up-to-date value.
+#[derive(Accounts)]
+pub struct RefreshTrustScore<'info> {
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, trust_score.wallet.as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+}
+
+pub fn handler(ctx: Context<RefreshTrustScore>) -> Result<()> {
+    let trust = &mut ctx.accounts.trust_score;
+
+    let clock = Clock::get()?;
+    trust.refresh(clock.unix_timestamp);
+
+    emit_trust_updated(trust, clock.slot);
+    Ok(())
+}
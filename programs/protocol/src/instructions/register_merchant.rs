@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::MerchantRegisteredEvent;
+use crate::state::{ComplianceConfig, Merchant, Treasury};
+
+/// Registers a merchant for `owner`.
+///
+/// The owner signs (and pays rent) to accept the registration; the treasury
+/// authority or designated compliance authority signs to onboard it and
+/// picks its fee tier. The merchant starts active.
+#[derive(Accounts)]
+pub struct RegisterMerchant<'info> {
+    /// Merchant owner; pays rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Treasury authority or designated compliance authority
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// Merchant PDA
+    ///
+    /// ["merchant", treasury.key(), owner.key()]
+    #[account(
+        init,
+        payer = owner,
+        space = Merchant::SPACE,
+        seeds = [Merchant::SEED, treasury.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<RegisterMerchant>,
+    payout_wallet: Pubkey,
+    settlement_mints: Vec<Pubkey>,
+    fee_tier: u8,
+) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+
+    // --- Compliance gate ---
+    require!(
+        ComplianceConfig::is_authorized(
+            ctx.accounts.compliance_config.as_deref(),
+            treasury,
+            &authority.key()
+        ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    require!(
+        payout_wallet != Pubkey::default()
+            && Merchant::is_valid_settlement_mints(&settlement_mints)
+            && (fee_tier as usize) < Merchant::MAX_FEE_TIERS,
+        ErrorCode::InvalidMerchantConfig
+    );
+
+    let clock = Clock::get()?;
+    let owner = ctx.accounts.owner.key();
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.treasury = treasury.key();
+    merchant.owner = owner;
+    merchant.payout_wallet = payout_wallet;
+    merchant.set_settlement_mints(&settlement_mints);
+    merchant.fee_tier = fee_tier;
+    merchant.status = Merchant::STATUS_ACTIVE;
+    merchant.pay_count = 0;
+    merchant.registered_at = clock.unix_timestamp;
+    merchant.updated_at = clock.unix_timestamp;
+    merchant.last_paid_at = 0;
+    merchant.bump = ctx.bumps.merchant;
+
+    emit!(MerchantRegisteredEvent {
+        treasury: treasury.key(),
+        merchant: merchant.key(),
+        owner,
+        payout_wallet,
+        authority: authority.key(),
+        fee_tier,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
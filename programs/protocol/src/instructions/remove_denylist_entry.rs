@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::DenylistEntryRemovedEvent;
+use crate::state::{ComplianceConfig, DenylistEntry, Treasury};
+
+/// Removes a wallet from the denylist, closing its entry.
+///
+/// Rent is returned to the signer. Signer must be the treasury authority or
+/// the designated compliance authority.
+#[derive(Accounts)]
+pub struct RemoveDenylistEntry<'info> {
+    /// Treasury authority or designated compliance authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// CHECK: Denylisted wallet; only used as a PDA seed.
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Denylist entry PDA
+    ///
+    /// ["denylist", treasury.key(), wallet.key()]
+    #[account(
+        mut,
+        close = authority,
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), wallet.key().as_ref()],
+        bump = denylist_entry.bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+}
+
+pub fn handler(ctx: Context<RemoveDenylistEntry>) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+
+    // --- Compliance gate ---
+    require!(
+        ComplianceConfig::is_authorized(
+            ctx.accounts.compliance_config.as_deref(),
+            treasury,
+            &authority.key()
+        ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    let entry = &ctx.accounts.denylist_entry;
+    let clock = Clock::get()?;
+    emit!(DenylistEntryRemovedEvent {
+        treasury: treasury.key(),
+        wallet: entry.wallet,
+        authority: authority.key(),
+        reason_code: entry.reason_code,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
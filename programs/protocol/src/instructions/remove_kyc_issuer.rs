@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::KycIssuerRemovedEvent;
+use crate::state::{ComplianceConfig, KycIssuer, Treasury};
+
+/// Stops trusting a KYC issuer, closing its issuer account.
+///
+/// Attestations the issuer already wrote stay valid until they expire or are
+/// revoked. Rent is returned to the signer, which must be the treasury
+/// authority or the designated compliance authority.
+#[derive(Accounts)]
+pub struct RemoveKycIssuer<'info> {
+    /// Treasury authority or designated compliance authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// KYC issuer PDA
+    ///
+    /// ["kyc_issuer", treasury.key(), issuer]
+    #[account(
+        mut,
+        close = authority,
+        seeds = [KycIssuer::SEED, treasury.key().as_ref(), kyc_issuer.issuer.as_ref()],
+        bump = kyc_issuer.bump
+    )]
+    pub kyc_issuer: Account<'info, KycIssuer>,
+}
+
+pub fn handler(ctx: Context<RemoveKycIssuer>) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+
+    // --- Compliance gate ---
+    require!(
+        ComplianceConfig::is_authorized(
+            ctx.accounts.compliance_config.as_deref(),
+            treasury,
+            &authority.key()
+        ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    let clock = Clock::get()?;
+    emit!(KycIssuerRemovedEvent {
+        treasury: treasury.key(),
+        issuer: ctx.accounts.kyc_issuer.issuer,
+        authority: authority.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
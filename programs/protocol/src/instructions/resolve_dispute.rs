@@ -0,0 +1,293 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::denylist::ensure_not_denylisted;
+use crate::errors::ErrorCode;
+use crate::events::DisputeResolvedEvent;
+use crate::state::{
+    DenylistEntry, Dispute, DisputeConfig, LedgerEntry, Merchant, MintLedger, Receipt,
+    ReceiptV2Ext, Treasury, TreasuryConfig, TrustScore, UserProfile,
+};
+use crate::trust::emit_trust_updated;
+use crate::xp::{credit_xp, XpFlow};
+
+/// Resolves an open dispute: refund (`refund_amount > 0`) or reject (0).
+///
+/// Refunds are only possible for merchant payment receipts, never exceed the
+/// disputed amount and are blocked while the treasury is paused or the payer
+/// is denylisted. The payment already settled to the merchant's payout
+/// wallet, which the program cannot debit, so the treasury fronts the refund
+/// from its ATA to the payer's ATA and books it against the merchant
+/// (`MerchantSettlement.refunded`) for recovery. Either way a linked receipt
+/// is written at ["receipt", dispute.key()], and the respondent's trust score
+/// (when supplied) records the outcome. A refund earns the payer XP
+/// (`XpFlow::Refund`) when the payer's profile is supplied.
+///
+/// Signer must be the treasury authority or the designated arbiter.
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// Treasury authority or designated arbiter; pays for the resolution receipt
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Dispute config PDA
+    ///
+    /// ["dispute_config", treasury.key()]
+    #[account(
+        seeds = [DisputeConfig::SEED, treasury.key().as_ref()],
+        bump = dispute_config.bump
+    )]
+    pub dispute_config: Account<'info, DisputeConfig>,
+
+    /// Dispute PDA
+    ///
+    /// ["dispute", dispute.receipt]
+    #[account(
+        mut,
+        seeds = [Dispute::SEED, dispute.receipt.as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.treasury == treasury.key() @ ErrorCode::UnauthorizedDispute
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    /// Linked resolution receipt
+    ///
+    /// ["receipt", dispute.key()]
+    #[account(
+        init,
+        payer = arbiter,
+        space = Receipt::SPACE,
+        seeds = [Receipt::RECEIPT_SEED, dispute.key().as_ref()],
+        bump
+    )]
+    pub resolution_receipt: Account<'info, Receipt>,
+
+    /// Mint of the disputed receipt
+    #[account(constraint = mint.key() == dispute.mint @ ErrorCode::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    /// Payer wallet (refund recipient)
+    /// CHECK: Only used as ATA authority; pinned to `dispute.payer`.
+    #[account(address = dispute.payer @ ErrorCode::UnauthorizedDispute)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// Payer ATA; required for refunds
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payer
+    )]
+    pub payer_ata: Option<Account<'info, TokenAccount>>,
+
+    /// Treasury ATA; required for refunds
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_ata: Option<Account<'info, TokenAccount>>,
+
+    /// Optional trust score of the respondent
+    ///
+    /// ["trust_score", dispute.respondent]
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, dispute.respondent.as_ref()],
+        bump = respondent_trust.bump
+    )]
+    pub respondent_trust: Option<Account<'info, TrustScore>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table); it may not exist yet, which disables
+    /// XP. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// Optional payer profile; when supplied, a refund earns the payer XP.
+    ///
+    /// ["user_profile", dispute.payer]
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, dispute.payer.as_ref()],
+        bump = payer_profile.bump,
+        constraint = payer_profile.authority == dispute.payer @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub payer_profile: Option<Account<'info, UserProfile>>,
+
+    /// Merchant that issued the disputed receipt; required for refunds
+    ///
+    /// ["merchant", treasury.key(), dispute.respondent]
+    #[account(
+        mut,
+        seeds = [Merchant::SEED, treasury.key().as_ref(), dispute.respondent.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Option<Account<'info, Merchant>>,
+
+    /// CHECK:
+    /// Payer denylist entry PDA; checked before a refund (see
+    /// `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), dispute.payer.as_ref()],
+        bump
+    )]
+    pub payer_denylist_entry: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ResolveDispute>, refund_amount: u64) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let arbiter = &ctx.accounts.arbiter;
+
+    require!(ctx.accounts.dispute.is_open(), ErrorCode::DisputeNotOpen);
+
+    // --- Arbiter gate ---
+    require!(
+        ctx.accounts
+            .dispute_config
+            .is_arbiter(treasury, &arbiter.key()),
+        ErrorCode::UnauthorizedDispute
+    );
+
+    let refunded = refund_amount > 0;
+
+    // --- Refund (value moves only for merchant payment receipts) ---
+    if refunded {
+        let dispute = &ctx.accounts.dispute;
+        require!(
+            dispute.receipt_direction == Receipt::DIR_MERCHANT_PAY
+                && refund_amount <= dispute.amount,
+            ErrorCode::InvalidRefundAmount
+        );
+        require!(!treasury.paused, ErrorCode::ProtocolPaused);
+        ensure_not_denylisted(
+            &ctx.accounts.payer_denylist_entry,
+            ErrorCode::RecipientDenylisted,
+        )?;
+
+        let (Some(payer_ata), Some(treasury_ata), Some(merchant)) = (
+            ctx.accounts.payer_ata.as_ref(),
+            ctx.accounts.treasury_ata.as_ref(),
+            ctx.accounts.merchant.as_mut(),
+        ) else {
+            return err!(ErrorCode::RefundAccountsRequired);
+        };
+
+        // --- Book the refund against the merchant ---
+        let settlement = merchant
+            .settlement_mut(&dispute.mint)
+            .ok_or(ErrorCode::MerchantMintNotAccepted)?;
+        settlement.refunded = settlement
+            .refunded
+            .checked_add(refund_amount)
+            .ok_or(ErrorCode::CounterOverflow)?;
+
+        let bump = treasury.bump;
+        let seeds: &[&[u8]] = &[b"treasury", &[bump]];
+        let signer = &[seeds];
+
+        let cpi_accounts = Transfer {
+            from: treasury_ata.to_account_info(),
+            to: payer_ata.to_account_info(),
+            authority: treasury.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        token::transfer(cpi_ctx, refund_amount)?;
+        MintLedger::record(&ctx.accounts.mint_ledger, LedgerEntry::Refund(refund_amount))?;
+    }
+
+    let clock = Clock::get()?;
+    let disputed_receipt = ctx.accounts.dispute.receipt;
+
+    // --- Linked resolution receipt ---
+    let r = &mut ctx.accounts.resolution_receipt;
+    r.user = ctx.accounts.dispute.payer;
+    r.direction = Receipt::DIR_DISPUTE;
+    r.asset_kind = Receipt::ASSET_SPL;
+    r.mint = ctx.accounts.mint.key();
+    r.amount = refund_amount;
+    r.fee = 0;
+    r.pre_balance = 0;
+    r.post_balance = 0;
+    r.ts = clock.unix_timestamp;
+    r.tx_count = 0;
+    r.bump = ctx.bumps.resolution_receipt;
+    r.version = Receipt::CURRENT_VERSION;
+    r.v2 = ReceiptV2Ext::spl_with_meta(
+        ctx.accounts.mint.key(),
+        Some(disputed_receipt.to_bytes()),
+        None,
+    );
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.status = if refunded {
+        Dispute::STATUS_REFUNDED
+    } else {
+        Dispute::STATUS_REJECTED
+    };
+    dispute.refund_amount = refund_amount;
+    dispute.resolved_at = clock.unix_timestamp;
+    dispute.resolved_by = arbiter.key();
+    dispute.resolution_receipt = ctx.accounts.resolution_receipt.key();
+
+    // --- Trust: a refund counts against the respondent ---
+    if let Some(trust) = ctx.accounts.respondent_trust.as_mut() {
+        trust.record_dispute(refunded, clock.unix_timestamp);
+        emit_trust_updated(trust, clock.slot);
+    }
+
+    // --- Optional payer XP for the refunded amount ---
+    let xp_delta = if refunded {
+        let config = TreasuryConfig::load(&ctx.accounts.treasury_config)?;
+        credit_xp(
+            ctx.accounts.payer_profile.as_mut(),
+            config.as_ref(),
+            XpFlow::Refund,
+            refund_amount,
+        )?
+    } else {
+        0
+    };
+
+    emit!(DisputeResolvedEvent {
+        treasury: treasury.key(),
+        dispute: dispute.key(),
+        receipt: disputed_receipt,
+        resolution_receipt: dispute.resolution_receipt,
+        arbiter: arbiter.key(),
+        status: dispute.status,
+        refund_amount,
+        xp_delta,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::KycAttestationRevokedEvent;
+use crate::state::{ComplianceConfig, KycAttestation, Treasury};
+
+/// Revokes a wallet's KYC/KYB attestation, closing it.
+///
+/// Signer must be the attestation's issuer, the treasury authority or the
+/// designated compliance authority; rent is returned to the signer.
+#[derive(Accounts)]
+pub struct RevokeKycAttestation<'info> {
+    /// Issuer, treasury authority or designated compliance authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// KYC attestation PDA
+    ///
+    /// ["kyc", treasury.key(), wallet]
+    #[account(
+        mut,
+        close = authority,
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), kyc_attestation.wallet.as_ref()],
+        bump = kyc_attestation.bump
+    )]
+    pub kyc_attestation: Account<'info, KycAttestation>,
+}
+
+pub fn handler(ctx: Context<RevokeKycAttestation>) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+    let attestation = &ctx.accounts.kyc_attestation;
+
+    // --- Issuer / compliance gate ---
+    require!(
+        authority.key() == attestation.issuer
+            || ComplianceConfig::is_authorized(
+                ctx.accounts.compliance_config.as_deref(),
+                treasury,
+                &authority.key()
+            ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    let clock = Clock::get()?;
+    emit!(KycAttestationRevokedEvent {
+        treasury: treasury.key(),
+        wallet: attestation.wallet,
+        issuer: attestation.issuer,
+        authority: authority.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::ComplianceAuthoritySetEvent;
+use crate::state::{ComplianceConfig, Treasury};
+
+/// Sets (or rotates) the designated compliance authority for the treasury.
+///
+/// The compliance config PDA is created on first use and overwritten on later
+/// calls. Passing `Pubkey::default()` disables the designated key, leaving the
+/// treasury authority as the only compliance signer.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetComplianceAuthority<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Compliance config PDA
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = ComplianceConfig::SPACE,
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub compliance_config: Account<'info, ComplianceConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetComplianceAuthority>, compliance_authority: Pubkey) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.compliance_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    config.treasury = treasury.key();
+    config.compliance_authority = compliance_authority;
+    config.bump = ctx.bumps.compliance_config;
+
+    let clock = Clock::get()?;
+    emit!(ComplianceAuthoritySetEvent {
+        treasury: treasury.key(),
+        compliance_authority,
+        authority: authority.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::DisputeConfigSetEvent;
+use crate::state::{DisputeConfig, Treasury};
+
+/// Sets the dispute window and (optionally) a designated arbiter.
+///
+/// The dispute config PDA is created on first use and overwritten on later
+/// calls. A `window_secs` of 0 stops new disputes; `Pubkey::default()` as the
+/// arbiter leaves the treasury authority as the only arbiter. Open disputes
+/// can still be resolved either way.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetDisputeConfig<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Dispute config PDA
+    ///
+    /// ["dispute_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = DisputeConfig::SPACE,
+        seeds = [DisputeConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub dispute_config: Account<'info, DisputeConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetDisputeConfig>, arbiter: Pubkey, window_secs: i64) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.dispute_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    require!(window_secs >= 0, ErrorCode::InvalidDisputeConfig);
+
+    config.treasury = treasury.key();
+    config.arbiter = arbiter;
+    config.window_secs = window_secs;
+    config.bump = ctx.bumps.dispute_config;
+
+    let clock = Clock::get()?;
+    emit!(DisputeConfigSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        arbiter,
+        window_secs,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::KycConfigSetEvent;
+use crate::state::{KycAttestation, Treasury, TreasuryConfig};
+
+/// Sets the KYC requirement for withdrawals and payouts.
+///
+/// The treasury config PDA is created on first use. Flows moving more than
+/// `threshold` to a wallet then require the wallet's attestation to be at
+/// least `min_level`; a `min_level` of 0 turns the requirement off.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetKycConfig<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury config PDA
+    ///
+    /// ["treasury_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = TreasuryConfig::SPACE,
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetKycConfig>, min_level: u8, threshold: u64) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.treasury_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    require!(
+        min_level <= KycAttestation::MAX_LEVEL,
+        ErrorCode::InvalidKycAttestation
+    );
+
+    config.treasury = treasury.key();
+    config.bump = ctx.bumps.treasury_config;
+    config.version = TreasuryConfig::CURRENT_VERSION;
+    config.kyc_min_level = min_level;
+    config.kyc_threshold = threshold;
+
+    let clock = Clock::get()?;
+    emit!(KycConfigSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        min_level,
+        threshold,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
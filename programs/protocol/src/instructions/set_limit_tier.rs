@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::ErrorCode;
+use crate::events::LimitTierSetEvent;
+use crate::state::{LimitTier, MintLimits, Treasury};
+
+/// Sets one spending limit tier for a mint.
+///
+/// The mint limits PDA is created on first use; from then on every deposit,
+/// withdrawal and payout in that mint is checked against the user's tier.
+/// A zero `period_secs` makes the tier unlimited.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetLimitTier<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Mint the limits are denominated in
+    pub mint: Account<'info, Mint>,
+
+    /// Mint limits PDA
+    ///
+    /// ["mint_limits", treasury.key(), mint.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = MintLimits::SPACE,
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: Account<'info, MintLimits>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetLimitTier>,
+    tier: u8,
+    period_secs: i64,
+    max_count: u32,
+    max_amount: u64,
+) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let limits = &mut ctx.accounts.mint_limits;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    require!(
+        usize::from(tier) < MintLimits::MAX_TIERS,
+        ErrorCode::InvalidLimitTier
+    );
+    require!(period_secs >= 0, ErrorCode::InvalidLimitTier);
+
+    limits.treasury = treasury.key();
+    limits.mint = ctx.accounts.mint.key();
+    limits.bump = ctx.bumps.mint_limits;
+    limits.version = MintLimits::CURRENT_VERSION;
+    limits.tiers[usize::from(tier)] = LimitTier {
+        period_secs,
+        max_count,
+        max_amount,
+    };
+
+    let clock = Clock::get()?;
+    emit!(LimitTierSetEvent {
+        treasury: treasury.key(),
+        mint: ctx.accounts.mint.key(),
+        authority: authority.key(),
+        tier,
+        period_secs,
+        max_count,
+        max_amount,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
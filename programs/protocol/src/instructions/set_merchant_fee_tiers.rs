@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::MerchantFeeTiersSetEvent;
+use crate::state::{Merchant, Treasury, TreasuryConfig};
+
+/// Sets the fee (basis points) charged on merchant payments per fee tier.
+///
+/// The treasury config PDA is created on first use. `fee_bps[i]` applies to
+/// merchants with `fee_tier == i`; each entry is at most
+/// `Merchant::MAX_FEE_BPS`. All zeros make merchant payments fee-free.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetMerchantFeeTiers<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury config PDA
+    ///
+    /// ["treasury_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = TreasuryConfig::SPACE,
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetMerchantFeeTiers>,
+    fee_bps: [u16; Merchant::MAX_FEE_TIERS],
+) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.treasury_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    require!(
+        fee_bps.iter().all(|bps| *bps <= Merchant::MAX_FEE_BPS),
+        ErrorCode::InvalidMerchantConfig
+    );
+
+    config.treasury = treasury.key();
+    config.bump = ctx.bumps.treasury_config;
+    config.version = TreasuryConfig::CURRENT_VERSION;
+    config.merchant_fee_bps = fee_bps;
+
+    let clock = Clock::get()?;
+    emit!(MerchantFeeTiersSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        fee_bps,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::MerchantStatusSetEvent;
+use crate::state::{ComplianceConfig, Merchant, Treasury};
+
+/// Suspends or reinstates a merchant and sets its fee tier.
+///
+/// A suspended merchant keeps its account and stats but cannot receive
+/// merchant payments.
+///
+/// Signer must be the treasury authority or the designated compliance
+/// authority.
+#[derive(Accounts)]
+pub struct SetMerchantStatus<'info> {
+    /// Treasury authority or designated compliance authority
+    pub authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Optional compliance config
+    ///
+    /// ["compliance", treasury.key()]
+    #[account(
+        seeds = [ComplianceConfig::SEED, treasury.key().as_ref()],
+        bump = compliance_config.bump
+    )]
+    pub compliance_config: Option<Account<'info, ComplianceConfig>>,
+
+    /// Merchant PDA
+    ///
+    /// ["merchant", treasury.key(), merchant.owner]
+    #[account(
+        mut,
+        seeds = [Merchant::SEED, treasury.key().as_ref(), merchant.owner.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+}
+
+pub fn handler(ctx: Context<SetMerchantStatus>, status: u8, fee_tier: u8) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.authority;
+
+    // --- Compliance gate ---
+    require!(
+        ComplianceConfig::is_authorized(
+            ctx.accounts.compliance_config.as_deref(),
+            treasury,
+            &authority.key()
+        ),
+        ErrorCode::UnauthorizedCompliance
+    );
+
+    require!(
+        (status == Merchant::STATUS_ACTIVE || status == Merchant::STATUS_SUSPENDED)
+            && (fee_tier as usize) < Merchant::MAX_FEE_TIERS,
+        ErrorCode::InvalidMerchantConfig
+    );
+
+    let clock = Clock::get()?;
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.status = status;
+    merchant.fee_tier = fee_tier;
+    merchant.updated_at = clock.unix_timestamp;
+
+    emit!(MerchantStatusSetEvent {
+        treasury: treasury.key(),
+        merchant: merchant.key(),
+        authority: authority.key(),
+        status,
+        fee_tier,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::ReceiptAttestorSetEvent;
+use crate::state::{AttestorConfig, Treasury};
+
+/// Sets (or rotates) the designated receipt attestor for the treasury.
+///
+/// The attestor config PDA is created on first use and overwritten on later
+/// calls. Passing `Pubkey::default()` disables the designated attestor, leaving
+/// the treasury authority as the only accepted signer.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetReceiptAttestor<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Attestor config PDA
+    ///
+    /// ["attestor", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = AttestorConfig::SPACE,
+        seeds = [AttestorConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub attestor_config: Account<'info, AttestorConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetReceiptAttestor>, attestor: Pubkey) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.attestor_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    config.treasury = treasury.key();
+    config.attestor = attestor;
+    config.bump = ctx.bumps.attestor_config;
+
+    let clock = Clock::get()?;
+    emit!(ReceiptAttestorSetEvent {
+        treasury: treasury.key(),
+        attestor,
+        authority: authority.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
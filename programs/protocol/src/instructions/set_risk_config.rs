@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::RiskConfigSetEvent;
+use crate::risk::RiskConfigParams;
+use crate::state::{MintThreshold, Treasury, TreasuryConfig};
+
+/// Sets which risk rules run, their thresholds, and which flags block a flow.
+///
+/// The treasury config PDA is created on first use. Rules outside
+/// `rules_mask`, or with a zero setting, stay off; raised flags outside
+/// `blocking_mask` are only reported in events and receipts.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetRiskConfig<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury config PDA
+    ///
+    /// ["treasury_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = TreasuryConfig::SPACE,
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetRiskConfig>, params: RiskConfigParams) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.treasury_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    params.validate()?;
+
+    let mut mint_thresholds = [MintThreshold::default(); TreasuryConfig::MAX_RISK_MINTS];
+    mint_thresholds[..params.mint_thresholds.len()].copy_from_slice(&params.mint_thresholds);
+
+    config.treasury = treasury.key();
+    config.bump = ctx.bumps.treasury_config;
+    config.version = TreasuryConfig::CURRENT_VERSION;
+    config.risk_rules_mask = params.rules_mask;
+    config.risk_blocking_mask = params.blocking_mask;
+    config.risk_default_threshold = params.default_threshold;
+    config.risk_mint_thresholds = mint_thresholds;
+    config.risk_new_profile_secs = params.new_profile_secs;
+    config.risk_velocity_window_slots = params.velocity_window_slots;
+    config.risk_velocity_max_count = params.velocity_max_count;
+    config.risk_round_whole_units = params.round_whole_units;
+
+    let clock = Clock::get()?;
+    emit!(RiskConfigSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        rules_mask: params.rules_mask,
+        blocking_mask: params.blocking_mask,
+        default_threshold: params.default_threshold,
+        mint_threshold_count: params.mint_thresholds.len() as u8,
+        new_profile_secs: params.new_profile_secs,
+        velocity_window_slots: params.velocity_window_slots,
+        velocity_max_count: params.velocity_max_count,
+        round_whole_units: params.round_whole_units,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::TravelRuleConfigSetEvent;
+use crate::state::{Treasury, TreasuryConfig};
+
+/// Sets the amount above which withdrawals and payouts require a
+/// travel-rule record.
+///
+/// The treasury config PDA is created on first use. A zero `threshold`
+/// turns the requirement off.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetTravelRuleConfig<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury config PDA
+    ///
+    /// ["treasury_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = TreasuryConfig::SPACE,
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetTravelRuleConfig>, threshold: u64) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.treasury_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    config.treasury = treasury.key();
+    config.bump = ctx.bumps.treasury_config;
+    config.version = TreasuryConfig::CURRENT_VERSION;
+    config.travel_rule_threshold = threshold;
+
+    let clock = Clock::get()?;
+    emit!(TravelRuleConfigSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        threshold,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::TrustConfigSetEvent;
+use crate::state::{MintLimits, Treasury, TreasuryConfig};
+
+/// Sets the trust scores that lift wallets to higher spending limit tiers,
+/// and the minimum flow amount that earns trust points.
+///
+/// The treasury config PDA is created on first use. `tier_scores[i]` is the
+/// minimum `TrustScore` for tier `i`; 0 leaves the tier reachable only by
+/// assignment (`set_user_limit_tier`), and entry 0 must be 0. All zeros turn
+/// trust-based tiers off. `min_amount` (raw units, 0 = any amount) keeps dust
+/// flows from earning trust.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetTrustConfig<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury config PDA
+    ///
+    /// ["treasury_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = TreasuryConfig::SPACE,
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetTrustConfig>,
+    tier_scores: [u16; MintLimits::MAX_TIERS],
+    min_amount: u64,
+) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.treasury_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    require!(
+        TreasuryConfig::is_valid_trust_tiers(&tier_scores),
+        ErrorCode::InvalidTrustConfig
+    );
+
+    config.treasury = treasury.key();
+    config.bump = ctx.bumps.treasury_config;
+    config.version = TreasuryConfig::CURRENT_VERSION;
+    config.trust_tier_scores = tier_scores;
+    config.trust_min_amount = min_amount;
+
+    let clock = Clock::get()?;
+    emit!(TrustConfigSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        tier_scores,
+        min_amount,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::ErrorCode;
+use crate::events::UserLimitTierSetEvent;
+use crate::state::{MintLimits, Treasury, UserLimits};
+
+/// Assigns a user's spending limit tier for a mint.
+///
+/// Creates the user limits PDA if needed (rent paid by the authority).
+/// Changing the tier keeps the recorded usage unless the new tier's period
+/// differs, in which case the window restarts on the next flow.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetUserLimitTier<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: Wallet the tier applies to; only used as a PDA seed.
+    pub user: UncheckedAccount<'info>,
+
+    /// Mint the tier applies to
+    pub mint: Account<'info, Mint>,
+
+    /// User limits PDA
+    ///
+    /// ["user_limits", treasury.key(), user.key(), mint.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = UserLimits::SPACE,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            user.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: Account<'info, UserLimits>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetUserLimitTier>, tier: u8) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    require!(
+        usize::from(tier) < MintLimits::MAX_TIERS,
+        ErrorCode::InvalidLimitTier
+    );
+
+    let user = ctx.accounts.user.key();
+    let mint = ctx.accounts.mint.key();
+    let limits = &mut ctx.accounts.user_limits;
+
+    if limits.user == Pubkey::default() {
+        limits.initialize(treasury.key(), user, mint, ctx.bumps.user_limits);
+    }
+    limits.tier = tier;
+
+    let clock = Clock::get()?;
+    emit!(UserLimitTierSetEvent {
+        treasury: treasury.key(),
+        user,
+        mint,
+        authority: authority.key(),
+        tier,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::XpConfigSetEvent;
+use crate::state::{Treasury, TreasuryConfig};
+
+/// Sets the XP-per-flow table and anti-farming rules.
+///
+/// The treasury config PDA is created on first use. Setting every per-flow
+/// value to zero disables XP without removing the config.
+///
+/// Only the configured treasury authority may call this.
+#[derive(Accounts)]
+pub struct SetXpConfig<'info> {
+    /// Authorized signer for treasury control
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury config PDA
+    ///
+    /// ["treasury_config", treasury.key()]
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        space = TreasuryConfig::SPACE,
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetXpConfig>,
+    xp_per_deposit: u32,
+    xp_per_withdraw: u32,
+    xp_per_pay: u32,
+    xp_min_amount: u64,
+    xp_epoch_cap: u64,
+) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let authority = &ctx.accounts.treasury_authority;
+    let config = &mut ctx.accounts.treasury_config;
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    config.treasury = treasury.key();
+    config.bump = ctx.bumps.treasury_config;
+    config.version = TreasuryConfig::CURRENT_VERSION;
+    config.xp_per_deposit = xp_per_deposit;
+    config.xp_per_withdraw = xp_per_withdraw;
+    config.xp_per_pay = xp_per_pay;
+    config.xp_min_amount = xp_min_amount;
+    config.xp_epoch_cap = xp_epoch_cap;
+
+    let clock = Clock::get()?;
+    emit!(XpConfigSetEvent {
+        treasury: treasury.key(),
+        authority: authority.key(),
+        xp_per_deposit,
+        xp_per_withdraw,
+        xp_per_pay,
+        xp_min_amount,
+        xp_epoch_cap,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -1,18 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::spl_token;
-
-use crate::errors::ErrorCode;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{self, Mint, Token, TokenAccount, Transfer},
 };
 
-use crate::events::{DepositEvent, AssetKind, PayDirection};
-
-
-use crate::state::treasury::Treasury;
-
-
+use crate::denylist::ensure_not_denylisted;
+use crate::errors::ErrorCode;
+use crate::events::{AssetKind, DepositEvent, PayDirection};
+use crate::limits::{enforce_limits, LimitAccounts};
+use crate::risk::evaluate_user_flow;
+use crate::state::{
+    DenylistEntry, LedgerEntry, MintLedger, MintLimits, Receipt, Treasury, TreasuryConfig,
+    TrustScore, UserLimits, UserProfile,
+};
+use crate::trust::trust_limit_tier;
+use crate::xp::{credit_xp, XpFlow};
 
 #[derive(Accounts)]
 pub struct SplDeposit<'info> {
@@ -61,6 +64,76 @@ pub struct SplDeposit<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    /// Optional user profile; when supplied, the deposit is recorded and earns XP.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == user.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table, risk rules); it may not exist yet, which
+    /// disables them. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            user.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// Optional trust score of the user; its score may lift the user's
+    /// limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", user.key()]
+    #[account(
+        seeds = [TrustScore::SEED, user.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
 }
 
 pub fn handler(ctx: Context<SplDeposit>, amount: u64) -> Result<()> {
@@ -70,6 +143,36 @@ pub fn handler(ctx: Context<SplDeposit>, amount: u64) -> Result<()> {
         ErrorCode::InsufficientFunds
     );
 
+    // --- Denylist (may reject) ---
+    ensure_not_denylisted(&ctx.accounts.denylist_entry, ErrorCode::WalletDenylisted)?;
+
+    // --- Risk rules (may reject) ---
+    let config = TreasuryConfig::load(&ctx.accounts.treasury_config)?;
+    let risk_flags = evaluate_user_flow(
+        config.as_ref(),
+        ctx.accounts.user_profile.as_mut(),
+        &ctx.accounts.mint.key(),
+        ctx.accounts.mint.decimals,
+        amount,
+    )?;
+
+    // --- Spending limits (may reject) ---
+    let trust_tier = trust_limit_tier(config.as_ref(), ctx.accounts.trust_score.as_deref())?;
+    enforce_limits(
+        &LimitAccounts {
+            mint_limits: &ctx.accounts.mint_limits,
+            user_limits: &ctx.accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            payer: &ctx.accounts.user,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.treasury.key(),
+        &ctx.accounts.user.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        trust_tier,
+    )?;
+
     // Transfer from user ATA -> treasury ATA
     let cpi_accounts = Transfer {
         from: ctx.accounts.user_ata.to_account_info(),
@@ -79,9 +182,22 @@ pub fn handler(ctx: Context<SplDeposit>, amount: u64) -> Result<()> {
 
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     token::transfer(cpi_ctx, amount)?;
+    MintLedger::record(&ctx.accounts.mint_ledger, LedgerEntry::Deposit(amount))?;
+
+    // --- Optional profile activity + XP ---
+    let clock = Clock::get()?;
+    let mint = ctx.accounts.mint.key();
+    if let Some(profile) = ctx.accounts.user_profile.as_mut() {
+        profile.record_activity(Receipt::DIR_DEPOSIT, mint, amount, clock.unix_timestamp)?;
+    }
+    let xp_delta = credit_xp(
+        ctx.accounts.user_profile.as_mut(),
+        config.as_ref(),
+        XpFlow::Deposit,
+        amount,
+    )?;
 
-    // Recommend: keep ONE canonical event (SplDepositEvent) until receipts/XP/risk are real.
-    let slot = Clock::get()?.slot;
+    let slot = clock.slot;
 
     emit!(DepositEvent {
         user: ctx.accounts.user.key(),
@@ -92,11 +208,14 @@ pub fn handler(ctx: Context<SplDeposit>, amount: u64) -> Result<()> {
         asset_kind: AssetKind::SPL,
         receipt: Pubkey::default(),
         nonce_or_tx: 0,
-        xp_delta: 1,
-        risk_flags: 0,
+        xp_delta,
+        risk_flags,
         slot,
+        has_reference: false,
+        reference: [0u8; 32],
+        has_memo: false,
+        memo_len: 0,
     });
 
-
     Ok(())
 }
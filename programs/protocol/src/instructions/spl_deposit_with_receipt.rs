@@ -1,14 +1,24 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
+    memo::Memo,
     token::{self, Mint, Token, TokenAccount, Transfer},
 };
 
 use crate::errors::ErrorCode;
-use crate::state::{Receipt, ReceiptV2Ext, Treasury};
+use crate::memo::{maybe_emit_memo, MemoCpiMode};
+use crate::state::{
+    DenylistEntry, LedgerEntry, MintLedger, MintLimits, Receipt, ReceiptV2Ext, Treasury,
+    TreasuryConfig, TrustScore, UserLimits, UserProfile,
+};
+use crate::limits::{enforce_limits, LimitAccounts};
+use crate::denylist::ensure_not_denylisted;
+use crate::trust::{credit_trust, trust_limit_tier};
+use crate::risk::evaluate_user_flow;
+use crate::xp::{credit_xp, XpFlow};
 use crate::events::{DepositEvent, AssetKind, PayDirection};
 #[derive(Accounts)]
-#[instruction(amount: u64, nonce: u64)]
+#[instruction(amount: u64, nonce: u64, reference: Option<[u8; 32]>, memo: Option<Vec<u8>>)]
 pub struct SplDepositWithReceipt<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -39,7 +49,7 @@ pub struct SplDepositWithReceipt<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + Receipt::LEN,
+        space = Receipt::SPACE,
         seeds = [b"receipt", user.key().as_ref(), &nonce.to_le_bytes()],
         bump
     )]
@@ -49,12 +59,127 @@ pub struct SplDepositWithReceipt<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    /// Optional SPL Memo program, required only when `memo_cpi` is set.
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// Optional user profile; when supplied, the deposit is recorded and earns XP.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == user.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table, risk rules); it may not exist yet, which
+    /// disables them. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            user.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// Optional trust score of the user; credited by this receipted flow, and
+    /// its score may lift the user's limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", user.key()]
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, user.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
 }
 
-pub fn handler(ctx: Context<SplDepositWithReceipt>, amount: u64, nonce: u64) -> Result<()> {
+pub fn handler(
+    ctx: Context<SplDepositWithReceipt>,
+    amount: u64,
+    nonce: u64,
+    reference: Option<[u8; 32]>,
+    memo: Option<Vec<u8>>,
+    memo_cpi: Option<MemoCpiMode>,
+) -> Result<()> {
     require!(!ctx.accounts.treasury.paused, ErrorCode::ProtocolPaused);
     require!(amount > 0, ErrorCode::InvalidAmount);
 
+    if let Some(ref m) = memo {
+        require!(m.len() <= ReceiptV2Ext::MAX_MEMO_LEN, ErrorCode::MemoTooLong);
+    }
+
+    // --- Denylist (may reject) ---
+    ensure_not_denylisted(&ctx.accounts.denylist_entry, ErrorCode::WalletDenylisted)?;
+
+    // --- Risk rules (may reject) ---
+    let config = TreasuryConfig::load(&ctx.accounts.treasury_config)?;
+    let risk_flags = evaluate_user_flow(
+        config.as_ref(),
+        ctx.accounts.user_profile.as_mut(),
+        &ctx.accounts.mint.key(),
+        ctx.accounts.mint.decimals,
+        amount,
+    )?;
+
+    // --- Spending limits (may reject) ---
+    let trust_tier = trust_limit_tier(config.as_ref(), ctx.accounts.trust_score.as_deref())?;
+    enforce_limits(
+        &LimitAccounts {
+            mint_limits: &ctx.accounts.mint_limits,
+            user_limits: &ctx.accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            payer: &ctx.accounts.user,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.treasury.key(),
+        &ctx.accounts.user.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        trust_tier,
+    )?;
+
     // SPL transfer: user -> treasury
     let cpi_accounts = Transfer {
         from: ctx.accounts.user_ata.to_account_info(),
@@ -64,6 +189,7 @@ pub fn handler(ctx: Context<SplDepositWithReceipt>, amount: u64, nonce: u64) ->
 
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     token::transfer(cpi_ctx, amount)?;
+    MintLedger::record(&ctx.accounts.mint_ledger, LedgerEntry::Deposit(amount))?;
 
     // Write receipt (immutable fact record)
     let r = &mut ctx.accounts.receipt;
@@ -76,17 +202,46 @@ pub fn handler(ctx: Context<SplDepositWithReceipt>, amount: u64, nonce: u64) ->
     r.fee = 0;
     r.pre_balance = 0; // optional for now
     r.post_balance = 0; // optional for now
-    r.ts = Clock::get()?.unix_timestamp;
+    let ts = Clock::get()?.unix_timestamp;
+    r.ts = ts;
 
     // Reuse tx_count field as a generic nonce for deposit receipts.
     // (Keeps Receipt layout stable without introducing new fields.)
     r.tx_count = nonce;
 
     r.bump = ctx.bumps.receipt;
-    r.v2 = ReceiptV2Ext::spl(ctx.accounts.mint.key());
+    r.version = Receipt::CURRENT_VERSION;
+    r.v2 = ReceiptV2Ext::spl_with_meta(ctx.accounts.mint.key(), reference, memo.as_deref());
+    r.v2.set_risk_flags(risk_flags);
+
+    // --- Optional wallet-visible memo (SPL Memo program CPI) ---
+    maybe_emit_memo(
+        memo_cpi,
+        ctx.accounts.memo_program.as_ref(),
+        memo.as_deref(),
+        &ctx.accounts.receipt.key(),
+    )?;
+
+    // --- Optional profile activity + XP ---
+    let mint = ctx.accounts.mint.key();
+    if let Some(profile) = ctx.accounts.user_profile.as_mut() {
+        profile.record_activity(Receipt::DIR_DEPOSIT, mint, amount, ts)?;
+    }
+    let xp_delta = credit_xp(
+        ctx.accounts.user_profile.as_mut(),
+        config.as_ref(),
+        XpFlow::Deposit,
+        amount,
+    )?;
+    credit_trust(ctx.accounts.trust_score.as_mut(), config.as_ref(), amount)?;
 
     let slot = Clock::get()?.slot;
 
+    let (has_memo, memo_len) = match memo.as_ref() {
+        Some(m) => (true, m.len() as u8),
+        None => (false, 0),
+    };
+
     emit!(DepositEvent {
         user: ctx.accounts.user.key(),
         mint: ctx.accounts.user_ata.mint,
@@ -97,9 +252,13 @@ pub fn handler(ctx: Context<SplDepositWithReceipt>, amount: u64, nonce: u64) ->
 
         receipt: ctx.accounts.receipt.key(),
         nonce_or_tx: nonce, // assuming your arg is named nonce
-        xp_delta: 1,
-        risk_flags: 0,
+        xp_delta,
+        risk_flags,
         slot,
+        has_reference: reference.is_some(),
+        reference: reference.unwrap_or_default(),
+        has_memo,
+        memo_len,
     });
     Ok(())
 }
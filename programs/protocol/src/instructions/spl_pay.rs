@@ -1,14 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    memo::Memo,
+    token::{Mint, Token, TokenAccount},
 };
 
 use crate::errors::ErrorCode;
-use crate::events::{AssetKind, PayDirection, SplPayEvent};
-use crate::state::{Receipt, ReceiptV2Ext, Treasury};
-
-const MEMO_MAX: usize = 64;
+use crate::memo::MemoCpiMode;
+use crate::payout::{pay_out, PayoutAccounts};
+use crate::state::{
+    DenylistEntry, KycAttestation, MintLedger, MintLimits, Receipt, TravelRuleRecord, Treasury,
+    TreasuryConfig, TrustScore, UserLimits, UserProfile,
+};
 
 /// Treasury-funded SPL payout.
 ///
@@ -21,6 +24,12 @@ const MEMO_MAX: usize = 64;
 /// - This makes SPL pay a single-writer logical path for deterministic receipt creation
 /// - concurrent pay attempts that read the same pay_count will target the same receipt PDA
 /// - tests must model this explicitly and verify safe failure under contention
+///
+/// `reference` must be `None`: referenced payouts go through
+/// `spl_pay_with_reference`, which indexes the reference. The argument stays
+/// for instruction layout compatibility.
+///
+/// The transfer, receipt and compliance hooks live in `crate::payout`.
 #[derive(Accounts)]
 #[instruction(amount: u64, reference: Option<[u8; 32]>, memo: Option<Vec<u8>>)]
 pub struct SplPay<'info> {
@@ -87,6 +96,99 @@ pub struct SplPay<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    /// Optional SPL Memo program, required only when `memo_cpi` is set.
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// Optional recipient profile; when supplied, the payout earns the recipient XP.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, recipient.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == recipient.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table, risk rules); it may not exist yet, which
+    /// disables them. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Recipient limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            recipient.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Recipient denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Recipient KYC attestation PDA (see `crate::kyc`).
+    #[account(
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// Optional travel-rule record for this transfer; required above the
+    /// config's `travel_rule_threshold`.
+    ///
+    /// ["travel_rule", treasury.key(), receipt.key()]
+    #[account(
+        mut,
+        seeds = [TravelRuleRecord::SEED, treasury.key().as_ref(), receipt.key().as_ref()],
+        bump = travel_rule.bump
+    )]
+    pub travel_rule: Option<Account<'info, TravelRuleRecord>>,
+
+    /// Optional trust score of the recipient; credited by this receipted flow, and
+    /// its score may lift the recipient's limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", recipient.key()]
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, recipient.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
 }
 
 pub fn handler(
@@ -94,116 +196,42 @@ pub fn handler(
     amount: u64,
     reference: Option<[u8; 32]>,
     memo: Option<Vec<u8>>,
+    memo_cpi: Option<MemoCpiMode>,
 ) -> Result<()> {
-    let treasury = &mut ctx.accounts.treasury;
-    let treasury_authority = &ctx.accounts.treasury_authority;
-    let recipient = &ctx.accounts.recipient;
-    let mint = &ctx.accounts.mint;
-    let receipt = &mut ctx.accounts.receipt;
-
-    // --- Basic validation ---
-    require!(amount > 0, ErrorCode::InvalidAmount);
-    require!(!treasury.paused, ErrorCode::ProtocolPaused);
-
-    if let Some(ref m) = memo {
-        require!(m.len() <= MEMO_MAX, ErrorCode::MemoTooLong);
-    }
-
-    // --- Authority gate ---
-    require_keys_eq!(
-        treasury_authority.key(),
-        treasury.authority,
-        ErrorCode::UnauthorizedWithdraw
-    );
-
-    // Capture the canonical pay index BEFORE mutation.
-    // This value is used for:
-    // - receipt PDA derivation
-    // - receipt.tx_count storage (flow-specific payment index)
-    // - emitted event indexing
-    let pay_count_before = treasury.pay_count;
-
-    // --- Treasury PDA signer seeds for token transfer ---
-    let bump = treasury.bump;
-    let signer_seeds: &[&[u8]] = &[b"treasury", &[bump]];
-    let signer = &[signer_seeds];
-
-    // --- Transfer treasury funds to recipient ATA ---
-    //
-    // Note:
-    // This occurs before receipt field population, but the instruction remains atomic.
-    // Any later failure in this instruction rolls back the transfer as well.
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.treasury_ata.to_account_info(),
-        to: ctx.accounts.recipient_ata.to_account_info(),
-        authority: treasury.to_account_info(),
-    };
-
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        cpi_accounts,
-        signer,
-    );
-
-    token::transfer(cpi_ctx, amount)?;
-
-    // --- Populate receipt ---
-    //
-    // For pay flows:
-    // - `user` stores the recipient wallet
-    // - `tx_count` stores the treasury pay_count snapshot BEFORE increment
-    receipt.user = recipient.key();
-    receipt.direction = Receipt::DIR_PAY;
-    receipt.asset_kind = Receipt::ASSET_SPL;
-    receipt.mint = mint.key();
-    receipt.amount = amount;
-    receipt.fee = 0;
-    receipt.pre_balance = 0;
-    receipt.post_balance = 0;
-    receipt.ts = Clock::get()?.unix_timestamp;
-    receipt.tx_count = pay_count_before;
-    receipt.bump = ctx.bumps.receipt;
-
-    let memo_slice = memo.as_deref();
-    receipt.v2 = ReceiptV2Ext::spl_with_meta(mint.key(), reference, memo_slice);
-
-    // --- Increment pay_count (must fail loudly on overflow) ---
-    treasury.pay_count = treasury
-        .pay_count
-        .checked_add(1)
-        .ok_or(ErrorCode::CounterOverflow)?;
-
-    // --- Emit event after successful transfer + receipt + counter mutation ---
-    let clock = Clock::get()?;
-
-    let (has_reference, reference_bytes) = match reference {
-        Some(r) => (true, r),
-        None => (false, [0u8; 32]),
-    };
-
-    let (has_memo, memo_len) = match memo.as_ref() {
-        Some(m) => (true, m.len() as u8),
-        None => (false, 0),
-    };
-
-    emit!(SplPayEvent {
-        pay_count: pay_count_before,
-        treasury: treasury.key(),
-        treasury_authority: treasury_authority.key(),
-        recipient: recipient.key(),
-        receipt: receipt.key(),
-        direction: PayDirection::TreasuryToRecipient,
-        asset_kind: AssetKind::SPL,
-        mint: mint.key(),
+    // A reference is only honoured where it is indexed; accepting one here
+    // would let a duplicate-reference payout through.
+    require!(reference.is_none(), ErrorCode::UnindexedReference);
+
+    let accounts = ctx.accounts;
+    pay_out(
+        PayoutAccounts {
+            treasury_authority: &accounts.treasury_authority,
+            recipient: &accounts.recipient,
+            treasury: &mut accounts.treasury,
+            mint: &accounts.mint,
+            recipient_ata: &accounts.recipient_ata,
+            treasury_ata: &accounts.treasury_ata,
+            receipt: &mut accounts.receipt,
+            receipt_bump: ctx.bumps.receipt,
+            token_program: &accounts.token_program,
+            system_program: &accounts.system_program,
+            memo_program: accounts.memo_program.as_ref(),
+            user_profile: accounts.user_profile.as_mut(),
+            treasury_config: &accounts.treasury_config,
+            mint_limits: &accounts.mint_limits,
+            user_limits: &accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            denylist_entry: &accounts.denylist_entry,
+            kyc_attestation: &accounts.kyc_attestation,
+            travel_rule: accounts.travel_rule.as_mut(),
+            trust_score: accounts.trust_score.as_mut(),
+            mint_ledger: &accounts.mint_ledger,
+        },
         amount,
-        has_reference,
-        reference: reference_bytes,
-        has_memo,
-        memo_len,
-        slot: clock.slot,
-        unix_timestamp: clock.unix_timestamp,
-    });
+        None,
+        memo.as_deref(),
+        memo_cpi,
+    )?;
 
     Ok(())
 }
-
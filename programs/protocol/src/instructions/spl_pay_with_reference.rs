@@ -0,0 +1,253 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    memo::Memo,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::errors::ErrorCode;
+use crate::memo::MemoCpiMode;
+use crate::payout::{pay_out, PayoutAccounts};
+use crate::state::{
+    DenylistEntry, KycAttestation, MintLedger, MintLimits, Receipt, ReferenceIndex,
+    TravelRuleRecord, Treasury, TreasuryConfig, TrustScore, UserLimits, UserProfile,
+};
+
+/// Treasury-funded SPL payout keyed by an external reference.
+///
+/// Same transfer + receipt semantics as `spl_pay`, with one addition:
+/// a reference-index PDA is created in the same instruction and points at
+/// the receipt.
+///
+/// Reference index PDA seeds:
+///   ["reference", treasury.key(), reference]
+///
+/// Important:
+/// - the index is `init`-only, so replaying a reference (e.g. a client retry
+///   after a lost response) fails before any funds move
+/// - the receipt PDA is still pay_count-based, exactly like `spl_pay`
+#[derive(Accounts)]
+#[instruction(amount: u64, reference: [u8; 32], memo: Option<Vec<u8>>)]
+pub struct SplPayWithReference<'info> {
+    /// Authority allowed to initiate treasury payouts
+    #[account(mut)]
+    pub treasury_authority: Signer<'info>,
+
+    /// Recipient wallet receiving the tokens
+    ///
+    /// CHECK:
+    /// This account is constrained indirectly through `recipient_ata`,
+    /// which must be the associated token account for `(recipient, mint)`.
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+        constraint = !treasury.paused @ ErrorCode::ProtocolPaused
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// SPL mint being paid out
+    pub mint: Account<'info, Mint>,
+
+    /// Recipient ATA for this mint
+    ///
+    /// Created if missing, paid by treasury_authority.
+    #[account(
+        init_if_needed,
+        payer = treasury_authority,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_ata: Account<'info, TokenAccount>,
+
+    /// Treasury ATA for this mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_ata: Account<'info, TokenAccount>,
+
+    /// Payment receipt PDA
+    ///
+    /// ["receipt", treasury.key(), treasury.pay_count_before.to_le_bytes()]
+    #[account(
+        init,
+        payer = treasury_authority,
+        space = Receipt::SPACE,
+        seeds = [
+            Receipt::RECEIPT_SEED,
+            treasury.key().as_ref(),
+            &treasury.pay_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    /// Reference idempotency index
+    ///
+    /// ["reference", treasury.key(), reference]
+    #[account(
+        init,
+        payer = treasury_authority,
+        space = ReferenceIndex::SPACE,
+        seeds = [
+            ReferenceIndex::SEED,
+            treasury.key().as_ref(),
+            reference.as_ref()
+        ],
+        bump
+    )]
+    pub reference_index: Account<'info, ReferenceIndex>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Optional SPL Memo program, required only when `memo_cpi` is set.
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// Optional recipient profile; when supplied, the payout earns the recipient XP.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, recipient.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == recipient.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table, risk rules); it may not exist yet, which
+    /// disables them. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Recipient limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            recipient.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Recipient denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Recipient KYC attestation PDA (see `crate::kyc`).
+    #[account(
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// Optional travel-rule record for this transfer; required above the
+    /// config's `travel_rule_threshold`.
+    ///
+    /// ["travel_rule", treasury.key(), receipt.key()]
+    #[account(
+        mut,
+        seeds = [TravelRuleRecord::SEED, treasury.key().as_ref(), receipt.key().as_ref()],
+        bump = travel_rule.bump
+    )]
+    pub travel_rule: Option<Account<'info, TravelRuleRecord>>,
+
+    /// Optional trust score of the recipient; credited by this receipted flow, and
+    /// its score may lift the recipient's limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", recipient.key()]
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, recipient.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
+}
+
+pub fn handler(
+    ctx: Context<SplPayWithReference>,
+    amount: u64,
+    reference: [u8; 32],
+    memo: Option<Vec<u8>>,
+    memo_cpi: Option<MemoCpiMode>,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let pay_count = pay_out(
+        PayoutAccounts {
+            treasury_authority: &accounts.treasury_authority,
+            recipient: &accounts.recipient,
+            treasury: &mut accounts.treasury,
+            mint: &accounts.mint,
+            recipient_ata: &accounts.recipient_ata,
+            treasury_ata: &accounts.treasury_ata,
+            receipt: &mut accounts.receipt,
+            receipt_bump: ctx.bumps.receipt,
+            token_program: &accounts.token_program,
+            system_program: &accounts.system_program,
+            memo_program: accounts.memo_program.as_ref(),
+            user_profile: accounts.user_profile.as_mut(),
+            treasury_config: &accounts.treasury_config,
+            mint_limits: &accounts.mint_limits,
+            user_limits: &accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            denylist_entry: &accounts.denylist_entry,
+            kyc_attestation: &accounts.kyc_attestation,
+            travel_rule: accounts.travel_rule.as_mut(),
+            trust_score: accounts.trust_score.as_mut(),
+            mint_ledger: &accounts.mint_ledger,
+        },
+        amount,
+        Some(reference),
+        memo.as_deref(),
+        memo_cpi,
+    )?;
+
+    // --- Populate reference index ---
+    let reference_index = &mut accounts.reference_index;
+    reference_index.treasury = accounts.treasury.key();
+    reference_index.reference = reference;
+    reference_index.receipt = accounts.receipt.key();
+    reference_index.pay_count = pay_count;
+    reference_index.ts = Clock::get()?.unix_timestamp;
+    reference_index.bump = ctx.bumps.reference_index;
+
+    Ok(())
+}
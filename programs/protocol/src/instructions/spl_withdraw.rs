@@ -5,7 +5,17 @@ use anchor_spl::{
 };
 
 use crate::errors::ErrorCode;
-use crate::state::treasury::Treasury;
+use crate::state::{
+    DenylistEntry, KycAttestation, LedgerEntry, MintLedger, MintLimits, Receipt, TravelRuleRecord,
+    Treasury, TreasuryConfig, TrustScore, UserLimits, UserProfile,
+};
+use crate::limits::{enforce_limits, LimitAccounts};
+use crate::denylist::ensure_not_denylisted;
+use crate::kyc::enforce_kyc;
+use crate::travel_rule::consume_travel_rule;
+use crate::trust::trust_limit_tier;
+use crate::risk::evaluate_user_flow;
+use crate::xp::{credit_xp, XpFlow};
 use crate::events::{WithdrawEvent, AssetKind, PayDirection};
 
 
@@ -56,6 +66,96 @@ pub struct SplWithdraw<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    /// Optional user profile; when supplied, the withdrawal is recorded and earns XP.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.authority == user.key() @ ErrorCode::InvalidUserProfileAuthority
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table, risk rules); it may not exist yet, which
+    /// disables them. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            user.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User KYC attestation PDA (see `crate::kyc`).
+    #[account(
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// Optional travel-rule record for this transfer; required above the
+    /// config's `travel_rule_threshold`. Without a receipt, any record id is
+    /// accepted as long as the record matches the transfer.
+    ///
+    /// ["travel_rule", treasury.key(), record_id]
+    #[account(
+        mut,
+        seeds = [TravelRuleRecord::SEED, treasury.key().as_ref(), travel_rule.record_id.as_ref()],
+        bump = travel_rule.bump
+    )]
+    pub travel_rule: Option<Account<'info, TravelRuleRecord>>,
+
+    /// Optional trust score of the user; its score may lift the user's
+    /// limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", user.key()]
+    #[account(
+        seeds = [TrustScore::SEED, user.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
 }
 
 pub fn handler(ctx: Context<SplWithdraw>, amount: u64) -> Result<()> {
@@ -69,6 +169,50 @@ pub fn handler(ctx: Context<SplWithdraw>, amount: u64) -> Result<()> {
         ErrorCode::UnauthorizedWithdraw
     );
 
+    // --- Denylist (may reject) ---
+    ensure_not_denylisted(&ctx.accounts.denylist_entry, ErrorCode::WalletDenylisted)?;
+
+    let config = TreasuryConfig::load(&ctx.accounts.treasury_config)?;
+
+    // --- KYC (may reject) ---
+    enforce_kyc(config.as_ref(), &ctx.accounts.kyc_attestation, amount)?;
+
+    // --- Travel rule (may reject; no receipt to flag) ---
+    consume_travel_rule(
+        config.as_ref(),
+        ctx.accounts.travel_rule.as_mut(),
+        &ctx.accounts.user.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        Pubkey::default(),
+    )?;
+
+    // --- Risk rules (may reject) ---
+    let risk_flags = evaluate_user_flow(
+        config.as_ref(),
+        ctx.accounts.user_profile.as_mut(),
+        &ctx.accounts.mint.key(),
+        ctx.accounts.mint.decimals,
+        amount,
+    )?;
+
+    // --- Spending limits (may reject) ---
+    let trust_tier = trust_limit_tier(config.as_ref(), ctx.accounts.trust_score.as_deref())?;
+    enforce_limits(
+        &LimitAccounts {
+            mint_limits: &ctx.accounts.mint_limits,
+            user_limits: &ctx.accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            payer: &ctx.accounts.treasury_authority,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.treasury.key(),
+        &ctx.accounts.user.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        trust_tier,
+    )?;
+
     // Treasury PDA signs for transfer out
     let bump = ctx.accounts.treasury.bump;
     let seeds: &[&[u8]] = &[b"treasury", &[bump]];
@@ -87,8 +231,22 @@ pub fn handler(ctx: Context<SplWithdraw>, amount: u64) -> Result<()> {
     );
 
     token::transfer(cpi_ctx, amount)?;
+    MintLedger::record(&ctx.accounts.mint_ledger, LedgerEntry::Withdraw(amount))?;
+
+    // --- Optional profile activity + XP ---
+    let clock = Clock::get()?;
+    let mint = ctx.accounts.mint.key();
+    if let Some(profile) = ctx.accounts.user_profile.as_mut() {
+        profile.record_activity(Receipt::DIR_WITHDRAW, mint, amount, clock.unix_timestamp)?;
+    }
+    let xp_delta = credit_xp(
+        ctx.accounts.user_profile.as_mut(),
+        config.as_ref(),
+        XpFlow::Withdraw,
+        amount,
+    )?;
 
-    let slot = Clock::get()?.slot;
+    let slot = clock.slot;
 
     emit!(WithdrawEvent {
         authority: ctx.accounts.treasury_authority.key(),
@@ -101,9 +259,13 @@ pub fn handler(ctx: Context<SplWithdraw>, amount: u64) -> Result<()> {
 
         receipt: Pubkey::default(),
         nonce_or_tx: 0,
-        xp_delta: 1,
-        risk_flags: 0,
+        xp_delta,
+        risk_flags,
         slot,
+        has_reference: false,
+        reference: [0u8; 32],
+        has_memo: false,
+        memo_len: 0,
     });
     Ok(())
 }
@@ -5,12 +5,22 @@ use anchor_spl::{
 };
 
 use crate::errors::ErrorCode;
-use crate::state::{Receipt, ReceiptV2Ext, Treasury, UserProfile};
+use crate::state::{
+    DenylistEntry, KycAttestation, LedgerEntry, MintLedger, MintLimits, Receipt, ReceiptV2Ext,
+    TravelRuleRecord, Treasury, TreasuryConfig, TrustScore, UserLimits, UserProfile,
+};
+use crate::limits::{enforce_limits, LimitAccounts};
+use crate::denylist::ensure_not_denylisted;
+use crate::kyc::enforce_kyc;
+use crate::travel_rule::consume_travel_rule;
+use crate::trust::{credit_trust, trust_limit_tier};
+use crate::risk::evaluate_user_flow;
+use crate::xp::{credit_xp, XpFlow};
 use crate::events::{WithdrawEvent, AssetKind, PayDirection};
 
 
 #[derive(Accounts)]
-#[instruction(amount: u64)]
+#[instruction(amount: u64, reference: Option<[u8; 32]>, memo: Option<Vec<u8>>)]
 pub struct SplWithdrawWithReceipt<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -21,8 +31,8 @@ pub struct SplWithdrawWithReceipt<'info> {
     #[account(
         init_if_needed,
         payer = user,
-        space = 8 + UserProfile::LEN,
-        seeds = [b"user_profile", user.key().as_ref()],
+        space = UserProfile::SPACE,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
         bump
     )]
     pub user_profile: Account<'info, UserProfile>,
@@ -59,7 +69,7 @@ pub struct SplWithdrawWithReceipt<'info> {
     #[account(
         init,
         payer = user,
-        space = 8 + Receipt::LEN,
+        space = Receipt::SPACE,
         seeds = [b"receipt", user.key().as_ref(), &user_profile.tx_count.to_le_bytes()],
         bump
     )]
@@ -69,16 +79,105 @@ pub struct SplWithdrawWithReceipt<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK:
+    /// Treasury config PDA (XP table, risk rules); it may not exist yet, which
+    /// disables them. Read through `TreasuryConfig::load`.
+    #[account(
+        seeds = [TreasuryConfig::SEED, treasury.key().as_ref()],
+        bump
+    )]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// Mint limits PDA (spending limit tiers); it may not exist yet, in which
+    /// case the mint has no limits. Read through `MintLimits::load`.
+    #[account(
+        seeds = [MintLimits::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User limits PDA for this mint; created by the flow once the mint
+    /// has limits (see `crate::limits`).
+    #[account(
+        mut,
+        seeds = [
+            UserLimits::SEED,
+            treasury.key().as_ref(),
+            user.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_limits: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User denylist entry PDA (see `crate::denylist`).
+    #[account(
+        seeds = [DenylistEntry::SEED, treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK:
+    /// User KYC attestation PDA (see `crate::kyc`).
+    #[account(
+        seeds = [KycAttestation::SEED, treasury.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub kyc_attestation: UncheckedAccount<'info>,
+
+    /// Optional travel-rule record for this transfer; required above the
+    /// config's `travel_rule_threshold`.
+    ///
+    /// ["travel_rule", treasury.key(), receipt.key()]
+    #[account(
+        mut,
+        seeds = [TravelRuleRecord::SEED, treasury.key().as_ref(), receipt.key().as_ref()],
+        bump = travel_rule.bump
+    )]
+    pub travel_rule: Option<Account<'info, TravelRuleRecord>>,
+
+    /// Optional trust score of the user; credited by this receipted flow, and
+    /// its score may lift the user's limit tier (see `crate::trust`).
+    ///
+    /// ["trust_score", user.key()]
+    #[account(
+        mut,
+        seeds = [TrustScore::SEED, user.key().as_ref()],
+        bump = trust_score.bump
+    )]
+    pub trust_score: Option<Account<'info, TrustScore>>,
+
+    /// CHECK:
+    /// Mint ledger PDA (per-mint books); it may not exist yet, in which case
+    /// nothing is recorded. Updated through `MintLedger::record`.
+    #[account(
+        mut,
+        seeds = [MintLedger::SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_ledger: UncheckedAccount<'info>,
 }
 
-pub fn handler(ctx: Context<SplWithdrawWithReceipt>, amount: u64) -> Result<()> {
+pub fn handler(
+    ctx: Context<SplWithdrawWithReceipt>,
+    amount: u64,
+    reference: Option<[u8; 32]>,
+    memo: Option<Vec<u8>>,
+) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
 
+    if let Some(ref m) = memo {
+        require!(m.len() <= ReceiptV2Ext::MAX_MEMO_LEN, ErrorCode::MemoTooLong);
+    }
+
     // Initialize or verify user_profile ownership.
     if ctx.accounts.user_profile.authority == Pubkey::default() {
-        ctx.accounts.user_profile.authority = ctx.accounts.user.key();
-        ctx.accounts.user_profile.tx_count = 0;
-        ctx.accounts.user_profile.bump = ctx.bumps.user_profile;
+        let bump = ctx.bumps.user_profile;
+        ctx.accounts.user_profile.initialize(ctx.accounts.user.key(), bump);
     } else {
         require_keys_eq!(
             ctx.accounts.user_profile.authority,
@@ -94,6 +193,50 @@ pub fn handler(ctx: Context<SplWithdrawWithReceipt>, amount: u64) -> Result<()>
         ErrorCode::UnauthorizedWithdraw
     );
 
+    // --- Denylist (may reject) ---
+    ensure_not_denylisted(&ctx.accounts.denylist_entry, ErrorCode::WalletDenylisted)?;
+
+    let config = TreasuryConfig::load(&ctx.accounts.treasury_config)?;
+
+    // --- KYC (may reject) ---
+    enforce_kyc(config.as_ref(), &ctx.accounts.kyc_attestation, amount)?;
+
+    // --- Travel rule (may reject) ---
+    let has_travel_rule = consume_travel_rule(
+        config.as_ref(),
+        ctx.accounts.travel_rule.as_mut(),
+        &ctx.accounts.user.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        ctx.accounts.receipt.key(),
+    )?;
+
+    // --- Risk rules (may reject) ---
+    let risk_flags = evaluate_user_flow(
+        config.as_ref(),
+        Some(&mut ctx.accounts.user_profile),
+        &ctx.accounts.mint.key(),
+        ctx.accounts.mint.decimals,
+        amount,
+    )?;
+
+    // --- Spending limits (may reject) ---
+    let trust_tier = trust_limit_tier(config.as_ref(), ctx.accounts.trust_score.as_deref())?;
+    enforce_limits(
+        &LimitAccounts {
+            mint_limits: &ctx.accounts.mint_limits,
+            user_limits: &ctx.accounts.user_limits,
+            user_limits_bump: ctx.bumps.user_limits,
+            payer: &ctx.accounts.user,
+            system_program: &ctx.accounts.system_program,
+        },
+        &ctx.accounts.treasury.key(),
+        &ctx.accounts.user.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        trust_tier,
+    )?;
+
     // Pre-increment tx_count (used for seed + receipt invariant)
     let tx_count = ctx.accounts.user_profile.tx_count;
 
@@ -115,6 +258,7 @@ pub fn handler(ctx: Context<SplWithdrawWithReceipt>, amount: u64) -> Result<()>
     );
 
     token::transfer(cpi_ctx, amount)?;
+    MintLedger::record(&ctx.accounts.mint_ledger, LedgerEntry::Withdraw(amount))?;
 
     // Write receipt (tx_count must reflect PRE-increment)
     let r = &mut ctx.accounts.receipt;
@@ -126,19 +270,37 @@ pub fn handler(ctx: Context<SplWithdrawWithReceipt>, amount: u64) -> Result<()>
     r.fee = 0;
     r.pre_balance = 0;
     r.post_balance = 0;
-    r.ts = Clock::get()?.unix_timestamp;
+    let ts = Clock::get()?.unix_timestamp;
+    r.ts = ts;
     r.tx_count = tx_count;
     r.bump = ctx.bumps.receipt;
-    r.v2 = ReceiptV2Ext::spl(ctx.accounts.mint.key());
+    r.version = Receipt::CURRENT_VERSION;
+    r.v2 = ReceiptV2Ext::spl_with_meta(ctx.accounts.mint.key(), reference, memo.as_deref());
+    r.v2.set_risk_flags(risk_flags);
+    if has_travel_rule {
+        r.v2.flags |= ReceiptV2Ext::FLAG_HAS_TRAVEL_RULE;
+    }
 
-    // Increment AFTER receipt is written
-    ctx.accounts.user_profile.tx_count = ctx.accounts
+    // Record activity (increments tx_count) AFTER receipt is written
+    let mint = ctx.accounts.mint.key();
+    ctx.accounts
         .user_profile
-        .tx_count
-        .saturating_add(1);
+        .record_activity(Receipt::DIR_WITHDRAW, mint, amount, ts)?;
+    let xp_delta = credit_xp(
+        Some(&mut ctx.accounts.user_profile),
+        config.as_ref(),
+        XpFlow::Withdraw,
+        amount,
+    )?;
+    credit_trust(ctx.accounts.trust_score.as_mut(), config.as_ref(), amount)?;
 
     let slot = Clock::get()?.slot;
 
+    let (has_memo, memo_len) = match memo.as_ref() {
+        Some(m) => (true, m.len() as u8),
+        None => (false, 0),
+    };
+
     emit!(WithdrawEvent {
         authority: ctx.accounts.treasury_authority.key(),
         user: ctx.accounts.user.key(),
@@ -150,9 +312,13 @@ pub fn handler(ctx: Context<SplWithdrawWithReceipt>, amount: u64) -> Result<()>
 
         receipt: ctx.accounts.receipt.key(),
         nonce_or_tx: tx_count, // <- pre-increment (matches receipt seed invariant)
-        xp_delta: 1,
-        risk_flags: 0,
+        xp_delta,
+        risk_flags,
         slot,
+        has_reference: reference.is_some(),
+        reference: reference.unwrap_or_default(),
+        has_memo,
+        memo_len,
     });
 
     Ok(())
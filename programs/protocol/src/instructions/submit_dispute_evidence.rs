@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::DisputeEvidenceSubmittedEvent;
+use crate::state::Dispute;
+
+/// Attaches (or replaces) one side's evidence hash on an open dispute.
+///
+/// The payer updates `payer_evidence`, the respondent `respondent_evidence`.
+#[derive(Accounts)]
+pub struct SubmitDisputeEvidence<'info> {
+    /// Payer or respondent of the dispute
+    pub party: Signer<'info>,
+
+    /// Dispute PDA
+    ///
+    /// ["dispute", dispute.receipt]
+    #[account(
+        mut,
+        seeds = [Dispute::SEED, dispute.receipt.as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+}
+
+pub fn handler(ctx: Context<SubmitDisputeEvidence>, evidence: [u8; 32]) -> Result<()> {
+    let party = ctx.accounts.party.key();
+    let dispute = &mut ctx.accounts.dispute;
+
+    require!(dispute.is_open(), ErrorCode::DisputeNotOpen);
+    require!(evidence != [0u8; 32], ErrorCode::InvalidDisputeEvidence);
+
+    if party == dispute.payer {
+        dispute.payer_evidence = evidence;
+    } else if party == dispute.respondent {
+        dispute.respondent_evidence = evidence;
+    } else {
+        return err!(ErrorCode::UnauthorizedDispute);
+    }
+
+    let clock = Clock::get()?;
+    emit!(DisputeEvidenceSubmittedEvent {
+        dispute: dispute.key(),
+        party,
+        evidence,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::MerchantUpdatedEvent;
+use crate::state::{Merchant, Treasury};
+
+/// Updates a merchant's payout wallet and accepted settlement mints.
+///
+/// Stats of mints that remain accepted are kept; dropped mints lose theirs
+/// (the totals stay recoverable from `MerchantPaidEvent`s). Allowed while
+/// suspended, so the owner can fix settlement details before reinstatement.
+///
+/// Only the merchant owner may call this.
+#[derive(Accounts)]
+pub struct UpdateMerchant<'info> {
+    /// Merchant owner
+    pub owner: Signer<'info>,
+
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Merchant PDA
+    ///
+    /// ["merchant", treasury.key(), merchant.owner]
+    #[account(
+        mut,
+        seeds = [Merchant::SEED, treasury.key().as_ref(), merchant.owner.as_ref()],
+        bump = merchant.bump,
+        constraint = merchant.owner == owner.key() @ ErrorCode::UnauthorizedMerchant
+    )]
+    pub merchant: Account<'info, Merchant>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateMerchant>,
+    payout_wallet: Pubkey,
+    settlement_mints: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        payout_wallet != Pubkey::default()
+            && Merchant::is_valid_settlement_mints(&settlement_mints),
+        ErrorCode::InvalidMerchantConfig
+    );
+
+    let clock = Clock::get()?;
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.payout_wallet = payout_wallet;
+    merchant.set_settlement_mints(&settlement_mints);
+    merchant.updated_at = clock.unix_timestamp;
+
+    emit!(MerchantUpdatedEvent {
+        merchant: merchant.key(),
+        owner: merchant.owner,
+        payout_wallet,
+        settlement_mints,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::errors::ErrorCode;
+use crate::events::UserProfileUpdatedEvent;
+use crate::state::UserProfile;
+
+/// Upgrades the caller's user profile to `UserProfile::CURRENT_VERSION` in place.
+///
+/// Behavior:
+/// - reads any historical layout via `UserProfile::try_deserialize_versioned`
+/// - reallocs to `UserProfile::SPACE`, topping up rent from `user`
+/// - preserves every stored field; fields added by newer versions start at zero
+///
+/// Already-current profiles are left untouched (no-op).
+#[derive(Accounts)]
+pub struct UpdateUserProfile<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK:
+    /// Legacy layouts cannot be loaded as `Account<UserProfile>`. Seeds and
+    /// ownership are enforced here; discriminator, layout and authority are
+    /// verified in the handler.
+    #[account(
+        mut,
+        seeds = [UserProfile::SEED, user.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub user_profile: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpdateUserProfile>) -> Result<()> {
+    let info = ctx.accounts.user_profile.to_account_info();
+
+    let mut profile = {
+        let data = info.try_borrow_data()?;
+        UserProfile::try_deserialize_versioned(&data)?
+    };
+
+    require_keys_eq!(
+        profile.authority,
+        ctx.accounts.user.key(),
+        ErrorCode::InvalidUserProfileAuthority
+    );
+
+    let from_version = profile.version;
+    if from_version == UserProfile::CURRENT_VERSION {
+        return Ok(());
+    }
+
+    // --- Rent top-up for the larger layout ---
+    let required = Rent::get()?.minimum_balance(UserProfile::SPACE);
+    let shortfall = required.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: info.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, shortfall)?;
+    }
+
+    info.resize(UserProfile::SPACE)?;
+
+    // --- Rewrite in the current layout (discriminator included) ---
+    profile.version = UserProfile::CURRENT_VERSION;
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        profile.try_serialize(&mut writer)?;
+    }
+
+    let clock = Clock::get()?;
+    emit!(UserProfileUpdatedEvent {
+        user: ctx.accounts.user.key(),
+        profile: info.key(),
+        from_version,
+        to_version: UserProfile::CURRENT_VERSION,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::sysvar::instructions as ix_sysvar;
+
+use crate::attestation::{parse_attestation_message, parse_ed25519_instruction};
+use crate::errors::ErrorCode;
+use crate::events::ReceiptAttestationVerifiedEvent;
+use crate::state::{AttestorConfig, Receipt, Treasury};
+
+/// Verifies an Ed25519-signed receipt attestation on-chain.
+///
+/// The transaction must place an Ed25519 program instruction immediately
+/// before this one, signing the canonical message from
+/// `crate::attestation::receipt_attestation_message`. The runtime checks the
+/// signature; this instruction checks that:
+/// - the signer is the treasury authority or the designated attestor
+/// - the message targets this program and this receipt address
+/// - the signed receipt body equals the current account content
+///
+/// Read-only apart from the emitted event, so other programs can CPI-compose
+/// or simply require it in the same transaction.
+#[derive(Accounts)]
+pub struct VerifyReceiptAttestation<'info> {
+    /// Canonical treasury PDA
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Receipt being attested (must be at the current schema version)
+    pub receipt: Account<'info, Receipt>,
+
+    /// Optional designated attestor config
+    ///
+    /// ["attestor", treasury.key()]
+    #[account(
+        seeds = [AttestorConfig::SEED, treasury.key().as_ref()],
+        bump = attestor_config.bump
+    )]
+    pub attestor_config: Option<Account<'info, AttestorConfig>>,
+
+    /// CHECK:
+    /// Address-constrained to the instructions sysvar.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<VerifyReceiptAttestation>) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let receipt = &ctx.accounts.receipt;
+    let ix_info = ctx.accounts.instructions.to_account_info();
+
+    // --- Load the Ed25519 instruction directly preceding this one ---
+    let current = load_current_index_checked(&ix_info)?;
+    require!(current > 0, ErrorCode::AttestationMissing);
+    let ed_ix = load_instruction_at_checked(usize::from(current - 1), &ix_info)?;
+
+    let (attestor, message) = parse_ed25519_instruction(&ed_ix.program_id, &ed_ix.data)?;
+
+    // --- Signer gate: treasury authority or designated attestor ---
+    let designated = ctx
+        .accounts
+        .attestor_config
+        .as_ref()
+        .map(|c| c.attestor)
+        .filter(|a| *a != Pubkey::default());
+    require!(
+        attestor == treasury.authority || Some(attestor) == designated,
+        ErrorCode::AttestationSignerUnauthorized
+    );
+
+    // --- Bind the signed message to this program + receipt ---
+    let (program_id, receipt_address, attested_slot, body) = parse_attestation_message(&message)?;
+    require_keys_eq!(program_id, crate::ID, ErrorCode::AttestationMismatch);
+    require_keys_eq!(receipt_address, receipt.key(), ErrorCode::AttestationMismatch);
+
+    let current_body = receipt
+        .try_to_vec()
+        .map_err(|_| ErrorCode::AttestationMalformed)?;
+    require!(body == current_body.as_slice(), ErrorCode::AttestationMismatch);
+
+    let clock = Clock::get()?;
+    emit!(ReceiptAttestationVerifiedEvent {
+        receipt: receipt.key(),
+        attestor,
+        attested_slot,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{KycAttestation, TreasuryConfig};
+
+/// Require a valid KYC attestation of the receiving wallet (the paying
+/// customer for merchant payments) when the treasury config asks for one.
+///
+/// Applies to withdrawals, payouts and merchant payments above `kyc_threshold` while
+/// `kyc_min_level` is non-zero. `attestation` is the wallet's attestation
+/// PDA, pinned by seeds in the flow's account struct and taken even when it
+/// does not exist, so the requirement cannot be skipped by leaving it out.
+pub fn enforce_kyc(
+    config: Option<&TreasuryConfig>,
+    attestation: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    if config.kyc_min_level == 0 || amount <= config.kyc_threshold {
+        return Ok(());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let attestation = KycAttestation::load(attestation)?
+        .filter(|a| a.is_valid(now))
+        .ok_or(ErrorCode::KycAttestationRequired)?;
+
+    require!(
+        attestation.level >= config.kyc_min_level,
+        ErrorCode::KycLevelTooLow
+    );
+
+    Ok(())
+}
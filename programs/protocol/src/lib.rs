@@ -2,10 +2,24 @@
 
 use anchor_lang::prelude::*;
 
+pub mod attestation;
+pub mod denylist;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod kyc;
+pub mod limits;
+pub mod memo;
+pub mod payout;
+pub mod risk;
 pub mod state;
+pub mod travel_rule;
+pub mod trust;
+pub mod xp;
+
+use crate::memo::MemoCpiMode;
+use crate::risk::RiskConfigParams;
+use crate::travel_rule::TravelRuleParams;
 
 declare_id!("BtP7rVw9sqN4pW5RuzZJ2c4576R5pJU9yRtjrRJ7b5bM");
 
@@ -18,6 +32,41 @@ use crate::instructions::{
     SplWithdraw,
     SplWithdrawWithReceipt,
     SplPay,
+    SplPayWithReference,
+    MigrateReceipt,
+    SetReceiptAttestor,
+    VerifyReceiptAttestation,
+    InitUserProfile,
+    CloseUserProfile,
+    UpdateUserProfile,
+    SetXpConfig,
+    SetRiskConfig,
+    SetLimitTier,
+    SetUserLimitTier,
+    SetComplianceAuthority,
+    AddDenylistEntry,
+    RemoveDenylistEntry,
+    SetKycConfig,
+    AddKycIssuer,
+    RemoveKycIssuer,
+    IssueKycAttestation,
+    RevokeKycAttestation,
+    SetTravelRuleConfig,
+    RecordTravelRule,
+    SetTrustConfig,
+    InitTrustScore,
+    RefreshTrustScore,
+    SetDisputeConfig,
+    OpenDispute,
+    SubmitDisputeEvidence,
+    ResolveDispute,
+    SetMerchantFeeTiers,
+    RegisterMerchant,
+    UpdateMerchant,
+    SetMerchantStatus,
+    PayMerchant,
+    OpenMintLedger,
+    AssertSolvency,
 };
 
 // --- Anchor macro bridge (crate-private) ---
@@ -30,6 +79,41 @@ pub(crate) use instructions::spl_deposit_with_receipt::__client_accounts_spl_dep
 pub(crate) use instructions::spl_withdraw::__client_accounts_spl_withdraw;
 pub(crate) use instructions::spl_withdraw_with_receipt::__client_accounts_spl_withdraw_with_receipt;
 pub(crate) use instructions::spl_pay::__client_accounts_spl_pay;
+pub(crate) use instructions::spl_pay_with_reference::__client_accounts_spl_pay_with_reference;
+pub(crate) use instructions::migrate_receipt::__client_accounts_migrate_receipt;
+pub(crate) use instructions::set_receipt_attestor::__client_accounts_set_receipt_attestor;
+pub(crate) use instructions::verify_receipt_attestation::__client_accounts_verify_receipt_attestation;
+pub(crate) use instructions::init_user_profile::__client_accounts_init_user_profile;
+pub(crate) use instructions::close_user_profile::__client_accounts_close_user_profile;
+pub(crate) use instructions::update_user_profile::__client_accounts_update_user_profile;
+pub(crate) use instructions::set_xp_config::__client_accounts_set_xp_config;
+pub(crate) use instructions::set_risk_config::__client_accounts_set_risk_config;
+pub(crate) use instructions::set_limit_tier::__client_accounts_set_limit_tier;
+pub(crate) use instructions::set_user_limit_tier::__client_accounts_set_user_limit_tier;
+pub(crate) use instructions::set_compliance_authority::__client_accounts_set_compliance_authority;
+pub(crate) use instructions::add_denylist_entry::__client_accounts_add_denylist_entry;
+pub(crate) use instructions::remove_denylist_entry::__client_accounts_remove_denylist_entry;
+pub(crate) use instructions::set_kyc_config::__client_accounts_set_kyc_config;
+pub(crate) use instructions::add_kyc_issuer::__client_accounts_add_kyc_issuer;
+pub(crate) use instructions::remove_kyc_issuer::__client_accounts_remove_kyc_issuer;
+pub(crate) use instructions::issue_kyc_attestation::__client_accounts_issue_kyc_attestation;
+pub(crate) use instructions::revoke_kyc_attestation::__client_accounts_revoke_kyc_attestation;
+pub(crate) use instructions::set_travel_rule_config::__client_accounts_set_travel_rule_config;
+pub(crate) use instructions::record_travel_rule::__client_accounts_record_travel_rule;
+pub(crate) use instructions::set_trust_config::__client_accounts_set_trust_config;
+pub(crate) use instructions::init_trust_score::__client_accounts_init_trust_score;
+pub(crate) use instructions::refresh_trust_score::__client_accounts_refresh_trust_score;
+pub(crate) use instructions::set_dispute_config::__client_accounts_set_dispute_config;
+pub(crate) use instructions::open_dispute::__client_accounts_open_dispute;
+pub(crate) use instructions::submit_dispute_evidence::__client_accounts_submit_dispute_evidence;
+pub(crate) use instructions::resolve_dispute::__client_accounts_resolve_dispute;
+pub(crate) use instructions::set_merchant_fee_tiers::__client_accounts_set_merchant_fee_tiers;
+pub(crate) use instructions::register_merchant::__client_accounts_register_merchant;
+pub(crate) use instructions::update_merchant::__client_accounts_update_merchant;
+pub(crate) use instructions::set_merchant_status::__client_accounts_set_merchant_status;
+pub(crate) use instructions::pay_merchant::__client_accounts_pay_merchant;
+pub(crate) use instructions::open_mint_ledger::__client_accounts_open_mint_ledger;
+pub(crate) use instructions::assert_solvency::__client_accounts_assert_solvency;
 
 // Anchor 0.32+ expects this exact module name at crate root in some layouts.
 #[allow(non_snake_case)]
@@ -41,6 +125,41 @@ pub mod __client_accounts_crate {
     pub use super::__client_accounts_spl_withdraw::*;
     pub use super::__client_accounts_spl_withdraw_with_receipt::*;
     pub use super::__client_accounts_spl_pay::*;
+    pub use super::__client_accounts_spl_pay_with_reference::*;
+    pub use super::__client_accounts_migrate_receipt::*;
+    pub use super::__client_accounts_set_receipt_attestor::*;
+    pub use super::__client_accounts_verify_receipt_attestation::*;
+    pub use super::__client_accounts_init_user_profile::*;
+    pub use super::__client_accounts_close_user_profile::*;
+    pub use super::__client_accounts_update_user_profile::*;
+    pub use super::__client_accounts_set_xp_config::*;
+    pub use super::__client_accounts_set_risk_config::*;
+    pub use super::__client_accounts_set_limit_tier::*;
+    pub use super::__client_accounts_set_user_limit_tier::*;
+    pub use super::__client_accounts_set_compliance_authority::*;
+    pub use super::__client_accounts_add_denylist_entry::*;
+    pub use super::__client_accounts_remove_denylist_entry::*;
+    pub use super::__client_accounts_set_kyc_config::*;
+    pub use super::__client_accounts_add_kyc_issuer::*;
+    pub use super::__client_accounts_remove_kyc_issuer::*;
+    pub use super::__client_accounts_issue_kyc_attestation::*;
+    pub use super::__client_accounts_revoke_kyc_attestation::*;
+    pub use super::__client_accounts_set_travel_rule_config::*;
+    pub use super::__client_accounts_record_travel_rule::*;
+    pub use super::__client_accounts_set_trust_config::*;
+    pub use super::__client_accounts_init_trust_score::*;
+    pub use super::__client_accounts_refresh_trust_score::*;
+    pub use super::__client_accounts_set_dispute_config::*;
+    pub use super::__client_accounts_open_dispute::*;
+    pub use super::__client_accounts_submit_dispute_evidence::*;
+    pub use super::__client_accounts_resolve_dispute::*;
+    pub use super::__client_accounts_set_merchant_fee_tiers::*;
+    pub use super::__client_accounts_register_merchant::*;
+    pub use super::__client_accounts_update_merchant::*;
+    pub use super::__client_accounts_set_merchant_status::*;
+    pub use super::__client_accounts_pay_merchant::*;
+    pub use super::__client_accounts_open_mint_ledger::*;
+    pub use super::__client_accounts_assert_solvency::*;
 }
 
 #[program]
@@ -63,8 +182,11 @@ pub mod protocol {
         ctx: Context<SplDepositWithReceipt>,
         amount: u64,
         nonce: u64,
+        reference: Option<[u8; 32]>,
+        memo: Option<Vec<u8>>,
+        memo_cpi: Option<MemoCpiMode>,
     ) -> Result<()> {
-        instructions::spl_deposit_with_receipt::handler(ctx, amount, nonce)
+        instructions::spl_deposit_with_receipt::handler(ctx, amount, nonce, reference, memo, memo_cpi)
     }
 
     pub fn spl_withdraw(ctx: Context<SplWithdraw>, amount: u64) -> Result<()> {
@@ -74,8 +196,10 @@ pub mod protocol {
     pub fn spl_withdraw_with_receipt(
         ctx: Context<SplWithdrawWithReceipt>,
         amount: u64,
+        reference: Option<[u8; 32]>,
+        memo: Option<Vec<u8>>,
     ) -> Result<()> {
-        instructions::spl_withdraw_with_receipt::handler(ctx, amount)
+        instructions::spl_withdraw_with_receipt::handler(ctx, amount, reference, memo)
     }
 
     pub fn spl_pay(
@@ -83,9 +207,222 @@ pub mod protocol {
     amount: u64,
     reference: Option<[u8; 32]>,
     memo: Option<Vec<u8>>,
+    memo_cpi: Option<MemoCpiMode>,
   ) -> Result<()> {
-    instructions::spl_pay::handler(ctx, amount, reference, memo)
+    instructions::spl_pay::handler(ctx, amount, reference, memo, memo_cpi)
  }
 
-}
+    pub fn spl_pay_with_reference(
+        ctx: Context<SplPayWithReference>,
+        amount: u64,
+        reference: [u8; 32],
+        memo: Option<Vec<u8>>,
+        memo_cpi: Option<MemoCpiMode>,
+    ) -> Result<()> {
+        instructions::spl_pay_with_reference::handler(ctx, amount, reference, memo, memo_cpi)
+    }
+
+    pub fn migrate_receipt(ctx: Context<MigrateReceipt>) -> Result<()> {
+        instructions::migrate_receipt::handler(ctx)
+    }
+
+    pub fn set_receipt_attestor(ctx: Context<SetReceiptAttestor>, attestor: Pubkey) -> Result<()> {
+        instructions::set_receipt_attestor::handler(ctx, attestor)
+    }
 
+    pub fn verify_receipt_attestation(ctx: Context<VerifyReceiptAttestation>) -> Result<()> {
+        instructions::verify_receipt_attestation::handler(ctx)
+    }
+
+    pub fn init_user_profile(ctx: Context<InitUserProfile>) -> Result<()> {
+        instructions::init_user_profile::handler(ctx)
+    }
+
+    pub fn close_user_profile(ctx: Context<CloseUserProfile>) -> Result<()> {
+        instructions::close_user_profile::handler(ctx)
+    }
+
+    pub fn update_user_profile(ctx: Context<UpdateUserProfile>) -> Result<()> {
+        instructions::update_user_profile::handler(ctx)
+    }
+
+    pub fn set_xp_config(
+        ctx: Context<SetXpConfig>,
+        xp_per_deposit: u32,
+        xp_per_withdraw: u32,
+        xp_per_pay: u32,
+        xp_min_amount: u64,
+        xp_epoch_cap: u64,
+    ) -> Result<()> {
+        instructions::set_xp_config::handler(
+            ctx,
+            xp_per_deposit,
+            xp_per_withdraw,
+            xp_per_pay,
+            xp_min_amount,
+            xp_epoch_cap,
+        )
+    }
+
+    pub fn set_risk_config(ctx: Context<SetRiskConfig>, params: RiskConfigParams) -> Result<()> {
+        instructions::set_risk_config::handler(ctx, params)
+    }
+
+    pub fn set_limit_tier(
+        ctx: Context<SetLimitTier>,
+        tier: u8,
+        period_secs: i64,
+        max_count: u32,
+        max_amount: u64,
+    ) -> Result<()> {
+        instructions::set_limit_tier::handler(ctx, tier, period_secs, max_count, max_amount)
+    }
+
+    pub fn set_user_limit_tier(ctx: Context<SetUserLimitTier>, tier: u8) -> Result<()> {
+        instructions::set_user_limit_tier::handler(ctx, tier)
+    }
+
+    pub fn set_compliance_authority(
+        ctx: Context<SetComplianceAuthority>,
+        compliance_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_compliance_authority::handler(ctx, compliance_authority)
+    }
+
+    pub fn add_denylist_entry(
+        ctx: Context<AddDenylistEntry>,
+        reason_code: u16,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::add_denylist_entry::handler(ctx, reason_code, expires_at)
+    }
+
+    pub fn remove_denylist_entry(ctx: Context<RemoveDenylistEntry>) -> Result<()> {
+        instructions::remove_denylist_entry::handler(ctx)
+    }
+
+    pub fn set_kyc_config(ctx: Context<SetKycConfig>, min_level: u8, threshold: u64) -> Result<()> {
+        instructions::set_kyc_config::handler(ctx, min_level, threshold)
+    }
+
+    pub fn add_kyc_issuer(ctx: Context<AddKycIssuer>) -> Result<()> {
+        instructions::add_kyc_issuer::handler(ctx)
+    }
+
+    pub fn remove_kyc_issuer(ctx: Context<RemoveKycIssuer>) -> Result<()> {
+        instructions::remove_kyc_issuer::handler(ctx)
+    }
+
+    pub fn issue_kyc_attestation(
+        ctx: Context<IssueKycAttestation>,
+        level: u8,
+        jurisdiction: [u8; 2],
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::issue_kyc_attestation::handler(ctx, level, jurisdiction, expires_at)
+    }
+
+    pub fn revoke_kyc_attestation(ctx: Context<RevokeKycAttestation>) -> Result<()> {
+        instructions::revoke_kyc_attestation::handler(ctx)
+    }
+
+    pub fn set_travel_rule_config(ctx: Context<SetTravelRuleConfig>, threshold: u64) -> Result<()> {
+        instructions::set_travel_rule_config::handler(ctx, threshold)
+    }
+
+    pub fn record_travel_rule(ctx: Context<RecordTravelRule>, params: TravelRuleParams) -> Result<()> {
+        instructions::record_travel_rule::handler(ctx, params)
+    }
+
+    pub fn set_trust_config(
+        ctx: Context<SetTrustConfig>,
+        tier_scores: [u16; 4],
+        min_amount: u64,
+    ) -> Result<()> {
+        instructions::set_trust_config::handler(ctx, tier_scores, min_amount)
+    }
+
+    pub fn init_trust_score(ctx: Context<InitTrustScore>) -> Result<()> {
+        instructions::init_trust_score::handler(ctx)
+    }
+
+    pub fn refresh_trust_score(ctx: Context<RefreshTrustScore>) -> Result<()> {
+        instructions::refresh_trust_score::handler(ctx)
+    }
+
+    pub fn set_dispute_config(
+        ctx: Context<SetDisputeConfig>,
+        arbiter: Pubkey,
+        window_secs: i64,
+    ) -> Result<()> {
+        instructions::set_dispute_config::handler(ctx, arbiter, window_secs)
+    }
+
+    pub fn open_dispute(
+        ctx: Context<OpenDispute>,
+        reason_code: u16,
+        evidence: [u8; 32],
+    ) -> Result<()> {
+        instructions::open_dispute::handler(ctx, reason_code, evidence)
+    }
+
+    pub fn submit_dispute_evidence(
+        ctx: Context<SubmitDisputeEvidence>,
+        evidence: [u8; 32],
+    ) -> Result<()> {
+        instructions::submit_dispute_evidence::handler(ctx, evidence)
+    }
+
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, refund_amount: u64) -> Result<()> {
+        instructions::resolve_dispute::handler(ctx, refund_amount)
+    }
+
+    pub fn set_merchant_fee_tiers(
+        ctx: Context<SetMerchantFeeTiers>,
+        fee_bps: [u16; 4],
+    ) -> Result<()> {
+        instructions::set_merchant_fee_tiers::handler(ctx, fee_bps)
+    }
+
+    pub fn register_merchant(
+        ctx: Context<RegisterMerchant>,
+        payout_wallet: Pubkey,
+        settlement_mints: Vec<Pubkey>,
+        fee_tier: u8,
+    ) -> Result<()> {
+        instructions::register_merchant::handler(ctx, payout_wallet, settlement_mints, fee_tier)
+    }
+
+    pub fn update_merchant(
+        ctx: Context<UpdateMerchant>,
+        payout_wallet: Pubkey,
+        settlement_mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_merchant::handler(ctx, payout_wallet, settlement_mints)
+    }
+
+    pub fn set_merchant_status(
+        ctx: Context<SetMerchantStatus>,
+        status: u8,
+        fee_tier: u8,
+    ) -> Result<()> {
+        instructions::set_merchant_status::handler(ctx, status, fee_tier)
+    }
+
+    pub fn pay_merchant(
+        ctx: Context<PayMerchant>,
+        amount: u64,
+        reference: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::pay_merchant::handler(ctx, amount, reference)
+    }
+
+    pub fn open_mint_ledger(ctx: Context<OpenMintLedger>) -> Result<()> {
+        instructions::open_mint_ledger::handler(ctx)
+    }
+
+    pub fn assert_solvency(ctx: Context<AssertSolvency>) -> Result<()> {
+        instructions::assert_solvency::handler(ctx)
+    }
+
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+
+use crate::errors::ErrorCode;
+use crate::state::{MintLimits, UserLimits};
+
+/// Accounts a value-moving flow passes to `enforce_limits`.
+///
+/// Both limits accounts are pinned by seeds in the flow's account struct, so
+/// clients cannot skip enforcement by omitting or substituting them.
+pub struct LimitAccounts<'a, 'info> {
+    /// ["mint_limits", treasury, mint]; may not exist yet.
+    pub mint_limits: &'a AccountInfo<'info>,
+
+    /// ["user_limits", treasury, user, mint]; created here when missing.
+    pub user_limits: &'a AccountInfo<'info>,
+
+    /// Canonical bump of `user_limits`.
+    pub user_limits_bump: u8,
+
+    /// Pays rent when `user_limits` has to be created.
+    pub payer: &'a AccountInfo<'info>,
+
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Check one flow against the user's limit tier for `mint` and record it.
+///
+/// No-op while the mint has no `MintLimits`. Otherwise the user's
+/// `UserLimits` account is created on first use (tier 0) and updated in place.
+/// The tier applied is the higher of the assigned one and `min_tier` (the
+/// tier earned through the user's trust score, see `crate::trust`).
+pub fn enforce_limits(
+    accounts: &LimitAccounts,
+    treasury: &Pubkey,
+    user: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    min_tier: u8,
+) -> Result<()> {
+    let Some(mint_limits) = MintLimits::load(accounts.mint_limits)? else {
+        return Ok(());
+    };
+
+    let info = accounts.user_limits;
+
+    let mut limits = if info.data_is_empty() {
+        create_user_limits(accounts, treasury, user, mint)?;
+
+        let mut limits = UserLimits::default();
+        limits.initialize(*treasury, *user, *mint, accounts.user_limits_bump);
+        limits
+    } else {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidUserLimits);
+        let data = info.try_borrow_data()?;
+        UserLimits::try_deserialize(&mut &data[..])?
+    };
+
+    let tier = mint_limits
+        .tiers
+        .get(usize::from(limits.tier.max(min_tier)))
+        .ok_or(ErrorCode::InvalidLimitTier)?;
+
+    limits.check_and_record(tier, amount, Clock::get()?.unix_timestamp)?;
+
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    limits.try_serialize(&mut writer)
+}
+
+/// Allocate the `user_limits` PDA, tolerating lamports sent to it beforehand.
+fn create_user_limits(
+    accounts: &LimitAccounts,
+    treasury: &Pubkey,
+    user: &Pubkey,
+    mint: &Pubkey,
+) -> Result<()> {
+    let bump = [accounts.user_limits_bump];
+    let seeds: &[&[u8]] = &[
+        UserLimits::SEED,
+        treasury.as_ref(),
+        user.as_ref(),
+        mint.as_ref(),
+        &bump,
+    ];
+    let signer = &[seeds];
+
+    let system = accounts.system_program.clone();
+    let target = accounts.user_limits.clone();
+    let required = Rent::get()?.minimum_balance(UserLimits::SPACE);
+    let current = target.lamports();
+
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system,
+                CreateAccount {
+                    from: accounts.payer.clone(),
+                    to: target,
+                },
+                signer,
+            ),
+            required,
+            UserLimits::SPACE as u64,
+            &crate::ID,
+        );
+    }
+
+    let shortfall = required.saturating_sub(current);
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                Transfer {
+                    from: accounts.payer.clone(),
+                    to: target.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system.clone(),
+            Allocate {
+                account_to_allocate: target.clone(),
+            },
+            signer,
+        ),
+        UserLimits::SPACE as u64,
+    )?;
+
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system,
+            Assign {
+                account_to_assign: target,
+            },
+            signer,
+        ),
+        &crate::ID,
+    )
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::memo::{self, BuildMemo, Memo};
+
+use crate::errors::ErrorCode;
+
+/// Prefix used by `MemoCpiMode::ReceiptLink`.
+pub const RECEIPT_LINK_PREFIX: &str = "zephyon:";
+
+/// Optional SPL Memo program CPI, selected per instruction.
+///
+/// Receipt memos (`ReceiptV2Ext.memo`) live in program state and are invisible
+/// to wallets and explorers. When a flow is given a mode, it also writes a
+/// memo through the SPL Memo program so the linkage shows up in wallet history.
+///
+/// APPEND ONLY — do not reorder.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemoCpiMode {
+    /// Forward the instruction's `memo` bytes as-is (must be UTF-8).
+    Raw,
+
+    /// Write `zephyon:<receipt pubkey>`.
+    ReceiptLink,
+}
+
+/// Build the bytes a given mode writes to the SPL Memo program.
+pub fn memo_cpi_bytes(mode: MemoCpiMode, memo: Option<&[u8]>, receipt: &Pubkey) -> Result<Vec<u8>> {
+    match mode {
+        MemoCpiMode::Raw => {
+            let m = memo.filter(|m| !m.is_empty()).ok_or(ErrorCode::MemoRequired)?;
+            require!(std::str::from_utf8(m).is_ok(), ErrorCode::MemoNotUtf8);
+            Ok(m.to_vec())
+        }
+        MemoCpiMode::ReceiptLink => {
+            Ok(format!("{}{}", RECEIPT_LINK_PREFIX, receipt).into_bytes())
+        }
+    }
+}
+
+/// CPI into the SPL Memo program when `mode` is set.
+///
+/// No-op when `mode` is `None`. Fails with `MemoProgramRequired` when a mode
+/// is requested but the optional memo program account was not supplied.
+pub fn maybe_emit_memo<'info>(
+    mode: Option<MemoCpiMode>,
+    memo_program: Option<&Program<'info, Memo>>,
+    memo: Option<&[u8]>,
+    receipt: &Pubkey,
+) -> Result<()> {
+    let Some(mode) = mode else {
+        return Ok(());
+    };
+
+    let memo_program = memo_program.ok_or(ErrorCode::MemoProgramRequired)?;
+    let bytes = memo_cpi_bytes(mode, memo, receipt)?;
+
+    let cpi_ctx = CpiContext::new(memo_program.to_account_info(), BuildMemo {});
+    memo::build_memo(cpi_ctx, &bytes)
+}
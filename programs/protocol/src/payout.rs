@@ -0,0 +1,255 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    memo::Memo,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::denylist::ensure_not_denylisted;
+use crate::errors::ErrorCode;
+use crate::events::{AssetKind, PayDirection, SplPayEvent};
+use crate::kyc::enforce_kyc;
+use crate::limits::{enforce_limits, LimitAccounts};
+use crate::memo::{maybe_emit_memo, MemoCpiMode};
+use crate::risk::evaluate_payout;
+use crate::state::{
+    LedgerEntry, MintLedger, Receipt, ReceiptV2Ext, TravelRuleRecord, Treasury, TreasuryConfig,
+    TrustScore, UserProfile,
+};
+use crate::travel_rule::consume_travel_rule;
+use crate::trust::{credit_trust, trust_limit_tier};
+use crate::xp::{credit_xp, XpFlow};
+
+/// Accounts of a treasury payout, shared by `spl_pay` and
+/// `spl_pay_with_reference`.
+///
+/// Every PDA here is pinned by seeds in the flow's account struct; see
+/// `SplPay` for what each one is.
+pub struct PayoutAccounts<'a, 'info> {
+    pub treasury_authority: &'a Signer<'info>,
+    pub recipient: &'a UncheckedAccount<'info>,
+    pub treasury: &'a mut Account<'info, Treasury>,
+    pub mint: &'a Account<'info, Mint>,
+    pub recipient_ata: &'a Account<'info, TokenAccount>,
+    pub treasury_ata: &'a Account<'info, TokenAccount>,
+    pub receipt: &'a mut Account<'info, Receipt>,
+    pub receipt_bump: u8,
+    pub token_program: &'a Program<'info, Token>,
+    pub system_program: &'a Program<'info, System>,
+    pub memo_program: Option<&'a Program<'info, Memo>>,
+    pub user_profile: Option<&'a mut Account<'info, UserProfile>>,
+    pub treasury_config: &'a AccountInfo<'info>,
+    pub mint_limits: &'a AccountInfo<'info>,
+    pub user_limits: &'a AccountInfo<'info>,
+    pub user_limits_bump: u8,
+    pub denylist_entry: &'a AccountInfo<'info>,
+    pub kyc_attestation: &'a AccountInfo<'info>,
+    pub travel_rule: Option<&'a mut Account<'info, TravelRuleRecord>>,
+    pub trust_score: Option<&'a mut Account<'info, TrustScore>>,
+    pub mint_ledger: &'a AccountInfo<'info>,
+}
+
+/// Pay `amount` of the mint from the treasury ATA to the recipient ATA and
+/// write the pay receipt.
+///
+/// Runs the authority gate and every compliance hook (denylist, KYC, travel
+/// rule, risk rules, spending limits), books the payout in the mint ledger,
+/// credits the recipient's XP and trust score, increments `pay_count` and
+/// emits `SplPayEvent`. Returns the `pay_count` the receipt was written
+/// under.
+pub fn pay_out(
+    accounts: PayoutAccounts,
+    amount: u64,
+    reference: Option<[u8; 32]>,
+    memo: Option<&[u8]>,
+    memo_cpi: Option<MemoCpiMode>,
+) -> Result<u64> {
+    let PayoutAccounts {
+        treasury_authority,
+        recipient,
+        treasury,
+        mint,
+        recipient_ata,
+        treasury_ata,
+        receipt,
+        receipt_bump,
+        token_program,
+        system_program,
+        memo_program,
+        mut user_profile,
+        treasury_config,
+        mint_limits,
+        user_limits,
+        user_limits_bump,
+        denylist_entry,
+        kyc_attestation,
+        travel_rule,
+        trust_score,
+        mint_ledger,
+    } = accounts;
+
+    // --- Basic validation ---
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(!treasury.paused, ErrorCode::ProtocolPaused);
+
+    if let Some(m) = memo {
+        require!(m.len() <= ReceiptV2Ext::MAX_MEMO_LEN, ErrorCode::MemoTooLong);
+    }
+
+    // --- Authority gate ---
+    require_keys_eq!(
+        treasury_authority.key(),
+        treasury.authority,
+        ErrorCode::UnauthorizedWithdraw
+    );
+
+    // --- Denylist (may reject) ---
+    ensure_not_denylisted(denylist_entry, ErrorCode::RecipientDenylisted)?;
+
+    let config = TreasuryConfig::load(treasury_config)?;
+
+    // --- KYC (may reject) ---
+    enforce_kyc(config.as_ref(), kyc_attestation, amount)?;
+
+    // --- Travel rule (may reject) ---
+    let has_travel_rule = consume_travel_rule(
+        config.as_ref(),
+        travel_rule,
+        &recipient.key(),
+        &mint.key(),
+        amount,
+        receipt.key(),
+    )?;
+
+    // --- Risk rules (may reject) ---
+    let risk_flags = evaluate_payout(
+        config.as_ref(),
+        user_profile.as_deref().map(|profile| &**profile),
+        &mint.key(),
+        mint.decimals,
+        amount,
+    )?;
+
+    // --- Spending limits (may reject) ---
+    let trust_tier = trust_limit_tier(
+        config.as_ref(),
+        trust_score.as_deref().map(|trust| &**trust),
+    )?;
+    enforce_limits(
+        &LimitAccounts {
+            mint_limits,
+            user_limits,
+            user_limits_bump,
+            payer: treasury_authority,
+            system_program,
+        },
+        &treasury.key(),
+        &recipient.key(),
+        &mint.key(),
+        amount,
+        trust_tier,
+    )?;
+
+    // Capture the canonical pay index BEFORE mutation.
+    // This value is used for:
+    // - receipt PDA derivation
+    // - receipt.tx_count storage (flow-specific payment index)
+    // - emitted event indexing
+    let pay_count_before = treasury.pay_count;
+
+    // --- Treasury PDA signer seeds for token transfer ---
+    let bump = treasury.bump;
+    let signer_seeds: &[&[u8]] = &[b"treasury", &[bump]];
+    let signer = &[signer_seeds];
+
+    // --- Transfer treasury funds to recipient ATA ---
+    //
+    // Note:
+    // This occurs before receipt field population, but the instruction remains atomic.
+    // Any later failure in this instruction rolls back the transfer as well.
+    let cpi_accounts = Transfer {
+        from: treasury_ata.to_account_info(),
+        to: recipient_ata.to_account_info(),
+        authority: treasury.to_account_info(),
+    };
+
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+
+    token::transfer(cpi_ctx, amount)?;
+    MintLedger::record(mint_ledger, LedgerEntry::Pay(amount))?;
+
+    let clock = Clock::get()?;
+
+    // --- Populate receipt ---
+    //
+    // For pay flows:
+    // - `user` stores the recipient wallet
+    // - `tx_count` stores the treasury pay_count snapshot BEFORE increment
+    receipt.user = recipient.key();
+    receipt.direction = Receipt::DIR_PAY;
+    receipt.asset_kind = Receipt::ASSET_SPL;
+    receipt.mint = mint.key();
+    receipt.amount = amount;
+    receipt.fee = 0;
+    receipt.pre_balance = 0;
+    receipt.post_balance = 0;
+    receipt.ts = clock.unix_timestamp;
+    receipt.tx_count = pay_count_before;
+    receipt.bump = receipt_bump;
+    receipt.version = Receipt::CURRENT_VERSION;
+
+    receipt.v2 = ReceiptV2Ext::spl_with_meta(mint.key(), reference, memo);
+    receipt.v2.set_risk_flags(risk_flags);
+    if has_travel_rule {
+        receipt.v2.flags |= ReceiptV2Ext::FLAG_HAS_TRAVEL_RULE;
+    }
+
+    // --- Optional wallet-visible memo (SPL Memo program CPI) ---
+    maybe_emit_memo(memo_cpi, memo_program, memo, &receipt.key())?;
+
+    // --- Optional recipient XP + payout count ---
+    let xp_delta = credit_xp(
+        user_profile.as_deref_mut(),
+        config.as_ref(),
+        XpFlow::Pay,
+        amount,
+    )?;
+    credit_trust(trust_score, config.as_ref(), amount)?;
+    if let Some(profile) = user_profile {
+        profile.record_pay_received()?;
+    }
+
+    // --- Increment pay_count (must fail loudly on overflow) ---
+    treasury.pay_count = treasury
+        .pay_count
+        .checked_add(1)
+        .ok_or(ErrorCode::CounterOverflow)?;
+
+    // --- Emit event after successful transfer + receipt + counter mutation ---
+    let (has_memo, memo_len) = match memo {
+        Some(m) => (true, m.len() as u8),
+        None => (false, 0),
+    };
+
+    emit!(SplPayEvent {
+        pay_count: pay_count_before,
+        treasury: treasury.key(),
+        treasury_authority: treasury_authority.key(),
+        recipient: recipient.key(),
+        receipt: receipt.key(),
+        direction: PayDirection::TreasuryToRecipient,
+        asset_kind: AssetKind::SPL,
+        mint: mint.key(),
+        amount,
+        has_reference: reference.is_some(),
+        reference: reference.unwrap_or_default(),
+        has_memo,
+        memo_len,
+        slot: clock.slot,
+        unix_timestamp: clock.unix_timestamp,
+        xp_delta,
+        risk_flags,
+    });
+
+    Ok(pay_count_before)
+}
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{MintThreshold, TreasuryConfig, UserProfile};
+
+/// Amount above the mint's large-amount threshold.
+pub const RISK_LARGE_AMOUNT: u32 = 1 << 0;
+
+/// User profile missing, without recorded activity, or younger than
+/// `TreasuryConfig.risk_new_profile_secs`.
+pub const RISK_NEW_PROFILE: u32 = 1 << 1;
+
+/// More than `risk_velocity_max_count` flows within the velocity window, or
+/// a user flow without the profile that tracks the window.
+pub const RISK_HIGH_VELOCITY: u32 = 1 << 2;
+
+/// Payout recipient without a profile, or one that never received a payout.
+pub const RISK_FIRST_TIME_RECIPIENT: u32 = 1 << 3;
+
+/// Amount is a whole multiple of the configured round-number unit.
+pub const RISK_ROUND_AMOUNT: u32 = 1 << 4;
+
+/// Every flag this program version can raise.
+///
+/// Receipts store risk flags in the high byte of `ReceiptV2Ext.flags`, so new
+/// flags must stay below `1 << 8`.
+pub const RISK_FLAGS_ALL: u32 = RISK_LARGE_AMOUNT
+    | RISK_NEW_PROFILE
+    | RISK_HIGH_VELOCITY
+    | RISK_FIRST_TIME_RECIPIENT
+    | RISK_ROUND_AMOUNT;
+
+/// Risk rule settings accepted by `set_risk_config`.
+///
+/// Mirrors the `risk_*` fields of `TreasuryConfig`; see there for semantics.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct RiskConfigParams {
+    pub rules_mask: u32,
+    pub blocking_mask: u32,
+    pub default_threshold: u64,
+    /// At most `TreasuryConfig::MAX_RISK_MINTS` entries, one per mint.
+    pub mint_thresholds: Vec<MintThreshold>,
+    pub new_profile_secs: i64,
+    pub velocity_window_slots: u64,
+    pub velocity_max_count: u32,
+    pub round_whole_units: u64,
+}
+
+impl RiskConfigParams {
+    /// Reject settings the rule evaluation cannot honor.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.rules_mask & !RISK_FLAGS_ALL == 0,
+            ErrorCode::InvalidRiskConfig
+        );
+        require!(
+            self.blocking_mask & !self.rules_mask == 0,
+            ErrorCode::InvalidRiskConfig
+        );
+        require!(
+            self.mint_thresholds.len() <= TreasuryConfig::MAX_RISK_MINTS,
+            ErrorCode::InvalidRiskConfig
+        );
+        require!(self.new_profile_secs >= 0, ErrorCode::InvalidRiskConfig);
+
+        for (i, t) in self.mint_thresholds.iter().enumerate() {
+            require!(t.mint != Pubkey::default(), ErrorCode::InvalidRiskConfig);
+            require!(
+                self.mint_thresholds[..i].iter().all(|o| o.mint != t.mint),
+                ErrorCode::InvalidRiskConfig
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluate the risk rules for a deposit or withdrawal.
+///
+/// Only rules in the config's `risk_rules_mask` run. Returns the raised
+/// flags, or `RiskFlagBlocked` when one of them is in the blocking mask.
+/// Without a config no rules run and the result is 0.
+///
+/// Also advances the profile's velocity window, so call this once per flow.
+pub fn evaluate_user_flow<'info>(
+    config: Option<&TreasuryConfig>,
+    profile: Option<&mut Account<'info, UserProfile>>,
+    mint: &Pubkey,
+    decimals: u8,
+    amount: u64,
+) -> Result<u32> {
+    let Some(config) = config else {
+        return Ok(0);
+    };
+
+    let mut flags = amount_flags(config, mint, decimals, amount);
+
+    match profile {
+        Some(profile) => {
+            let clock = Clock::get()?;
+
+            if profile.is_new(clock.unix_timestamp, config.risk_new_profile_secs) {
+                flags |= RISK_NEW_PROFILE;
+            }
+
+            if rule_on(config, RISK_HIGH_VELOCITY) && config.risk_velocity_window_slots > 0 {
+                let count =
+                    profile.note_velocity(clock.slot, config.risk_velocity_window_slots)?;
+                if count > config.risk_velocity_max_count {
+                    flags |= RISK_HIGH_VELOCITY;
+                }
+            }
+        }
+        // No history to judge by, and no window to count in: raise both so
+        // blocking either forces clients to pass the profile. Otherwise
+        // omitting the profile would skip a blocking velocity rule.
+        None => {
+            flags |= RISK_NEW_PROFILE;
+            if rule_on(config, RISK_HIGH_VELOCITY) && config.risk_velocity_window_slots > 0 {
+                flags |= RISK_HIGH_VELOCITY;
+            }
+        }
+    }
+
+    enforce(config, flags)
+}
+
+/// Evaluate the risk rules for a treasury payout.
+///
+/// Same contract as `evaluate_user_flow`; `recipient_profile` is the
+/// recipient's profile when supplied.
+pub fn evaluate_payout(
+    config: Option<&TreasuryConfig>,
+    recipient_profile: Option<&UserProfile>,
+    mint: &Pubkey,
+    decimals: u8,
+    amount: u64,
+) -> Result<u32> {
+    let Some(config) = config else {
+        return Ok(0);
+    };
+
+    let mut flags = amount_flags(config, mint, decimals, amount);
+
+    if recipient_profile.is_none_or(|p| p.pays_received == 0) {
+        flags |= RISK_FIRST_TIME_RECIPIENT;
+    }
+
+    enforce(config, flags)
+}
+
+fn amount_flags(config: &TreasuryConfig, mint: &Pubkey, decimals: u8, amount: u64) -> u32 {
+    let mut flags = 0;
+
+    let threshold = config.risk_threshold_for(mint);
+    if threshold > 0 && amount > threshold {
+        flags |= RISK_LARGE_AMOUNT;
+    }
+
+    // A unit that overflows u64 can never divide a u64 amount.
+    let round_unit = 10u64
+        .checked_pow(u32::from(decimals))
+        .and_then(|scale| scale.checked_mul(config.risk_round_whole_units));
+    if let Some(unit) = round_unit.filter(|u| *u > 0) {
+        if amount.checked_rem(unit) == Some(0) {
+            flags |= RISK_ROUND_AMOUNT;
+        }
+    }
+
+    flags
+}
+
+fn rule_on(config: &TreasuryConfig, flag: u32) -> bool {
+    config.risk_rules_mask & flag != 0
+}
+
+fn enforce(config: &TreasuryConfig, flags: u32) -> Result<u32> {
+    let flags = flags & config.risk_rules_mask;
+    let blocked = flags & config.risk_blocking_mask;
+    if blocked != 0 {
+        msg!("risk flags {:#x} blocked by policy", blocked);
+        return err!(ErrorCode::RiskFlagBlocked);
+    }
+
+    Ok(flags)
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+/// Designated receipt attestor for a treasury.
+///
+/// Receipt attestations (see `crate::attestation`) are accepted when signed by
+/// either the treasury authority or the key stored here. This lets the
+/// authority keep its key cold while a hot service key signs proofs.
+///
+/// PDA seeds:
+/// ["attestor", treasury.key()]
+#[account]
+pub struct AttestorConfig {
+    /// Treasury this attestor signs for.
+    pub treasury: Pubkey,
+
+    /// Designated attestor key. `Pubkey::default()` disables it.
+    pub attestor: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl AttestorConfig {
+    pub const SEED: &'static [u8] = b"attestor";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // attestor
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Treasury;
+
+/// Designated compliance authority for a treasury.
+///
+/// Compliance actions (denylist management) are accepted when signed by
+/// either the treasury authority or the key stored here, so a compliance team
+/// can act without holding the treasury key.
+///
+/// PDA seeds:
+/// ["compliance", treasury.key()]
+#[account]
+pub struct ComplianceConfig {
+    /// Treasury this authority acts for.
+    pub treasury: Pubkey,
+
+    /// Designated compliance key. `Pubkey::default()` disables it.
+    pub compliance_authority: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl ComplianceConfig {
+    pub const SEED: &'static [u8] = b"compliance";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // compliance_authority
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Whether `signer` may perform compliance actions for `treasury`.
+    pub fn is_authorized(
+        config: Option<&ComplianceConfig>,
+        treasury: &Treasury,
+        signer: &Pubkey,
+    ) -> bool {
+        let designated = config
+            .map(|c| c.compliance_authority)
+            .filter(|k| *k != Pubkey::default());
+
+        *signer == treasury.authority || Some(*signer) == designated
+    }
+}
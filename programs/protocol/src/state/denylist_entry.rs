@@ -0,0 +1,73 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::*;
+
+/// A denylisted (frozen) wallet.
+///
+/// PDA seeds:
+/// ["denylist", treasury.key(), wallet.key()]
+///
+/// While an active entry exists, the wallet cannot deposit, withdraw or
+/// receive payouts. Entries are added and removed by the compliance
+/// authority (see `ComplianceConfig`).
+#[account]
+pub struct DenylistEntry {
+    /// Treasury this entry belongs to.
+    pub treasury: Pubkey,
+
+    /// Denylisted wallet.
+    pub wallet: Pubkey,
+
+    /// Why the wallet was listed (compliance-defined code, e.g. sanctions
+    /// match vs. reported compromise).
+    pub reason_code: u16,
+
+    /// Unix timestamp the entry was added or last updated.
+    pub added_at: i64,
+
+    /// Unix timestamp the entry stops applying. 0 = never expires.
+    pub expires_at: i64,
+
+    /// Key that added the entry.
+    pub added_by: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl DenylistEntry {
+    pub const SEED: &'static [u8] = b"denylist";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // wallet
+        2 +  // reason_code
+        8 +  // added_at
+        8 +  // expires_at
+        32 + // added_by
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Whether the entry still applies at `now`.
+    pub fn is_active(&self, now: i64) -> bool {
+        self.expires_at == 0 || now < self.expires_at
+    }
+
+    /// Read an entry passed at its PDA address; `None` if it does not exist.
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            AnchorErrorCode::AccountOwnedByWrongProgram
+        );
+
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..]).map(Some)
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+/// Dispute (chargeback request) against one receipt.
+///
+/// PDA seeds:
+/// ["dispute", receipt.key()]
+///
+/// One dispute per receipt. Opened by the receipt's payer within the
+/// treasury's dispute window:
+/// - merchant payment receipts: the customer (`receipt.user`) paid, and the
+///   merchant owner responds; an upheld dispute is refunded by the treasury
+///   and booked against the merchant's settlement
+/// - pay receipts: the treasury paid, so the treasury authority opens it and
+///   the recipient (`receipt.user`) responds; there is no escrow to claw
+///   back from, so these resolve without a refund
+///
+/// Deposits are not disputable; depositors withdraw instead.
+///
+/// Both sides attach evidence as 32-byte hashes of off-chain documents.
+/// Resolution writes a linked receipt at ["receipt", dispute.key()] (see
+/// `Receipt::DIR_DISPUTE`) and updates the respondent's trust score when
+/// supplied.
+#[account]
+pub struct Dispute {
+    /// Treasury the disputed receipt belongs to.
+    pub treasury: Pubkey,
+
+    /// Disputed receipt.
+    pub receipt: Pubkey,
+
+    /// `direction` of the disputed receipt (`Receipt::DIR_MERCHANT_PAY` or
+    /// `Receipt::DIR_PAY`).
+    pub receipt_direction: u8,
+
+    /// Paying side: opened the dispute and receives any refund.
+    pub payer: Pubkey,
+
+    /// Responding side.
+    pub respondent: Pubkey,
+
+    /// Mint and amount of the disputed receipt.
+    pub mint: Pubkey,
+    pub amount: u64,
+
+    /// Payer-supplied reason code (off-chain taxonomy).
+    pub reason_code: u16,
+
+    /// Evidence hashes; zeroed until each side submits.
+    pub payer_evidence: [u8; 32],
+    pub respondent_evidence: [u8; 32],
+
+    /// `STATUS_*`.
+    pub status: u8,
+
+    /// Amount refunded to the payer (0 unless `STATUS_REFUNDED`).
+    pub refund_amount: u64,
+
+    pub opened_at: i64,
+
+    /// 0 while open.
+    pub resolved_at: i64,
+
+    /// Arbiter that resolved the dispute.
+    pub resolved_by: Pubkey,
+
+    /// Linked resolution receipt (default while open).
+    pub resolution_receipt: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const SEED: &'static [u8] = b"dispute";
+
+    pub const STATUS_OPEN: u8 = 1;
+    pub const STATUS_REFUNDED: u8 = 2;
+    pub const STATUS_REJECTED: u8 = 3;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // receipt
+        1 +  // receipt_direction
+        32 + // payer
+        32 + // respondent
+        32 + // mint
+        8 +  // amount
+        2 +  // reason_code
+        32 + // payer_evidence
+        32 + // respondent_evidence
+        1 +  // status
+        8 +  // refund_amount
+        8 +  // opened_at
+        8 +  // resolved_at
+        32 + // resolved_by
+        32 + // resolution_receipt
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    pub fn is_open(&self) -> bool {
+        self.status == Self::STATUS_OPEN
+    }
+}
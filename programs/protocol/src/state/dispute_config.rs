@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Treasury;
+
+/// Dispute settings for a treasury.
+///
+/// Disputes are resolved by either the treasury authority or the arbiter
+/// stored here, so an arbitration team can act without holding the treasury
+/// key. Until this account exists (or while `window_secs` is 0), receipts
+/// cannot be disputed.
+///
+/// PDA seeds:
+/// ["dispute_config", treasury.key()]
+#[account]
+pub struct DisputeConfig {
+    /// Treasury this config belongs to.
+    pub treasury: Pubkey,
+
+    /// Designated arbiter key. `Pubkey::default()` disables it.
+    pub arbiter: Pubkey,
+
+    /// Seconds after a receipt's `ts` during which it can be disputed
+    /// (0 = disputes disabled).
+    pub window_secs: i64,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl DisputeConfig {
+    pub const SEED: &'static [u8] = b"dispute_config";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // arbiter
+        8 +  // window_secs
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Whether `signer` may resolve disputes for `treasury`.
+    pub fn is_arbiter(&self, treasury: &Treasury, signer: &Pubkey) -> bool {
+        *signer == treasury.authority
+            || (self.arbiter != Pubkey::default() && *signer == self.arbiter)
+    }
+}
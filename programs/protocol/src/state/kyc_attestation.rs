@@ -0,0 +1,92 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::*;
+
+/// A wallet's KYC/KYB attestation.
+///
+/// PDA seeds:
+/// ["kyc", treasury.key(), wallet.key()]
+///
+/// Written by a trusted issuer (see `KycIssuer`); re-issuing overwrites it.
+/// Withdrawals and payouts above `TreasuryConfig.kyc_threshold` require a
+/// valid attestation of at least `TreasuryConfig.kyc_min_level`.
+#[account]
+pub struct KycAttestation {
+    /// Treasury this attestation is for.
+    pub treasury: Pubkey,
+
+    /// Attested wallet.
+    pub wallet: Pubkey,
+
+    /// Issuer that wrote the attestation.
+    pub issuer: Pubkey,
+
+    /// Verification level, see the `LEVEL_*` constants.
+    pub level: u8,
+
+    /// ISO 3166-1 alpha-2 jurisdiction code (ASCII, e.g. `b"DE"`).
+    pub jurisdiction: [u8; 2],
+
+    /// Unix timestamp the attestation was issued.
+    pub issued_at: i64,
+
+    /// Unix timestamp the attestation stops being valid.
+    pub expires_at: i64,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl KycAttestation {
+    pub const SEED: &'static [u8] = b"kyc";
+
+    /// Verification levels, ordered; they mirror the off-chain
+    /// `VerificationLevel` in `src/identity` ("none" is the absence of an
+    /// attestation).
+    pub const LEVEL_BASIC: u8 = 1;
+    pub const LEVEL_ENHANCED: u8 = 2;
+    pub const LEVEL_BUSINESS: u8 = 3;
+    pub const LEVEL_INSTITUTIONAL: u8 = 4;
+
+    pub const MAX_LEVEL: u8 = Self::LEVEL_INSTITUTIONAL;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // wallet
+        32 + // issuer
+        1 +  // level
+        2 +  // jurisdiction
+        8 +  // issued_at
+        8 +  // expires_at
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Whether the attestation has not expired at `now`.
+    pub fn is_valid(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+
+    /// Whether `code` looks like an ISO 3166-1 alpha-2 code.
+    pub fn is_jurisdiction_code(code: &[u8; 2]) -> bool {
+        code.iter().all(u8::is_ascii_uppercase)
+    }
+
+    /// Read an attestation passed at its PDA address; `None` if it does not
+    /// exist.
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            AnchorErrorCode::AccountOwnedByWrongProgram
+        );
+
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..]).map(Some)
+    }
+}
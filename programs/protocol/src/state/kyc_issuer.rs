@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+/// A trusted KYC/KYB issuer for a treasury.
+///
+/// PDA seeds:
+/// ["kyc_issuer", treasury.key(), issuer.key()]
+///
+/// Only keys with an issuer account may write `KycAttestation`s. Added and
+/// removed by the compliance authority (see `ComplianceConfig`). Removing an
+/// issuer does not revoke attestations it already wrote.
+#[account]
+pub struct KycIssuer {
+    /// Treasury this issuer is trusted by.
+    pub treasury: Pubkey,
+
+    /// Issuer signing key.
+    pub issuer: Pubkey,
+
+    /// Unix timestamp the issuer was added.
+    pub added_at: i64,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl KycIssuer {
+    pub const SEED: &'static [u8] = b"kyc_issuer";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // issuer
+        8 +  // added_at
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+}
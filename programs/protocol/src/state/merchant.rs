@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Receipt;
+
+/// Registered merchant of a treasury.
+///
+/// PDA seeds:
+/// ["merchant", treasury.key(), owner.key()]
+///
+/// Registered by the treasury authority (or compliance authority) together
+/// with the owner. Merchant payments (`pay_merchant`) resolve the payee, the
+/// accepted mints and the fee from this account rather than from
+/// client-supplied accounts, and keep per-mint settlement stats here.
+#[account]
+pub struct Merchant {
+    /// Treasury the merchant is registered with.
+    pub treasury: Pubkey,
+
+    /// Wallet that manages the merchant (payout wallet, settlement mints).
+    pub owner: Pubkey,
+
+    /// Wallet whose associated token accounts receive merchant payments.
+    pub payout_wallet: Pubkey,
+
+    /// Accepted settlement mints with their lifetime stats; unused entries
+    /// have a default mint.
+    pub settlements: [MerchantSettlement; Merchant::MAX_SETTLEMENT_MINTS],
+
+    /// Index into `TreasuryConfig.merchant_fee_bps`.
+    pub fee_tier: u8,
+
+    /// `STATUS_ACTIVE` or `STATUS_SUSPENDED`.
+    pub status: u8,
+
+    /// Lifetime merchant payments received.
+    pub pay_count: u64,
+
+    /// Unix timestamp of registration.
+    pub registered_at: i64,
+
+    /// Unix timestamp of the last registration, update or status change.
+    pub updated_at: i64,
+
+    /// Unix timestamp of the last payment (0 = none yet).
+    pub last_paid_at: i64,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl Merchant {
+    pub const SEED: &'static [u8] = b"merchant";
+
+    pub const MAX_SETTLEMENT_MINTS: usize = 4;
+
+    pub const MAX_FEE_TIERS: usize = 4;
+
+    /// Fee ceiling (100%).
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    pub const STATUS_ACTIVE: u8 = 1;
+    pub const STATUS_SUSPENDED: u8 = 2;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // owner
+        32 + // payout_wallet
+        MerchantSettlement::LEN * Self::MAX_SETTLEMENT_MINTS +
+        1 +  // fee_tier
+        1 +  // status
+        8 +  // pay_count
+        8 +  // registered_at
+        8 +  // updated_at
+        8 +  // last_paid_at
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    pub fn is_active(&self) -> bool {
+        self.status == Self::STATUS_ACTIVE
+    }
+
+    /// Whether `mints` is a valid settlement list: 1..=`MAX_SETTLEMENT_MINTS`
+    /// distinct, non-default mints.
+    pub fn is_valid_settlement_mints(mints: &[Pubkey]) -> bool {
+        if mints.is_empty() || mints.len() > Self::MAX_SETTLEMENT_MINTS {
+            return false;
+        }
+
+        mints.iter().enumerate().all(|(i, mint)| {
+            *mint != Pubkey::default() && !mints[..i].contains(mint)
+        })
+    }
+
+    /// Replace the accepted mints, keeping the stats of mints that stay.
+    pub fn set_settlement_mints(&mut self, mints: &[Pubkey]) {
+        let mut next = [MerchantSettlement::default(); Self::MAX_SETTLEMENT_MINTS];
+        for (slot, mint) in next.iter_mut().zip(mints) {
+            *slot = self
+                .settlements
+                .iter()
+                .find(|s| s.mint == *mint)
+                .copied()
+                .unwrap_or(MerchantSettlement {
+                    mint: *mint,
+                    ..Default::default()
+                });
+        }
+        self.settlements = next;
+    }
+
+    /// Whether `receipt` is a payment receipt of the merchant whose PDA is
+    /// `merchant`.
+    pub fn issued(merchant: &Pubkey, receipt: &Account<Receipt>) -> bool {
+        let pay_index = receipt.tx_count.to_le_bytes();
+        let seeds: &[&[u8]] = &[
+            Receipt::RECEIPT_SEED,
+            merchant.as_ref(),
+            &pay_index,
+            &[receipt.bump],
+        ];
+        receipt.direction == Receipt::DIR_MERCHANT_PAY
+            && Pubkey::create_program_address(seeds, &crate::ID)
+                .is_ok_and(|address| address == receipt.key())
+    }
+
+    /// Settlement entry for `mint`, if the merchant accepts it.
+    pub fn settlement_mut(&mut self, mint: &Pubkey) -> Option<&mut MerchantSettlement> {
+        self.settlements
+            .iter_mut()
+            .find(|s| s.mint != Pubkey::default() && s.mint == *mint)
+    }
+}
+
+/// One accepted settlement mint and its lifetime totals (raw token units).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MerchantSettlement {
+    /// `Pubkey::default()` marks an unused entry.
+    pub mint: Pubkey,
+
+    /// Gross amount paid to the merchant, fees included.
+    pub gross_volume: u64,
+
+    /// Fees kept by the treasury.
+    pub fees: u64,
+
+    /// Upheld disputes the treasury refunded to payers (see
+    /// `resolve_dispute`); the merchant owes these back.
+    pub refunded: u64,
+}
+
+impl MerchantSettlement {
+    pub const LEN: usize =
+        32 + // mint
+        8 +  // gross_volume
+        8 +  // fees
+        8;   // refunded
+}
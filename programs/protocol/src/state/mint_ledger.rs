@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+
+/// Per-mint treasury books.
+///
+/// PDA seeds:
+/// ["mint_ledger", treasury.key(), mint.key()]
+///
+/// Opened by `open_mint_ledger`, which snapshots the treasury ATA balance as
+/// `opening_balance`. From then on every flow that moves tokens in or out of
+/// the treasury ATA records the amount here, so
+/// `expected_balance()` is what the ATA must hold (see `assert_solvency`).
+/// Tokens sent to the ATA outside the program show up as a surplus.
+#[account]
+pub struct MintLedger {
+    /// Treasury these books belong to.
+    pub treasury: Pubkey,
+
+    /// Mint the amounts are denominated in.
+    pub mint: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+
+    /// Layout version.
+    pub version: u8,
+
+    /// Treasury ATA balance when the ledger was opened.
+    pub opening_balance: u64,
+
+    /// Deposited by users (`spl_deposit*`).
+    pub deposited: u64,
+
+    /// Withdrawn by the authority (`spl_withdraw*`).
+    pub withdrawn: u64,
+
+    /// Paid out by the authority (`spl_pay*`).
+    pub paid: u64,
+
+    /// Merchant fees retained by the treasury (`pay_merchant`).
+    pub fees: u64,
+
+    /// Refunded through disputes (`resolve_dispute`).
+    pub refunded: u64,
+
+    /// Slot the ledger was opened at.
+    pub opened_slot: u64,
+
+    /// Slot of the last recorded flow.
+    pub last_slot: u64,
+}
+
+/// One treasury ATA movement recorded by a flow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LedgerEntry {
+    Deposit(u64),
+    Withdraw(u64),
+    Pay(u64),
+    Fee(u64),
+    Refund(u64),
+}
+
+impl MintLedger {
+    pub const SEED: &'static [u8] = b"mint_ledger";
+
+    pub const VERSION_1: u8 = 1;
+
+    /// Layout version written by `open_mint_ledger`.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_1;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // mint
+        1 +  // bump
+        1 +  // version
+        8 +  // opening_balance
+        8 +  // deposited
+        8 +  // withdrawn
+        8 +  // paid
+        8 +  // fees
+        8 +  // refunded
+        8 +  // opened_slot
+        8;   // last_slot
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Balance the treasury ATA must hold according to the books.
+    pub fn expected_balance(&self) -> Result<u64> {
+        self.opening_balance
+            .checked_add(self.deposited)
+            .and_then(|v| v.checked_add(self.fees))
+            .and_then(|v| v.checked_sub(self.withdrawn))
+            .and_then(|v| v.checked_sub(self.paid))
+            .and_then(|v| v.checked_sub(self.refunded))
+            .ok_or_else(|| error!(ErrorCode::LedgerOverflow))
+    }
+
+    /// Add one movement to the books.
+    pub fn apply(&mut self, entry: LedgerEntry, slot: u64) -> Result<()> {
+        let (total, amount) = match entry {
+            LedgerEntry::Deposit(amount) => (&mut self.deposited, amount),
+            LedgerEntry::Withdraw(amount) => (&mut self.withdrawn, amount),
+            LedgerEntry::Pay(amount) => (&mut self.paid, amount),
+            LedgerEntry::Fee(amount) => (&mut self.fees, amount),
+            LedgerEntry::Refund(amount) => (&mut self.refunded, amount),
+        };
+        *total = total
+            .checked_add(amount)
+            .ok_or(ErrorCode::LedgerOverflow)?;
+        self.last_slot = slot;
+        Ok(())
+    }
+
+    /// Record a flow in the ledger behind its `mint_ledger` account.
+    ///
+    /// The account address is pinned by seeds in every flow, which always
+    /// takes it so flows cannot bypass the books; this is a no-op while the
+    /// ledger has not been opened.
+    pub fn record(info: &AccountInfo, entry: LedgerEntry) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidMintLedger);
+
+        let mut ledger = {
+            let data = info.try_borrow_data()?;
+            Self::try_deserialize(&mut &data[..])?
+        };
+        ledger.apply(entry, Clock::get()?.slot)?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        ledger.try_serialize(&mut writer)
+    }
+}
@@ -0,0 +1,90 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::*;
+
+/// Per-mint spending limit tiers.
+///
+/// PDA seeds:
+/// ["mint_limits", treasury.key(), mint.key()]
+///
+/// Created on first use by `set_limit_tier`. Every user is assigned one tier
+/// per mint through their `UserLimits` account (tier 0 by default). Until
+/// this account exists, the mint has no limits.
+#[account]
+pub struct MintLimits {
+    /// Treasury these limits belong to.
+    pub treasury: Pubkey,
+
+    /// Mint the amounts are denominated in.
+    pub mint: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+
+    /// Layout version.
+    pub version: u8,
+
+    /// Limit tiers, indexed by `UserLimits.tier`.
+    pub tiers: [LimitTier; MintLimits::MAX_TIERS],
+}
+
+/// One limit tier. Zero values mean "no limit" for that dimension.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LimitTier {
+    /// Rolling window length in seconds (0 = tier unlimited).
+    pub period_secs: i64,
+
+    /// Flows allowed per window.
+    pub max_count: u32,
+
+    /// Raw token units allowed per window.
+    pub max_amount: u64,
+}
+
+impl LimitTier {
+    pub const LEN: usize =
+        8 + // period_secs
+        4 + // max_count
+        8;  // max_amount
+}
+
+impl MintLimits {
+    pub const SEED: &'static [u8] = b"mint_limits";
+
+    pub const VERSION_1: u8 = 1;
+
+    /// Layout version written by every limits-creating flow.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_1;
+
+    pub const MAX_TIERS: usize = 4;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // mint
+        1 +  // bump
+        1 +  // version
+        LimitTier::LEN * Self::MAX_TIERS;
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Read the limits behind a flow's `mint_limits` account.
+    ///
+    /// The account address is pinned by seeds in every flow, which always
+    /// takes it so limits cannot be skipped; this returns `None` while the
+    /// PDA has not been created yet.
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            AnchorErrorCode::AccountOwnedByWrongProgram
+        );
+
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..]).map(Some)
+    }
+}
@@ -1,8 +1,38 @@
+pub mod attestor_config;
+pub mod compliance_config;
+pub mod denylist_entry;
+pub mod dispute;
+pub mod dispute_config;
+pub mod kyc_attestation;
+pub mod kyc_issuer;
+pub mod merchant;
+pub mod mint_ledger;
+pub mod mint_limits;
 pub mod receipt;
+pub mod reference_index;
+pub mod travel_rule_record;
 pub mod treasury;
+pub mod treasury_config;
+pub mod trust_score;
+pub mod user_limits;
 pub mod user_profile;
 
 
+pub use attestor_config::*;
+pub use compliance_config::*;
+pub use denylist_entry::*;
+pub use dispute::*;
+pub use dispute_config::*;
+pub use kyc_attestation::*;
+pub use kyc_issuer::*;
+pub use merchant::*;
+pub use mint_ledger::*;
+pub use mint_limits::*;
 pub use receipt::*;
+pub use reference_index::*;
+pub use travel_rule_record::*;
 pub use treasury::*;
+pub use treasury_config::*;
+pub use trust_score::*;
+pub use user_limits::*;
 pub use user_profile::*;
@@ -1,5 +1,8 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
 use anchor_lang::prelude::*;
 
+use crate::errors::ErrorCode;
+
 /// Canonical on-chain receipt record.
 ///
 /// Important:
@@ -12,11 +15,23 @@ use anchor_lang::prelude::*;
 /// - 1 = deposit
 /// - 2 = withdraw
 /// - 3 = pay
+/// - 4 = dispute resolution (`amount` is the refund, `v2.reference` the
+///   disputed receipt's address)
+/// - 5 = merchant payment (`user` is the payer, `amount` the gross payment
+///   and `fee` the part kept by the treasury)
 ///
 /// Current asset kind values:
 /// - 0 = unknown
 /// - 1 = SOL
 /// - 2 = SPL
+///
+/// Schema versions:
+/// - 1 = original layout (already carrying `ReceiptV2Ext`) with no version byte
+/// - 2 = adds the trailing `version` byte
+///
+/// New fields are appended only, with a version bump. Older accounts are read
+/// through `Receipt::try_deserialize_versioned` and upgraded in place by the
+/// `migrate_receipt` instruction.
 #[account]
 pub struct Receipt {
     /// Counterparty / user wallet associated with this receipt.
@@ -68,17 +83,28 @@ pub struct Receipt {
 
     /// Fixed-size extension payload for future-proof metadata.
     pub v2: ReceiptV2Ext,
+
+    /// Receipt schema version (see type-level docs).
+    pub version: u8,
 }
 
 impl Receipt {
     pub const DIR_DEPOSIT: u8 = 1;
     pub const DIR_WITHDRAW: u8 = 2;
     pub const DIR_PAY: u8 = 3;
+    pub const DIR_DISPUTE: u8 = 4;
+    pub const DIR_MERCHANT_PAY: u8 = 5;
 
     pub const ASSET_UNKNOWN: u8 = 0;
     pub const ASSET_SOL: u8 = 1;
     pub const ASSET_SPL: u8 = 2;
 
+    pub const VERSION_1: u8 = 1;
+    pub const VERSION_2: u8 = 2;
+
+    /// Schema version written by every receipt-creating flow.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_2;
+
     /// Shared receipt seed prefix.
     ///
     /// Warning:
@@ -115,8 +141,8 @@ impl Receipt {
         ]
     }
 
-    /// Account data length excluding Anchor's 8-byte discriminator.
-    pub const LEN: usize =
+    /// Schema version 1 data length excluding Anchor's 8-byte discriminator.
+    pub const LEN_V1: usize =
         32 + // user
         1 +  // direction
         1 +  // asset_kind
@@ -130,13 +156,94 @@ impl Receipt {
         1 +  // bump
         ReceiptV2Ext::LEN;
 
+    /// Schema version 1 account space including discriminator.
+    pub const SPACE_V1: usize = 8 + Self::LEN_V1;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        Self::LEN_V1 +
+        1; // version
+
     /// Full Anchor account space including discriminator.
     pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Decode receipt account data of any historical schema version.
+    ///
+    /// The returned value uses the current in-memory layout; `version` reports
+    /// the schema version the bytes were stored in, so callers can tell
+    /// whether the account still needs `migrate_receipt`.
+    pub fn try_deserialize_versioned(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= 8, AnchorErrorCode::AccountDiscriminatorNotFound);
+        require!(
+            data[..8] == *Self::DISCRIMINATOR,
+            AnchorErrorCode::AccountDiscriminatorMismatch
+        );
+
+        let mut body = &data[8..];
+
+        if data.len() == Self::SPACE_V1 {
+            let v1 = ReceiptV1::deserialize(&mut body)
+                .map_err(|_| AnchorErrorCode::AccountDidNotDeserialize)?;
+            return Ok(v1.into());
+        }
+
+        require!(data.len() >= Self::SPACE, AnchorErrorCode::AccountDidNotDeserialize);
+
+        let receipt = Self::deserialize(&mut body)
+            .map_err(|_| AnchorErrorCode::AccountDidNotDeserialize)?;
+
+        require!(
+            receipt.version >= Self::VERSION_2 && receipt.version <= Self::CURRENT_VERSION,
+            ErrorCode::UnsupportedReceiptVersion
+        );
+
+        Ok(receipt)
+    }
+}
+
+/// Schema version 1 receipt layout (no version byte).
+///
+/// Read-only: kept so legacy accounts can be decoded and migrated.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ReceiptV1 {
+    pub user: Pubkey,
+    pub direction: u8,
+    pub asset_kind: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub pre_balance: u64,
+    pub post_balance: u64,
+    pub ts: i64,
+    pub tx_count: u64,
+    pub bump: u8,
+    pub v2: ReceiptV2Ext,
+}
+
+impl From<ReceiptV1> for Receipt {
+    fn from(v1: ReceiptV1) -> Self {
+        Self {
+            user: v1.user,
+            direction: v1.direction,
+            asset_kind: v1.asset_kind,
+            mint: v1.mint,
+            amount: v1.amount,
+            fee: v1.fee,
+            pre_balance: v1.pre_balance,
+            post_balance: v1.post_balance,
+            ts: v1.ts,
+            tx_count: v1.tx_count,
+            bump: v1.bump,
+            v2: v1.v2,
+            version: Receipt::VERSION_1,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct ReceiptV2Ext {
-    /// Reserved flags for future expansion.
+    /// Metadata flags in the low byte (`FLAG_*`); risk flags raised by the
+    /// creating flow in the high byte (see `crate::risk`).
     pub flags: u16,
 
     /// Optional explicit SPL mint mirror.
@@ -158,6 +265,13 @@ impl ReceiptV2Ext {
     pub const FLAG_HAS_REFERENCE: u16 = 1 << 0;
     pub const FLAG_HAS_MEMO: u16 = 1 << 1;
 
+    /// A consumed `TravelRuleRecord` exists at
+    /// ["travel_rule", treasury, receipt.key()].
+    pub const FLAG_HAS_TRAVEL_RULE: u16 = 1 << 2;
+
+    /// Bit offset of the risk flags within `flags`.
+    pub const RISK_FLAGS_SHIFT: u32 = 8;
+
     pub const MAX_MEMO_LEN: usize = 64;
 
     pub const LEN: usize =
@@ -215,6 +329,16 @@ impl ReceiptV2Ext {
 
         ext
     }
+
+    /// Store the flow's risk flags in the high byte of `flags`.
+    pub fn set_risk_flags(&mut self, risk_flags: u32) {
+        self.flags = (self.flags & 0x00ff) | (((risk_flags & 0xff) as u16) << Self::RISK_FLAGS_SHIFT);
+    }
+
+    /// Risk flags recorded by the creating flow.
+    pub fn risk_flags(&self) -> u32 {
+        u32::from(self.flags >> Self::RISK_FLAGS_SHIFT)
+    }
 }
 
 impl Default for ReceiptV2Ext {
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+/// Reference-keyed idempotency index for SPL pay.
+///
+/// One account per `(treasury, reference)` pair, created atomically with the
+/// pay receipt it points at. Because the PDA is `init`-only, a second pay
+/// carrying the same reference fails deterministically instead of paying out
+/// twice.
+///
+/// PDA seeds:
+/// ["reference", treasury.key(), reference]
+///
+/// Clients can resolve a receipt from a reference alone by deriving this PDA
+/// and reading `receipt`.
+#[account]
+pub struct ReferenceIndex {
+    /// Treasury the reference was consumed against.
+    pub treasury: Pubkey,
+
+    /// The 32-byte external reference (invoice/order id).
+    pub reference: [u8; 32],
+
+    /// Receipt PDA written by the pay that consumed this reference.
+    pub receipt: Pubkey,
+
+    /// Treasury pay_count snapshot (BEFORE increment) of that pay.
+    pub pay_count: u64,
+
+    /// Unix timestamp at index creation.
+    pub ts: i64,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl ReferenceIndex {
+    pub const SEED: &'static [u8] = b"reference";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // reference
+        32 + // receipt
+        8 +  // pay_count
+        8 +  // ts
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+}
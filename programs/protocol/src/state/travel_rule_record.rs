@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+/// Travel-rule data for one withdrawal or payout.
+///
+/// PDA seeds:
+/// ["travel_rule", treasury.key(), record_id]
+///
+/// Created by the treasury authority ahead of the transfer and consumed by
+/// it. For flows that write a receipt, `record_id` must be the receipt
+/// address, so a receipt with `ReceiptV2Ext::FLAG_HAS_TRAVEL_RULE` locates its
+/// record by seeds alone; receipt-less `spl_withdraw` accepts any id.
+///
+/// Personal data never goes on-chain: only a hash of the encrypted PII
+/// package held off-chain is stored.
+#[account]
+pub struct TravelRuleRecord {
+    /// Treasury this record belongs to.
+    pub treasury: Pubkey,
+
+    /// Client-chosen id (the receipt address for receipt flows).
+    pub record_id: [u8; 32],
+
+    /// Wallet receiving the transfer.
+    pub beneficiary: Pubkey,
+
+    /// Mint of the transfer.
+    pub mint: Pubkey,
+
+    /// Exact transfer amount (raw units).
+    pub amount: u64,
+
+    /// Originating VASP identifier (e.g. zero-padded LEI or hash of a DID).
+    pub originator_vasp: [u8; 32],
+
+    /// Beneficiary VASP identifier, same encoding as `originator_vasp`.
+    pub beneficiary_vasp: [u8; 32],
+
+    /// SHA-256 of the encrypted originator/beneficiary PII held off-chain.
+    pub pii_hash: [u8; 32],
+
+    /// Unix timestamp the record was created.
+    pub created_at: i64,
+
+    /// Unix timestamp the transfer consumed the record (0 = pending).
+    pub consumed_at: i64,
+
+    /// Receipt of the consuming transfer (`Pubkey::default()` while pending
+    /// and for receipt-less withdrawals).
+    pub receipt: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+}
+
+impl TravelRuleRecord {
+    pub const SEED: &'static [u8] = b"travel_rule";
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // record_id
+        32 + // beneficiary
+        32 + // mint
+        8 +  // amount
+        32 + // originator_vasp
+        32 + // beneficiary_vasp
+        32 + // pii_hash
+        8 +  // created_at
+        8 +  // consumed_at
+        32 + // receipt
+        1;   // bump
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Whether a transfer already consumed this record.
+    pub fn is_consumed(&self) -> bool {
+        self.consumed_at != 0
+    }
+}
@@ -33,6 +33,9 @@ pub struct Treasury {
 }
 
 impl Treasury {
+    /// PDA seed of the single canonical treasury: ["treasury"].
+    pub const SEED: &'static [u8] = b"treasury";
+
     /// Full Anchor account space including discriminator.
     ///
     /// Layout:
@@ -0,0 +1,216 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::*;
+
+use crate::state::{Merchant, MintLimits, TrustScore};
+
+/// Treasury-wide protocol configuration.
+///
+/// PDA seeds:
+/// ["treasury_config", treasury.key()]
+///
+/// Created on first use by the treasury authority through one of the
+/// `set_*_config` instructions. Value-moving flows always take the PDA
+/// address (so clients cannot skip enforced settings by omitting it) and read
+/// it through `TreasuryConfig::load`; until it is created, every configurable
+/// feature behaves as disabled.
+///
+/// Layout evolution:
+/// New settings are carved out of `reserved` (shrinking it by the same number
+/// of bytes), so `SPACE` never changes and existing accounts never need a
+/// realloc. Bump `CURRENT_VERSION` whenever that happens.
+#[account]
+pub struct TreasuryConfig {
+    /// Treasury this config belongs to.
+    pub treasury: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+
+    /// Config layout version.
+    pub version: u8,
+
+    /// XP credited per deposit (0 = no XP for this flow).
+    pub xp_per_deposit: u32,
+
+    /// XP credited per withdrawal (0 = no XP for this flow).
+    pub xp_per_withdraw: u32,
+
+    /// XP credited to the recipient per treasury payout (0 = no XP for this flow).
+    pub xp_per_pay: u32,
+
+    /// Minimum flow amount (raw units) that earns XP.
+    pub xp_min_amount: u64,
+
+    /// Maximum XP a profile may earn per Solana epoch (0 = uncapped).
+    pub xp_epoch_cap: u64,
+
+    /// Risk rules (see `crate::risk`) that are evaluated; all are off by
+    /// default.
+    pub risk_rules_mask: u32,
+
+    /// Risk flags that reject a flow instead of only being reported
+    /// (a subset of `risk_rules_mask`).
+    pub risk_blocking_mask: u32,
+
+    /// Large-amount threshold (raw units) for mints without an entry in
+    /// `risk_mint_thresholds` (0 = rule off).
+    pub risk_default_threshold: u64,
+
+    /// Per-mint large-amount thresholds; unused entries have a default mint.
+    pub risk_mint_thresholds: [MintThreshold; TreasuryConfig::MAX_RISK_MINTS],
+
+    /// A profile whose first recorded activity is younger than this many
+    /// seconds counts as new (a profile with no activity always does).
+    pub risk_new_profile_secs: i64,
+
+    /// Length of the velocity window in slots (0 = rule off).
+    pub risk_velocity_window_slots: u64,
+
+    /// Flows allowed per velocity window before the velocity flag is raised.
+    pub risk_velocity_max_count: u32,
+
+    /// Amounts that are a whole multiple of this many tokens (scaled by the
+    /// mint's decimals) count as round numbers (0 = rule off).
+    pub risk_round_whole_units: u64,
+
+    /// Minimum `KycAttestation` level required of the receiving wallet for
+    /// withdrawals and payouts above `kyc_threshold` (0 = no KYC required).
+    pub kyc_min_level: u8,
+
+    /// Amount (raw units) above which `kyc_min_level` applies.
+    pub kyc_threshold: u64,
+
+    /// Amount (raw units) above which withdrawals and payouts require a
+    /// `TravelRuleRecord` (0 = never required).
+    pub travel_rule_threshold: u64,
+
+    /// Minimum `TrustScore` that lifts a wallet to each spending limit tier
+    /// (0 = tier not reachable by score; see `crate::trust`).
+    pub trust_tier_scores: [u16; MintLimits::MAX_TIERS],
+
+    /// Fee in basis points charged on merchant payments, per merchant fee
+    /// tier (see `Merchant.fee_tier`).
+    pub merchant_fee_bps: [u16; Merchant::MAX_FEE_TIERS],
+
+    /// Minimum flow amount (raw units) that earns trust points; smaller
+    /// flows still count as successful flows.
+    pub trust_min_amount: u64,
+
+    /// Zeroed space for future settings (see type-level docs).
+    pub reserved: [u8; TreasuryConfig::RESERVED_LEN],
+}
+
+impl TreasuryConfig {
+    pub const SEED: &'static [u8] = b"treasury_config";
+
+    pub const VERSION_1: u8 = 1;
+    pub const VERSION_2: u8 = 2;
+    pub const VERSION_3: u8 = 3;
+    pub const VERSION_4: u8 = 4;
+    pub const VERSION_5: u8 = 5;
+    pub const VERSION_6: u8 = 6;
+    pub const VERSION_7: u8 = 7;
+
+    /// Layout version written by every config-creating flow.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_7;
+
+    pub const MAX_RISK_MINTS: usize = 4;
+
+    pub const RESERVED_LEN: usize = 11;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        1 +  // bump
+        1 +  // version
+        4 +  // xp_per_deposit
+        4 +  // xp_per_withdraw
+        4 +  // xp_per_pay
+        8 +  // xp_min_amount
+        8 +  // xp_epoch_cap
+        4 +  // risk_rules_mask
+        4 +  // risk_blocking_mask
+        8 +  // risk_default_threshold
+        MintThreshold::LEN * Self::MAX_RISK_MINTS +
+        8 +  // risk_new_profile_secs
+        8 +  // risk_velocity_window_slots
+        4 +  // risk_velocity_max_count
+        8 +  // risk_round_whole_units
+        1 +  // kyc_min_level
+        8 +  // kyc_threshold
+        8 +  // travel_rule_threshold
+        2 * MintLimits::MAX_TIERS + // trust_tier_scores
+        2 * Merchant::MAX_FEE_TIERS + // merchant_fee_bps
+        8 +  // trust_min_amount
+        Self::RESERVED_LEN;
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Read the config behind a flow's `treasury_config` account.
+    ///
+    /// The account address is pinned by seeds in every flow, which always
+    /// takes it so enforced settings cannot be skipped; this returns `None`
+    /// while the PDA has not been created yet.
+    pub fn load(info: &AccountInfo) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            AnchorErrorCode::AccountOwnedByWrongProgram
+        );
+
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..]).map(Some)
+    }
+
+    /// Whether `scores` is a valid `trust_tier_scores` table: tier 0 needs no
+    /// score, and reachable tiers ask for non-decreasing scores within range.
+    pub fn is_valid_trust_tiers(scores: &[u16; MintLimits::MAX_TIERS]) -> bool {
+        if scores[0] != 0 {
+            return false;
+        }
+
+        let mut prev = 0u16;
+        for &min in scores.iter().filter(|min| **min > 0) {
+            if min > TrustScore::MAX_SCORE || min < prev {
+                return false;
+            }
+            prev = min;
+        }
+        true
+    }
+
+    /// Fee (basis points) charged to merchants in `fee_tier`.
+    pub fn merchant_fee_bps_for(&self, fee_tier: u8) -> u16 {
+        self.merchant_fee_bps
+            .get(fee_tier as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Large-amount threshold that applies to `mint` (0 = rule off).
+    pub fn risk_threshold_for(&self, mint: &Pubkey) -> u64 {
+        self.risk_mint_thresholds
+            .iter()
+            .find(|t| t.mint == *mint)
+            .map_or(self.risk_default_threshold, |t| t.threshold)
+    }
+}
+
+/// Large-amount risk threshold for one mint (raw token units).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintThreshold {
+    /// `Pubkey::default()` marks an unused entry.
+    pub mint: Pubkey,
+    pub threshold: u64,
+}
+
+impl MintThreshold {
+    pub const LEN: usize =
+        32 + // mint
+        8;   // threshold
+}
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+
+/// Zephyon Trust Score (ZTS) of one participant.
+///
+/// PDA seeds:
+/// ["trust_score", wallet.key()]
+///
+/// Not tied to a treasury, so the score follows the wallet everywhere the
+/// protocol is used and any program can read it. Created by the wallet with
+/// `init_trust_score`; updated deterministically by successful receipted
+/// flows, dispute outcomes and time (see `TrustScore::score_at`):
+///
+/// score = activity_points + age points - dispute_penalty, clamped to
+/// `0..=MAX_SCORE`, where
+/// - each receipted flow of at least `TreasuryConfig.trust_min_amount` adds
+///   `FLOW_POINTS` to `activity_points` (capped at `MAX_ACTIVITY_POINTS`),
+///   for at most `MAX_CREDITED_FLOWS_PER_WINDOW` flows per
+///   `CREDIT_WINDOW_SECS`, so dust flows cannot buy limit tiers
+/// - age earns `AGE_POINTS_PER_DAY` since `created_at`
+///   (capped at `MAX_AGE_POINTS`)
+/// - each lost dispute adds `DISPUTE_LOST_PENALTY` to `dispute_penalty`
+/// - `activity_points` and `dispute_penalty` halve every
+///   `DECAY_HALF_LIFE_SECS`, so trust has to be maintained and old
+///   penalties fade
+///
+/// `score` is the value as of `updated_at`; readers wanting the current value
+/// call `score_at(now)` or `refresh_trust_score` first.
+#[account]
+pub struct TrustScore {
+    /// Wallet this score belongs to.
+    pub wallet: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+
+    /// Layout version.
+    pub version: u8,
+
+    /// Score in basis points of the 0–100 ZTS scale (0..=`MAX_SCORE`).
+    pub score: u16,
+
+    /// Decaying points earned by receipted flows.
+    pub activity_points: u32,
+
+    /// Decaying points lost to dispute outcomes.
+    pub dispute_penalty: u32,
+
+    /// Lifetime successful receipted flows.
+    pub successful_flows: u64,
+
+    /// Disputes resolved in this wallet's favour.
+    pub disputes_won: u32,
+
+    /// Disputes resolved against this wallet.
+    pub disputes_lost: u32,
+
+    /// Unix timestamp the account was created (account age starts here).
+    pub created_at: i64,
+
+    /// Unix timestamp up to which decay has been applied.
+    pub decayed_at: i64,
+
+    /// Unix timestamp `score` was last recomputed.
+    pub updated_at: i64,
+
+    /// Unix timestamp the current credit window started.
+    pub credit_window_start: i64,
+
+    /// Flows credited with points in the current credit window.
+    pub credit_window_flows: u32,
+
+    /// Zeroed space for future inputs.
+    pub reserved: [u8; TrustScore::RESERVED_LEN],
+}
+
+impl TrustScore {
+    pub const SEED: &'static [u8] = b"trust_score";
+
+    pub const VERSION_1: u8 = 1;
+    /// Adds the credit window; v1 accounts read it as zeroed, i.e. a window
+    /// that has already elapsed.
+    pub const VERSION_2: u8 = 2;
+
+    /// Layout version written by `init_trust_score`.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_2;
+
+    pub const RESERVED_LEN: usize = 20;
+
+    /// Top of the score scale (100.00).
+    pub const MAX_SCORE: u16 = 10_000;
+
+    /// Points per successful receipted flow.
+    pub const FLOW_POINTS: u32 = 50;
+
+    pub const MAX_ACTIVITY_POINTS: u32 = 8_000;
+
+    /// Length of the window that caps credited flows (1 day).
+    pub const CREDIT_WINDOW_SECS: i64 = 86_400;
+
+    /// Flows earning `FLOW_POINTS` per credit window; later flows in the
+    /// window still count toward `successful_flows`.
+    pub const MAX_CREDITED_FLOWS_PER_WINDOW: u32 = 10;
+
+    pub const AGE_POINTS_PER_DAY: u64 = 10;
+
+    pub const MAX_AGE_POINTS: u64 = 2_000;
+
+    /// Penalty per dispute resolved against the wallet.
+    pub const DISPUTE_LOST_PENALTY: u32 = 1_500;
+
+    pub const MAX_DISPUTE_PENALTY: u32 = 20_000;
+
+    /// ~180 days.
+    pub const DECAY_HALF_LIFE_SECS: i64 = 180 * 86_400;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // wallet
+        1 +  // bump
+        1 +  // version
+        2 +  // score
+        4 +  // activity_points
+        4 +  // dispute_penalty
+        8 +  // successful_flows
+        4 +  // disputes_won
+        4 +  // disputes_lost
+        8 +  // created_at
+        8 +  // decayed_at
+        8 +  // updated_at
+        8 +  // credit_window_start
+        4 +  // credit_window_flows
+        Self::RESERVED_LEN;
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    pub fn initialize(&mut self, wallet: Pubkey, bump: u8, now: i64) {
+        self.wallet = wallet;
+        self.bump = bump;
+        self.version = Self::CURRENT_VERSION;
+        self.score = 0;
+        self.activity_points = 0;
+        self.dispute_penalty = 0;
+        self.successful_flows = 0;
+        self.disputes_won = 0;
+        self.disputes_lost = 0;
+        self.created_at = now;
+        self.decayed_at = now;
+        self.updated_at = now;
+        self.credit_window_start = now;
+        self.credit_window_flows = 0;
+        self.reserved = [0u8; Self::RESERVED_LEN];
+    }
+
+    /// Score as of `now`, without mutating the account.
+    pub fn score_at(&self, now: i64) -> u16 {
+        let mut copy = self.clone();
+        copy.refresh(now);
+        copy.score
+    }
+
+    /// Apply decay up to `now` and recompute `score`.
+    pub fn refresh(&mut self, now: i64) {
+        let periods = now.saturating_sub(self.decayed_at) / Self::DECAY_HALF_LIFE_SECS;
+        if periods > 0 {
+            self.activity_points = halve(self.activity_points, periods);
+            self.dispute_penalty = halve(self.dispute_penalty, periods);
+            // Whole periods only: the remainder keeps counting toward the next halving.
+            self.decayed_at = self
+                .decayed_at
+                .saturating_add(periods.saturating_mul(Self::DECAY_HALF_LIFE_SECS));
+        }
+
+        let age_days = (now.saturating_sub(self.created_at).max(0) / 86_400) as u64;
+        let age_points = age_days
+            .saturating_mul(Self::AGE_POINTS_PER_DAY)
+            .min(Self::MAX_AGE_POINTS);
+
+        let positive = u64::from(self.activity_points) + age_points;
+        let score = positive
+            .saturating_sub(u64::from(self.dispute_penalty))
+            .min(u64::from(Self::MAX_SCORE));
+
+        self.score = score as u16;
+        self.updated_at = now;
+    }
+
+    /// Record one successful receipted flow.
+    ///
+    /// `eligible` is whether the flow's amount may earn points at all; an
+    /// eligible flow earns `FLOW_POINTS` unless the credit window is full.
+    pub fn record_flow(&mut self, eligible: bool, now: i64) {
+        self.refresh(now);
+        self.successful_flows = self.successful_flows.saturating_add(1);
+
+        if now.saturating_sub(self.credit_window_start) >= Self::CREDIT_WINDOW_SECS {
+            self.credit_window_start = now;
+            self.credit_window_flows = 0;
+        }
+
+        if eligible && self.credit_window_flows < Self::MAX_CREDITED_FLOWS_PER_WINDOW {
+            self.credit_window_flows += 1;
+            self.activity_points = self
+                .activity_points
+                .saturating_add(Self::FLOW_POINTS)
+                .min(Self::MAX_ACTIVITY_POINTS);
+        }
+        self.refresh(now);
+    }
+
+    /// Record a resolved dispute; `lost` means it went against this wallet.
+    pub fn record_dispute(&mut self, lost: bool, now: i64) {
+        self.refresh(now);
+        if lost {
+            self.disputes_lost = self.disputes_lost.saturating_add(1);
+            self.dispute_penalty = self
+                .dispute_penalty
+                .saturating_add(Self::DISPUTE_LOST_PENALTY)
+                .min(Self::MAX_DISPUTE_PENALTY);
+        } else {
+            self.disputes_won = self.disputes_won.saturating_add(1);
+        }
+        self.refresh(now);
+    }
+}
+
+/// `points` halved `periods` times.
+fn halve(points: u32, periods: i64) -> u32 {
+    if periods >= 32 {
+        0
+    } else {
+        points >> periods
+    }
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::LimitTier;
+
+/// A user's limit tier and rolling usage window for one mint.
+///
+/// PDA seeds:
+/// ["user_limits", treasury.key(), user.key(), mint.key()]
+///
+/// Created by `set_user_limit_tier`, or by the first value-moving flow for
+/// the user and mint once `MintLimits` exist for that mint.
+///
+/// Usage is tracked with a sliding window approximation over two fixed
+/// buckets of `period_secs`: the previous bucket is weighted by how much of
+/// it still overlaps the window ending now.
+#[account]
+#[derive(Default)]
+pub struct UserLimits {
+    /// Treasury these limits belong to.
+    pub treasury: Pubkey,
+
+    /// Wallet the limits apply to.
+    pub user: Pubkey,
+
+    /// Mint the amounts are denominated in.
+    pub mint: Pubkey,
+
+    /// PDA bump.
+    pub bump: u8,
+
+    /// Layout version.
+    pub version: u8,
+
+    /// Index into `MintLimits.tiers`.
+    pub tier: u8,
+
+    /// Window length the buckets were filled with; a changed tier period
+    /// resets the usage.
+    pub window_period_secs: i64,
+
+    /// Start of the current bucket (a multiple of `window_period_secs`).
+    pub bucket_start_ts: i64,
+
+    /// Flows recorded in the current bucket.
+    pub curr_count: u32,
+
+    /// Amount recorded in the current bucket.
+    pub curr_amount: u64,
+
+    /// Flows recorded in the previous bucket.
+    pub prev_count: u32,
+
+    /// Amount recorded in the previous bucket.
+    pub prev_amount: u64,
+}
+
+impl UserLimits {
+    pub const SEED: &'static [u8] = b"user_limits";
+
+    pub const VERSION_1: u8 = 1;
+
+    /// Layout version written by every limits-creating flow.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_1;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        32 + // treasury
+        32 + // user
+        32 + // mint
+        1 +  // bump
+        1 +  // version
+        1 +  // tier
+        8 +  // window_period_secs
+        8 +  // bucket_start_ts
+        4 +  // curr_count
+        8 +  // curr_amount
+        4 +  // prev_count
+        8;   // prev_amount
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Reset to a fresh tier-0 account.
+    pub fn initialize(&mut self, treasury: Pubkey, user: Pubkey, mint: Pubkey, bump: u8) {
+        self.treasury = treasury;
+        self.user = user;
+        self.mint = mint;
+        self.bump = bump;
+        self.version = Self::CURRENT_VERSION;
+        self.tier = 0;
+        self.window_period_secs = 0;
+        self.bucket_start_ts = 0;
+        self.curr_count = 0;
+        self.curr_amount = 0;
+        self.prev_count = 0;
+        self.prev_amount = 0;
+    }
+
+    /// Check one flow of `amount` against `tier` and record it.
+    ///
+    /// Fails with `LimitCountExceeded` / `LimitAmountExceeded` when the flow
+    /// would push the windowed usage past the tier's limits.
+    pub fn check_and_record(&mut self, tier: &LimitTier, amount: u64, now: i64) -> Result<()> {
+        let period = tier.period_secs;
+        if period <= 0 {
+            return Ok(());
+        }
+
+        self.roll(period, now);
+
+        // Weight of the previous bucket still inside the window, in
+        // 1/period units.
+        let overlap = period - (now - self.bucket_start_ts);
+        let weighted = |prev: u64| (u128::from(prev) * overlap as u128).div_ceil(period as u128);
+
+        let count = weighted(u64::from(self.prev_count)) + u128::from(self.curr_count) + 1;
+        if tier.max_count > 0 {
+            require!(
+                count <= u128::from(tier.max_count),
+                ErrorCode::LimitCountExceeded
+            );
+        }
+
+        let total = weighted(self.prev_amount) + u128::from(self.curr_amount) + u128::from(amount);
+        if tier.max_amount > 0 {
+            require!(
+                total <= u128::from(tier.max_amount),
+                ErrorCode::LimitAmountExceeded
+            );
+        }
+
+        self.curr_count = self
+            .curr_count
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+        self.curr_amount = self
+            .curr_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::CounterOverflow)?;
+
+        Ok(())
+    }
+
+    /// Advance the buckets so the current one contains `now`.
+    fn roll(&mut self, period: i64, now: i64) {
+        let bucket = now - now.rem_euclid(period);
+
+        if self.window_period_secs != period {
+            self.window_period_secs = period;
+            self.prev_count = 0;
+            self.prev_amount = 0;
+            self.curr_count = 0;
+            self.curr_amount = 0;
+        } else if bucket == self.bucket_start_ts {
+            return;
+        } else if bucket == self.bucket_start_ts.saturating_add(period) {
+            self.prev_count = self.curr_count;
+            self.prev_amount = self.curr_amount;
+            self.curr_count = 0;
+            self.curr_amount = 0;
+        } else {
+            self.prev_count = 0;
+            self.prev_amount = 0;
+            self.curr_count = 0;
+            self.curr_amount = 0;
+        }
+
+        self.bucket_start_ts = bucket;
+    }
+}
@@ -1,24 +1,476 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+use crate::errors::ErrorCode;
+use crate::state::Receipt;
 
 /// PDA that tracks a user's activity with the protocol (deposit/withdraw, SOL/SPL).
 /// One per user wallet.
+///
+/// PDA seeds:
+/// ["user_profile", user.key()]
+///
+/// Schema versions:
+/// - 1 = authority / tx_count / bump only (no version byte)
+/// - 2 = adds version, per-direction counts, activity timestamps and volume
+/// - 3 = adds XP accounting (`xp`, `xp_epoch`, `xp_epoch_earned`)
+/// - 4 = adds the risk velocity window and `pays_received`
+///
+/// Deposits, withdrawals and merchant payments are recorded (and XP
+/// credited) whenever the profile is passed to a value-moving flow;
+/// `spl_withdraw_with_receipt` always requires it. Payouts credit XP and count `pays_received` on the
+/// recipient's profile only.
+/// Older profiles are read through `UserProfile::try_deserialize_versioned`
+/// and upgraded in place by `update_user_profile`.
 #[account]
 pub struct UserProfile {
     /// Wallet this profile belongs to
     pub authority: Pubkey,
 
     /// Total number of protocol transactions performed by this user
-    /// (deposit + withdraw, SOL + SPL)
+    /// (deposit + withdraw + merchant payment, SOL + SPL)
+    ///
+    /// Also the withdraw receipt seed index, so it must never go backwards.
     pub tx_count: u64,
 
     /// PDA bump
     pub bump: u8,
+
+    /// Profile schema version (see type-level docs)
+    pub version: u8,
+
+    /// Recorded deposits
+    pub deposit_count: u64,
+
+    /// Recorded withdrawals
+    pub withdraw_count: u64,
+
+    /// Unix timestamp of the first recorded activity (0 = none yet)
+    pub first_activity_ts: i64,
+
+    /// Unix timestamp of the most recent recorded activity (0 = none yet)
+    pub last_activity_ts: i64,
+
+    /// Lifetime volume for the first `MAX_TRACKED_MINTS` mints used
+    pub mint_volumes: [MintVolume; UserProfile::MAX_TRACKED_MINTS],
+
+    /// Rolling hash over every recorded activity, including mints beyond the
+    /// tracked slots:
+    /// sha256(prev || mint || direction || amount_le)
+    ///
+    /// Lets indexers prove their per-mint totals against on-chain state.
+    pub volume_digest: [u8; 32],
+
+    /// Lifetime XP credited by value-moving flows (see `crate::xp`)
+    pub xp: u64,
+
+    /// Solana epoch `xp_epoch_earned` refers to
+    pub xp_epoch: u64,
+
+    /// XP earned during `xp_epoch`, bounded by `TreasuryConfig.xp_epoch_cap`
+    pub xp_epoch_earned: u64,
+
+    /// First slot of the current risk velocity window (see `crate::risk`)
+    pub velocity_window_start_slot: u64,
+
+    /// Deposits/withdrawals evaluated within the current velocity window
+    pub velocity_window_count: u32,
+
+    /// Treasury payouts received while this profile was supplied
+    pub pays_received: u64,
+}
+
+/// Lifetime volume for one mint (raw token units).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintVolume {
+    /// `Pubkey::default()` marks an unused slot.
+    pub mint: Pubkey,
+    pub deposited: u64,
+    pub withdrawn: u64,
+}
+
+impl MintVolume {
+    pub const LEN: usize =
+        32 + // mint
+        8 +  // deposited
+        8;   // withdrawn
 }
 
 impl UserProfile {
-    /// Total bytes for account allocation (INCLUDING discriminator)
-    pub const LEN: usize = 8  // discriminator
-        + 32 // authority
-        + 8  // tx_count
-        + 1; // bump
+    pub const SEED: &'static [u8] = b"user_profile";
+
+    pub const VERSION_1: u8 = 1;
+    pub const VERSION_2: u8 = 2;
+    pub const VERSION_3: u8 = 3;
+    pub const VERSION_4: u8 = 4;
+
+    /// Schema version written by every profile-creating flow.
+    pub const CURRENT_VERSION: u8 = Self::VERSION_4;
+
+    pub const MAX_TRACKED_MINTS: usize = 4;
+
+    /// Account space historically allocated for schema version 1 profiles
+    /// (`8 + 49`; the trailing 8 bytes were never used).
+    pub const SPACE_V1: usize = 8 + 8 + 32 + 8 + 1;
+
+    /// Schema version 2 data length excluding Anchor's 8-byte discriminator.
+    pub const LEN_V2: usize =
+        32 + // authority
+        8 +  // tx_count
+        1 +  // bump
+        1 +  // version
+        8 +  // deposit_count
+        8 +  // withdraw_count
+        8 +  // first_activity_ts
+        8 +  // last_activity_ts
+        MintVolume::LEN * Self::MAX_TRACKED_MINTS +
+        32;  // volume_digest
+
+    /// Schema version 2 account space including discriminator.
+    pub const SPACE_V2: usize = 8 + Self::LEN_V2;
+
+    /// Schema version 3 data length excluding Anchor's 8-byte discriminator.
+    pub const LEN_V3: usize =
+        Self::LEN_V2 +
+        8 + // xp
+        8 + // xp_epoch
+        8;  // xp_epoch_earned
+
+    /// Schema version 3 account space including discriminator.
+    pub const SPACE_V3: usize = 8 + Self::LEN_V3;
+
+    /// Account data length excluding Anchor's 8-byte discriminator.
+    pub const LEN: usize =
+        Self::LEN_V3 +
+        8 + // velocity_window_start_slot
+        4 + // velocity_window_count
+        8;  // pays_received
+
+    /// Full Anchor account space including discriminator.
+    pub const SPACE: usize = 8 + Self::LEN;
+
+    /// Reset to a fresh current-version profile.
+    pub fn initialize(&mut self, authority: Pubkey, bump: u8) {
+        self.authority = authority;
+        self.tx_count = 0;
+        self.bump = bump;
+        self.version = Self::CURRENT_VERSION;
+        self.deposit_count = 0;
+        self.withdraw_count = 0;
+        self.first_activity_ts = 0;
+        self.last_activity_ts = 0;
+        self.mint_volumes = Default::default();
+        self.volume_digest = [0u8; 32];
+        self.xp = 0;
+        self.xp_epoch = 0;
+        self.xp_epoch_earned = 0;
+        self.velocity_window_start_slot = 0;
+        self.velocity_window_count = 0;
+        self.pays_received = 0;
+    }
+
+    /// Whether this profile counts as new for risk evaluation.
+    ///
+    /// A profile with no recorded activity is always new; otherwise it is new
+    /// while its first activity is younger than `new_profile_secs`.
+    pub fn is_new(&self, now: i64, new_profile_secs: i64) -> bool {
+        self.first_activity_ts == 0
+            || now.saturating_sub(self.first_activity_ts) < new_profile_secs
+    }
+
+    /// Count one flow in the velocity window of `window_slots` slots.
+    ///
+    /// Starts a new window when the current one has expired. Returns the
+    /// number of flows in the window including this one.
+    pub fn note_velocity(&mut self, slot: u64, window_slots: u64) -> Result<u32> {
+        if self.velocity_window_count == 0
+            || slot.saturating_sub(self.velocity_window_start_slot) >= window_slots
+        {
+            self.velocity_window_start_slot = slot;
+            self.velocity_window_count = 0;
+        }
+
+        self.velocity_window_count = self
+            .velocity_window_count
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+
+        Ok(self.velocity_window_count)
+    }
+
+    /// Record one treasury payout received by this profile's wallet.
+    pub fn record_pay_received(&mut self) -> Result<()> {
+        self.pays_received = self
+            .pays_received
+            .checked_add(1)
+            .ok_or(ErrorCode::CounterOverflow)?;
+        Ok(())
+    }
+
+    /// Credit up to `base` XP for one flow, honoring anti-farming rules.
+    ///
+    /// - flows below `min_amount` earn nothing
+    /// - per-epoch earnings are capped at `epoch_cap` (0 = uncapped); the
+    ///   window resets when `epoch` differs from `xp_epoch`
+    ///
+    /// Returns the XP actually credited.
+    pub fn credit_xp(
+        &mut self,
+        base: u32,
+        amount: u64,
+        min_amount: u64,
+        epoch_cap: u64,
+        epoch: u64,
+    ) -> Result<u32> {
+        if base == 0 || amount < min_amount {
+            return Ok(0);
+        }
+
+        if self.xp_epoch != epoch {
+            self.xp_epoch = epoch;
+            self.xp_epoch_earned = 0;
+        }
+
+        let mut credit = u64::from(base);
+        if epoch_cap > 0 {
+            credit = credit.min(epoch_cap.saturating_sub(self.xp_epoch_earned));
+        }
+
+        self.xp_epoch_earned = self
+            .xp_epoch_earned
+            .checked_add(credit)
+            .ok_or(ErrorCode::CounterOverflow)?;
+        self.xp = self.xp.checked_add(credit).ok_or(ErrorCode::CounterOverflow)?;
+
+        // credit <= base, so this never truncates.
+        Ok(credit as u32)
+    }
+
+    /// Record one deposit, withdrawal or merchant payment.
+    ///
+    /// Increments `tx_count` and the per-direction count, updates activity
+    /// timestamps, the tracked mint slot (if one is free or already assigned)
+    /// and the rolling volume digest. Merchant payments have no per-direction
+    /// count or tracked volume; they only reach `tx_count`, the timestamps
+    /// and the digest.
+    pub fn record_activity(&mut self, direction: u8, mint: Pubkey, amount: u64, ts: i64) -> Result<()> {
+        match direction {
+            Receipt::DIR_DEPOSIT => {
+                self.deposit_count = self
+                    .deposit_count
+                    .checked_add(1)
+                    .ok_or(ErrorCode::CounterOverflow)?;
+            }
+            Receipt::DIR_WITHDRAW => {
+                self.withdraw_count = self
+                    .withdraw_count
+                    .checked_add(1)
+                    .ok_or(ErrorCode::CounterOverflow)?;
+            }
+            Receipt::DIR_MERCHANT_PAY => {}
+            _ => return err!(ErrorCode::InvalidDirection),
+        }
+
+        self.tx_count = self.tx_count.checked_add(1).ok_or(ErrorCode::CounterOverflow)?;
+
+        if self.first_activity_ts == 0 {
+            self.first_activity_ts = ts;
+        }
+        self.last_activity_ts = ts;
+
+        let slot = self
+            .mint_volumes
+            .iter()
+            .position(|v| v.mint == mint)
+            .or_else(|| self.mint_volumes.iter().position(|v| v.mint == Pubkey::default()))
+            .filter(|_| direction != Receipt::DIR_MERCHANT_PAY);
+
+        if let Some(i) = slot {
+            let v = &mut self.mint_volumes[i];
+            v.mint = mint;
+            let total = if direction == Receipt::DIR_DEPOSIT {
+                &mut v.deposited
+            } else {
+                &mut v.withdrawn
+            };
+            *total = total.checked_add(amount).ok_or(ErrorCode::CounterOverflow)?;
+        }
+
+        self.volume_digest = hashv(&[
+            &self.volume_digest,
+            mint.as_ref(),
+            &[direction],
+            &amount.to_le_bytes(),
+        ])
+        .to_bytes();
+
+        Ok(())
+    }
+
+    /// Decode profile account data of any historical schema version.
+    ///
+    /// The returned value uses the current in-memory layout; `version` reports
+    /// the schema version the bytes were stored in.
+    pub fn try_deserialize_versioned(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= 8, AnchorErrorCode::AccountDiscriminatorNotFound);
+        require!(
+            data[..8] == *Self::DISCRIMINATOR,
+            AnchorErrorCode::AccountDiscriminatorMismatch
+        );
+
+        let mut body = &data[8..];
+
+        if data.len() < Self::SPACE_V2 {
+            let v1 = UserProfileV1::deserialize(&mut body)
+                .map_err(|_| AnchorErrorCode::AccountDidNotDeserialize)?;
+            return Ok(v1.into());
+        }
+
+        if data.len() < Self::SPACE_V3 {
+            let v2 = UserProfileV2::deserialize(&mut body)
+                .map_err(|_| AnchorErrorCode::AccountDidNotDeserialize)?;
+            require!(
+                v2.version == Self::VERSION_2,
+                ErrorCode::UnsupportedUserProfileVersion
+            );
+            return Ok(v2.into());
+        }
+
+        if data.len() < Self::SPACE {
+            let v3 = UserProfileV3::deserialize(&mut body)
+                .map_err(|_| AnchorErrorCode::AccountDidNotDeserialize)?;
+            require!(
+                v3.version == Self::VERSION_3,
+                ErrorCode::UnsupportedUserProfileVersion
+            );
+            return Ok(v3.into());
+        }
+
+        let profile = Self::deserialize(&mut body)
+            .map_err(|_| AnchorErrorCode::AccountDidNotDeserialize)?;
+
+        require!(
+            profile.version >= Self::VERSION_4 && profile.version <= Self::CURRENT_VERSION,
+            ErrorCode::UnsupportedUserProfileVersion
+        );
+
+        Ok(profile)
+    }
+}
+
+/// Schema version 1 profile layout (no version byte).
+///
+/// Read-only: kept so legacy accounts can be decoded and upgraded.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserProfileV1 {
+    pub authority: Pubkey,
+    pub tx_count: u64,
+    pub bump: u8,
+}
+
+impl From<UserProfileV1> for UserProfile {
+    fn from(v1: UserProfileV1) -> Self {
+        Self {
+            authority: v1.authority,
+            tx_count: v1.tx_count,
+            bump: v1.bump,
+            version: UserProfile::VERSION_1,
+            deposit_count: 0,
+            withdraw_count: 0,
+            first_activity_ts: 0,
+            last_activity_ts: 0,
+            mint_volumes: Default::default(),
+            volume_digest: [0u8; 32],
+            xp: 0,
+            xp_epoch: 0,
+            xp_epoch_earned: 0,
+            velocity_window_start_slot: 0,
+            velocity_window_count: 0,
+            pays_received: 0,
+        }
+    }
+}
+
+/// Schema version 2 profile layout (no XP fields).
+///
+/// Read-only: kept so legacy accounts can be decoded and upgraded.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserProfileV2 {
+    pub authority: Pubkey,
+    pub tx_count: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub deposit_count: u64,
+    pub withdraw_count: u64,
+    pub first_activity_ts: i64,
+    pub last_activity_ts: i64,
+    pub mint_volumes: [MintVolume; UserProfile::MAX_TRACKED_MINTS],
+    pub volume_digest: [u8; 32],
+}
+
+impl From<UserProfileV2> for UserProfile {
+    fn from(v2: UserProfileV2) -> Self {
+        Self {
+            authority: v2.authority,
+            tx_count: v2.tx_count,
+            bump: v2.bump,
+            version: v2.version,
+            deposit_count: v2.deposit_count,
+            withdraw_count: v2.withdraw_count,
+            first_activity_ts: v2.first_activity_ts,
+            last_activity_ts: v2.last_activity_ts,
+            mint_volumes: v2.mint_volumes,
+            volume_digest: v2.volume_digest,
+            xp: 0,
+            xp_epoch: 0,
+            xp_epoch_earned: 0,
+            velocity_window_start_slot: 0,
+            velocity_window_count: 0,
+            pays_received: 0,
+        }
+    }
+}
+
+/// Schema version 3 profile layout (no risk velocity window).
+///
+/// Read-only: kept so legacy accounts can be decoded and upgraded.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserProfileV3 {
+    pub authority: Pubkey,
+    pub tx_count: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub deposit_count: u64,
+    pub withdraw_count: u64,
+    pub first_activity_ts: i64,
+    pub last_activity_ts: i64,
+    pub mint_volumes: [MintVolume; UserProfile::MAX_TRACKED_MINTS],
+    pub volume_digest: [u8; 32],
+    pub xp: u64,
+    pub xp_epoch: u64,
+    pub xp_epoch_earned: u64,
+}
+
+impl From<UserProfileV3> for UserProfile {
+    fn from(v3: UserProfileV3) -> Self {
+        Self {
+            authority: v3.authority,
+            tx_count: v3.tx_count,
+            bump: v3.bump,
+            version: v3.version,
+            deposit_count: v3.deposit_count,
+            withdraw_count: v3.withdraw_count,
+            first_activity_ts: v3.first_activity_ts,
+            last_activity_ts: v3.last_activity_ts,
+            mint_volumes: v3.mint_volumes,
+            volume_digest: v3.volume_digest,
+            xp: v3.xp,
+            xp_epoch: v3.xp_epoch,
+            xp_epoch_earned: v3.xp_epoch_earned,
+            velocity_window_start_slot: 0,
+            velocity_window_count: 0,
+            pays_received: 0,
+        }
+    }
 }
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{TravelRuleRecord, TreasuryConfig};
+
+/// Travel-rule record contents accepted by `record_travel_rule`.
+///
+/// Mirrors `TravelRuleRecord`; see there for field semantics.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct TravelRuleParams {
+    pub record_id: [u8; 32],
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub originator_vasp: [u8; 32],
+    pub beneficiary_vasp: [u8; 32],
+    pub pii_hash: [u8; 32],
+}
+
+impl TravelRuleParams {
+    /// Reject records no transfer could consume or that carry no data.
+    pub fn validate(&self) -> Result<()> {
+        require!(self.amount > 0, ErrorCode::InvalidTravelRuleRecord);
+        require!(
+            self.beneficiary != Pubkey::default(),
+            ErrorCode::InvalidTravelRuleRecord
+        );
+        require!(
+            self.originator_vasp != [0u8; 32] && self.beneficiary_vasp != [0u8; 32],
+            ErrorCode::InvalidTravelRuleRecord
+        );
+        require!(self.pii_hash != [0u8; 32], ErrorCode::InvalidTravelRuleRecord);
+
+        Ok(())
+    }
+}
+
+/// Consume the flow's travel-rule record, if one is attached.
+///
+/// A record is required when the amount exceeds the config's
+/// `travel_rule_threshold`; one attached voluntarily is checked and consumed
+/// the same way. The record must match the transfer exactly (beneficiary,
+/// mint, amount) and be unused. Returns whether a record was consumed, so the
+/// caller can flag its receipt.
+pub fn consume_travel_rule(
+    config: Option<&TreasuryConfig>,
+    record: Option<&mut Account<TravelRuleRecord>>,
+    beneficiary: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    receipt: Pubkey,
+) -> Result<bool> {
+    let required = config
+        .is_some_and(|c| c.travel_rule_threshold > 0 && amount > c.travel_rule_threshold);
+
+    let Some(record) = record else {
+        require!(!required, ErrorCode::TravelRuleRecordRequired);
+        return Ok(false);
+    };
+
+    require!(!record.is_consumed(), ErrorCode::TravelRuleRecordConsumed);
+    require!(
+        record.beneficiary == *beneficiary && record.mint == *mint && record.amount == amount,
+        ErrorCode::TravelRuleRecordMismatch
+    );
+
+    record.consumed_at = Clock::get()?.unix_timestamp;
+    record.receipt = receipt;
+
+    Ok(true)
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::events::TrustScoreUpdatedEvent;
+use crate::state::{TreasuryConfig, TrustScore};
+
+/// Spending limit tier a wallet qualifies for through its trust score.
+///
+/// `TreasuryConfig.trust_tier_scores[i]` is the minimum score that lifts a
+/// wallet to limit tier `i` (0 = tier not reachable by score); flows apply the
+/// higher of this and the wallet's assigned tier, so higher tiers are expected
+/// to be the more generous ones. Returns 0 unless both accounts are supplied.
+pub fn trust_limit_tier(
+    config: Option<&TreasuryConfig>,
+    trust: Option<&TrustScore>,
+) -> Result<u8> {
+    let (Some(config), Some(trust)) = (config, trust) else {
+        return Ok(0);
+    };
+
+    let score = trust.score_at(Clock::get()?.unix_timestamp);
+
+    let tier = config
+        .trust_tier_scores
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, min)| **min > 0 && score >= **min)
+        .map_or(0, |(i, _)| i as u8);
+
+    Ok(tier)
+}
+
+/// Credit one successful receipted flow of `amount` to the wallet's trust
+/// score.
+///
+/// The account is optional on every flow; nothing happens without it. Flows
+/// below the config's `trust_min_amount` are counted but earn no points, and
+/// `TrustScore` caps the flows credited per window.
+pub fn credit_trust(
+    trust: Option<&mut Account<TrustScore>>,
+    config: Option<&TreasuryConfig>,
+    amount: u64,
+) -> Result<()> {
+    let Some(trust) = trust else {
+        return Ok(());
+    };
+
+    let min_amount = config.map_or(0, |c| c.trust_min_amount);
+    let clock = Clock::get()?;
+    trust.record_flow(amount >= min_amount, clock.unix_timestamp);
+
+    emit_trust_updated(trust, clock.slot);
+    Ok(())
+}
+
+/// Emit the score a trust account holds after an update.
+pub fn emit_trust_updated(trust: &Account<TrustScore>, slot: u64) {
+    emit!(TrustScoreUpdatedEvent {
+        wallet: trust.wallet,
+        trust_score: trust.key(),
+        score: trust.score,
+        successful_flows: trust.successful_flows,
+        disputes_lost: trust.disputes_lost,
+        slot,
+    });
+}
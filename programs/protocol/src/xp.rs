@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{TreasuryConfig, UserProfile};
+
+/// Value-moving flow kinds that earn XP.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum XpFlow {
+    Deposit,
+    Withdraw,
+    Pay,
+    /// Customer payment to a merchant; earns the `xp_per_pay` entry.
+    MerchantPay,
+    /// Dispute refund back to the payer; earns the `xp_per_withdraw` entry.
+    Refund,
+}
+
+impl XpFlow {
+    /// XP-per-flow entry from the treasury config table.
+    pub fn base_xp(self, config: &TreasuryConfig) -> u32 {
+        match self {
+            XpFlow::Deposit => config.xp_per_deposit,
+            XpFlow::Withdraw => config.xp_per_withdraw,
+            XpFlow::Pay | XpFlow::MerchantPay => config.xp_per_pay,
+            XpFlow::Refund => config.xp_per_withdraw,
+        }
+    }
+}
+
+/// Credit XP for one flow to the user's profile.
+///
+/// Both accounts are optional on every flow; XP is only credited when both
+/// are supplied. Returns the XP actually credited, which flows report as the
+/// event `xp_delta`.
+pub fn credit_xp<'info>(
+    profile: Option<&mut Account<'info, UserProfile>>,
+    config: Option<&TreasuryConfig>,
+    flow: XpFlow,
+    amount: u64,
+) -> Result<u32> {
+    let (Some(profile), Some(config)) = (profile, config) else {
+        return Ok(0);
+    };
+
+    let epoch = Clock::get()?.epoch;
+    profile.credit_xp(
+        flow.base_xp(config),
+        amount,
+        config.xp_min_amount,
+        config.xp_epoch_cap,
+        epoch,
+    )
+}
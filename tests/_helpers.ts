@@ -162,6 +162,258 @@ export function derivePayReceiptPda(
   );
 }
 
+/** Reference idempotency index PDA — SPL pay with reference
+ * seeds = ["reference", treasury, reference_32]
+ * Resolves a pay receipt from its external reference alone.
+ */
+export function deriveReferenceIndexPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  reference: Buffer | Uint8Array | number[]
+): [PublicKey, number] {
+  const ref = Buffer.from(reference as any);
+  if (ref.length !== 32) {
+    throw new Error(`reference must be 32 bytes, got ${ref.length}`);
+  }
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("reference"), treasury.toBuffer(), ref],
+    programId
+  );
+}
+
+/**
+ * Treasury config PDA (XP table, anti-farming rules, future settings):
+ * ["treasury_config", treasury]
+ */
+export function deriveTreasuryConfigPda(
+  programId: PublicKey,
+  treasury: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury_config"), treasury.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Per-mint spending limit tiers PDA:
+ * ["mint_limits", treasury, mint]
+ */
+export function deriveMintLimitsPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  mint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_limits"), treasury.toBuffer(), mint.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Per-mint treasury ledger PDA:
+ * ["mint_ledger", treasury, mint]
+ */
+export function deriveMintLedgerPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  mint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_ledger"), treasury.toBuffer(), mint.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Per-user spending limit window PDA:
+ * ["user_limits", treasury, user, mint]
+ */
+export function deriveUserLimitsPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  user: PublicKey,
+  mint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("user_limits"), treasury.toBuffer(), user.toBuffer(), mint.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Denylist entry PDA (exists only while the wallet is listed):
+ * ["denylist", treasury, wallet]
+ */
+export function deriveDenylistEntryPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  wallet: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("denylist"), treasury.toBuffer(), wallet.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * KYC attestation PDA:
+ * ["kyc", treasury, wallet]
+ */
+export function deriveKycAttestationPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  wallet: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("kyc"), treasury.toBuffer(), wallet.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Trusted KYC issuer PDA:
+ * ["kyc_issuer", treasury, issuer]
+ */
+export function deriveKycIssuerPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  issuer: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("kyc_issuer"), treasury.toBuffer(), issuer.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Travel-rule record PDA:
+ * ["travel_rule", treasury, recordId]
+ * (receipt flows use the receipt address as recordId)
+ */
+export function deriveTravelRulePda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  recordId: Uint8Array
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("travel_rule"), treasury.toBuffer(), Buffer.from(recordId)],
+    programId
+  );
+}
+
+/**
+ * Trust score PDA (portable; not tied to a treasury):
+ * ["trust_score", wallet]
+ */
+export function deriveTrustScorePda(
+  programId: PublicKey,
+  wallet: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("trust_score"), wallet.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Dispute config PDA (window + designated arbiter):
+ * ["dispute_config", treasury]
+ */
+export function deriveDisputeConfigPda(
+  programId: PublicKey,
+  treasury: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("dispute_config"), treasury.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Dispute PDA (one per disputed receipt):
+ * ["dispute", receipt]
+ */
+export function deriveDisputePda(
+  programId: PublicKey,
+  receipt: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("dispute"), receipt.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Merchant PDA:
+ * ["merchant", treasury, owner]
+ */
+export function deriveMerchantPda(
+  programId: PublicKey,
+  treasury: PublicKey,
+  owner: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("merchant"), treasury.toBuffer(), owner.toBuffer()],
+    programId
+  );
+}
+
+/** Merchant payment receipt PDA
+ * seeds = ["receipt", merchant, u64_le(merchant.pay_count)]
+ */
+export function deriveMerchantReceiptPda(
+  programId: PublicKey,
+  merchant: PublicKey,
+  payCount: BN | bigint | number
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt"), merchant.toBuffer(), toLeU64(payCount)],
+    programId
+  );
+}
+
+/** Dispute resolution receipt PDA
+ * seeds = ["receipt", dispute]
+ */
+export function deriveDisputeReceiptPda(
+  programId: PublicKey,
+  dispute: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt"), dispute.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Compliance config PDA (designated compliance authority):
+ * ["compliance", treasury]
+ */
+export function deriveComplianceConfigPda(
+  programId: PublicKey,
+  treasury: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("compliance"), treasury.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Designated receipt attestor PDA:
+ * ["attestor", treasury]
+ */
+export function deriveAttestorConfigPda(
+  programId: PublicKey,
+  treasury: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("attestor"), treasury.toBuffer()],
+    programId
+  );
+}
+
 /** Historical / V2-style generalized receipt PDA helper.
  * seeds = ["receipt", treasury, user, mint, txCount_le_u64, direction_u8]
  */
@@ -263,13 +515,70 @@ export async function getAccountInfoOrNull(
   return await conn.getAccountInfo(address, commitment);
 }
 
+/* Risk flags (mirrors RISK_* in programs/protocol/src/risk.rs).
+ * Receipts carry them in the high byte of v2.flags.
+ */
+export const RISK_LARGE_AMOUNT = 1 << 0;
+export const RISK_NEW_PROFILE = 1 << 1;
+export const RISK_HIGH_VELOCITY = 1 << 2;
+export const RISK_FIRST_TIME_RECIPIENT = 1 << 3;
+export const RISK_ROUND_AMOUNT = 1 << 4;
+export const RISK_FLAGS_ALL = 0x1f;
+
+/** Risk flags stored in a receipt's v2.flags. */
+export function receiptRiskFlags(v2Flags: number): number {
+  return (v2Flags >> 8) & 0xff;
+}
+
+/* Receipt schema versions (mirrors Receipt::VERSION_* in state/receipt.rs).
+ * v1 = original layout, no version byte
+ * v2 = trailing `version: u8`
+ */
+export const RECEIPT_VERSION_1 = 1;
+export const RECEIPT_VERSION_2 = 2;
+export const RECEIPT_CURRENT_VERSION = RECEIPT_VERSION_2;
+export const RECEIPT_SPACE_V1 = 254;
+export const RECEIPT_SPACE = 255;
+
+/** Decode a receipt of any schema version (mirrors Receipt::try_deserialize_versioned). */
 export function decodeReceiptFromAccountInfo(
   program: any,
   info: AccountInfo<Buffer>
 ) {
+  if (info.data.length === RECEIPT_SPACE_V1) {
+    const padded = Buffer.concat([info.data, Buffer.from([RECEIPT_VERSION_1])]);
+    return program.coder.accounts.decode("receipt", padded);
+  }
   return program.coder.accounts.decode("receipt", info.data);
 }
 
+/* Receipt attestation message (mirrors crate::attestation::receipt_attestation_message). */
+export const ATTESTATION_DOMAIN = Buffer.from("zephyon:receipt-attestation:v1", "utf8");
+
+/**
+ * Canonical bytes an attestor signs for a receipt.
+ *
+ * `receiptData` is the raw current-version account data; the receipt body is
+ * everything after the 8-byte discriminator.
+ */
+export function buildReceiptAttestationMessage(
+  programId: PublicKey,
+  receipt: PublicKey,
+  slot: BN | bigint | number,
+  receiptData: Buffer
+): Buffer {
+  if (receiptData.length !== RECEIPT_SPACE) {
+    throw new Error("receipt must be migrated to the current version before attesting");
+  }
+  return Buffer.concat([
+    ATTESTATION_DOMAIN,
+    programId.toBuffer(),
+    receipt.toBuffer(),
+    toLeU64(slot),
+    receiptData.subarray(8),
+  ]);
+}
+
 export async function receiptExists(
   providerOrConn: AnchorProvider | Connection,
   address: PublicKey,
@@ -277,6 +277,8 @@ async function buildSplPayTx(args: {
     associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
     system_program: anchor.web3.SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    // Optional account: passing the program id means "None".
+    memoProgram: program.programId,
     treasuryAuthority: authority.publicKey,
     recipientAta,
     treasuryAta,
@@ -0,0 +1,259 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveComplianceConfigPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+const REASON_SANCTIONS = 1;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * Wallet denylist.
+ *
+ * Canon:
+ * - a listed wallet can neither deposit/withdraw (WalletDenylisted) nor
+ *   receive payouts (RecipientDenylisted)
+ * - entries are managed by the treasury authority or the designated
+ *   compliance authority only
+ * - removing the entry restores access
+ */
+describe("protocol - denylist", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let complianceConfigPda: PublicKey;
+
+  let officer: Keypair;
+  let user: Keypair;
+  let entryPda: PublicKey;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+
+  async function setComplianceAuthority(key: PublicKey) {
+    await program.methods
+      .setComplianceAuthority(key)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: complianceConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function addEntry(signer: Keypair, reasonCode: number, expiresAt: number) {
+    await program.methods
+      .addDenylistEntry(reasonCode, new anchor.BN(expiresAt))
+      .accounts({
+        authority: signer.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: complianceConfigPda,
+        wallet: user.publicKey,
+        denylistEntry: entryPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function removeEntry(signer: Keypair) {
+    await program.methods
+      .removeDenylistEntry()
+      .accounts({
+        authority: signer.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: complianceConfigPda,
+        wallet: user.publicKey,
+        denylistEntry: entryPda,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function deposit(amount: number) {
+    await program.methods
+      .splDeposit(new anchor.BN(amount))
+      .accounts({
+        user: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: entryPda,
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc();
+  }
+
+  async function payToUser(amount: number) {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    const [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCount);
+
+    await program.methods
+      .splPay(new anchor.BN(amount), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, user.publicKey),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: entryPda,
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [complianceConfigPda] = deriveComplianceConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    officer = Keypair.generate();
+    user = Keypair.generate();
+    await airdrop(provider, officer.publicKey, 1, "confirmed");
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+    [entryPda] = deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey);
+
+    ({ mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      user,
+      treasuryPda,
+      1_000_000n
+    ));
+
+    // Fund the treasury for the payout case before the user is listed.
+    await deposit(100_000);
+
+    await setComplianceAuthority(officer.publicKey);
+  });
+
+  after(async () => {
+    // Leave the shared treasury without a designated compliance key.
+    await setComplianceAuthority(PublicKey.default);
+  });
+
+  it("non-compliance signers cannot denylist wallets", async () => {
+    const rogue = Keypair.generate();
+    await airdrop(provider, rogue.publicKey, 1, "confirmed");
+
+    await expectReject(addEntry(rogue, REASON_SANCTIONS, 0), "UnauthorizedCompliance");
+  });
+
+  it("rejects entries that already expired", async () => {
+    await expectReject(addEntry(officer, REASON_SANCTIONS, 1), "InvalidDenylistEntry");
+  });
+
+  it("the compliance authority denylists a wallet", async () => {
+    await addEntry(officer, REASON_SANCTIONS, 0);
+
+    const e: any = await program.account.denylistEntry.fetch(entryPda);
+    expect(e.treasury.toBase58()).to.eq(treasuryPda.toBase58());
+    expect(e.wallet.toBase58()).to.eq(user.publicKey.toBase58());
+    expect(toNum(e.reasonCode)).to.eq(REASON_SANCTIONS);
+    expect(toNum(e.expiresAt)).to.eq(0);
+    expect(toNum(e.addedAt)).to.be.greaterThan(0);
+    expect(e.addedBy.toBase58()).to.eq(officer.publicKey.toBase58());
+  });
+
+  it("a denylisted wallet cannot deposit", async () => {
+    await expectReject(deposit(1_000), "WalletDenylisted");
+  });
+
+  it("a denylisted wallet cannot receive payouts", async () => {
+    await expectReject(payToUser(1_000), "RecipientDenylisted");
+  });
+
+  it("removing the entry restores access", async () => {
+    await removeEntry(officer);
+    expect(await provider.connection.getAccountInfo(entryPda)).to.eq(null);
+
+    await deposit(1_000);
+    await payToUser(1_000);
+  });
+
+  it("the treasury authority can always manage entries", async () => {
+    await addEntry(protocolAuth, REASON_SANCTIONS, 0);
+    await removeEntry(protocolAuth);
+  });
+});
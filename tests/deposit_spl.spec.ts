@@ -1,5 +1,5 @@
 import * as anchor from "@coral-xyz/anchor";
-import { deriveTreasuryPda, loadProtocolAuthority, airdrop } from "./_helpers";
+import { deriveTreasuryPda, loadProtocolAuthority, airdrop, deriveTreasuryConfigPda, deriveMintLimitsPda, deriveMintLedgerPda, deriveUserLimitsPda, deriveDenylistEntryPda } from "./_helpers";
 import { Program } from "@coral-xyz/anchor";
 import { Protocol } from "../target/types/protocol";
 
@@ -155,6 +155,13 @@ const payer = (provider.wallet as any).payer as anchor.web3.Keypair;
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          userProfile: null,
+          treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+          mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+          mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+          userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+          denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+          trustScore: null,
         } as any)
         .signers([user])
         .rpc();
@@ -296,6 +303,13 @@ const payer = (provider.wallet as any).payer as anchor.web3.Keypair;
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
       }as any)
       .signers([user])
       .rpc();
@@ -408,6 +422,13 @@ const payer = (provider.wallet as any).payer as anchor.web3.Keypair;
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      userProfile: null,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+      trustScore: null,
     } as any)
     .signers([user])
     .rpc();
@@ -7,6 +7,11 @@ import {
   airdrop,
   DIR_DEPOSIT,
   ASSET_SPL,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
 } from "./_helpers";
 
 import {
@@ -121,7 +126,7 @@ describe("protocol - spl deposit with receipt", () => {
 
     // --- Deposit with receipt
     await program.methods
-      .splDepositWithReceipt(new anchor.BN(amount), nonce)
+      .splDepositWithReceipt(new anchor.BN(amount), nonce, null, null, null)
       .accounts({
         user: payer.publicKey,
         treasury: treasuryPda,
@@ -133,6 +138,14 @@ describe("protocol - spl deposit with receipt", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+        trustScore: null,
       } as any)
       .signers([payer])
       .rpc();
@@ -237,7 +250,7 @@ describe("protocol - spl deposit with receipt", () => {
 
   // --- Deposit with receipt (CAPTURE txSig)
   const txSig = await program.methods
-    .splDepositWithReceipt(new anchor.BN(amount), nonce)
+    .splDepositWithReceipt(new anchor.BN(amount), nonce, null, null, null)
     .accounts({
       user: payer.publicKey,
       treasury: treasuryPda,
@@ -249,6 +262,14 @@ describe("protocol - spl deposit with receipt", () => {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      memoProgram: null,
+      userProfile: null,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+      trustScore: null,
     } as any)
     .signers([payer])
     .rpc();
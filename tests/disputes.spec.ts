@@ -0,0 +1,445 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+  deriveDisputeConfigPda,
+  deriveDisputePda,
+  deriveDisputeReceiptPda,
+  deriveMerchantPda,
+  deriveMerchantReceiptPda,
+} from "./_helpers";
+
+const WINDOW_SECS = 3_600;
+const DEPOSIT_AMOUNT = 10_000;
+const PAY_AMOUNT = 4_000;
+
+// Mirrors Dispute::STATUS_* and Receipt::DIR_DISPUTE.
+const STATUS_OPEN = 1;
+const STATUS_REFUNDED = 2;
+const STATUS_REJECTED = 3;
+const DIR_DISPUTE = 4;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+function evidence(byte: number): number[] {
+  return Array(32).fill(byte);
+}
+
+/**
+ * Disputes (chargebacks) against receipts.
+ *
+ * Canon:
+ * - the receipt's payer opens a dispute within the window, once per receipt:
+ *   the customer for merchant payments, the treasury for payouts; deposits
+ *   are not disputable
+ * - both sides attach evidence hashes while it is open
+ * - only the treasury authority or the arbiter resolves it: refund (merchant
+ *   payments only, up to the receipt amount, fronted by the treasury and
+ *   booked against the merchant) or reject
+ * - resolution writes a linked receipt (direction 4, reference = disputed receipt)
+ */
+describe("protocol - disputes", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let disputeConfigPda: PublicKey;
+
+  let arbiter: Keypair;
+  let user: Keypair;
+  let owner: Keypair;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+  let merchantPda: PublicKey;
+
+  let depositReceipt: PublicKey;
+  let merchantReceipt: PublicKey;
+  let merchantDispute: PublicKey;
+
+  async function setDisputeConfig(windowSecs: number) {
+    await program.methods
+      .setDisputeConfig(arbiter.publicKey, new anchor.BN(windowSecs))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        disputeConfig: disputeConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function depositWithReceipt(): Promise<PublicKey> {
+    const nonce = new anchor.BN(Date.now());
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, user.publicKey, nonce);
+
+    await program.methods
+      .splDepositWithReceipt(new anchor.BN(DEPOSIT_AMOUNT), nonce, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc();
+
+    return receiptPda;
+  }
+
+  async function registerMerchant() {
+    await program.methods
+      .registerMerchant(owner.publicKey, [mint], 0)
+      .accounts({
+        owner: owner.publicKey,
+        authority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: null,
+        merchant: merchantPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([owner, protocolAuth])
+      .rpc();
+  }
+
+  async function payMerchant(): Promise<PublicKey> {
+    const m: any = await program.account.merchant.fetch(merchantPda);
+    const [receiptPda] = deriveMerchantReceiptPda(program.programId, merchantPda, toNum(m.payCount));
+
+    await program.methods
+      .payMerchant(new anchor.BN(PAY_AMOUNT), null)
+      .accounts({
+        payer: user.publicKey,
+        treasury: treasuryPda,
+        merchant: merchantPda,
+        payoutWallet: owner.publicKey,
+        mint,
+        payerAta: userAta,
+        payoutAta: getAssociatedTokenAddressSync(mint, owner.publicKey),
+        treasuryAta,
+        receipt: receiptPda,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        payoutDenylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, owner.publicKey)[0],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userProfile: null,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc();
+
+    return receiptPda;
+  }
+
+  async function payToUser(): Promise<PublicKey> {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    const [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCount);
+
+    await program.methods
+      .splPay(new anchor.BN(100), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    return receiptPda;
+  }
+
+  async function openDispute(
+    opener: Keypair,
+    receipt: PublicKey,
+    proof: number[],
+    merchant: PublicKey | null = null
+  ) {
+    await program.methods
+      .openDispute(7, proof)
+      .accounts({
+        opener: opener.publicKey,
+        treasury: treasuryPda,
+        disputeConfig: disputeConfigPda,
+        receipt,
+        dispute: deriveDisputePda(program.programId, receipt)[0],
+        systemProgram: SystemProgram.programId,
+        merchant,
+      } as any)
+      .signers([opener])
+      .rpc();
+  }
+
+  async function submitEvidence(party: Keypair, dispute: PublicKey, proof: number[]) {
+    await program.methods
+      .submitDisputeEvidence(proof)
+      .accounts({ party: party.publicKey, dispute } as any)
+      .signers([party])
+      .rpc();
+  }
+
+  async function resolve(
+    signer: Keypair,
+    dispute: PublicKey,
+    refund: number,
+    payer: PublicKey,
+    merchant: PublicKey | null = null
+  ) {
+    await program.methods
+      .resolveDispute(new anchor.BN(refund))
+      .accounts({
+        arbiter: signer.publicKey,
+        treasury: treasuryPda,
+        disputeConfig: disputeConfigPda,
+        dispute,
+        resolutionReceipt: deriveDisputeReceiptPda(program.programId, dispute)[0],
+        mint,
+        payer,
+        payerAta: refund > 0 ? getAssociatedTokenAddressSync(mint, payer) : null,
+        treasuryAta: refund > 0 ? treasuryAta : null,
+        respondentTrust: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        payerProfile: null,
+        merchant,
+        payerDenylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer)[0],
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [disputeConfigPda] = deriveDisputeConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    arbiter = Keypair.generate();
+    user = Keypair.generate();
+    owner = Keypair.generate();
+    await airdrop(provider, arbiter.publicKey, 1, "confirmed");
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+    await airdrop(provider, owner.publicKey, 1, "confirmed");
+    [merchantPda] = deriveMerchantPda(program.programId, treasuryPda, owner.publicKey);
+
+    const setup = await setupMintAndAtas(provider, user, treasuryPda, 1_000_000n);
+    mint = setup.mint;
+    userAta = setup.userAta;
+    treasuryAta = setup.treasuryAta;
+
+    await setDisputeConfig(WINDOW_SECS);
+    await registerMerchant();
+    // The deposit also funds the treasury that fronts refunds.
+    depositReceipt = await depositWithReceipt();
+    merchantReceipt = await payMerchant();
+    [merchantDispute] = deriveDisputePda(program.programId, merchantReceipt);
+  });
+
+  after(async () => {
+    // Leave the treasury without an open dispute window.
+    await setDisputeConfig(0);
+  });
+
+  it("deposits are not disputable", async () => {
+    await expectReject(openDispute(user, depositReceipt, evidence(1)), "ReceiptNotDisputable");
+  });
+
+  it("only the receipt's payer can open a dispute, naming the merchant", async () => {
+    const stranger = Keypair.generate();
+    await airdrop(provider, stranger.publicKey, 1, "confirmed");
+
+    await expectReject(
+      openDispute(stranger, merchantReceipt, evidence(1), merchantPda),
+      "UnauthorizedDispute"
+    );
+    await expectReject(
+      openDispute(user, merchantReceipt, evidence(0), merchantPda),
+      "InvalidDisputeEvidence"
+    );
+    await expectReject(openDispute(user, merchantReceipt, evidence(1)), "MerchantRequired");
+  });
+
+  it("the payer opens a dispute once per receipt", async () => {
+    await openDispute(user, merchantReceipt, evidence(1), merchantPda);
+
+    const d: any = await program.account.dispute.fetch(merchantDispute);
+    expect(d.receipt.toBase58()).to.eq(merchantReceipt.toBase58());
+    expect(d.payer.toBase58()).to.eq(user.publicKey.toBase58());
+    expect(d.respondent.toBase58()).to.eq(owner.publicKey.toBase58());
+    expect(toNum(d.amount)).to.eq(PAY_AMOUNT);
+    expect(toNum(d.status)).to.eq(STATUS_OPEN);
+
+    await expectReject(
+      openDispute(user, merchantReceipt, evidence(1), merchantPda),
+      "already in use"
+    );
+  });
+
+  it("both sides attach evidence", async () => {
+    await submitEvidence(owner, merchantDispute, evidence(2));
+    await expectReject(submitEvidence(arbiter, merchantDispute, evidence(3)), "UnauthorizedDispute");
+
+    const d: any = await program.account.dispute.fetch(merchantDispute);
+    expect(d.payerEvidence).to.deep.eq(evidence(1));
+    expect(d.respondentEvidence).to.deep.eq(evidence(2));
+  });
+
+  it("only an arbiter resolves, within the disputed amount", async () => {
+    await expectReject(resolve(user, merchantDispute, 0, user.publicKey), "UnauthorizedDispute");
+    await expectReject(
+      resolve(arbiter, merchantDispute, PAY_AMOUNT + 1, user.publicKey, merchantPda),
+      "InvalidRefundAmount"
+    );
+  });
+
+  it("a refund pays the payer back, books it on the merchant and writes a linked receipt", async () => {
+    const before = (await getAccount(provider.connection, userAta)).amount;
+
+    await resolve(arbiter, merchantDispute, PAY_AMOUNT / 2, user.publicKey, merchantPda);
+
+    const after = (await getAccount(provider.connection, userAta)).amount;
+    expect(after - before).to.eq(BigInt(PAY_AMOUNT / 2));
+
+    const m: any = await program.account.merchant.fetch(merchantPda);
+    expect(toNum(m.settlements[0].refunded)).to.eq(PAY_AMOUNT / 2);
+
+    const d: any = await program.account.dispute.fetch(merchantDispute);
+    expect(toNum(d.status)).to.eq(STATUS_REFUNDED);
+    expect(toNum(d.refundAmount)).to.eq(PAY_AMOUNT / 2);
+    expect(d.resolvedBy.toBase58()).to.eq(arbiter.publicKey.toBase58());
+
+    const [resolutionPda] = deriveDisputeReceiptPda(program.programId, merchantDispute);
+    expect(d.resolutionReceipt.toBase58()).to.eq(resolutionPda.toBase58());
+
+    const r: any = await program.account.receipt.fetch(resolutionPda);
+    expect(toNum(r.direction)).to.eq(DIR_DISPUTE);
+    expect(toNum(r.amount)).to.eq(PAY_AMOUNT / 2);
+    expect(r.user.toBase58()).to.eq(user.publicKey.toBase58());
+    expect(Buffer.from(r.v2.reference).equals(merchantReceipt.toBuffer())).to.eq(true);
+  });
+
+  it("pay receipts are disputed by the treasury and cannot be refunded", async () => {
+    const payReceipt = await payToUser();
+    const [payDispute] = deriveDisputePda(program.programId, payReceipt);
+
+    await expectReject(openDispute(user, payReceipt, evidence(4)), "UnauthorizedDispute");
+    await openDispute(protocolAuth, payReceipt, evidence(4));
+
+    // The recipient answers.
+    await submitEvidence(user, payDispute, evidence(5));
+
+    await expectReject(
+      resolve(arbiter, payDispute, 1, protocolAuth.publicKey),
+      "InvalidRefundAmount"
+    );
+    await resolve(arbiter, payDispute, 0, protocolAuth.publicKey);
+
+    const d: any = await program.account.dispute.fetch(payDispute);
+    expect(toNum(d.status)).to.eq(STATUS_REJECTED);
+    expect(d.respondent.toBase58()).to.eq(user.publicKey.toBase58());
+  });
+
+  it("disputes outside the window are rejected", async () => {
+    await setDisputeConfig(1);
+    const receipt = await payMerchant();
+    await new Promise((r) => setTimeout(r, 3_000));
+
+    await expectReject(openDispute(user, receipt, evidence(6), merchantPda), "DisputeWindowClosed");
+
+    await setDisputeConfig(0);
+    await expectReject(openDispute(user, receipt, evidence(6), merchantPda), "DisputesDisabled");
+  });
+});
@@ -0,0 +1,283 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import {
+  Connection,
+  Keypair,
+  NONCE_ACCOUNT_LENGTH,
+  NonceAccount,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+function sleep(ms: number) {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+/**
+ * Treasury payouts signed against a durable nonce.
+ *
+ * Canon:
+ * - a payout signed against a nonce still lands after the blockhash it would
+ *   otherwise have carried has expired (approvals that take hours)
+ * - landing advances the nonce, so nothing else signed against the same
+ *   value can land afterwards
+ * - advancing the nonce explicitly cancels a signed, unsubmitted payout
+ *
+ * The local validator cannot skip hours ahead; outliving the blockhash
+ * validity window is what matters, after that the nonce account just sits
+ * untouched for however long the approval takes.
+ */
+describe("protocol - durable nonce payouts", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let connection: Connection;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let recipient: Keypair;
+  let mint: PublicKey;
+  let treasuryAta: PublicKey;
+  let nonceAccount: Keypair;
+
+  async function readNonce(): Promise<string> {
+    const info = await connection.getAccountInfo(nonceAccount.publicKey, "confirmed");
+    expect(info, "nonce account").to.not.eq(null);
+    return NonceAccount.fromAccountData(info!.data).nonce;
+  }
+
+  async function nextPayReceipt(): Promise<PublicKey> {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    return derivePayReceiptPda(program.programId, treasuryPda, payCount)[0];
+  }
+
+  async function payIx(amount: number, receiptPda: PublicKey): Promise<TransactionInstruction> {
+    return program.methods
+      .splPay(new anchor.BN(amount), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: recipient.publicKey,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient.publicKey),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient.publicKey)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .instruction();
+  }
+
+  /** Sign like the offline approver would: no blockhash, only the nonce. */
+  async function signWithNonce(ix: TransactionInstruction): Promise<Buffer> {
+    const tx = new Transaction({
+      feePayer: protocolAuth.publicKey,
+      nonceInfo: {
+        nonce: await readNonce(),
+        nonceInstruction: SystemProgram.nonceAdvance({
+          noncePubkey: nonceAccount.publicKey,
+          authorizedPubkey: protocolAuth.publicKey,
+        }),
+      },
+    }).add(ix);
+    tx.sign(protocolAuth);
+    return tx.serialize();
+  }
+
+  async function submit(raw: Buffer): Promise<string> {
+    const sig = await connection.sendRawTransaction(raw, {
+      skipPreflight: false,
+      preflightCommitment: "confirmed",
+    });
+    const res = await connection.confirmTransaction(sig, "confirmed");
+    if (res.value.err) throw new Error(`Transaction failed: ${JSON.stringify(res.value.err)}`);
+    return sig;
+  }
+
+  async function waitPastBlockHeight(height: number) {
+    while ((await connection.getBlockHeight("confirmed")) <= height) {
+      await sleep(1_000);
+    }
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+    connection = provider.connection;
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    recipient = Keypair.generate();
+    const funder = Keypair.generate();
+    await airdrop(provider, recipient.publicKey, 1, "confirmed");
+    await airdrop(provider, funder.publicKey, 2, "confirmed");
+
+    const setup = await setupMintAndAtas(provider, funder, treasuryPda, 1_000_000n);
+    mint = setup.mint;
+    treasuryAta = setup.treasuryAta;
+
+    await program.methods
+      .splDeposit(new anchor.BN(1_000_000))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: setup.userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+
+    nonceAccount = Keypair.generate();
+    const lamports = await connection.getMinimumBalanceForRentExemption(NONCE_ACCOUNT_LENGTH);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createNonceAccount({
+          fromPubkey: protocolAuth.publicKey,
+          noncePubkey: nonceAccount.publicKey,
+          authorizedPubkey: protocolAuth.publicKey,
+          lamports,
+        })
+      ),
+      [nonceAccount]
+    );
+  });
+
+  it("lands a payout signed before its blockhash would have expired", async () => {
+    const receiptPda = await nextPayReceipt();
+    const ix = await payIx(100, receiptPda);
+
+    // The same payout signed against a blockhash, at the same moment.
+    const latest = await connection.getLatestBlockhash("confirmed");
+    const withBlockhash = new Transaction({
+      feePayer: protocolAuth.publicKey,
+      blockhash: latest.blockhash,
+      lastValidBlockHeight: latest.lastValidBlockHeight,
+    }).add(ix);
+    withBlockhash.sign(protocolAuth);
+
+    const nonceBefore = await readNonce();
+    const withNonce = await signWithNonce(ix);
+
+    await waitPastBlockHeight(latest.lastValidBlockHeight);
+
+    await expectReject(submit(withBlockhash.serialize()), "Blockhash not found");
+    await submit(withNonce);
+
+    const receipt = await (program.account as any).receipt.fetch(receiptPda);
+    expect(receipt.user.toBase58()).to.eq(recipient.publicKey.toBase58());
+    expect(toNum(receipt.amount)).to.eq(100);
+
+    expect(await readNonce()).to.not.eq(nonceBefore);
+  });
+
+  it("rejects anything else signed against a nonce value that was used", async () => {
+    const receiptPda = await nextPayReceipt();
+    const first = await signWithNonce(await payIx(10, receiptPda));
+    const second = await signWithNonce(await payIx(20, receiptPda));
+
+    await submit(first);
+    await expectReject(submit(second), "Blockhash not found");
+
+    const receipt = await (program.account as any).receipt.fetch(receiptPda);
+    expect(toNum(receipt.amount)).to.eq(10);
+  });
+
+  it("cancels a signed payout by advancing the nonce", async () => {
+    const payCountBefore = await getTreasuryPayCount(program, treasuryPda);
+    const receiptPda = await nextPayReceipt();
+    const signed = await signWithNonce(await payIx(30, receiptPda));
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.nonceAdvance({
+          noncePubkey: nonceAccount.publicKey,
+          authorizedPubkey: protocolAuth.publicKey,
+        })
+      )
+    );
+
+    await expectReject(submit(signed), "Blockhash not found");
+    expect(await connection.getAccountInfo(receiptPda, "confirmed")).to.eq(null);
+    expect(await getTreasuryPayCount(program, treasuryPda)).to.eq(payCountBefore);
+  });
+});
@@ -0,0 +1,322 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+  deriveKycIssuerPda,
+} from "./_helpers";
+
+const KYC_BASIC = 1;
+const KYC_ENHANCED = 2;
+const KYC_THRESHOLD = 1_000;
+const ONE_DAY_SECS = 86_400;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+function jurisdiction(code: string): number[] {
+  return Array.from(Buffer.from(code, "ascii"));
+}
+
+/**
+ * KYC attestations consumed by payout and withdrawal flows.
+ *
+ * Canon:
+ * - only trusted issuers (added by compliance) write attestations
+ * - above kyc_threshold, the receiving wallet needs an unexpired attestation
+ *   of at least kyc_min_level (KycAttestationRequired / KycLevelTooLow)
+ * - amounts at or below the threshold need no attestation
+ */
+describe("protocol - kyc attestations", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let issuer: Keypair;
+  let issuerPda: PublicKey;
+  let recipient: Keypair;
+  let attestationPda: PublicKey;
+  let mint: PublicKey;
+  let treasuryAta: PublicKey;
+
+  async function setKycConfig(minLevel: number, threshold: number) {
+    await program.methods
+      .setKycConfig(minLevel, new anchor.BN(threshold))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        treasuryConfig: treasuryConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function addIssuer(signer: Keypair) {
+    await program.methods
+      .addKycIssuer()
+      .accounts({
+        authority: signer.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: null,
+        issuer: issuer.publicKey,
+        kycIssuer: issuerPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function issue(signer: Keypair, level: number, code: string, expiresAt?: number) {
+    const expiry = expiresAt ?? Math.floor(Date.now() / 1000) + ONE_DAY_SECS;
+    await program.methods
+      .issueKycAttestation(level, jurisdiction(code), new anchor.BN(expiry))
+      .accounts({
+        issuer: signer.publicKey,
+        treasury: treasuryPda,
+        kycIssuer: deriveKycIssuerPda(program.programId, treasuryPda, signer.publicKey)[0],
+        wallet: recipient.publicKey,
+        kycAttestation: attestationPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function revoke(signer: Keypair) {
+    await program.methods
+      .revokeKycAttestation()
+      .accounts({
+        authority: signer.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: null,
+        kycAttestation: attestationPda,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  function flowAccounts() {
+    return {
+      treasuryConfig: treasuryConfigPda,
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient.publicKey)[0],
+      kycAttestation: attestationPda,
+      travelRule: null,
+      trustScore: null,
+    };
+  }
+
+  async function pay(amount: number) {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    const [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCount);
+
+    await program.methods
+      .splPay(new anchor.BN(amount), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: recipient.publicKey,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient.publicKey),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        ...flowAccounts(),
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function withdraw(amount: number) {
+    await program.methods
+      .splWithdraw(new anchor.BN(amount))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        user: recipient.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: getAssociatedTokenAddressSync(mint, recipient.publicKey),
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        ...flowAccounts(),
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    issuer = Keypair.generate();
+    recipient = Keypair.generate();
+    const funder = Keypair.generate();
+    await airdrop(provider, issuer.publicKey, 1, "confirmed");
+    await airdrop(provider, funder.publicKey, 2, "confirmed");
+    [issuerPda] = deriveKycIssuerPda(program.programId, treasuryPda, issuer.publicKey);
+    [attestationPda] = deriveKycAttestationPda(program.programId, treasuryPda, recipient.publicKey);
+
+    const setup = await setupMintAndAtas(provider, funder, treasuryPda, 1_000_000n);
+    mint = setup.mint;
+    treasuryAta = setup.treasuryAta;
+
+    await program.methods
+      .splDeposit(new anchor.BN(1_000_000))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: setup.userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+
+    await setKycConfig(KYC_ENHANCED, KYC_THRESHOLD);
+  });
+
+  after(async () => {
+    // Leave the shared treasury config without a KYC requirement.
+    await setKycConfig(0, 0);
+
+    await program.methods
+      .removeKycIssuer()
+      .accounts({
+        authority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: null,
+        kycIssuer: issuerPda,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  });
+
+  it("non-compliance signers cannot add issuers", async () => {
+    const rogue = Keypair.generate();
+    await airdrop(provider, rogue.publicKey, 1, "confirmed");
+
+    await expectReject(addIssuer(rogue), "UnauthorizedCompliance");
+  });
+
+  it("untrusted keys cannot issue attestations", async () => {
+    await expectReject(issue(issuer, KYC_ENHANCED, "DE"), "AccountNotInitialized");
+  });
+
+  it("a trusted issuer rejects malformed attestations", async () => {
+    await addIssuer(protocolAuth);
+
+    await expectReject(issue(issuer, 0, "DE"), "InvalidKycAttestation");
+    await expectReject(issue(issuer, 5, "DE"), "InvalidKycAttestation");
+    await expectReject(issue(issuer, KYC_ENHANCED, "de"), "InvalidKycAttestation");
+    await expectReject(issue(issuer, KYC_ENHANCED, "DE", 1), "InvalidKycAttestation");
+  });
+
+  it("amounts at or below the threshold need no attestation", async () => {
+    await pay(KYC_THRESHOLD);
+  });
+
+  it("payouts and withdrawals above the threshold require an attestation", async () => {
+    await expectReject(pay(KYC_THRESHOLD + 1), "KycAttestationRequired");
+    await expectReject(withdraw(KYC_THRESHOLD + 1), "KycAttestationRequired");
+  });
+
+  it("an attestation below the minimum level is rejected", async () => {
+    await issue(issuer, KYC_BASIC, "DE");
+    await expectReject(pay(KYC_THRESHOLD + 1), "KycLevelTooLow");
+  });
+
+  it("a sufficient attestation unlocks payouts and withdrawals", async () => {
+    await issue(issuer, KYC_ENHANCED, "DE");
+
+    const a: any = await program.account.kycAttestation.fetch(attestationPda);
+    expect(a.wallet.toBase58()).to.eq(recipient.publicKey.toBase58());
+    expect(a.issuer.toBase58()).to.eq(issuer.publicKey.toBase58());
+    expect(toNum(a.level)).to.eq(KYC_ENHANCED);
+    expect(Buffer.from(a.jurisdiction).toString("ascii")).to.eq("DE");
+
+    await pay(KYC_THRESHOLD + 1);
+    await withdraw(KYC_THRESHOLD + 1);
+  });
+
+  it("revoking the attestation locks large payouts again", async () => {
+    await revoke(issuer);
+    expect(await provider.connection.getAccountInfo(attestationPda)).to.eq(null);
+
+    await expectReject(pay(KYC_THRESHOLD + 1), "KycAttestationRequired");
+  });
+});
@@ -0,0 +1,290 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveTreasuryConfigPda,
+  deriveDenylistEntryPda,
+  deriveMintLedgerPda,
+  deriveMintLimitsPda,
+  deriveUserLimitsPda,
+  deriveKycAttestationPda,
+  deriveMerchantPda,
+  deriveMerchantReceiptPda,
+} from "./_helpers";
+
+const DIR_MERCHANT_PAY = 5;
+const STATUS_ACTIVE = 1;
+const STATUS_SUSPENDED = 2;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * Merchant registry and merchant payments.
+ *
+ * Canon:
+ * - one merchant per (treasury, owner); registration needs the owner and
+ *   compliance, updates need the owner, suspension needs compliance
+ * - payMerchant resolves the payout wallet, accepted mints and fee from the
+ *   merchant account and the config's fee tier table
+ * - the receipt records gross amount and fee; per-mint stats accumulate on
+ *   the merchant
+ */
+describe("protocol - merchants", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let owner: Keypair;
+  let payoutWallet: Keypair;
+  let customer: Keypair;
+  let merchantPda: PublicKey;
+  let mint: PublicKey;
+  let otherMint: PublicKey;
+  let customerAta: PublicKey;
+  let treasuryAta: PublicKey;
+
+  async function setFeeTiers(feeBps: number[]) {
+    await program.methods
+      .setMerchantFeeTiers(feeBps)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        treasuryConfig: treasuryConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function register(authority: Keypair, mints: PublicKey[], feeTier: number) {
+    await program.methods
+      .registerMerchant(payoutWallet.publicKey, mints, feeTier)
+      .accounts({
+        owner: owner.publicKey,
+        authority: authority.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: null,
+        merchant: merchantPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([owner, authority])
+      .rpc();
+  }
+
+  async function setStatus(status: number, feeTier: number) {
+    await program.methods
+      .setMerchantStatus(status, feeTier)
+      .accounts({
+        authority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        complianceConfig: null,
+        merchant: merchantPda,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function payMerchant(amount: number, payMint: PublicKey, payout = payoutWallet.publicKey) {
+    const m: any = await program.account.merchant.fetch(merchantPda);
+    const [receiptPda] = deriveMerchantReceiptPda(program.programId, merchantPda, toNum(m.payCount));
+
+    await program.methods
+      .payMerchant(new anchor.BN(amount), null)
+      .accounts({
+        payer: customer.publicKey,
+        treasury: treasuryPda,
+        merchant: merchantPda,
+        payoutWallet: payout,
+        mint: payMint,
+        payerAta: getAssociatedTokenAddressSync(payMint, customer.publicKey),
+        payoutAta: getAssociatedTokenAddressSync(payMint, payout),
+        treasuryAta: getAssociatedTokenAddressSync(payMint, treasuryPda, true),
+        receipt: receiptPda,
+        treasuryConfig: treasuryConfigPda,
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, customer.publicKey)[0],
+        payoutDenylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payout)[0],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, payMint)[0],
+        userProfile: null,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, payMint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, customer.publicKey, payMint)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, customer.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([customer])
+      .rpc();
+
+    return receiptPda;
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    owner = Keypair.generate();
+    payoutWallet = Keypair.generate();
+    customer = Keypair.generate();
+    await airdrop(provider, owner.publicKey, 1, "confirmed");
+    await airdrop(provider, customer.publicKey, 2, "confirmed");
+    [merchantPda] = deriveMerchantPda(program.programId, treasuryPda, owner.publicKey);
+
+    const setup = await setupMintAndAtas(provider, customer, treasuryPda, 1_000_000n);
+    mint = setup.mint;
+    customerAta = setup.userAta;
+    treasuryAta = setup.treasuryAta;
+
+    otherMint = (await setupMintAndAtas(provider, customer, treasuryPda, 1_000_000n)).mint;
+  });
+
+  after(async () => {
+    // Leave the shared treasury config with fee-free merchant payments.
+    await setFeeTiers([0, 0, 0, 0]);
+  });
+
+  it("registration needs the treasury or compliance authority", async () => {
+    await expectReject(register(Keypair.generate(), [mint], 1), "UnauthorizedCompliance");
+  });
+
+  it("registration rejects malformed settings", async () => {
+    await expectReject(register(protocolAuth, [], 1), "InvalidMerchantConfig");
+    await expectReject(register(protocolAuth, [mint, mint], 1), "InvalidMerchantConfig");
+    await expectReject(register(protocolAuth, [mint], 4), "InvalidMerchantConfig");
+  });
+
+  it("owner and authority register an active merchant", async () => {
+    await register(protocolAuth, [mint], 1);
+
+    const m: any = await program.account.merchant.fetch(merchantPda);
+    expect(m.owner.toBase58()).to.eq(owner.publicKey.toBase58());
+    expect(m.payoutWallet.toBase58()).to.eq(payoutWallet.publicKey.toBase58());
+    expect(m.settlements[0].mint.toBase58()).to.eq(mint.toBase58());
+    expect(toNum(m.feeTier)).to.eq(1);
+    expect(toNum(m.status)).to.eq(STATUS_ACTIVE);
+    expect(toNum(m.payCount)).to.eq(0);
+  });
+
+  it("setMerchantFeeTiers rejects fees above 100%", async () => {
+    await expectReject(setFeeTiers([0, 10_001, 0, 0]), "InvalidMerchantConfig");
+  });
+
+  it("a payment splits into merchant payout and treasury fee", async () => {
+    await setFeeTiers([0, 250, 0, 0]);
+
+    const treasuryBefore = (await getAccount(provider.connection, treasuryAta)).amount;
+    const receiptPda = await payMerchant(10_000, mint);
+
+    const payout = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(mint, payoutWallet.publicKey)
+    );
+    expect(payout.amount).to.eq(9_750n);
+    expect((await getAccount(provider.connection, treasuryAta)).amount - treasuryBefore).to.eq(250n);
+    expect((await getAccount(provider.connection, customerAta)).amount).to.eq(990_000n);
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(toNum(r.direction)).to.eq(DIR_MERCHANT_PAY);
+    expect(r.user.toBase58()).to.eq(customer.publicKey.toBase58());
+    expect(toNum(r.amount)).to.eq(10_000);
+    expect(toNum(r.fee)).to.eq(250);
+    expect(toNum(r.txCount)).to.eq(0);
+
+    const m: any = await program.account.merchant.fetch(merchantPda);
+    expect(toNum(m.payCount)).to.eq(1);
+    expect(toNum(m.settlements[0].grossVolume)).to.eq(10_000);
+    expect(toNum(m.settlements[0].fees)).to.eq(250);
+  });
+
+  it("the payee cannot be substituted", async () => {
+    await expectReject(payMerchant(100, mint, customer.publicKey), "InvalidMerchantConfig");
+  });
+
+  it("mints outside the settlement list are rejected", async () => {
+    await expectReject(payMerchant(100, otherMint), "MerchantMintNotAccepted");
+  });
+
+  it("only the owner updates settlement details, keeping stats of kept mints", async () => {
+    await expectReject(
+      program.methods
+        .updateMerchant(customer.publicKey, [mint])
+        .accounts({ owner: customer.publicKey, treasury: treasuryPda, merchant: merchantPda } as any)
+        .signers([customer])
+        .rpc(),
+      "UnauthorizedMerchant"
+    );
+
+    await program.methods
+      .updateMerchant(payoutWallet.publicKey, [otherMint, mint])
+      .accounts({ owner: owner.publicKey, treasury: treasuryPda, merchant: merchantPda } as any)
+      .signers([owner])
+      .rpc();
+
+    const m: any = await program.account.merchant.fetch(merchantPda);
+    expect(m.settlements[0].mint.toBase58()).to.eq(otherMint.toBase58());
+    expect(m.settlements[1].mint.toBase58()).to.eq(mint.toBase58());
+    expect(toNum(m.settlements[1].grossVolume)).to.eq(10_000);
+
+    await payMerchant(100, otherMint);
+  });
+
+  it("a suspended merchant cannot be paid until reinstated", async () => {
+    await setStatus(STATUS_SUSPENDED, 1);
+    await expectReject(payMerchant(100, mint), "MerchantSuspended");
+
+    // Reinstated on the fee-free tier.
+    await setStatus(STATUS_ACTIVE, 0);
+    const receiptPda = await payMerchant(100, mint);
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(toNum(r.fee)).to.eq(0);
+  });
+});
@@ -0,0 +1,261 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAccount,
+  transfer,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * Per-mint ledger + assert_solvency.
+ *
+ * Canon:
+ * - only the treasury authority opens a ledger, once per mint
+ * - the treasury ATA balance at opening becomes the opening balance
+ * - every flow moving tokens in or out of the treasury ATA is recorded
+ * - assert_solvency passes while the ATA covers the ledger balance;
+ *   tokens sent to the ATA outside the program are a surplus
+ *
+ * Uses a fresh mint, so no other spec touches this ledger.
+ */
+describe("protocol - mint ledger + solvency", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+
+  let user: Keypair;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+  let mintLedgerPda: PublicKey;
+
+  function flowAccounts(wallet: PublicKey) {
+    return {
+      treasury: treasuryPda,
+      mint,
+      treasuryAta,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      userProfile: null,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: mintLedgerPda,
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, wallet, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, wallet)[0],
+      trustScore: null,
+    };
+  }
+
+  async function deposit(amount: number) {
+    await program.methods
+      .splDeposit(new anchor.BN(amount))
+      .accounts({ ...flowAccounts(user.publicKey), user: user.publicKey, userAta } as any)
+      .signers([user])
+      .rpc();
+  }
+
+  async function withdraw(amount: number) {
+    await program.methods
+      .splWithdraw(new anchor.BN(amount))
+      .accounts({
+        ...flowAccounts(user.publicKey),
+        treasuryAuthority: protocolAuth.publicKey,
+        user: user.publicKey,
+        userAta,
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+        travelRule: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function pay(amount: number) {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    await program.methods
+      .splPay(new anchor.BN(amount), null, null, null)
+      .accounts({
+        ...flowAccounts(user.publicKey),
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: user.publicKey,
+        recipientAta: userAta,
+        receipt: derivePayReceiptPda(program.programId, treasuryPda, payCount)[0],
+        memoProgram: null,
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+        travelRule: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function openLedger(signer: Keypair) {
+    await program.methods
+      .openMintLedger()
+      .accounts({
+        treasuryAuthority: signer.publicKey,
+        treasury: treasuryPda,
+        mint,
+        treasuryAta,
+        mintLedger: mintLedgerPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function assertSolvency() {
+    return program.methods
+      .assertSolvency()
+      .accounts({ treasury: treasuryPda, mint, treasuryAta, mintLedger: mintLedgerPda } as any)
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function ledgerBalance(): Promise<number> {
+    const l: any = await program.account.mintLedger.fetch(mintLedgerPda);
+    return (
+      toNum(l.openingBalance) +
+      toNum(l.deposited) +
+      toNum(l.fees) -
+      toNum(l.withdrawn) -
+      toNum(l.paid) -
+      toNum(l.refunded)
+    );
+  }
+
+  async function ataBalance(): Promise<number> {
+    return Number((await getAccount(provider.connection, treasuryAta)).amount);
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    user = Keypair.generate();
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+
+    ({ mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      user,
+      treasuryPda,
+      1_000_000n
+    ));
+
+    [mintLedgerPda] = deriveMintLedgerPda(program.programId, treasuryPda, mint);
+  });
+
+  it("flows before the ledger is opened record nothing", async () => {
+    await deposit(5_000);
+    expect(await provider.connection.getAccountInfo(mintLedgerPda)).to.eq(null);
+  });
+
+  it("only the treasury authority may open a ledger", async () => {
+    const stranger = Keypair.generate();
+    await airdrop(provider, stranger.publicKey, 1, "confirmed");
+    await expectReject(openLedger(stranger), "UnauthorizedWithdraw");
+  });
+
+  it("openMintLedger snapshots the treasury ATA balance", async () => {
+    await openLedger(protocolAuth);
+
+    const l: any = await program.account.mintLedger.fetch(mintLedgerPda);
+    expect(l.treasury.toBase58()).to.eq(treasuryPda.toBase58());
+    expect(l.mint.toBase58()).to.eq(mint.toBase58());
+    expect(toNum(l.openingBalance)).to.eq(5_000);
+    expect(toNum(l.deposited)).to.eq(0);
+    expect(await ledgerBalance()).to.eq(await ataBalance());
+  });
+
+  it("a ledger cannot be reopened", async () => {
+    await expectReject(openLedger(protocolAuth), "already in use");
+  });
+
+  it("deposits, withdrawals and payouts are recorded", async () => {
+    await deposit(2_000);
+    await withdraw(700);
+    await pay(300);
+
+    const l: any = await program.account.mintLedger.fetch(mintLedgerPda);
+    expect(toNum(l.deposited)).to.eq(2_000);
+    expect(toNum(l.withdrawn)).to.eq(700);
+    expect(toNum(l.paid)).to.eq(300);
+    expect(await ledgerBalance()).to.eq(6_000);
+    expect(await ataBalance()).to.eq(6_000);
+  });
+
+  it("assertSolvency passes when the ATA matches the ledger", async () => {
+    await assertSolvency();
+  });
+
+  it("tokens sent to the ATA outside the program are a surplus", async () => {
+    await transfer(provider.connection, user, userAta, treasuryAta, user, 50);
+
+    await assertSolvency();
+    expect(await ataBalance()).to.eq((await ledgerBalance()) + 50);
+  });
+
+  it("assertSolvency rejects a token account other than the treasury ATA", async () => {
+    await expectReject(
+      program.methods
+        .assertSolvency()
+        .accounts({ treasury: treasuryPda, mint, treasuryAta: userAta, mintLedger: mintLedgerPda } as any)
+        .rpc(),
+      "Constraint"
+    );
+  });
+});
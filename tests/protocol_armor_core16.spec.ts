@@ -18,6 +18,12 @@ import {
   deriveUserProfilePda,
   deriveWithdrawReceiptPda,
   PROGRAM_ID,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 function bn(x: number | bigint | string) {
@@ -161,6 +167,14 @@ async function buildSplDepositTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -228,6 +242,16 @@ async function buildSplWithdrawTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user)[0],
+    kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user)[0],
+    // Optional accounts: passing the program id means "None".
+    travelRule: program.programId,
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -301,6 +325,15 @@ async function buildSplWithdrawWithReceiptTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    travelRule: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -0,0 +1,222 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import {
+  Ed25519Program,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  deriveAttestorConfigPda,
+  getTreasuryPayCount,
+  buildReceiptAttestationMessage,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+/**
+ * Ed25519-signed receipt attestations.
+ *
+ * Canon:
+ * - message = domain || program_id || receipt || slot_le || receipt body
+ * - accepted signers: treasury authority, or the designated attestor PDA key
+ * - verifyReceiptAttestation requires the Ed25519 ix immediately before it
+ */
+describe("protocol - receipt attestation", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let attestorConfigPda: PublicKey;
+  let receiptPda: PublicKey;
+  let receiptData: Buffer;
+
+  const attestor = Keypair.generate();
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "finalized",
+      preflightCommitment: "finalized",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [attestorConfigPda] = deriveAttestorConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    const funder = Keypair.generate();
+    await airdrop(provider, funder.publicKey, 2, "finalized");
+    const { mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      funder,
+      treasuryPda,
+      1_000_000n
+    );
+
+    await program.methods
+      .splDeposit(new anchor.BN(1_000_000))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+
+    const recipient = Keypair.generate().publicKey;
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCount);
+
+    await program.methods
+      .splPay(new anchor.BN(10), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    const info = await provider.connection.getAccountInfo(receiptPda, "finalized");
+    receiptData = Buffer.from(info!.data);
+  });
+
+  async function verifyIx(withConfig: boolean): Promise<TransactionInstruction> {
+    return program.methods
+      .verifyReceiptAttestation()
+      .accounts({
+        treasury: treasuryPda,
+        receipt: receiptPda,
+        attestorConfig: withConfig ? attestorConfigPda : null,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      } as any)
+      .instruction();
+  }
+
+  async function sendVerify(signer: Keypair, message: Buffer, withConfig = false) {
+    const edIx = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: signer.secretKey,
+      message,
+    });
+    const tx = new Transaction().add(edIx, await verifyIx(withConfig));
+    return provider.sendAndConfirm(tx, [], { commitment: "confirmed" });
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  it("accepts an attestation signed by the treasury authority", async () => {
+    const slot = await provider.connection.getSlot("finalized");
+    const msg = buildReceiptAttestationMessage(program.programId, receiptPda, slot, receiptData);
+
+    const sig = await sendVerify(protocolAuth, msg);
+    expect(sig).to.be.a("string");
+  });
+
+  it("rejects an attestation from an unknown key", async () => {
+    const msg = buildReceiptAttestationMessage(program.programId, receiptPda, 1, receiptData);
+    await expectReject(sendVerify(Keypair.generate(), msg), "AttestationSignerUnauthorized");
+  });
+
+  it("accepts the designated attestor once set", async () => {
+    await program.methods
+      .setReceiptAttestor(attestor.publicKey)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        attestorConfig: attestorConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    const msg = buildReceiptAttestationMessage(program.programId, receiptPda, 7, receiptData);
+    const sig = await sendVerify(attestor, msg, true);
+    expect(sig).to.be.a("string");
+  });
+
+  it("rejects an attestation over tampered receipt content", async () => {
+    const tampered = Buffer.from(receiptData);
+    tampered[8 + 32 + 1 + 1 + 32] ^= 0xff; // amount low byte
+    const msg = buildReceiptAttestationMessage(program.programId, receiptPda, 1, tampered);
+    await expectReject(sendVerify(protocolAuth, msg), "AttestationMismatch");
+  });
+
+  it("rejects when no Ed25519 instruction precedes it", async () => {
+    const tx = new Transaction().add(await verifyIx(false));
+    await expectReject(
+      provider.sendAndConfirm(tx, [], { commitment: "confirmed" }),
+      "AttestationMissing"
+    );
+  });
+});
@@ -17,6 +17,11 @@ import {
   getAccountInfoOrNull,
   decodeReceiptFromAccountInfo,
   airdrop,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
 } from "./_helpers";
 
 function bn(x: number | string | bigint) {
@@ -122,6 +127,15 @@ async function buildSplDepositWithReceiptTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    memoProgram: program.programId,
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
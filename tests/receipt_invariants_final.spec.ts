@@ -18,6 +18,12 @@ import {
   deriveDepositReceiptPda,
   deriveWithdrawReceiptPda,
   getAccountInfoOrNull,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 function bn(x: number | bigint) {
@@ -136,9 +142,21 @@ describe("protocol - receipt invariants final (Core15.3)", function () {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+      // Optional accounts: passing the program id means "None".
+      memoProgram: program.programId,
+      userProfile: program.programId,
+      trustScore: program.programId,
     }, {
       amount: bn(amount),
       nonce: bn(nonce),
+      reference: null,
+      memo: null,
+      memoCpi: null,
     });
 
     await sendRawTx(provider, new Transaction().add(ix), [user]);
@@ -187,9 +205,21 @@ describe("protocol - receipt invariants final (Core15.3)", function () {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, userA.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, userA.publicKey)[0],
+      // Optional accounts: passing the program id means "None".
+      memoProgram: program.programId,
+      userProfile: program.programId,
+      trustScore: program.programId,
     }, {
       amount: bn(500),
       nonce: bn(nonce),
+      reference: null,
+      memo: null,
+      memoCpi: null,
     });
 
     await sendRawTx(provider, new Transaction().add(ixA), [userA]);
@@ -208,9 +238,21 @@ describe("protocol - receipt invariants final (Core15.3)", function () {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, userB.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, userB.publicKey)[0],
+        // Optional accounts: passing the program id means "None".
+        memoProgram: program.programId,
+        userProfile: program.programId,
+        trustScore: program.programId,
       }, {
         amount: bn(500),
         nonce: bn(nonce),
+        reference: null,
+        memo: null,
+        memoCpi: null,
       });
 
       await sendRawTx(provider, new Transaction().add(ixB), [userB]);
@@ -242,6 +284,14 @@ describe("protocol - receipt invariants final (Core15.3)", function () {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+      // Optional accounts: passing the program id means "None".
+      userProfile: program.programId,
+      trustScore: program.programId,
     }, {
       amount: bn(1_000_000),
     });
@@ -283,8 +333,19 @@ describe("protocol - receipt invariants final (Core15.3)", function () {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+      kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+      // Optional accounts: passing the program id means "None".
+      travelRule: program.programId,
+      trustScore: program.programId,
     }, {
       amount: bn(1000),
+      reference: null,
+      memo: null,
     });
 
     await sendRawTx(
@@ -0,0 +1,225 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+import {
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  deriveWithdrawReceiptPda,
+  deriveTreasuryPda,
+  deriveUserProfilePda,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+import { findEvent } from "./helpers/events";
+
+import { Protocol } from "../target/types/protocol";
+
+const V2_FLAG_HAS_REFERENCE = 1 << 0;
+const V2_FLAG_HAS_MEMO = 1 << 1;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * Deposit/withdraw-with-receipt reference + memo metadata.
+ *
+ * Canon:
+ * - optional reference/memo are stored through ReceiptV2Ext::spl_with_meta
+ * - memo is bounded by ReceiptV2Ext::MAX_MEMO_LEN (64)
+ * - DepositEvent / WithdrawEvent carry has_reference/reference/has_memo/memo_len
+ */
+describe("protocol - deposit/withdraw receipt metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Protocol as Program<Protocol>;
+  const payer = (provider.wallet as any).payer as anchor.web3.Keypair;
+  const protocolAuth = loadProtocolAuthority();
+
+  let treasuryPda: PublicKey;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, protocolAuth.publicKey, 2);
+    [treasuryPda] = deriveTreasuryPda();
+
+    try {
+      await program.methods
+        .initializeTreasury()
+        .accounts({
+          authority: protocolAuth.publicKey,
+          treasury: treasuryPda,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([protocolAuth])
+        .rpc();
+    } catch (_e) {
+      // Treasury likely already exists — safe to ignore
+    }
+
+    mint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    userAta = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, payer.publicKey)
+    ).address;
+    treasuryAta = getAssociatedTokenAddressSync(mint, treasuryPda, true);
+    await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, treasuryPda, true);
+
+    await mintTo(provider.connection, payer, mint, userAta, payer, 10_000_000);
+  });
+
+  async function depositWithReceipt(reference: number[] | null, memo: Buffer | null) {
+    const nonce = new anchor.BN(Date.now());
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, payer.publicKey, nonce);
+
+    const txSig = await program.methods
+      .splDepositWithReceipt(new anchor.BN(1_000), nonce, reference, memo, null)
+      .accounts({
+        user: payer.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([payer])
+      .rpc({ commitment: "confirmed" });
+
+    return { txSig, receiptPda };
+  }
+
+  async function logsOf(txSig: string): Promise<string[]> {
+    const tx = await provider.connection.getTransaction(txSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    } as any);
+    if (!tx) throw new Error("getTransaction returned null (even after confirm)");
+    return tx.meta?.logMessages ?? [];
+  }
+
+  it("deposit-with-receipt stores reference + memo and emits them", async () => {
+    const reference = new Array(32).fill(27);
+    const memo = Buffer.from("order:8812", "utf8");
+
+    const { txSig, receiptPda } = await depositWithReceipt(reference, memo);
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(toNum(r.v2.flags)).to.eq(V2_FLAG_HAS_REFERENCE | V2_FLAG_HAS_MEMO);
+    expect(Array.from(r.v2.reference)).to.deep.eq(reference);
+    expect(toNum(r.v2.memoLen)).to.eq(memo.length);
+    expect(Buffer.from(r.v2.memo).subarray(0, memo.length).equals(memo)).to.eq(true);
+
+    const { hit } = findEvent(program as any, await logsOf(txSig), ["DepositEvent"]);
+    expect(hit).to.not.eq(null);
+    expect(hit.hasReference).to.eq(true);
+    expect(Array.from(hit.reference)).to.deep.eq(reference);
+    expect(hit.hasMemo).to.eq(true);
+    expect(toNum(hit.memoLen)).to.eq(memo.length);
+  });
+
+  it("deposit-with-receipt without metadata keeps v2 empty", async () => {
+    const { receiptPda } = await depositWithReceipt(null, null);
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(toNum(r.v2.flags)).to.eq(0);
+    expect(toNum(r.v2.memoLen)).to.eq(0);
+  });
+
+  it("deposit-with-receipt rejects memo > 64 bytes", async () => {
+    let threw = false;
+    try {
+      await depositWithReceipt(null, Buffer.alloc(65, 1));
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e).toLowerCase()).to.satisfy(
+        (m: string) => m.includes("memo too long") || m.includes("memotoolong")
+      );
+    }
+    expect(threw).to.eq(true);
+  });
+
+  it("withdraw-with-receipt stores reference + memo and emits them", async () => {
+    const [userProfilePda] = deriveUserProfilePda(payer.publicKey);
+
+    let txCount = new anchor.BN(0);
+    try {
+      const up: any = await program.account.userProfile.fetch(userProfilePda);
+      txCount = new anchor.BN(up.txCount.toString());
+    } catch (_e) {
+      // fresh profile => txCount = 0
+    }
+    const [receiptPda] = deriveWithdrawReceiptPda(program.programId, payer.publicKey, txCount);
+
+    const reference = new Array(32).fill(42);
+    const memo = Buffer.from("payout:ref-42", "utf8");
+
+    const txSig = await program.methods
+      .splWithdrawWithReceipt(new anchor.BN(500), reference, memo)
+      .accounts({
+        user: payer.publicKey,
+        treasuryAuthority: protocolAuth.publicKey,
+        userProfile: userProfilePda,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, payer.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc({ commitment: "confirmed" });
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(toNum(r.v2.flags)).to.eq(V2_FLAG_HAS_REFERENCE | V2_FLAG_HAS_MEMO);
+    expect(Array.from(r.v2.reference)).to.deep.eq(reference);
+    expect(toNum(r.v2.memoLen)).to.eq(memo.length);
+
+    const { hit } = findEvent(program as any, await logsOf(txSig), ["WithdrawEvent"]);
+    expect(hit).to.not.eq(null);
+    expect(hit.hasReference).to.eq(true);
+    expect(Array.from(hit.reference)).to.deep.eq(reference);
+    expect(hit.hasMemo).to.eq(true);
+    expect(toNum(hit.memoLen)).to.eq(memo.length);
+  });
+});
@@ -21,6 +21,12 @@ import {
   deriveUserProfilePda,
   airdrop,
   loadProtocolAuthority,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 function bn(x: number | bigint | string) {
@@ -113,6 +119,14 @@ async function buildSplDepositTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -180,6 +194,15 @@ async function buildSplDepositWithReceiptTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    memoProgram: program.programId,
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -252,6 +275,15 @@ async function buildSplWithdrawWithReceiptTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    travelRule: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -0,0 +1,193 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  decodeReceiptFromAccountInfo,
+  RECEIPT_CURRENT_VERSION,
+  RECEIPT_SPACE,
+  RECEIPT_SPACE_V1,
+  RECEIPT_VERSION_1,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+/**
+ * Receipt schema versioning + migrate_receipt.
+ *
+ * Canon:
+ * - every flow writes Receipt::CURRENT_VERSION
+ * - v1 (no version byte) accounts decode as version 1
+ * - migrateReceipt is a no-op on current receipts and rejects foreign accounts
+ */
+describe("protocol - receipt versioning", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let receiptPda: PublicKey;
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "finalized",
+      preflightCommitment: "finalized",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    const funder = Keypair.generate();
+    await airdrop(provider, funder.publicKey, 2, "finalized");
+    const { mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      funder,
+      treasuryPda,
+      1_000_000n
+    );
+
+    await program.methods
+      .splDeposit(new anchor.BN(1_000_000))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+
+    const recipient = Keypair.generate().publicKey;
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCount);
+
+    await program.methods
+      .splPay(new anchor.BN(10), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  });
+
+  it("new receipts are written at the current schema version", async () => {
+    const info = await provider.connection.getAccountInfo(receiptPda, "finalized");
+    expect(info).to.not.eq(null);
+    expect(info!.data.length).to.eq(RECEIPT_SPACE);
+
+    const r: any = decodeReceiptFromAccountInfo(program, info!);
+    expect(Number(r.version)).to.eq(RECEIPT_CURRENT_VERSION);
+  });
+
+  it("v1 (no version byte) data decodes as version 1 with fields intact", async () => {
+    const info = await provider.connection.getAccountInfo(receiptPda, "finalized");
+    const current: any = decodeReceiptFromAccountInfo(program, info!);
+
+    const legacy = { ...info!, data: info!.data.subarray(0, RECEIPT_SPACE_V1) };
+    const r: any = decodeReceiptFromAccountInfo(program, legacy as any);
+
+    expect(Number(r.version)).to.eq(RECEIPT_VERSION_1);
+    expect(r.user.toBase58()).to.eq(current.user.toBase58());
+    expect(r.amount.toString()).to.eq(current.amount.toString());
+    expect(r.txCount.toString()).to.eq(current.txCount.toString());
+  });
+
+  it("migrateReceipt is a no-op on a current receipt", async () => {
+    const before = await provider.connection.getAccountInfo(receiptPda, "finalized");
+
+    await program.methods
+      .migrateReceipt()
+      .accounts({
+        payer: protocolAuth.publicKey,
+        receipt: receiptPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    const after = await provider.connection.getAccountInfo(receiptPda, "finalized");
+    expect(Buffer.compare(before!.data, after!.data)).to.eq(0);
+    expect(after!.lamports).to.eq(before!.lamports);
+  });
+
+  it("migrateReceipt rejects accounts not owned by the program", async () => {
+    const foreign = Keypair.generate();
+    await airdrop(provider, foreign.publicKey, 1, "finalized");
+
+    let threw = false;
+    try {
+      await program.methods
+        .migrateReceipt()
+        .accounts({
+          payer: protocolAuth.publicKey,
+          receipt: foreign.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([protocolAuth])
+        .rpc();
+    } catch {
+      threw = true;
+    }
+    expect(threw).to.eq(true);
+  });
+});
@@ -0,0 +1,237 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  deriveUserProfilePda,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  receiptRiskFlags,
+  RISK_FLAGS_ALL,
+  RISK_HIGH_VELOCITY,
+  RISK_LARGE_AMOUNT,
+  RISK_NEW_PROFILE,
+  RISK_ROUND_AMOUNT,
+} from "./_helpers";
+import { findEvent } from "./helpers/events";
+
+const LARGE_THRESHOLD = 100_000;
+const ONE_TOKEN = 1_000_000; // 6 decimals
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * On-chain risk flag evaluation.
+ *
+ * Canon:
+ * - only rules in risk_rules_mask run; the result lands in event risk_flags
+ *   and in the high byte of receipt v2.flags
+ * - flags in risk_blocking_mask reject the flow with RiskFlagBlocked
+ * - the treasury config PDA is always passed, so rules cannot be skipped
+ */
+describe("protocol - risk flags", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let user: Keypair;
+  let profilePda: PublicKey;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+
+  function riskParams(overrides: Record<string, any> = {}) {
+    return {
+      rulesMask: 0,
+      blockingMask: 0,
+      defaultThreshold: new anchor.BN(0),
+      mintThresholds: [],
+      newProfileSecs: new anchor.BN(0),
+      velocityWindowSlots: new anchor.BN(0),
+      velocityMaxCount: 0,
+      roundWholeUnits: new anchor.BN(0),
+      ...overrides,
+    };
+  }
+
+  async function setRiskConfig(params: any) {
+    await program.methods
+      .setRiskConfig(params)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        treasuryConfig: treasuryConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  function activeParams(blockingMask = 0) {
+    return riskParams({
+      rulesMask: RISK_FLAGS_ALL,
+      blockingMask,
+      mintThresholds: [{ mint, threshold: new anchor.BN(LARGE_THRESHOLD) }],
+      velocityWindowSlots: new anchor.BN(10_000),
+      velocityMaxCount: 2,
+      roundWholeUnits: new anchor.BN(1),
+    });
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    user = Keypair.generate();
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+    [profilePda] = deriveUserProfilePda(user.publicKey);
+
+    await program.methods
+      .initUserProfile()
+      .accounts({
+        user: user.publicKey,
+        userProfile: profilePda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([user])
+      .rpc();
+
+    ({ mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      user,
+      treasuryPda,
+      10_000_000n
+    ));
+
+    await setRiskConfig(activeParams());
+  });
+
+  after(async () => {
+    // Leave the shared treasury config with every risk rule off.
+    await setRiskConfig(riskParams());
+  });
+
+  async function deposit(amount: number, withProfile = true) {
+    const nonce = new anchor.BN(Date.now()).add(new anchor.BN(Math.floor(Math.random() * 1000)));
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, user.publicKey, nonce);
+
+    const txSig = await program.methods
+      .splDepositWithReceipt(new anchor.BN(amount), nonce, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: withProfile ? profilePda : null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(txSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    } as any);
+    const { hit } = findEvent(program as any, tx?.meta?.logMessages ?? [], ["DepositEvent"]);
+    expect(hit).to.not.eq(null);
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    return { eventFlags: toNum(hit.riskFlags), receiptFlags: toNum(r.v2.flags) };
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  it("a deposit without a profile raises NEW_PROFILE and HIGH_VELOCITY", async () => {
+    // Without the profile the velocity window cannot be counted.
+    const { eventFlags, receiptFlags } = await deposit(1_234, false);
+    expect(eventFlags).to.eq(RISK_NEW_PROFILE | RISK_HIGH_VELOCITY);
+    expect(receiptRiskFlags(receiptFlags)).to.eq(eventFlags);
+  });
+
+  it("the first profile deposit flags a new profile, a large amount and a round amount", async () => {
+    const { eventFlags, receiptFlags } = await deposit(2 * ONE_TOKEN);
+    expect(eventFlags).to.eq(RISK_NEW_PROFILE | RISK_LARGE_AMOUNT | RISK_ROUND_AMOUNT);
+
+    // Metadata flags (low byte) are untouched by risk flags.
+    expect(receiptFlags & 0xff).to.eq(0);
+    expect(receiptRiskFlags(receiptFlags)).to.eq(eventFlags);
+  });
+
+  it("an ordinary follow-up deposit raises nothing", async () => {
+    const { eventFlags } = await deposit(1_234);
+    expect(eventFlags).to.eq(0);
+  });
+
+  it("exceeding the velocity window count raises HIGH_VELOCITY", async () => {
+    const { eventFlags } = await deposit(1_235);
+    expect(eventFlags).to.eq(RISK_HIGH_VELOCITY);
+  });
+
+  it("blocking flags reject the flow", async () => {
+    await setRiskConfig(activeParams(RISK_LARGE_AMOUNT));
+    await expectReject(deposit(LARGE_THRESHOLD + 1), "RiskFlagBlocked");
+    await setRiskConfig(activeParams());
+  });
+
+  it("setRiskConfig rejects blocking flags outside the enabled rules", async () => {
+    await expectReject(
+      setRiskConfig(riskParams({ rulesMask: RISK_LARGE_AMOUNT, blockingMask: RISK_NEW_PROFILE })),
+      "InvalidRiskConfig"
+    );
+  });
+});
@@ -0,0 +1,217 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+} from "./_helpers";
+
+const DAY_SECS = 86_400;
+const TIER0_MAX_COUNT = 3;
+const TIER0_MAX_AMOUNT = 1_000_000;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * Per-user spending limits.
+ *
+ * Canon:
+ * - limits apply only to mints with a MintLimits account
+ * - the user limits PDA is created by the first flow (tier 0)
+ * - count and amount are checked over a sliding window of the tier's period
+ * - only the treasury authority sets tiers
+ *
+ * Uses a fresh mint, so other specs never see these limits.
+ */
+describe("protocol - spending limits", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+
+  let user: Keypair;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+  let mintLimitsPda: PublicKey;
+  let userLimitsPda: PublicKey;
+
+  async function setLimitTier(
+    tier: number,
+    periodSecs: number,
+    maxCount: number,
+    maxAmount: number,
+    signer: Keypair = protocolAuth
+  ) {
+    await program.methods
+      .setLimitTier(tier, new anchor.BN(periodSecs), maxCount, new anchor.BN(maxAmount))
+      .accounts({
+        treasuryAuthority: signer.publicKey,
+        treasury: treasuryPda,
+        mint,
+        mintLimits: mintLimitsPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  async function setUserLimitTier(tier: number) {
+    await program.methods
+      .setUserLimitTier(tier)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        user: user.publicKey,
+        mint,
+        userLimits: userLimitsPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function deposit(amount: number) {
+    const nonce = new anchor.BN(Date.now()).add(new anchor.BN(Math.floor(Math.random() * 1000)));
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, user.publicKey, nonce);
+
+    await program.methods
+      .splDepositWithReceipt(new anchor.BN(amount), nonce, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: mintLimitsPda,
+        userLimits: userLimitsPda,
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    user = Keypair.generate();
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+
+    ({ mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      user,
+      treasuryPda,
+      10_000_000n
+    ));
+
+    [mintLimitsPda] = deriveMintLimitsPda(program.programId, treasuryPda, mint);
+    [userLimitsPda] = deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint);
+  });
+
+  it("flows in a mint without limits do not create a user limits account", async () => {
+    await deposit(1_000);
+    expect(await provider.connection.getAccountInfo(userLimitsPda)).to.eq(null);
+  });
+
+  it("setLimitTier stores the tier", async () => {
+    await setLimitTier(0, DAY_SECS, TIER0_MAX_COUNT, TIER0_MAX_AMOUNT);
+
+    const l: any = await program.account.mintLimits.fetch(mintLimitsPda);
+    expect(l.treasury.toBase58()).to.eq(treasuryPda.toBase58());
+    expect(l.mint.toBase58()).to.eq(mint.toBase58());
+    expect(toNum(l.tiers[0].periodSecs)).to.eq(DAY_SECS);
+    expect(toNum(l.tiers[0].maxCount)).to.eq(TIER0_MAX_COUNT);
+    expect(toNum(l.tiers[0].maxAmount)).to.eq(TIER0_MAX_AMOUNT);
+  });
+
+  it("an amount above the tier allowance is rejected", async () => {
+    await expectReject(deposit(TIER0_MAX_AMOUNT + 1), "LimitAmountExceeded");
+  });
+
+  it("flows within the tier succeed and create the user limits account", async () => {
+    for (let i = 0; i < TIER0_MAX_COUNT; i++) await deposit(1_000);
+
+    const u: any = await program.account.userLimits.fetch(userLimitsPda);
+    expect(u.user.toBase58()).to.eq(user.publicKey.toBase58());
+    expect(u.mint.toBase58()).to.eq(mint.toBase58());
+    expect(toNum(u.tier)).to.eq(0);
+    expect(toNum(u.windowPeriodSecs)).to.eq(DAY_SECS);
+  });
+
+  it("a flow past the tier count is rejected", async () => {
+    await expectReject(deposit(1_000), "LimitCountExceeded");
+  });
+
+  it("moving the user to a higher tier allows further flows", async () => {
+    await setLimitTier(1, DAY_SECS, 10, 0);
+    await setUserLimitTier(1);
+
+    await deposit(1_000);
+
+    const u: any = await program.account.userLimits.fetch(userLimitsPda);
+    expect(toNum(u.tier)).to.eq(1);
+  });
+
+  it("setLimitTier rejects non-authority signers", async () => {
+    const rogue = Keypair.generate();
+    await airdrop(provider, rogue.publicKey, 1, "confirmed");
+
+    await expectReject(setLimitTier(0, 0, 0, 0, rogue), "UnauthorizedWithdraw");
+  });
+
+  it("setLimitTier rejects tier indices past the table", async () => {
+    await expectReject(setLimitTier(4, DAY_SECS, 1, 1), "InvalidLimitTier");
+  });
+});
@@ -0,0 +1,204 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  derivePayReceiptPda,
+  deriveReferenceIndexPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/**
+ * Optional SPL Memo program CPI (wallet-visible memos).
+ *
+ * Canon:
+ * - memoCpi = null      → no CPI, memoProgram may be omitted (None)
+ * - memoCpi = raw       → instruction memo bytes are forwarded (UTF-8 only)
+ * - memoCpi = receiptLink → "zephyon:<receipt pubkey>" is written
+ */
+describe("protocol - spl memo cpi", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+
+  let funder: Keypair;
+  let mint: PublicKey;
+  let funderAta: PublicKey;
+  let treasuryAta: PublicKey;
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "finalized",
+      preflightCommitment: "finalized",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    funder = Keypair.generate();
+    await airdrop(provider, funder.publicKey, 2, "finalized");
+    ({ mint, userAta: funderAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      funder,
+      treasuryPda,
+      10_000_000n
+    ));
+  });
+
+  async function logsOf(txSig: string): Promise<string[]> {
+    const tx = await provider.connection.getTransaction(txSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    } as any);
+    if (!tx) throw new Error("getTransaction returned null (even after confirm)");
+    return tx.meta?.logMessages ?? [];
+  }
+
+  async function depositWithReceipt(memo: Buffer | null, memoCpi: any, memoProgram: PublicKey | null) {
+    const nonce = new anchor.BN(Date.now());
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, funder.publicKey, nonce);
+
+    const txSig = await program.methods
+      .splDepositWithReceipt(new anchor.BN(1_000_000), nonce, null, memo, memoCpi)
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: funderAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc({ commitment: "confirmed" });
+
+    return { txSig, receiptPda };
+  }
+
+  it("deposit-with-receipt forwards raw memo bytes to the SPL Memo program", async () => {
+    const memo = Buffer.from("order:5531", "utf8");
+    const { txSig } = await depositWithReceipt(memo, { raw: {} }, MEMO_PROGRAM_ID);
+
+    const logs = await logsOf(txSig);
+    expect(logs.some((l) => l.includes(`Program ${MEMO_PROGRAM_ID.toBase58()} invoke`))).to.eq(true);
+    expect(logs.some((l) => l.includes("order:5531"))).to.eq(true);
+  });
+
+  it("pay-with-reference writes a zephyon:<receipt> link memo", async () => {
+    const recipient = Keypair.generate().publicKey;
+    const reference = Keypair.generate().publicKey.toBuffer();
+
+    const payCountBefore = await getTreasuryPayCount(program, treasuryPda);
+    const [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCountBefore);
+    const [referenceIndexPda] = deriveReferenceIndexPda(program.programId, treasuryPda, reference);
+
+    const txSig = await program.methods
+      .splPayWithReference(new anchor.BN(100), Array.from(reference), null, { receiptLink: {} })
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient),
+        treasuryAta,
+        receipt: receiptPda,
+        referenceIndex: referenceIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: MEMO_PROGRAM_ID,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc({ commitment: "confirmed" });
+
+    const logs = await logsOf(txSig);
+    expect(logs.some((l) => l.includes(`zephyon:${receiptPda.toBase58()}`))).to.eq(true);
+  });
+
+  it("no memo CPI when memoCpi is null", async () => {
+    const { txSig } = await depositWithReceipt(Buffer.from("quiet", "utf8"), null, null);
+
+    const logs = await logsOf(txSig);
+    expect(logs.some((l) => l.includes(MEMO_PROGRAM_ID.toBase58()))).to.eq(false);
+  });
+
+  it("rejects a memo CPI mode without the memo program account", async () => {
+    let threw = false;
+    try {
+      await depositWithReceipt(Buffer.from("x", "utf8"), { raw: {} }, null);
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e)).to.satisfy(
+        (m: string) => m.includes("MemoProgramRequired") || m.toLowerCase().includes("memo program")
+      );
+    }
+    expect(threw).to.eq(true);
+  });
+
+  it("rejects raw memo CPI with non-UTF-8 bytes", async () => {
+    let threw = false;
+    try {
+      await depositWithReceipt(Buffer.from([0xff, 0xfe, 0xfd]), { raw: {} }, MEMO_PROGRAM_ID);
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e)).to.satisfy(
+        (m: string) => m.includes("MemoNotUtf8") || m.includes("UTF-8")
+      );
+    }
+    expect(threw).to.eq(true);
+  });
+});
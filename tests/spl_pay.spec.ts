@@ -24,6 +24,12 @@ import {
   airdrop,
   DIR_PAY,
   ASSET_SPL,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 const DEBUG = process.env.DEBUG_TESTS === "1";
@@ -202,6 +208,14 @@ async function buildSplDepositTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -276,6 +290,17 @@ async function buildSplPayTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+    kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+    // Optional accounts: passing the program id means "None".
+    travelRule: program.programId,
+    memoProgram: program.programId,
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -283,7 +308,7 @@ async function buildSplPayTx(args: {
     const lower = String(a.name).toLowerCase();
     if (lower === "amount") argsObj[a.name] = bn(amount);
     else if (lower.includes("reference")) argsObj[a.name] = reference;
-    else if (lower.includes("memo")) argsObj[a.name] = memo;
+    else if (lower === "memo") argsObj[a.name] = memo;
     else if (lower.includes("nonce")) {
       throw new Error("spl_pay.spec.ts expects canonical payCount-mode splPay, not nonce-mode.");
     } else {
@@ -773,13 +798,12 @@ describe("protocol - spl pay (Core17)", function () {
     }
   });
 
-  it("Core21) splPay writes reference + memo metadata into receipt.v2", async () => {
+  it("Core21) splPay writes memo metadata into receipt.v2", async () => {
     const { mint, treasuryAta } = await seedTreasury(1_000_000n);
 
     const recipient = Keypair.generate();
     const { receiptPda, recipientAta } = await payCountBeforeAndReceipt(mint, recipient.publicKey);
 
-    const reference = new Array(32).fill(7);
     const memoBuf = Buffer.from("invoice:1234|core21", "utf8");
 
     const payTx = await buildSplPayTx({
@@ -792,7 +816,7 @@ describe("protocol - spl pay (Core17)", function () {
       treasuryAta,
       receipt: receiptPda,
       amount: 777n,
-      reference,
+      reference: null,
       memo: memoBuf,
     });
 
@@ -805,14 +829,54 @@ describe("protocol - spl pay (Core17)", function () {
 
     const r: any = await (program.account as any).receipt.fetch(receiptPda);
 
-    expect(toNum(r.v2.flags)).to.eq(V2_FLAG_HAS_REFERENCE | V2_FLAG_HAS_MEMO);
-    expect(Array.from(r.v2.reference)).to.deep.eq(reference);
+    expect(toNum(r.v2.flags)).to.eq(V2_FLAG_HAS_MEMO);
     expect(toNum(r.v2.memoLen)).to.eq(memoBuf.length);
 
     const gotMemo = Uint8Array.from(r.v2.memo).slice(0, toNum(r.v2.memoLen));
     expect(Array.from(gotMemo)).to.deep.eq(Array.from(memoBuf));
   });
 
+  it("Core21) splPay rejects a reference (splPayWithReference indexes it)", async () => {
+    const { mint, treasuryAta } = await seedTreasury(1_000_000n);
+
+    const recipient = Keypair.generate();
+    const { receiptPda, recipientAta } = await payCountBeforeAndReceipt(mint, recipient.publicKey);
+
+    let threw = false;
+    try {
+      const payTx = await buildSplPayTx({
+        program: programAny,
+        treasuryAuthority: protocolAuth,
+        treasuryPda,
+        mint,
+        recipient: recipient.publicKey,
+        recipientAta,
+        treasuryAta,
+        receipt: receiptPda,
+        amount: 777n,
+        reference: new Array(32).fill(7),
+        memo: null,
+      });
+
+      await sendRawTxFresh({
+        provider,
+        tx: payTx,
+        signers: [protocolAuth],
+        commitment: "finalized",
+      });
+    } catch (e: any) {
+      threw = true;
+      const msg = String(e?.message ?? e).toLowerCase();
+      expect(msg).to.satisfy(
+        (m: string) => m.includes("spl_pay_with_reference") || m.includes("unindexedreference")
+      );
+    }
+
+    expect(threw).to.eq(true);
+    const info = await provider.connection.getAccountInfo(receiptPda);
+    expect(info).to.eq(null);
+  });
+
   it("Core21) splPay with null metadata stores empty v2 fields", async () => {
     const { mint, treasuryAta } = await seedTreasury(1_000_000n);
 
@@ -853,7 +917,6 @@ describe("protocol - spl pay (Core17)", function () {
     const recipient = Keypair.generate();
     const { receiptPda, recipientAta } = await payCountBeforeAndReceipt(mint, recipient.publicKey);
 
-    const reference = new Array(32).fill(1);
     const tooLongMemo = Buffer.from(new Uint8Array(65).fill(9));
 
     let threw = false;
@@ -868,7 +931,7 @@ describe("protocol - spl pay (Core17)", function () {
         treasuryAta,
         receipt: receiptPda,
         amount: 1n,
-        reference,
+        reference: null,
         memo: tooLongMemo,
       });
 
@@ -0,0 +1,231 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  deriveReferenceIndexPda,
+  getTreasuryPayCount,
+  getTokenBalanceOrZero,
+  DIR_PAY,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
+const V2_FLAG_HAS_REFERENCE = 1 << 0;
+
+/**
+ * splPayWithReference — reference-keyed idempotency index.
+ *
+ * Canon:
+ * - index PDA seeds = ["reference", treasury, reference]
+ * - the index is created atomically with the pay receipt and points at it
+ * - a second pay with the same reference must fail and move no funds
+ */
+describe("protocol - spl pay reference index", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "finalized",
+      preflightCommitment: "finalized",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+
+    const foundation = await initFoundationOnce(provider, program);
+    treasuryPda = foundation.treasuryPda;
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+  });
+
+  async function seedTreasury(amount: bigint) {
+    const funder = Keypair.generate();
+    await airdrop(provider, funder.publicKey, 2, "finalized");
+
+    const { mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      funder,
+      treasuryPda,
+      amount
+    );
+
+    await program.methods
+      .splDeposit(new anchor.BN(amount.toString()))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+
+    return { mint, treasuryAta };
+  }
+
+  async function payWithReference(args: {
+    mint: PublicKey;
+    treasuryAta: PublicKey;
+    recipient: PublicKey;
+    reference: Buffer;
+    amount: bigint;
+  }) {
+    const { mint, treasuryAta, recipient, reference, amount } = args;
+
+    const payCountBefore = await getTreasuryPayCount(program, treasuryPda);
+    const [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCountBefore);
+    const [referenceIndexPda] = deriveReferenceIndexPda(program.programId, treasuryPda, reference);
+    const recipientAta = getAssociatedTokenAddressSync(mint, recipient);
+
+    await program.methods
+      .splPayWithReference(new anchor.BN(amount.toString()), Array.from(reference), null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient,
+        treasury: treasuryPda,
+        mint,
+        recipientAta,
+        treasuryAta,
+        receipt: receiptPda,
+        referenceIndex: referenceIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+        travelRule: null,
+        trustScore: null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    return { payCountBefore, receiptPda, referenceIndexPda, recipientAta };
+  }
+
+  it("creates a reference index pointing at the pay receipt", async () => {
+    const { mint, treasuryAta } = await seedTreasury(1_000_000n);
+    const recipient = Keypair.generate().publicKey;
+    const reference = Keypair.generate().publicKey.toBuffer();
+
+    const { payCountBefore, receiptPda, referenceIndexPda } = await payWithReference({
+      mint,
+      treasuryAta,
+      recipient,
+      reference,
+      amount: 1_234n,
+    });
+
+    const idx: any = await program.account.referenceIndex.fetch(referenceIndexPda);
+    expect(idx.treasury.toBase58()).to.eq(treasuryPda.toBase58());
+    expect(idx.receipt.toBase58()).to.eq(receiptPda.toBase58());
+    expect(Array.from(idx.reference)).to.deep.eq(Array.from(reference));
+    expect(idx.payCount.toString()).to.eq(payCountBefore.toString());
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(Number(r.direction)).to.eq(DIR_PAY);
+    expect(Number(r.v2.flags) & V2_FLAG_HAS_REFERENCE).to.eq(V2_FLAG_HAS_REFERENCE);
+    expect(Array.from(r.v2.reference)).to.deep.eq(Array.from(reference));
+  });
+
+  it("resolves a receipt from the reference alone", async () => {
+    const { mint, treasuryAta } = await seedTreasury(1_000_000n);
+    const recipient = Keypair.generate().publicKey;
+    const reference = Keypair.generate().publicKey.toBuffer();
+
+    const { receiptPda } = await payWithReference({
+      mint,
+      treasuryAta,
+      recipient,
+      reference,
+      amount: 55n,
+    });
+
+    // Fresh derivation, no knowledge of pay_count.
+    const [lookup] = deriveReferenceIndexPda(program.programId, treasuryPda, reference);
+    const idx: any = await program.account.referenceIndex.fetch(lookup);
+    const r: any = await program.account.receipt.fetch(idx.receipt);
+
+    expect(idx.receipt.toBase58()).to.eq(receiptPda.toBase58());
+    expect(r.user.toBase58()).to.eq(recipient.toBase58());
+    expect(r.amount.toString()).to.eq("55");
+  });
+
+  it("rejects a second pay with the same reference and moves no funds", async () => {
+    const { mint, treasuryAta } = await seedTreasury(1_000_000n);
+    const recipient = Keypair.generate().publicKey;
+    const reference = Keypair.generate().publicKey.toBuffer();
+
+    const { recipientAta } = await payWithReference({
+      mint,
+      treasuryAta,
+      recipient,
+      reference,
+      amount: 100n,
+    });
+
+    const payCountBefore = await getTreasuryPayCount(program, treasuryPda);
+    const treasuryBefore = await getTokenBalanceOrZero(provider, treasuryAta);
+    const recipientBefore = await getTokenBalanceOrZero(provider, recipientAta);
+
+    let threw = false;
+    try {
+      await payWithReference({ mint, treasuryAta, recipient, reference, amount: 100n });
+    } catch {
+      threw = true;
+    }
+    expect(threw).to.eq(true);
+
+    const payCountAfter = await getTreasuryPayCount(program, treasuryPda);
+    expect(payCountAfter.toString()).to.eq(payCountBefore.toString());
+    expect(await getTokenBalanceOrZero(provider, treasuryAta)).to.eq(treasuryBefore);
+    expect(await getTokenBalanceOrZero(provider, recipientAta)).to.eq(recipientBefore);
+  });
+});
@@ -3,7 +3,7 @@ import * as anchor from "@coral-xyz/anchor";
 
 import { PublicKey, SystemProgram, Keypair } from "@solana/web3.js";
 import BN from "bn.js";
-import { loadProtocolAuthority, airdrop } from "./_helpers";
+import { loadProtocolAuthority, airdrop, deriveTreasuryConfigPda, deriveMintLimitsPda, deriveMintLedgerPda, deriveUserLimitsPda, deriveDenylistEntryPda, deriveKycAttestationPda } from "./_helpers";
 
 
 import {
@@ -102,6 +102,13 @@ describe("protocol - spl roundtrip", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
       })
       .signers([user])
       .rpc();
@@ -123,6 +130,15 @@ describe("protocol - spl roundtrip", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
       } as any)
       .signers([protocolAuth])
       .rpc();
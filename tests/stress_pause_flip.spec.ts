@@ -47,6 +47,12 @@ import {
   expect,
   loadProtocolAuthority,
   createAtaStrict,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 /* -----------------------------
@@ -280,6 +286,14 @@ async function buildSplDepositTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, authority.publicKey, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, authority.publicKey)[0],
+    // Optional accounts: passing the program id means "None".
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
@@ -348,6 +362,17 @@ async function buildSplPayTx(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+    kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+    // Optional accounts: passing the program id means "None".
+    travelRule: program.programId,
+    memoProgram: program.programId,
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   const argsObj: any = {};
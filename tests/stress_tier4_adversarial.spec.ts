@@ -29,7 +29,7 @@ import {
 } from "@solana/spl-token";
 import { expect } from "chai";
 
-import { loadProtocolAuthority, airdrop, withRetry, NONCE_PAY_BASE } from "./_helpers";
+import { loadProtocolAuthority, airdrop, withRetry, NONCE_PAY_BASE, deriveTreasuryConfigPda, deriveMintLimitsPda, deriveMintLedgerPda, deriveUserLimitsPda, deriveDenylistEntryPda, deriveKycAttestationPda } from "./_helpers";
 
 // ---------- tiny utils ----------
 type BN = anchor.BN;
@@ -242,6 +242,17 @@ async function splPayStrict(args: {
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: anchor.web3.SystemProgram.programId,
     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+    mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+    mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+    userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient, mint)[0],
+    denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient)[0],
+    kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient)[0],
+    // Optional accounts: passing the program id means "None".
+    travelRule: program.programId,
+    memoProgram: program.programId,
+    userProfile: program.programId,
+    trustScore: program.programId,
   };
 
   // Build args object based on IDL arg names (amount + nonce-like + memo-like)
@@ -0,0 +1,282 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+  deriveTravelRulePda,
+} from "./_helpers";
+
+const TRAVEL_RULE_THRESHOLD = 1_000;
+const FLAG_HAS_TRAVEL_RULE = 1 << 2;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+function filled(byte: number): number[] {
+  return Array(32).fill(byte);
+}
+
+/**
+ * Travel-rule records consumed by payout and withdrawal flows.
+ *
+ * Canon:
+ * - above travel_rule_threshold, the transfer needs a matching, unconsumed
+ *   record (TravelRuleRecordRequired / TravelRuleRecordMismatch /
+ *   TravelRuleRecordConsumed)
+ * - receipt flows key the record by the receipt address and set
+ *   FLAG_HAS_TRAVEL_RULE on the receipt
+ * - a record is consumed once and links back to the receipt
+ */
+describe("protocol - travel rule", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let recipient: Keypair;
+  let mint: PublicKey;
+  let treasuryAta: PublicKey;
+
+  // Record keyed by the next pay receipt, shared by the matching tests.
+  let receiptPda: PublicKey;
+  let recordPda: PublicKey;
+
+  async function setTravelRuleConfig(threshold: number) {
+    await program.methods
+      .setTravelRuleConfig(new anchor.BN(threshold))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        treasuryConfig: treasuryConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function record(recordId: Uint8Array, amount: number): Promise<PublicKey> {
+    const [recordPda] = deriveTravelRulePda(program.programId, treasuryPda, recordId);
+    await program.methods
+      .recordTravelRule({
+        recordId: Array.from(recordId),
+        beneficiary: recipient.publicKey,
+        mint,
+        amount: new anchor.BN(amount),
+        originatorVasp: filled(1),
+        beneficiaryVasp: filled(2),
+        piiHash: filled(3),
+      })
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        travelRule: recordPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+    return recordPda;
+  }
+
+  function flowAccounts(travelRule: PublicKey | null) {
+    return {
+      treasuryConfig: treasuryConfigPda,
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient.publicKey)[0],
+      kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient.publicKey)[0],
+      travelRule,
+      trustScore: null,
+    };
+  }
+
+  async function nextPayReceipt(): Promise<PublicKey> {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    return derivePayReceiptPda(program.programId, treasuryPda, payCount)[0];
+  }
+
+  async function pay(amount: number, receiptPda: PublicKey, travelRule: PublicKey | null) {
+    await program.methods
+      .splPay(new anchor.BN(amount), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: recipient.publicKey,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient.publicKey),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        ...flowAccounts(travelRule),
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function withdraw(amount: number, travelRule: PublicKey | null) {
+    await program.methods
+      .splWithdraw(new anchor.BN(amount))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        user: recipient.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: getAssociatedTokenAddressSync(mint, recipient.publicKey),
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        ...flowAccounts(travelRule),
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    recipient = Keypair.generate();
+    const funder = Keypair.generate();
+    await airdrop(provider, funder.publicKey, 2, "confirmed");
+
+    const setup = await setupMintAndAtas(provider, funder, treasuryPda, 1_000_000n);
+    mint = setup.mint;
+    treasuryAta = setup.treasuryAta;
+
+    await program.methods
+      .splDeposit(new anchor.BN(1_000_000))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: setup.userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+
+    await setTravelRuleConfig(TRAVEL_RULE_THRESHOLD);
+  });
+
+  after(async () => {
+    // Leave the shared treasury config without a travel-rule requirement.
+    await setTravelRuleConfig(0);
+  });
+
+  it("amounts at or below the threshold need no record", async () => {
+    await pay(TRAVEL_RULE_THRESHOLD, await nextPayReceipt(), null);
+  });
+
+  it("payouts and withdrawals above the threshold require a record", async () => {
+    await expectReject(
+      pay(TRAVEL_RULE_THRESHOLD + 1, await nextPayReceipt(), null),
+      "TravelRuleRecordRequired"
+    );
+    await expectReject(withdraw(TRAVEL_RULE_THRESHOLD + 1, null), "TravelRuleRecordRequired");
+  });
+
+  it("records with missing originator data are rejected", async () => {
+    await expectReject(record(Keypair.generate().publicKey.toBytes(), 0), "InvalidTravelRuleRecord");
+  });
+
+  it("a record must match the transfer amount", async () => {
+    receiptPda = await nextPayReceipt();
+    recordPda = await record(receiptPda.toBytes(), TRAVEL_RULE_THRESHOLD + 2);
+
+    await expectReject(
+      pay(TRAVEL_RULE_THRESHOLD + 1, receiptPda, recordPda),
+      "TravelRuleRecordMismatch"
+    );
+  });
+
+  it("a matching record unlocks the payout and flags the receipt", async () => {
+    // The rejected payout above left the pay counter, and so the receipt, unchanged.
+    expect((await nextPayReceipt()).toBase58()).to.eq(receiptPda.toBase58());
+
+    await pay(TRAVEL_RULE_THRESHOLD + 2, receiptPda, recordPda);
+
+    const r: any = await program.account.receipt.fetch(receiptPda);
+    expect(toNum(r.v2.flags) & FLAG_HAS_TRAVEL_RULE).to.eq(FLAG_HAS_TRAVEL_RULE);
+
+    const rec: any = await program.account.travelRuleRecord.fetch(recordPda);
+    expect(toNum(rec.consumedAt)).to.be.greaterThan(0);
+    expect(rec.receipt.toBase58()).to.eq(receiptPda.toBase58());
+  });
+
+  it("a withdrawal consumes a record once", async () => {
+    const recordPda = await record(Keypair.generate().publicKey.toBytes(), TRAVEL_RULE_THRESHOLD + 1);
+
+    await withdraw(TRAVEL_RULE_THRESHOLD + 1, recordPda);
+    await expectReject(withdraw(TRAVEL_RULE_THRESHOLD + 1, recordPda), "TravelRuleRecordConsumed");
+  });
+});
@@ -10,6 +10,12 @@ import {
   deriveTreasuryPda,
   loadProtocolAuthority,
   airdrop,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 import {
@@ -84,6 +90,13 @@ describe("protocol - treasury pause", () => {
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user)[0],
+        trustScore: null,
       } as any)
       .rpc();
 
@@ -116,6 +129,13 @@ describe("protocol - treasury pause", () => {
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          userProfile: null,
+          treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+          mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+          mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+          userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user, mint)[0],
+          denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user)[0],
+          trustScore: null,
         } as any)
         .rpc();
 
@@ -142,6 +162,15 @@ describe("protocol - treasury pause", () => {
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          userProfile: null,
+          treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+          mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+          mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+          userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user, mint)[0],
+          denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user)[0],
+          kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user)[0],
+          travelRule: null,
+          trustScore: null,
         } as any)
         .signers([protocolAuth])
         .rpc();
@@ -177,6 +206,13 @@ describe("protocol - treasury pause", () => {
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user)[0],
+        trustScore: null,
       } as any)
       .rpc();
   });
@@ -0,0 +1,262 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  derivePayReceiptPda,
+  getTreasuryPayCount,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+  deriveTrustScorePda,
+} from "./_helpers";
+
+// Mirrors TrustScore::FLOW_POINTS.
+const FLOW_POINTS = 50;
+const DAY_SECS = 86_400;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * Portable trust score PDA.
+ *
+ * Canon:
+ * - one account per wallet (["trust_score", wallet]), created by the wallet
+ * - successful receipted flows credit it when it is passed in
+ * - anyone may refresh it (decay + account age)
+ * - trust_tier_scores lift a wallet to a higher spending limit tier;
+ *   omitting the account only forfeits that lift
+ */
+describe("protocol - trust score", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let recipient: Keypair;
+  let trustPda: PublicKey;
+  let mint: PublicKey;
+  let mintLimitsPda: PublicKey;
+  let treasuryAta: PublicKey;
+
+  async function setTrustConfig(tierScores: number[], minAmount = 0) {
+    await program.methods
+      .setTrustConfig(tierScores, new anchor.BN(minAmount))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        treasuryConfig: treasuryConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function setLimitTier(tier: number, periodSecs: number, maxCount: number) {
+    await program.methods
+      .setLimitTier(tier, new anchor.BN(periodSecs), maxCount, new anchor.BN(0))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        mint,
+        mintLimits: mintLimitsPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function initTrustScore() {
+    await program.methods
+      .initTrustScore()
+      .accounts({
+        wallet: recipient.publicKey,
+        trustScore: trustPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([recipient])
+      .rpc();
+  }
+
+  async function pay(amount: number, withTrust: boolean) {
+    const payCount = await getTreasuryPayCount(program, treasuryPda);
+    const [receiptPda] = derivePayReceiptPda(program.programId, treasuryPda, payCount);
+
+    await program.methods
+      .splPay(new anchor.BN(amount), null, null, null)
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        recipient: recipient.publicKey,
+        treasury: treasuryPda,
+        mint,
+        recipientAta: getAssociatedTokenAddressSync(mint, recipient.publicKey),
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: mintLimitsPda,
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient.publicKey)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient.publicKey)[0],
+        travelRule: null,
+        trustScore: withTrust ? trustPda : null,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  async function expectReject(p: Promise<any>, needle: string) {
+    let threw = false;
+    try {
+      await p;
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e) + JSON.stringify(e?.logs ?? [])).to.include(needle);
+    }
+    expect(threw).to.eq(true);
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    recipient = Keypair.generate();
+    const funder = Keypair.generate();
+    await airdrop(provider, recipient.publicKey, 1, "confirmed");
+    await airdrop(provider, funder.publicKey, 2, "confirmed");
+    [trustPda] = deriveTrustScorePda(program.programId, recipient.publicKey);
+
+    const setup = await setupMintAndAtas(provider, funder, treasuryPda, 1_000_000n);
+    mint = setup.mint;
+    treasuryAta = setup.treasuryAta;
+    [mintLimitsPda] = deriveMintLimitsPda(program.programId, treasuryPda, mint);
+
+    await program.methods
+      .splDeposit(new anchor.BN(1_000_000))
+      .accounts({
+        user: funder.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta: setup.userAta,
+        treasuryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: mintLimitsPda,
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, funder.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, funder.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([funder])
+      .rpc();
+  });
+
+  after(async () => {
+    // Leave the shared treasury config without trust-based tiers.
+    await setTrustConfig([0, 0, 0, 0]);
+  });
+
+  it("the wallet creates its trust score at zero", async () => {
+    await initTrustScore();
+
+    const t: any = await program.account.trustScore.fetch(trustPda);
+    expect(t.wallet.toBase58()).to.eq(recipient.publicKey.toBase58());
+    expect(toNum(t.score)).to.eq(0);
+    expect(toNum(t.successfulFlows)).to.eq(0);
+    expect(toNum(t.createdAt)).to.be.greaterThan(0);
+
+    await expectReject(initTrustScore(), "already in use");
+  });
+
+  it("a receipted payout credits the recipient's score", async () => {
+    await pay(100, true);
+
+    const t: any = await program.account.trustScore.fetch(trustPda);
+    expect(toNum(t.successfulFlows)).to.eq(1);
+    expect(toNum(t.activityPoints)).to.eq(FLOW_POINTS);
+    expect(toNum(t.score)).to.eq(FLOW_POINTS);
+  });
+
+  it("a payout without the account leaves the score untouched", async () => {
+    await pay(100, false);
+
+    const t: any = await program.account.trustScore.fetch(trustPda);
+    expect(toNum(t.successfulFlows)).to.eq(1);
+  });
+
+  it("anyone can refresh a score", async () => {
+    // Fee payer is the protocol authority, not the scored wallet.
+    await program.methods
+      .refreshTrustScore()
+      .accounts({ trustScore: trustPda } as any)
+      .rpc();
+
+    const t: any = await program.account.trustScore.fetch(trustPda);
+    expect(toNum(t.score)).to.eq(FLOW_POINTS);
+  });
+
+  it("setTrustConfig rejects malformed tier tables", async () => {
+    await expectReject(setTrustConfig([1, 0, 0, 0]), "InvalidTrustConfig");
+    await expectReject(setTrustConfig([0, 500, 100, 0]), "InvalidTrustConfig");
+    await expectReject(setTrustConfig([0, 10_001, 0, 0]), "InvalidTrustConfig");
+  });
+
+  it("a high enough score lifts the recipient to a more generous tier", async () => {
+    // Tier 0 allows one payout per day; tier 1 allows three.
+    await setLimitTier(0, DAY_SECS, 1);
+    await setLimitTier(1, DAY_SECS, 3);
+    await setTrustConfig([0, FLOW_POINTS, 0, 0]);
+
+    const c: any = await program.account.treasuryConfig.fetch(treasuryConfigPda);
+    expect(c.trustTierScores.map(toNum)).to.deep.eq([0, FLOW_POINTS, 0, 0]);
+
+    await pay(100, true);
+    await pay(100, true);
+
+    // Without the trust account the assigned tier 0 applies.
+    await expectReject(pay(100, false), "LimitCountExceeded");
+  });
+});
@@ -0,0 +1,197 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  deriveUserProfilePda,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+} from "./_helpers";
+
+const USER_PROFILE_CURRENT_VERSION = 4;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * User profile lifecycle: init / update (realloc) / close + activity fields.
+ *
+ * Canon:
+ * - initUserProfile creates ["user_profile", user] at the current version
+ * - splDepositWithReceipt records activity when the optional profile is passed
+ * - closeUserProfile only succeeds while tx_count == 0
+ * - updateUserProfile is a no-op on current profiles
+ */
+describe("protocol - user profile lifecycle", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let treasuryPda: PublicKey;
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    const protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+  });
+
+  async function freshUser(): Promise<{ user: Keypair; profilePda: PublicKey }> {
+    const user = Keypair.generate();
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+    const [profilePda] = deriveUserProfilePda(user.publicKey);
+    return { user, profilePda };
+  }
+
+  async function initProfile(user: Keypair, profilePda: PublicKey) {
+    await program.methods
+      .initUserProfile()
+      .accounts({
+        user: user.publicKey,
+        userProfile: profilePda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([user])
+      .rpc();
+  }
+
+  async function closeProfile(user: Keypair, profilePda: PublicKey) {
+    await program.methods
+      .closeUserProfile()
+      .accounts({ user: user.publicKey, userProfile: profilePda } as any)
+      .signers([user])
+      .rpc();
+  }
+
+  it("initUserProfile creates an empty current-version profile", async () => {
+    const { user, profilePda } = await freshUser();
+    await initProfile(user, profilePda);
+
+    const p: any = await program.account.userProfile.fetch(profilePda);
+    expect(p.authority.toBase58()).to.eq(user.publicKey.toBase58());
+    expect(toNum(p.version)).to.eq(USER_PROFILE_CURRENT_VERSION);
+    expect(toNum(p.txCount)).to.eq(0);
+    expect(toNum(p.depositCount)).to.eq(0);
+    expect(toNum(p.withdrawCount)).to.eq(0);
+    expect(toNum(p.firstActivityTs)).to.eq(0);
+  });
+
+  it("deposit-with-receipt records activity on a supplied profile", async () => {
+    const { user, profilePda } = await freshUser();
+    await initProfile(user, profilePda);
+
+    const { mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      user,
+      treasuryPda,
+      1_000_000n
+    );
+
+    const nonce = new anchor.BN(Date.now());
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, user.publicKey, nonce);
+
+    await program.methods
+      .splDepositWithReceipt(new anchor.BN(250_000), nonce, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: profilePda,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc();
+
+    const p: any = await program.account.userProfile.fetch(profilePda);
+    expect(toNum(p.txCount)).to.eq(1);
+    expect(toNum(p.depositCount)).to.eq(1);
+    expect(toNum(p.withdrawCount)).to.eq(0);
+    expect(toNum(p.firstActivityTs)).to.be.greaterThan(0);
+    expect(toNum(p.lastActivityTs)).to.eq(toNum(p.firstActivityTs));
+
+    expect(p.mintVolumes[0].mint.toBase58()).to.eq(mint.toBase58());
+    expect(p.mintVolumes[0].deposited.toString()).to.eq("250000");
+    expect(Buffer.from(p.volumeDigest).equals(Buffer.alloc(32))).to.eq(false);
+
+    // An active profile cannot be closed (withdraw receipts are keyed by tx_count).
+    let threw = false;
+    try {
+      await closeProfile(user, profilePda);
+    } catch (e: any) {
+      threw = true;
+      expect(String(e?.message ?? e)).to.include("UserProfileHasActivity");
+    }
+    expect(threw).to.eq(true);
+  });
+
+  it("updateUserProfile is a no-op on a current profile", async () => {
+    const { user, profilePda } = await freshUser();
+    await initProfile(user, profilePda);
+
+    const before = await provider.connection.getAccountInfo(profilePda, "confirmed");
+
+    await program.methods
+      .updateUserProfile()
+      .accounts({
+        user: user.publicKey,
+        userProfile: profilePda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([user])
+      .rpc();
+
+    const after = await provider.connection.getAccountInfo(profilePda, "confirmed");
+    expect(Buffer.compare(before!.data, after!.data)).to.eq(0);
+  });
+
+  it("closeUserProfile returns rent for an unused profile", async () => {
+    const { user, profilePda } = await freshUser();
+    await initProfile(user, profilePda);
+
+    const balBefore = await provider.connection.getBalance(user.publicKey, "confirmed");
+    await closeProfile(user, profilePda);
+    const balAfter = await provider.connection.getBalance(user.publicKey, "confirmed");
+
+    expect(await provider.connection.getAccountInfo(profilePda, "confirmed")).to.eq(null);
+    expect(balAfter).to.be.greaterThan(balBefore);
+  });
+});
@@ -17,6 +17,12 @@ import {
   loadProtocolAuthority,
   airdrop,
   initFoundationOnce,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
 } from "./_helpers";
 
 describe("protocol - spl withdraw armor", () => {
@@ -122,6 +128,15 @@ describe("protocol - spl withdraw armor", () => {
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          userProfile: null,
+          treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+          mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+          mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+          userLimits: deriveUserLimitsPda(program.programId, treasuryPda, recipient.publicKey, mint)[0],
+          denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, recipient.publicKey)[0],
+          kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, recipient.publicKey)[0],
+          travelRule: null,
+          trustScore: null,
         } as any)
         .signers([protocolAuth]) // authority pays for init_if_needed in withdraw
         .rpc();
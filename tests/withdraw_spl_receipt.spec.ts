@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
-import { loadProtocolAuthority, airdrop } from "./_helpers";
+import { loadProtocolAuthority, airdrop, deriveTreasuryConfigPda, deriveMintLimitsPda, deriveMintLedgerPda, deriveUserLimitsPda, deriveDenylistEntryPda, deriveKycAttestationPda } from "./_helpers";
 import { expect } from "chai";
 
 
@@ -116,6 +116,13 @@ describe("protocol - spl withdraw with receipt", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+        trustScore: null,
       } as any)
       .signers([payer])
       .rpc();
@@ -135,7 +142,7 @@ describe("protocol - spl withdraw with receipt", () => {
 
     // --- Withdraw with receipt (IDL accounts: user, treasuryAuthority, userProfile, treasury, mint, userAta, treasuryAta, receipt, ...)
     await program.methods
-      .splWithdrawWithReceipt(new anchor.BN(amount))
+      .splWithdrawWithReceipt(new anchor.BN(amount), null, null)
       .accounts({
         user: payer.publicKey,
         treasuryAuthority: protocolAuth.publicKey,
@@ -149,6 +156,14 @@ describe("protocol - spl withdraw with receipt", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+        kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, payer.publicKey)[0],
+        travelRule: null,
+        trustScore: null,
       } as any)
       .signers([protocolAuth])
       .rpc();
@@ -218,6 +233,13 @@ describe("protocol - spl withdraw with receipt", () => {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      userProfile: null,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+      trustScore: null,
     } as any)
     .signers([payer])
     .rpc();
@@ -238,7 +260,7 @@ describe("protocol - spl withdraw with receipt", () => {
 
   // --- Execute withdraw-with-receipt and CAPTURE signature
   const txSig = await program.methods
-    .splWithdrawWithReceipt(new anchor.BN(amount))
+    .splWithdrawWithReceipt(new anchor.BN(amount), null, null)
     .accounts({
       user: payer.publicKey,
       treasuryAuthority: protocolAuth.publicKey,
@@ -252,6 +274,14 @@ describe("protocol - spl withdraw with receipt", () => {
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+      mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+      mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+      userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+      denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+      kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, payer.publicKey)[0],
+      travelRule: null,
+      trustScore: null,
     } as any)
     .signers([protocolAuth])
     .rpc();
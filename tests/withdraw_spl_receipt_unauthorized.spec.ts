@@ -11,6 +11,15 @@ import {
   mintTo,
 } from "@solana/spl-token";
 
+import {
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
 import { Protocol } from "../target/types/protocol";
 
 function u64LE(n: anchor.BN): Buffer {
@@ -81,6 +90,13 @@ describe("protocol - spl withdraw with receipt unauthorized", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        userProfile: null,
+        treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, payer.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, payer.publicKey)[0],
+        trustScore: null,
       } as any)
       .signers([payer])
       .rpc();
@@ -115,7 +131,7 @@ describe("protocol - spl withdraw with receipt unauthorized", () => {
     // --- Attack: attacker tries to pretend they're the treasury authority
     try {
       await program.methods
-        .splWithdrawWithReceipt(new anchor.BN(amount))
+        .splWithdrawWithReceipt(new anchor.BN(amount), null, null)
         .accounts({
           user: attacker.publicKey,
           treasuryAuthority: attacker.publicKey, // WRONG on purpose
@@ -129,6 +145,14 @@ describe("protocol - spl withdraw with receipt unauthorized", () => {
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+          mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+          mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+          userLimits: deriveUserLimitsPda(program.programId, treasuryPda, attacker.publicKey, mint)[0],
+          denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, attacker.publicKey)[0],
+          kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, attacker.publicKey)[0],
+          travelRule: null,
+          trustScore: null,
         } as any)
         .signers([attacker])
         .rpc();
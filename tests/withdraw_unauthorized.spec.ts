@@ -13,6 +13,15 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 
+import {
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+  deriveKycAttestationPda,
+} from "./_helpers";
+
 describe("protocol - spl withdraw unauthorized", () => {
   const DEBUG = false;
   const provider = anchor.AnchorProvider.env();
@@ -161,6 +170,15 @@ describe("protocol - spl withdraw unauthorized", () => {
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          userProfile: null,
+          treasuryConfig: deriveTreasuryConfigPda(program.programId, treasuryPda)[0],
+          mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+          mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+          userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+          denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+          kycAttestation: deriveKycAttestationPda(program.programId, treasuryPda, user.publicKey)[0],
+          travelRule: null,
+          trustScore: null,
         })
         .signers([attacker]) // attacker signs (wrong signer)
         .rpc();
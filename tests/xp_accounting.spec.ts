@@ -0,0 +1,222 @@
+import * as anchor from "@coral-xyz/anchor";
+import { AnchorProvider, Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+  initFoundationOnce,
+  setupMintAndAtas,
+  loadProtocolAuthority,
+  airdrop,
+  deriveDepositReceiptPda,
+  deriveUserProfilePda,
+  deriveTreasuryConfigPda,
+  deriveMintLimitsPda,
+  deriveMintLedgerPda,
+  deriveUserLimitsPda,
+  deriveDenylistEntryPda,
+} from "./_helpers";
+import { findEvent } from "./helpers/events";
+
+const XP_PER_DEPOSIT = 10;
+const XP_PER_WITHDRAW = 5;
+const XP_PER_PAY = 3;
+const XP_MIN_AMOUNT = 1_000;
+const XP_EPOCH_CAP = 25;
+
+function toNum(v: any): number {
+  if (v instanceof anchor.BN) return v.toNumber();
+  return Number(v);
+}
+
+/**
+ * On-chain XP accounting.
+ *
+ * Canon:
+ * - XP is credited only when the user profile is passed and the treasury config exists
+ * - flows below xp_min_amount earn 0
+ * - per-epoch earnings are capped at xp_epoch_cap
+ * - event xp_delta always equals the XP actually credited
+ */
+describe("protocol - xp accounting", function () {
+  this.timeout(3_600_000);
+
+  let provider: AnchorProvider;
+  let program: Program<any>;
+  let protocolAuth: Keypair;
+  let treasuryPda: PublicKey;
+  let treasuryConfigPda: PublicKey;
+
+  let user: Keypair;
+  let profilePda: PublicKey;
+  let mint: PublicKey;
+  let userAta: PublicKey;
+  let treasuryAta: PublicKey;
+
+  async function setXpConfig(deposit: number, withdraw: number, pay: number, min: number, cap: number) {
+    await program.methods
+      .setXpConfig(deposit, withdraw, pay, new anchor.BN(min), new anchor.BN(cap))
+      .accounts({
+        treasuryAuthority: protocolAuth.publicKey,
+        treasury: treasuryPda,
+        treasuryConfig: treasuryConfigPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([protocolAuth])
+      .rpc();
+  }
+
+  before(async () => {
+    const envProvider = anchor.AnchorProvider.env();
+    protocolAuth = loadProtocolAuthority();
+
+    provider = new AnchorProvider(envProvider.connection, new anchor.Wallet(protocolAuth), {
+      commitment: "confirmed",
+      preflightCommitment: "confirmed",
+      skipPreflight: false,
+    });
+    anchor.setProvider(provider);
+
+    program = anchor.workspace.Protocol as Program<any>;
+    treasuryPda = (await initFoundationOnce(provider, program)).treasuryPda;
+    [treasuryConfigPda] = deriveTreasuryConfigPda(program.programId, treasuryPda);
+
+    await program.methods
+      .setTreasuryPaused(false)
+      .accounts({ treasury: treasuryPda, treasuryAuthority: protocolAuth.publicKey } as any)
+      .signers([protocolAuth])
+      .rpc();
+
+    await setXpConfig(XP_PER_DEPOSIT, XP_PER_WITHDRAW, XP_PER_PAY, XP_MIN_AMOUNT, XP_EPOCH_CAP);
+
+    user = Keypair.generate();
+    await airdrop(provider, user.publicKey, 2, "confirmed");
+    [profilePda] = deriveUserProfilePda(user.publicKey);
+
+    await program.methods
+      .initUserProfile()
+      .accounts({
+        user: user.publicKey,
+        userProfile: profilePda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([user])
+      .rpc();
+
+    ({ mint, userAta, treasuryAta } = await setupMintAndAtas(
+      provider,
+      user,
+      treasuryPda,
+      1_000_000n
+    ));
+  });
+
+  after(async () => {
+    // Leave the shared treasury config with XP disabled.
+    await setXpConfig(0, 0, 0, 0, 0);
+  });
+
+  async function deposit(amount: number, withProfile = true): Promise<number> {
+    const nonce = new anchor.BN(Date.now()).add(new anchor.BN(Math.floor(Math.random() * 1000)));
+    const [receiptPda] = deriveDepositReceiptPda(program.programId, user.publicKey, nonce);
+
+    const txSig = await program.methods
+      .splDepositWithReceipt(new anchor.BN(amount), nonce, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        treasury: treasuryPda,
+        mint,
+        userAta,
+        treasuryAta,
+        receipt: receiptPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        memoProgram: null,
+        userProfile: withProfile ? profilePda : null,
+        treasuryConfig: treasuryConfigPda,
+        mintLimits: deriveMintLimitsPda(program.programId, treasuryPda, mint)[0],
+        mintLedger: deriveMintLedgerPda(program.programId, treasuryPda, mint)[0],
+        userLimits: deriveUserLimitsPda(program.programId, treasuryPda, user.publicKey, mint)[0],
+        denylistEntry: deriveDenylistEntryPda(program.programId, treasuryPda, user.publicKey)[0],
+        trustScore: null,
+      } as any)
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(txSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    } as any);
+    const { hit } = findEvent(program as any, tx?.meta?.logMessages ?? [], ["DepositEvent"]);
+    expect(hit).to.not.eq(null);
+    return toNum(hit.xpDelta);
+  }
+
+  async function profileXp(): Promise<number> {
+    const p: any = await program.account.userProfile.fetch(profilePda);
+    return toNum(p.xp);
+  }
+
+  it("setXpConfig stores the XP table", async () => {
+    const c: any = await program.account.treasuryConfig.fetch(treasuryConfigPda);
+    expect(c.treasury.toBase58()).to.eq(treasuryPda.toBase58());
+    expect(toNum(c.xpPerDeposit)).to.eq(XP_PER_DEPOSIT);
+    expect(toNum(c.xpPerWithdraw)).to.eq(XP_PER_WITHDRAW);
+    expect(toNum(c.xpPerPay)).to.eq(XP_PER_PAY);
+    expect(toNum(c.xpMinAmount)).to.eq(XP_MIN_AMOUNT);
+    expect(toNum(c.xpEpochCap)).to.eq(XP_EPOCH_CAP);
+  });
+
+  it("setXpConfig rejects non-authority signers", async () => {
+    const rogue = Keypair.generate();
+    await airdrop(provider, rogue.publicKey, 1, "confirmed");
+
+    let threw = false;
+    try {
+      await program.methods
+        .setXpConfig(1_000, 1_000, 1_000, new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          treasuryAuthority: rogue.publicKey,
+          treasury: treasuryPda,
+          treasuryConfig: treasuryConfigPda,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([rogue])
+        .rpc();
+    } catch {
+      threw = true;
+    }
+    expect(threw).to.eq(true);
+  });
+
+  it("omitting the user profile credits no XP", async () => {
+    const before = await profileXp();
+    expect(await deposit(5_000, false)).to.eq(0);
+    expect(await profileXp()).to.eq(before);
+  });
+
+  it("a deposit below the minimum amount earns no XP", async () => {
+    const before = await profileXp();
+    expect(await deposit(XP_MIN_AMOUNT - 1)).to.eq(0);
+    expect(await profileXp()).to.eq(before);
+  });
+
+  it("deposits credit XP up to the per-epoch cap", async () => {
+    const before = await profileXp();
+
+    const deltas: number[] = [];
+    for (let i = 0; i < 3; i++) deltas.push(await deposit(5_000));
+
+    // 10 + 10 + 5 (capped at 25 for the epoch)
+    expect(deltas).to.deep.eq([10, 10, 5]);
+    expect(await profileXp()).to.eq(before + XP_EPOCH_CAP);
+
+    expect(await deposit(5_000)).to.eq(0);
+
+    const p: any = await program.account.userProfile.fetch(profilePda);
+    expect(toNum(p.xpEpochEarned)).to.eq(XP_EPOCH_CAP);
+  });
+});